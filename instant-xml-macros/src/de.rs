@@ -1,11 +1,12 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use proc_macro2::{Ident, Literal, Span, TokenStream};
 use quote::quote;
 use syn::spanned::Spanned;
 
 use super::{
-    discard_lifetimes, meta_items, ContainerMeta, FieldMeta, Mode, Namespace, VariantMeta,
+    discard_lifetimes, flattenable, is_other, is_phantom_data, variant_tag, ContainerMeta,
+    FieldMeta, Mode, Namespace, TypeAttr, VariantMeta,
 };
 
 pub(crate) fn from_xml(input: &syn::DeriveInput) -> TokenStream {
@@ -22,14 +23,20 @@ pub(crate) fn from_xml(input: &syn::DeriveInput) -> TokenStream {
         },
         (syn::Data::Struct(data), Some(Mode::Transparent)) => match &data.fields {
             syn::Fields::Named(fields) => deserialize_inline_struct(input, fields, meta),
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                deserialize_newtype(input, &fields.unnamed[0], meta)
+            }
             _ => syn::Error::new(
                 input.span(),
-                "inline mode is only supported on types with named fields",
+                "inline mode is only supported on types with named fields or a single unnamed field",
             )
             .to_compile_error(),
         },
         (syn::Data::Enum(data), Some(Mode::Scalar)) => deserialize_scalar_enum(input, data, meta),
         (syn::Data::Enum(data), Some(Mode::Forward)) => deserialize_forward_enum(input, data, meta),
+        (syn::Data::Enum(data), None) if meta.type_attr.is_some() => {
+            deserialize_tagged_enum(input, data, meta)
+        }
         (syn::Data::Struct(_), Some(mode)) => syn::Error::new(
             input.span(),
             format_args!("{mode:?} mode not allowed on struct type"),
@@ -67,6 +74,11 @@ fn deserialize_scalar_enum(
     }
 
     let default_namespace = meta.default_namespace();
+    // Without a namespace of its own, the enum takes the namespace of the field
+    let field_ns = match &meta.ns.uri {
+        Some(_) => default_namespace.clone(),
+        None => quote!(fid.ns),
+    };
 
     let generics = meta.xml_generics(BTreeSet::new());
     let (impl_generics, _, _) = generics.split_for_impl();
@@ -77,12 +89,11 @@ fn deserialize_scalar_enum(
         impl #impl_generics FromXml<'xml> for #ident #ty_generics #where_clause {
             #[inline]
             fn matches(id: ::instant_xml::Id<'_>, field: Option<::instant_xml::Id<'_>>) -> bool {
-                id == ::instant_xml::Id {
-                    ns: #default_namespace,
-                    name: match field {
-                        Some(fid) => fid.name,
-                        None => id.name,
-                    },
+                match field {
+                    Some(fid) => {
+                        id.name == fid.name && ::instant_xml::de::ns_matches(id.ns, #field_ns)
+                    }
+                    None => id.ns == #default_namespace,
                 }
             }
 
@@ -130,44 +141,76 @@ fn deserialize_forward_enum(
 
     let ident = &input.ident;
     let field_str = format!("{ident}::0");
+    let default_namespace = meta.default_namespace();
     let mut matches = TokenStream::new();
     let mut variants = TokenStream::new();
     let mut borrowed = BTreeSet::new();
+    let mut other = None;
     for variant in data.variants.iter() {
+        let v_ident = &variant.ident;
+        if !matches!(variant.fields, syn::Fields::Named(_)) {
+            match is_other(variant) {
+                Ok(false) => {}
+                Ok(true) if other.is_some() => {
+                    return syn::Error::new(variant.span(), "only one variant can be 'other'")
+                        .to_compile_error()
+                }
+                Ok(true) => {
+                    other = Some(quote!(Self::#v_ident(::core::convert::From::from(id))));
+                    continue;
+                }
+                Err(err) => return err.to_compile_error(),
+            }
+        }
+
+        if !matches.is_empty() {
+            matches.extend(quote!(||));
+        }
+
+        if !variants.is_empty() {
+            variants.extend(quote!(else));
+        }
+
         let field = match &variant.fields {
             syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
                 fields.unnamed.first().unwrap()
             }
+            syn::Fields::Named(fields) => {
+                let tag = match variant_tag(variant) {
+                    Ok(tag) => tag,
+                    Err(err) => return err.to_compile_error(),
+                };
+
+                let body = match deserialize_fields(
+                    fields,
+                    &meta,
+                    v_ident,
+                    quote!(Self::#v_ident),
+                    &mut borrowed,
+                ) {
+                    Ok(body) => body,
+                    Err(err) => return err.into_compile_error(),
+                };
+
+                let id = quote!(::instant_xml::Id { ns: #default_namespace, name: #tag });
+                matches.extend(quote!(id == #id));
+                variants.extend(quote!(if (id == #id) {
+                    #body
+                }));
+                continue;
+            }
             _ => {
                 return syn::Error::new(
                     input.span(),
-                    "wrapped enum variants must have 1 unnamed field",
+                    "wrapped enum variants must have 1 unnamed field or named fields",
                 )
                 .to_compile_error()
             }
         };
 
-        if !meta_items(&variant.attrs).is_empty() {
-            return syn::Error::new(
-                input.span(),
-                "attributes not allowed on wrapped enum variants",
-            )
-            .to_compile_error();
-        }
-
         let mut no_lifetime_type = field.ty.clone();
         discard_lifetimes(&mut no_lifetime_type, &mut borrowed, false, true);
-
-        if !matches.is_empty() {
-            matches.extend(quote!(||));
-        }
         matches.extend(quote!(<#no_lifetime_type as FromXml>::matches(id, field)));
-
-        if !variants.is_empty() {
-            variants.extend(quote!(else));
-        }
-
-        let v_ident = &variant.ident;
         variants.extend(
             quote!(if <#no_lifetime_type as FromXml>::matches(id, None) {
                 let mut value = None;
@@ -177,6 +220,31 @@ fn deserialize_forward_enum(
         );
     }
 
+    let fallback = match other {
+        Some(other) => {
+            // Any other element in the enum's namespace goes into the catch-all variant
+            let other_ns = quote!(id.ns == #default_namespace);
+            matches = match matches.is_empty() {
+                true => other_ns.clone(),
+                false => quote!(#matches || #other_ns),
+            };
+            quote!(if #other_ns {
+                *into = Some(#other);
+                deserializer.ignore()?;
+            } else {
+                return Err(Error::UnexpectedTag(format!("{:?}", id)));
+            })
+        }
+        None => quote!({
+            return Err(Error::UnexpectedTag(format!("{:?}", id)));
+        }),
+    };
+
+    let variants = match variants.is_empty() {
+        true => fallback,
+        false => quote!(#variants else #fallback),
+    };
+
     let generics = meta.xml_generics(borrowed);
     let (xml_impl_generics, _, _) = generics.split_for_impl();
     let (_, ty_generics, where_clause) = input.generics.split_for_impl();
@@ -194,12 +262,10 @@ fn deserialize_forward_enum(
                 deserializer: &mut ::instant_xml::Deserializer<'cx, 'xml>,
             ) -> ::std::result::Result<(), ::instant_xml::Error> {
                 use ::instant_xml::de::Node;
-                use ::instant_xml::{Accumulate, Error, FromXml};
+                use ::instant_xml::{Accumulate, Error, FromXml, Kind};
 
-                let id = deserializer.parent();
-                #variants else {
-                    return Err(Error::UnexpectedTag(format!("{:?}", id)));
-                };
+                let id = deserializer.parent()?;
+                #variants
 
                 if let Some(_) = deserializer.next() {
                     return Err(Error::UnexpectedState("unexpected node after wrapped enum variant"));
@@ -214,18 +280,191 @@ fn deserialize_forward_enum(
     )
 }
 
+fn deserialize_tagged_enum(
+    input: &syn::DeriveInput,
+    data: &syn::DataEnum,
+    meta: ContainerMeta,
+) -> TokenStream {
+    let ident = &input.ident;
+    let type_attr = meta.type_attr.as_ref().unwrap();
+    let type_name = type_attr.name();
+    let default_namespace = meta.default_namespace();
+    let (type_id, value) = match type_attr {
+        TypeAttr::Xsi => (
+            quote!(::instant_xml::Id { ns: ::instant_xml::xsd::XSI, name: #type_name }),
+            // The value is a QName, which names a type in the enum's namespace
+            quote!(match deserializer.resolve_qname(&value)? {
+                id if id.ns == #default_namespace => Some(id.name),
+                _ => None,
+            }),
+        ),
+        TypeAttr::Attribute(_) => (
+            quote!(::instant_xml::Id { ns: "", name: #type_name }),
+            quote!(Some(&*value)),
+        ),
+    };
+
+    let mut variants = TokenStream::new();
+    let mut borrowed = BTreeSet::new();
+    let empty = syn::FieldsNamed {
+        brace_token: Default::default(),
+        named: Default::default(),
+    };
+
+    for variant in data.variants.iter() {
+        let tag = match variant_tag(variant) {
+            Ok(tag) => tag,
+            Err(err) => return err.to_compile_error(),
+        };
+
+        let fields = match &variant.fields {
+            syn::Fields::Named(fields) => fields,
+            syn::Fields::Unit => &empty,
+            syn::Fields::Unnamed(_) => {
+                return syn::Error::new(
+                    variant.span(),
+                    "tagged enum variants must be unit variants or have named fields",
+                )
+                .to_compile_error()
+            }
+        };
+
+        let v_ident = &variant.ident;
+        let body = match deserialize_fields(
+            fields,
+            &meta,
+            v_ident,
+            quote!(Self::#v_ident),
+            &mut borrowed,
+        ) {
+            Ok(body) => body,
+            Err(err) => return err.into_compile_error(),
+        };
+
+        variants.extend(quote!(Some(#tag) => { #body }));
+    }
+
+    let name = meta.tag();
+    let missing = format!("{ident}::{}", type_name.to_string().trim_matches('"'));
+    let generics = meta.xml_generics(borrowed);
+    let (xml_impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote!(
+        impl #xml_impl_generics FromXml<'xml> for #ident #ty_generics #where_clause {
+            #[inline]
+            fn matches(id: ::instant_xml::Id<'_>, field: Option<::instant_xml::Id<'_>>) -> bool {
+                id.name == #name && ::instant_xml::de::ns_matches(id.ns, #default_namespace)
+            }
+
+            fn deserialize<'cx>(
+                into: &mut Self::Accumulator,
+                field: &'static str,
+                deserializer: &mut ::instant_xml::Deserializer<'cx, 'xml>,
+            ) -> ::std::result::Result<(), ::instant_xml::Error> {
+                use ::instant_xml::de::Node;
+                use ::instant_xml::{Accumulate, Error, FromXml, Kind};
+
+                let value = match deserializer.take_attribute(#type_id)? {
+                    Some(value) => value,
+                    None => return Err(Error::MissingValue(#missing)),
+                };
+
+                match #value {
+                    #variants
+                    _ => {
+                        return Err(Error::UnexpectedValue(format!(
+                            "unknown type `{}` for {}",
+                            value,
+                            stringify!(#ident)
+                        )))
+                    }
+                }
+
+                Ok(())
+            }
+
+            type Accumulator = Option<Self>;
+            const KIND: ::instant_xml::Kind = ::instant_xml::Kind::Element;
+        }
+    )
+}
+
 fn deserialize_struct(
     input: &syn::DeriveInput,
     fields: &syn::FieldsNamed,
     container_meta: ContainerMeta,
 ) -> TokenStream {
-    let mut namespaces_map = quote!(let mut namespaces_map = std::collections::HashMap::new(););
-    for (k, v) in container_meta.ns.prefixes.iter() {
-        namespaces_map.extend(quote!(
-            namespaces_map.insert(#k, #v);
-        ))
-    }
+    let ident = &input.ident;
+    let mut borrowed = BTreeSet::new();
+    let body = match deserialize_fields(fields, &container_meta, ident, quote!(Self), &mut borrowed)
+    {
+        Ok(body) => body,
+        Err(err) => return err.into_compile_error(),
+    };
 
+    let default_namespace = container_meta.default_namespace();
+    let matches = match container_meta.name_from {
+        // The element's local name is read into a field, so any name matches
+        Some(_) => quote!(::instant_xml::de::ns_matches(id.ns, #default_namespace)),
+        None => {
+            let name = container_meta.tag();
+            quote!(id.name == #name && ::instant_xml::de::ns_matches(id.ns, #default_namespace))
+        }
+    };
+
+    let generics = container_meta.xml_generics(borrowed);
+
+    let (xml_impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // Errors were reported for the struct's own impl already
+    let flatten = match flattenable(fields, &container_meta) {
+        true => flatten_impl(input, fields, &container_meta, false).unwrap_or_default(),
+        false => TokenStream::new(),
+    };
+
+    quote!(
+        #flatten
+
+        impl #xml_impl_generics FromXml<'xml> for #ident #ty_generics #where_clause {
+            #[inline]
+            fn matches(id: ::instant_xml::Id<'_>, field: Option<::instant_xml::Id<'_>>) -> bool {
+                #matches
+            }
+
+            fn deserialize<'cx>(
+                into: &mut Self::Accumulator,
+                field: &'static str,
+                deserializer: &mut ::instant_xml::Deserializer<'cx, 'xml>,
+            ) -> ::std::result::Result<(), ::instant_xml::Error> {
+                use ::instant_xml::de::Node;
+                use ::instant_xml::{Accumulate, Error, FromXml, Kind};
+
+                if deserializer.nil()? {
+                    return Err(Error::UnexpectedNil);
+                }
+
+                #body
+                Ok(())
+            }
+
+            type Accumulator = Option<Self>;
+            const KIND: ::instant_xml::Kind = ::instant_xml::Kind::Element;
+        }
+    )
+}
+
+/// Generate code deserializing the content of an element into the named `fields`
+///
+/// The generated code stores the value built with `path` (like `Self`) into `into`.
+fn deserialize_fields(
+    fields: &syn::FieldsNamed,
+    container_meta: &ContainerMeta,
+    type_name: &Ident,
+    path: TokenStream,
+    borrowed: &mut BTreeSet<syn::Lifetime>,
+) -> Result<TokenStream, syn::Error> {
     // Varying values
     let mut elements_tokens = Tokens::default();
     let mut attributes_tokens = Tokens::default();
@@ -235,144 +474,285 @@ fn deserialize_struct(
     let mut return_val = TokenStream::new();
     let mut direct = TokenStream::new();
     let mut after_loop = TokenStream::new();
+    let mut any = None;
+    let mut any_attribute = None;
 
-    let mut borrowed = BTreeSet::new();
     for (index, field) in fields.named.iter().enumerate() {
         if !direct.is_empty() {
-            return syn::Error::new(field.span(), "direct field must be the last")
-                .into_compile_error();
+            return Err(syn::Error::new(
+                field.span(),
+                "direct field must be the last",
+            ));
         }
 
-        let field_meta = match FieldMeta::from_field(field, &container_meta) {
-            Ok(meta) => meta,
-            Err(err) => return err.into_compile_error(),
-        };
+        if is_phantom_data(&field.ty) {
+            let field_name = field.ident.as_ref().unwrap();
+            return_val.extend(quote!(#field_name: ::core::marker::PhantomData,));
+            continue;
+        } else if field.ident.is_some() && field.ident == container_meta.name_from {
+            let field_name = field.ident.as_ref().unwrap();
+            return_val.extend(quote!(
+                #field_name: ::core::convert::From::from(deserializer.parent()?.name),
+            ));
+            continue;
+        }
+
+        let field_meta = FieldMeta::from_field(field, container_meta)?;
+        if field_meta.mixed {
+            if any.is_some() {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "only one field can collect unknown elements",
+                ));
+            }
+
+            let field_name = field.ident.as_ref().unwrap();
+            let val_name = Ident::new(&format!("__value{index}"), Span::call_site());
+            let field_str = format!("{type_name}::{field_name}");
+            declare_values.extend(quote!(
+                let mut #val_name = ::core::default::Default::default();
+            ));
+
+            any = Some(quote!(
+                let mut nested = deserializer.nested(data);
+                let mut element = None;
+                <::instant_xml::Element as FromXml>::deserialize(&mut element, #field_str, &mut nested)?;
+                ::core::iter::Extend::extend(
+                    &mut #val_name,
+                    element.map(::instant_xml::dom::Node::Element),
+                );
+            ));
+
+            direct.extend(quote!(
+                Node::Text(text) => {
+                    ::core::iter::Extend::extend(
+                        &mut #val_name,
+                        ::core::iter::once(::instant_xml::dom::Node::Text(text.into_owned())),
+                    );
+                }
+            ));
+
+            return_val.extend(quote!(#field_name: #val_name,));
+            continue;
+        }
+
+        if field_meta.any {
+            if any.is_some() {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "only one field can collect unknown elements",
+                ));
+            }
+
+            let field_name = field.ident.as_ref().unwrap();
+            let val_name = Ident::new(&format!("__value{index}"), Span::call_site());
+            let field_str = format!("{type_name}::{field_name}");
+            let mut no_lifetime_type = field.ty.clone();
+            discard_lifetimes(&mut no_lifetime_type, borrowed, field_meta.borrow, true);
+            declare_values.extend(quote!(
+                let mut #val_name = <#no_lifetime_type as FromXml>::Accumulator::default();
+            ));
+
+            any = Some(quote!(
+                let mut nested = deserializer.nested(data);
+                <#no_lifetime_type as FromXml>::deserialize(&mut #val_name, #field_str, &mut nested)?;
+            ));
+
+            let value = try_done(quote!(#val_name), &field_str, field_meta.default.as_ref());
+            return_val.extend(quote!(#field_name: #value,));
+            continue;
+        }
+
+        if field_meta.any_attribute {
+            if any_attribute.is_some() {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "only one field can collect unknown attributes",
+                ));
+            }
+
+            let field_name = field.ident.as_ref().unwrap();
+            let val_name = Ident::new(&format!("__value{index}"), Span::call_site());
+            declare_values.extend(quote!(
+                let mut #val_name = ::core::default::Default::default();
+            ));
+
+            any_attribute = Some(quote!(
+                ::core::iter::Extend::extend(
+                    &mut #val_name,
+                    ::core::iter::once((id.to_string(), attr.value.into_owned())),
+                );
+            ));
+
+            return_val.extend(quote!(#field_name: #val_name,));
+            continue;
+        }
+
+        if field_meta.flatten {
+            flatten_field(
+                field,
+                index,
+                &mut declare_values,
+                &mut return_val,
+                &mut elements_tokens,
+                &mut attributes_tokens,
+                borrowed,
+                field_meta,
+                type_name,
+            )?;
+
+            continue;
+        }
+
+        if field_meta.range.is_some() {
+            range_field(
+                field,
+                index,
+                &mut declare_values,
+                &mut return_val,
+                &mut elements_tokens,
+                borrowed,
+                field_meta,
+                type_name,
+                container_meta,
+            );
+
+            continue;
+        }
 
         let tokens = match field_meta.attribute {
             true => &mut attributes_tokens,
             false => &mut elements_tokens,
         };
 
-        let result = named_field(
+        named_field(
             field,
             index,
             &mut declare_values,
             &mut return_val,
             tokens,
-            &mut borrowed,
+            borrowed,
             &mut direct,
             field_meta,
-            &input.ident,
-            &container_meta,
+            type_name,
+            container_meta,
             &mut after_loop,
-        );
-
-        if let Err(err) = result {
-            return err.into_compile_error();
-        }
+        )?;
     }
 
     if direct.is_empty() {
-        direct.extend(quote!(Node::Text(_) => {
+        direct.extend(quote!(Node::Text(text) => {
             // no direct field, ignore
+            deserializer.skip_text(&text);
         }));
     }
 
     // Elements
+    let elements_branches = elements_tokens.dispatch(quote!(__Elements::__Ignore));
+    let elements_known = elements_tokens.known();
+    let ident_str = format!("{type_name}");
+    let ignore_element = match any {
+        Some(any) => quote!({ #any }),
+        // Unless collecting errors, `recover()` returns the error; otherwise it is recorded with
+        // the position of the unknown element, which is then skipped
+        None if container_meta.deny_unknown => quote!({
+            let error = Error::UnexpectedTag(format!("unknown element `{}` in {}", id, #ident_str));
+            let mut nested = deserializer.nested(data);
+            nested.recover(error, "")?;
+            nested.ignore()?;
+        }),
+        None => quote!({
+            deserializer.skip_element(id, &[#elements_known]);
+            let mut nested = deserializer.nested(data);
+            nested.ignore()?;
+        }),
+    };
+    let element_field = elements_tokens.field(quote!(element));
     let elements_enum = elements_tokens.r#enum;
-    let mut elements_branches = elements_tokens.branches;
     let elem_type_match = elements_tokens.r#match;
-    elements_branches.extend(match elements_branches.is_empty() {
-        true => quote!(__Elements::__Ignore),
-        false => quote!(else { __Elements::__Ignore }),
-    });
 
     // Attributes
+    let attributes_branches = attributes_tokens.dispatch(quote!(__Attributes::__Ignore));
+    let attributes_known = attributes_tokens.known();
+    let ignore_attribute = match any_attribute {
+        Some(any) => quote!({ #any }),
+        None if container_meta.deny_unknown => quote!({
+            return Err(Error::UnexpectedTag(
+                format!("unknown attribute `{}` in {}", id, #ident_str),
+            ));
+        }),
+        None => quote!({
+            deserializer.skip_attribute(id, &[#attributes_known]);
+        }),
+    };
+    let attribute_field = attributes_tokens.field(quote!(field));
     let attributes_enum = attributes_tokens.r#enum;
-    let mut attributes_branches = attributes_tokens.branches;
     let attr_type_match = attributes_tokens.r#match;
-    attributes_branches.extend(match attributes_branches.is_empty() {
-        true => quote!(__Attributes::__Ignore),
-        false => quote!(else { __Attributes::__Ignore }),
-    });
 
-    let ident = &input.ident;
-    let ident_str = format!("{ident}");
-    let name = container_meta.tag();
-    let default_namespace = container_meta.default_namespace();
-    let generics = container_meta.xml_generics(borrowed);
-
-    let (xml_impl_generics, _, _) = generics.split_for_impl();
-    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
-
-    quote!(
-        impl #xml_impl_generics FromXml<'xml> for #ident #ty_generics #where_clause {
-            #[inline]
-            fn matches(id: ::instant_xml::Id<'_>, field: Option<::instant_xml::Id<'_>>) -> bool {
-                id == ::instant_xml::Id { ns: #default_namespace, name: #name }
-            }
+    Ok(quote!(
+        enum __Elements {
+            #elements_enum
+            __Ignore,
+        }
 
-            fn deserialize<'cx>(
-                into: &mut Self::Accumulator,
-                field: &'static str,
-                deserializer: &mut ::instant_xml::Deserializer<'cx, 'xml>,
-            ) -> ::std::result::Result<(), ::instant_xml::Error> {
-                use ::instant_xml::de::Node;
-                use ::instant_xml::{Accumulate, Error, FromXml, Id, Kind};
+        enum __Attributes {
+            #attributes_enum
+            __Ignore,
+        }
 
-                enum __Elements {
-                    #elements_enum
-                    __Ignore,
-                }
+        #declare_values
+        loop {
+            let node = match deserializer.next() {
+                Some(result) => result?,
+                None => break,
+            };
+
+            match node {
+                Node::Attribute(attr) => {
+                    let id = deserializer.attribute_id(&attr)?;
+                    let field = #attributes_branches;
+                    let name = #attribute_field;
+
+                    // Errors go through `recover()`, which returns them unless collecting errors
+                    #[allow(unreachable_code)]
+                    let result = (|| -> ::std::result::Result<(), Error> {
+                        match field {
+                            #attr_type_match
+                            __Attributes::__Ignore => #ignore_attribute
+                        }
+                        Ok(())
+                    })();
 
-                enum __Attributes {
-                    #attributes_enum
-                    __Ignore,
+                    if let Err(error) = result {
+                        deserializer.recover(error, name)?;
+                    }
                 }
-
-                #declare_values
-                loop {
-                    let node = match deserializer.next() {
-                        Some(result) => result?,
-                        None => break,
-                    };
-
-                    match node {
-                        Node::Attribute(attr) => {
-                            let id = deserializer.attribute_id(&attr)?;
-                            let field = #attributes_branches;
-
-                            match field {
-                                #attr_type_match
-                                __Attributes::__Ignore => {}
-                            }
+                Node::Open(data) => {
+                    let id = deserializer.element_id(&data)?;
+                    let element = #elements_branches;
+                    let name = #element_field;
+
+                    #[allow(unreachable_code)]
+                    let result = (|| -> ::std::result::Result<(), Error> {
+                        match element {
+                            #elem_type_match
+                            __Elements::__Ignore => #ignore_element
                         }
-                        Node::Open(data) => {
-                            let id = deserializer.element_id(&data)?;
-                            let element = #elements_branches;
-
-                            match element {
-                                #elem_type_match
-                                __Elements::__Ignore => {
-                                    let mut nested = deserializer.nested(data);
-                                    nested.ignore()?;
-                                }
-                            }
-                        }
-                        #direct
-                        node => return Err(Error::UnexpectedNode(format!("{:?} in {}", node, #ident_str))),
+                        Ok(())
+                    })();
+
+                    if let Err(error) = result {
+                        deserializer.recover(error, name)?;
                     }
                 }
-                #after_loop
-
-                *into = Some(Self { #return_val });
-                Ok(())
+                #direct
+                node => return Err(Error::UnexpectedNode(format!("{:?} in {}", node, #ident_str))),
             }
-
-            type Accumulator = Option<Self>;
-            const KIND: ::instant_xml::Kind = ::instant_xml::Kind::Element;
         }
-    )
+        #after_loop
+
+        *into = Some(#path { #return_val });
+    ))
 }
 
 fn deserialize_inline_struct(
@@ -397,6 +777,22 @@ fn deserialize_inline_struct(
             .to_compile_error();
     }
 
+    match flatten_impl(input, fields, &meta, true) {
+        Ok(tokens) => tokens,
+        Err(err) => err.into_compile_error(),
+    }
+}
+
+/// Generate the `FromXmlFlatten` impl deserializing the `fields` from their parent's element
+///
+/// With `inline`, the struct has no element of its own and also gets a `FromXml` impl that
+/// shares its accumulator with the `FromXmlFlatten` impl.
+fn flatten_impl(
+    input: &syn::DeriveInput,
+    fields: &syn::FieldsNamed,
+    meta: &ContainerMeta,
+    inline: bool,
+) -> Result<TokenStream, syn::Error> {
     // Varying values
     let mut elements_tokens = Tokens::default();
 
@@ -411,22 +807,53 @@ fn deserialize_inline_struct(
     let mut acc_field_defs = TokenStream::new();
     let mut acc_field_inits = TokenStream::new();
     let mut deserialize = TokenStream::new();
+    let mut attr_matches = TokenStream::new();
+    let mut attr_deserialize = TokenStream::new();
     let mut acc_field_defaults = TokenStream::new();
     for (index, field) in fields.named.iter().enumerate() {
-        let field_meta = match FieldMeta::from_field(field, &meta) {
-            Ok(meta) => meta,
-            Err(err) => return err.into_compile_error(),
+        if is_phantom_data(&field.ty) {
+            // Keep the marker in the accumulator so its type parameters stay in use
+            let (field_name, field_ty) = (&field.ident, &field.ty);
+            acc_field_defs.extend(quote!(#field_name: #field_ty,));
+            acc_field_inits.extend(quote!(#field_name: ::core::marker::PhantomData,));
+            acc_field_defaults.extend(quote!(#field_name: ::core::marker::PhantomData,));
+            continue;
+        }
+
+        let field_meta = FieldMeta::from_field(field, meta)?;
+        let unsupported = if field_meta.direct {
+            Some("a direct field")
+        } else if field_meta.flatten {
+            Some("flattened fields")
+        } else if field_meta.with.is_some() {
+            Some("`with` fields")
+        } else if field_meta.range.is_some() {
+            Some("range fields")
+        } else if field_meta.raw {
+            Some("raw fields")
+        } else if field_meta.fixed.is_some() || field_meta.min.is_some() || field_meta.max.is_some()
+        {
+            Some("fixed values or occurrence constraints")
+        } else {
+            None
         };
 
-        if field_meta.direct {
-            return syn::Error::new(field.span(), "inline structs cannot have a direct field")
-                .to_compile_error();
-        } else if field_meta.attribute {
-            return syn::Error::new(field.span(), "inline structs cannot have attribute fields")
-                .to_compile_error();
+        if let Some(unsupported) = unsupported {
+            return Err(syn::Error::new(
+                field.span(),
+                format!("inline structs cannot have {unsupported}"),
+            ));
         }
 
-        let result = named_field(
+        let attribute = field_meta.attribute;
+        let tag = field_meta.tag.clone();
+        let ns = match (&field_meta.ns.uri, &meta.ns.uri) {
+            (Some(ns), _) => quote!(#ns),
+            (None, Some(ns)) if !attribute => quote!(#ns),
+            (None, _) => quote!(""),
+        };
+
+        let data = named_field(
             field,
             index,
             &mut declare_values,
@@ -436,22 +863,23 @@ fn deserialize_inline_struct(
             &mut direct,
             field_meta,
             &input.ident,
-            &meta,
+            meta,
             &mut after_loop,
-        );
+        )?;
 
-        let data = match result {
-            Ok(data) => data,
-            Err(err) => return err.into_compile_error(),
+        let field_ty = data.no_lifetime_type;
+        let (matches, deserialize) = match attribute {
+            true => (&mut attr_matches, &mut attr_deserialize),
+            false => (&mut matches, &mut deserialize),
         };
 
         if !matches.is_empty() {
             matches.extend(quote!(||));
         }
 
-        let field_ty = data.no_lifetime_type;
+        let field_id = quote!(Some(::instant_xml::Id { ns: #ns, name: #tag }));
         matches.extend(quote!(
-            <#field_ty as FromXml<'xml>>::matches(id, None)
+            <#field_ty as FromXml<'xml>>::matches(id, #field_id)
         ));
 
         let field_name = &field.ident;
@@ -459,7 +887,8 @@ fn deserialize_inline_struct(
         acc_field_defs
             .extend(quote!(#field_name: <#field_ty_with_lifetime as FromXml<'xml>>::Accumulator,));
         let field_str = format!("{}::{}", input.ident, data.field_name);
-        acc_field_inits.extend(quote!(#field_name: self.#field_name.try_done(#field_str)?,));
+        let value = try_done(quote!(self.#field_name), &field_str, data.default.as_ref());
+        acc_field_inits.extend(quote!(#field_name: #value,));
         acc_field_defaults.extend(quote!(#field_name: Default::default(),));
 
         if !deserialize.is_empty() {
@@ -467,18 +896,24 @@ fn deserialize_inline_struct(
         }
         if let Some(with) = data.deserialize_with {
             deserialize.extend(
-                quote!(if <#field_ty as FromXml<'xml>>::matches(current, None) {
+                quote!(if <#field_ty as FromXml<'xml>>::matches(current, #field_id) {
                     #with(&mut into.#field_name, #field_str, deserializer)?;
                 }),
             );
+        } else if attribute {
+            deserialize.extend(
+                quote!(if <#field_ty as FromXml<'xml>>::matches(current, #field_id) {
+                    <#field_ty as FromXml<'xml>>::deserialize(&mut into.#field_name, #field_str, deserializer)?;
+                }),
+            );
         } else {
-            deserialize.extend(quote!(if <#field_ty as FromXml<'xml>>::matches(current, None) {
+            deserialize.extend(quote!(if <#field_ty as FromXml<'xml>>::matches(current, #field_id) {
                 match <#field_ty as FromXml>::KIND {
                     Kind::Element => {
-                        <#field_ty>::deserialize(&mut into.#field_name, #field_str, deserializer)?;
+                        <#field_ty as FromXml<'xml>>::deserialize(&mut into.#field_name, #field_str, deserializer)?;
                     }
                     Kind::Scalar => {
-                        <#field_ty>::deserialize(&mut into.#field_name, #field_str, deserializer)?;
+                        <#field_ty as FromXml<'xml>>::deserialize(&mut into.#field_name, #field_str, deserializer)?;
                         deserializer.ignore()?;
                     }
                 }
@@ -493,33 +928,83 @@ fn deserialize_inline_struct(
 
     let (xml_impl_generics, xml_ty_generics, _) = generics.split_for_impl();
     let (_, ty_generics, where_clause) = input.generics.split_for_impl();
-    let visibility = &input.vis;
+    if matches.is_empty() {
+        matches.extend(quote!(false));
+    }
+    if attr_matches.is_empty() {
+        attr_matches.extend(quote!(false));
+    }
+
+    let from_xml = match inline {
+        true => quote!(
+            impl #xml_impl_generics FromXml<'xml> for #ident #ty_generics #where_clause {
+                #[inline]
+                fn matches(id: ::instant_xml::Id<'_>, _: Option<::instant_xml::Id<'_>>) -> bool {
+                    <Self as ::instant_xml::de::FromXmlFlatten<'xml>>::matches_element(id)
+                }
+
+                fn deserialize<'cx>(
+                    into: &mut Self::Accumulator,
+                    field: &'static str,
+                    deserializer: &mut ::instant_xml::Deserializer<'cx, 'xml>,
+                ) -> ::std::result::Result<(), ::instant_xml::Error> {
+                    <Self as ::instant_xml::de::FromXmlFlatten<'xml>>::deserialize_element(
+                        into,
+                        field,
+                        deserializer,
+                    )
+                }
+
+                type Accumulator = #accumulator #xml_ty_generics;
+                const KIND: ::instant_xml::Kind = ::instant_xml::Kind::Element;
+            }
+        ),
+        false => TokenStream::new(),
+    };
+
+    // Scoped to keep the accumulator out of the user's namespace; it has to be `pub` to be
+    // used as an associated type on Rust 1.61, but can't be named outside the block
+    Ok(quote!(const _: () = {
+        #from_xml
+
+        impl #xml_impl_generics ::instant_xml::de::FromXmlFlatten<'xml> for #ident #ty_generics #where_clause {
+            type Fields = #accumulator #xml_ty_generics;
 
-    quote!(
-        impl #xml_impl_generics FromXml<'xml> for #ident #ty_generics #where_clause {
             #[inline]
-            fn matches(id: ::instant_xml::Id<'_>, _: Option<::instant_xml::Id<'_>>) -> bool {
+            fn matches_element(id: ::instant_xml::Id<'_>) -> bool {
                 #matches
             }
 
-            fn deserialize<'cx>(
-                into: &mut Self::Accumulator,
+            fn deserialize_element<'cx>(
+                into: &mut Self::Fields,
                 _: &'static str,
                 deserializer: &mut ::instant_xml::Deserializer<'cx, 'xml>,
             ) -> ::std::result::Result<(), ::instant_xml::Error> {
                 use ::instant_xml::Kind;
 
-                let current = deserializer.parent();
+                let current = deserializer.parent()?;
                 #deserialize
 
                 Ok(())
             }
 
-            type Accumulator = #accumulator #xml_ty_generics;
-            const KIND: ::instant_xml::Kind = ::instant_xml::Kind::Element;
+            #[inline]
+            fn matches_attribute(id: ::instant_xml::Id<'_>) -> bool {
+                #attr_matches
+            }
+
+            fn deserialize_attribute<'cx>(
+                into: &mut Self::Fields,
+                current: ::instant_xml::Id<'_>,
+                _: &'static str,
+                deserializer: &mut ::instant_xml::Deserializer<'cx, 'xml>,
+            ) -> ::std::result::Result<(), ::instant_xml::Error> {
+                #attr_deserialize
+                Ok(())
+            }
         }
 
-        #visibility struct #accumulator #xml_ty_generics #where_clause {
+        pub struct #accumulator #xml_impl_generics #where_clause {
             #acc_field_defs
         }
 
@@ -538,7 +1023,81 @@ fn deserialize_inline_struct(
                 }
             }
         }
-    )
+    };))
+}
+
+fn deserialize_newtype(
+    input: &syn::DeriveInput,
+    field: &syn::Field,
+    meta: ContainerMeta,
+) -> TokenStream {
+    if !field.attrs.is_empty() {
+        return syn::Error::new(field.span(), "unnamed fields cannot have attributes")
+            .to_compile_error();
+    } else if !meta.ns.prefixes.is_empty() {
+        return syn::Error::new(
+            input.span(),
+            "transparent newtypes cannot have namespace prefixes",
+        )
+        .to_compile_error();
+    }
+
+    // Without a field name, a renamed newtype uses its own name for the element
+    let field_id = match meta.rename.is_some() || meta.ns.uri.is_some() {
+        true => {
+            let name = meta.tag();
+            let ns = meta.default_namespace();
+            quote!(field.or(Some(::instant_xml::Id { ns: #ns, name: #name })))
+        }
+        false => quote!(field),
+    };
+
+    let mut borrowed = BTreeSet::new();
+    let mut no_lifetime_type = field.ty.clone();
+    discard_lifetimes(&mut no_lifetime_type, &mut borrowed, false, true);
+
+    let ident = &input.ident;
+    let ty = &field.ty;
+    let accumulator = Ident::new(&format!("__{}Accumulator", ident), Span::call_site());
+    let generics = meta.xml_generics(borrowed);
+
+    let (xml_impl_generics, xml_ty_generics, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // Scoped like the accumulator in `flatten_impl()`
+    quote!(const _: () = {
+        impl #xml_impl_generics FromXml<'xml> for #ident #ty_generics #where_clause {
+            #[inline]
+            fn matches(id: ::instant_xml::Id<'_>, field: Option<::instant_xml::Id<'_>>) -> bool {
+                <#ty as FromXml<'xml>>::matches(id, #field_id)
+            }
+
+            fn deserialize<'cx>(
+                into: &mut Self::Accumulator,
+                field: &'static str,
+                deserializer: &mut ::instant_xml::Deserializer<'cx, 'xml>,
+            ) -> ::std::result::Result<(), ::instant_xml::Error> {
+                <#ty as FromXml<'xml>>::deserialize(&mut into.0, field, deserializer)
+            }
+
+            type Accumulator = #accumulator #xml_ty_generics;
+            const KIND: ::instant_xml::Kind = <#ty as FromXml<'xml>>::KIND;
+        }
+
+        pub struct #accumulator #xml_ty_generics (<#ty as FromXml<'xml>>::Accumulator) #where_clause;
+
+        impl #xml_impl_generics ::instant_xml::Accumulate<#ident #ty_generics> for #accumulator #xml_ty_generics #where_clause {
+            fn try_done(self, field: &'static str) -> ::std::result::Result<#ident #ty_generics, ::instant_xml::Error> {
+                Ok(#ident(self.0.try_done(field)?))
+            }
+        }
+
+        impl #xml_impl_generics Default for #accumulator #xml_ty_generics #where_clause {
+            fn default() -> Self {
+                Self(Default::default())
+            }
+        }
+    };)
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -564,38 +1123,197 @@ fn named_field<'a>(
     };
 
     let ns = match default_ns {
+        // Compare against the namespace of the node itself, to match its local name only
+        _ if field_meta.any_ns => quote!(id.ns),
         Some(Namespace::Path(path)) => quote!(#path),
         Some(Namespace::Literal(ns)) => quote!(#ns),
         None => quote!(""),
     };
 
+    if let Some(fixed) = &field_meta.fixed {
+        let enum_name = Ident::new(&format!("__Value{index}"), Span::call_site());
+        let field_str = format!("{type_name}::{field_name}");
+        let (variant, value) = match field_meta.attribute {
+            true => (quote!(__Attributes::#enum_name), quote!(attr.value)),
+            false => (
+                quote!(__Elements::#enum_name),
+                quote!({
+                    let mut nested = deserializer.nested(data);
+                    let value = nested.take_str()?.unwrap_or_default();
+                    nested.ignore()?;
+                    value
+                }),
+            ),
+        };
+
+        tokens.r#enum.extend(quote!(#enum_name,));
+        tokens.branches.push(Branch {
+            name: tag_name(&field_tag),
+            field: field_str.clone(),
+            condition: quote!(id == (::instant_xml::Id { ns: #ns, name: #field_tag })),
+            variant: variant.clone(),
+        });
+        tokens.r#match.extend(quote!(#variant => {
+            let value = #value;
+            if value != #fixed {
+                return Err(Error::UnexpectedValue(format!(
+                    "expected `{}` for {}, found `{}`",
+                    #fixed, #field_str, value
+                )));
+            }
+        },));
+
+        return_val.extend(quote!(#field_name: ::core::default::Default::default(),));
+        return Ok(FieldData {
+            field_name,
+            no_lifetime_type: field.ty.clone(),
+            deserialize_with: None,
+            default: None,
+        });
+    }
+
     if field_meta.borrow && field_meta.deserialize_with.is_none() {
-        if is_cow(&field.ty, is_str) {
+        let is_cow_str = |ty: &syn::Type| is_cow(ty, is_str);
+        if is_cow_str(&field.ty) {
             field_meta.deserialize_with =
                 Some(Literal::string("::instant_xml::de::borrow_cow_str"));
         } else if is_cow(&field.ty, is_slice_u8) {
             field_meta.deserialize_with =
                 Some(Literal::string("::instant_xml::de::borrow_cow_slice_u8"));
+        } else if is_wrapped(&field.ty, "Option", is_cow_str) {
+            field_meta.deserialize_with =
+                Some(Literal::string("::instant_xml::de::borrow_option_cow_str"));
+        } else if is_wrapped(&field.ty, "Vec", is_cow_str) {
+            field_meta.deserialize_with =
+                Some(Literal::string("::instant_xml::de::borrow_vec_cow_str"));
         }
     }
 
     let mut no_lifetime_type = field.ty.clone();
-    discard_lifetimes(&mut no_lifetime_type, borrowed, field_meta.borrow, true);
+    discard_lifetimes(
+        &mut no_lifetime_type,
+        borrowed,
+        field_meta.borrow || field_meta.raw,
+        true,
+    );
 
     let enum_name = Ident::new(&format!("__Value{index}"), Span::call_site());
-    if !field_meta.direct {
+    if field_meta.raw {
+        if field_meta.direct {
+            return Err(syn::Error::new(
+                field.span(),
+                "raw direct fields are not supported on deserialization",
+            ));
+        }
+
+        let val_name = Ident::new(&format!("__value{index}"), Span::call_site());
+        let field_str = format!("{type_name}::{field_name}");
+        declare_values.extend(quote!(
+            let mut #val_name: Option<#no_lifetime_type> = None;
+        ));
+
         tokens.r#enum.extend(quote!(#enum_name,));
+        tokens.branches.push(Branch {
+            name: tag_name(&field_tag),
+            field: field_str.clone(),
+            condition: quote!(id == (::instant_xml::Id { ns: #ns, name: #field_tag })),
+            variant: quote!(__Elements::#enum_name),
+        });
+        tokens.r#match.extend(quote!(__Elements::#enum_name => {
+            let mut nested = deserializer.nested(data);
+            ::instant_xml::de::raw(&mut #val_name, #field_str, &mut nested)?;
+        },));
+
+        let value = try_done(quote!(#val_name), &field_str, field_meta.default.as_ref());
+        return_val.extend(quote!(#field_name: #value,));
+        return Ok(FieldData {
+            field_name,
+            no_lifetime_type,
+            deserialize_with: None,
+            default: field_meta.default,
+        });
+    }
 
-        if !tokens.branches.is_empty() {
-            tokens.branches.extend(quote!(else));
-        }
-        tokens.branches.extend(quote!(
-            if <#no_lifetime_type as FromXml>::matches(id, Some(Id { ns: #ns, name: #field_tag }))
+    let preserve_space = match field_meta.preserve_space {
+        true => quote!(nested.preserve_space();),
+        false => TokenStream::new(),
+    };
+
+    if let Some(with) = &field_meta.with {
+        let val_name = Ident::new(&format!("__value{index}"), Span::call_site());
+        let field_str = format!("{type_name}::{field_name}");
+        declare_values.extend(quote!(
+            let mut #val_name: Option<#no_lifetime_type> = None;
         ));
 
-        tokens.branches.extend(match field_meta.attribute {
-            true => quote!({ __Attributes::#enum_name }),
-            false => quote!({ __Elements::#enum_name }),
+        let (variant, deserialize) = match field_meta.attribute {
+            true => (
+                quote!(__Attributes::#enum_name),
+                quote!(
+                    let mut nested = deserializer.for_node(Node::AttributeValue(attr.value));
+                    #with::deserialize(&mut #val_name, #field_str, &mut nested)?;
+                ),
+            ),
+            false => (
+                quote!(__Elements::#enum_name),
+                quote!(
+                    let mut nested = deserializer.nested(data);
+                    #preserve_space
+                    #with::deserialize(&mut #val_name, #field_str, &mut nested)?;
+                    nested.ignore()?;
+                ),
+            ),
+        };
+
+        tokens.r#enum.extend(quote!(#enum_name,));
+        tokens.branches.push(Branch {
+            name: tag_name(&field_tag),
+            field: field_str.clone(),
+            condition: quote!(id == (::instant_xml::Id { ns: #ns, name: #field_tag })),
+            variant: variant.clone(),
+        });
+        tokens.r#match.extend(quote!(#variant => { #deserialize },));
+
+        let value = try_done(quote!(#val_name), &field_str, field_meta.default.as_ref());
+        return_val.extend(quote!(
+            #field_name: #value,
+        ));
+
+        return Ok(FieldData {
+            field_name,
+            no_lifetime_type,
+            deserialize_with: None,
+            default: field_meta.default,
+        });
+    }
+
+    if let Some(wrapper) = &field_meta.wrapper {
+        tokens.r#enum.extend(quote!(#enum_name,));
+        tokens.branches.push(Branch {
+            name: tag_name(&quote!(#wrapper)),
+            field: format!("{type_name}::{field_name}"),
+            condition: quote!(id == (::instant_xml::Id { ns: #ns, name: #wrapper })),
+            variant: quote!(__Elements::#enum_name),
+        });
+    } else if !field_meta.direct {
+        tokens.r#enum.extend(quote!(#enum_name,));
+        tokens.branches.push(Branch {
+            name: tag_name(&field_tag),
+            field: format!("{type_name}::{field_name}"),
+            condition: match field_meta.any_ns {
+                // Pass the wildcard through, so nested types ignore their own namespace as well
+                true => quote!(<#no_lifetime_type as FromXml>::matches(
+                    ::instant_xml::Id { ns: ::instant_xml::de::ANY_NS, name: id.name },
+                    Some(::instant_xml::Id { ns: ::instant_xml::de::ANY_NS, name: #field_tag }),
+                )),
+                false => quote!(
+                    <#no_lifetime_type as FromXml>::matches(id, Some(::instant_xml::Id { ns: #ns, name: #field_tag }))
+                ),
+            },
+            variant: match field_meta.attribute {
+                true => quote!(__Attributes::#enum_name),
+                false => quote!(__Elements::#enum_name),
+            },
         });
     }
 
@@ -627,8 +1345,64 @@ fn named_field<'a>(
         .transpose()?;
 
     let field_str = format!("{type_name}::{field_name}");
+    if let Some(min) = field_meta.min {
+        after_loop.extend(quote!(
+            if #val_name.len() < #min {
+                return Err(Error::UnexpectedValue(format!(
+                    "too few elements for {}: expected at least {}, found {}",
+                    #field_str, #min, #val_name.len()
+                )));
+            }
+        ));
+    }
+
+    if let Some(max) = field_meta.max {
+        after_loop.extend(quote!(
+            if #val_name.len() > #max {
+                return Err(Error::UnexpectedValue(format!(
+                    "too many elements for {}: expected at most {}, found {}",
+                    #field_str, #max, #val_name.len()
+                )));
+            }
+        ));
+    }
+
     if !field_meta.attribute {
-        if let Some(with) = &deserialize_with {
+        if field_meta.wrapper.is_some() {
+            let deserialize = match &deserialize_with {
+                Some(with) => quote!(#with(&mut #val_name, #field_str, &mut nested)?;),
+                None => quote!(
+                    <#no_lifetime_type as FromXml>::deserialize(&mut #val_name, #field_str, &mut nested)?;
+                ),
+            };
+
+            // Items are read from the children of the wrapper element; anything else is ignored
+            tokens.r#match.extend(quote!(
+                __Elements::#enum_name => {
+                    let mut wrapper = deserializer.nested(data);
+                    while let Some(node) = wrapper.next() {
+                        let data = match node? {
+                            Node::Open(data) => data,
+                            _ => continue,
+                        };
+
+                        let id = wrapper.element_id(&data)?;
+                        if !<#no_lifetime_type as FromXml>::matches(id, Some(::instant_xml::Id { ns: #ns, name: #field_tag })) {
+                            wrapper.skip_element(id, &[]);
+                            wrapper.nested(data).ignore()?;
+                            continue;
+                        }
+
+                        let mut nested = wrapper.nested(data);
+                        #preserve_space
+                        #deserialize
+                        if let Kind::Scalar = <#no_lifetime_type as FromXml>::KIND {
+                            nested.ignore()?;
+                        }
+                    }
+                },
+            ));
+        } else if let Some(with) = &deserialize_with {
             if field_meta.direct {
                 return Err(syn::Error::new(
                     field.span(),
@@ -639,6 +1413,7 @@ fn named_field<'a>(
             tokens.r#match.extend(quote!(
                 __Elements::#enum_name => {
                     let mut nested = deserializer.nested(data);
+                    #preserve_space
                     #with(&mut #val_name, #field_str, &mut nested)?;
                 },
             ));
@@ -647,7 +1422,8 @@ fn named_field<'a>(
                 Node::Text(text) => {
                     seen_direct = true;
                     let mut nested = deserializer.for_node(Node::Text(text));
-                    <#no_lifetime_type>::deserialize(&mut #val_name, #field_str, &mut nested)?;
+                    #preserve_space
+                    <#no_lifetime_type as FromXml>::deserialize(&mut #val_name, #field_str, &mut nested)?;
                 }
             ));
             // We can only enter this FromXml impl if the caller found the opening
@@ -657,7 +1433,7 @@ fn named_field<'a>(
             after_loop.extend(quote!(
                 if !seen_direct {
                     let mut nested = deserializer.for_node(Node::Text("".into()));
-                    <#no_lifetime_type>::deserialize(&mut #val_name, #field_str, &mut nested)?;
+                    <#no_lifetime_type as FromXml>::deserialize(&mut #val_name, #field_str, &mut nested)?;
                 }
             ));
         } else {
@@ -665,11 +1441,13 @@ fn named_field<'a>(
                 __Elements::#enum_name => match <#no_lifetime_type as FromXml>::KIND {
                     Kind::Element => {
                         let mut nested = deserializer.nested(data);
-                        <#no_lifetime_type>::deserialize(&mut #val_name, #field_str, &mut nested)?;
+                        #preserve_space
+                        <#no_lifetime_type as FromXml>::deserialize(&mut #val_name, #field_str, &mut nested)?;
                     }
                     Kind::Scalar => {
                         let mut nested = deserializer.nested(data);
-                        <#no_lifetime_type>::deserialize(&mut #val_name, #field_str, &mut nested)?;
+                        #preserve_space
+                        <#no_lifetime_type as FromXml>::deserialize(&mut #val_name, #field_str, &mut nested)?;
                         nested.ignore()?;
                     }
                 },
@@ -686,10 +1464,17 @@ fn named_field<'a>(
         if let Some(with) = &deserialize_with {
             tokens.r#match.extend(quote!(
                 __Attributes::#enum_name => {
-                    let mut nested = deserializer.nested(data);
+                    let mut nested = deserializer.for_node(Node::AttributeValue(attr.value));
                     #with(&mut #val_name, #field_str, &mut nested)?;
                 },
             ));
+        } else if let Some(null) = &field_meta.null {
+            tokens.r#match.extend(quote!(
+                __Attributes::#enum_name => if attr.value != #null {
+                    let mut nested = deserializer.for_node(Node::AttributeValue(attr.value));
+                    <#no_lifetime_type as FromXml>::deserialize(&mut #val_name, #field_str, &mut nested)?;
+                },
+            ));
         } else {
             tokens.r#match.extend(quote!(
                 __Attributes::#enum_name => {
@@ -700,21 +1485,202 @@ fn named_field<'a>(
         }
     };
 
+    let value = try_done(quote!(#val_name), &field_str, field_meta.default.as_ref());
     return_val.extend(quote!(
-        #field_name: #val_name.try_done(#field_str)?,
+        #field_name: #value,
     ));
 
     Ok(FieldData {
         field_name,
         no_lifetime_type,
         deserialize_with,
+        default: field_meta.default,
     })
 }
 
+#[allow(clippy::too_many_arguments)]
+fn flatten_field(
+    field: &syn::Field,
+    index: usize,
+    declare_values: &mut TokenStream,
+    return_val: &mut TokenStream,
+    elements: &mut Tokens,
+    attributes: &mut Tokens,
+    borrowed: &mut BTreeSet<syn::Lifetime>,
+    field_meta: FieldMeta,
+    type_name: &Ident,
+) -> Result<(), syn::Error> {
+    if field_meta.attribute || field_meta.direct || field_meta.deserialize_with.is_some() {
+        return Err(syn::Error::new(
+            field.span(),
+            "flatten cannot be combined with attribute, direct or deserialize_with",
+        ));
+    }
+
+    let field_name = field.ident.as_ref().unwrap();
+    let mut no_lifetime_type = field.ty.clone();
+    discard_lifetimes(&mut no_lifetime_type, borrowed, field_meta.borrow, true);
+
+    let enum_name = Ident::new(&format!("__Value{index}"), Span::call_site());
+    let val_name = Ident::new(&format!("__value{index}"), Span::call_site());
+    let seen = Ident::new(&format!("__seen{index}"), Span::call_site());
+    let field_str = format!("{type_name}::{field_name}");
+    declare_values.extend(quote!(
+        let mut #val_name = <#no_lifetime_type as ::instant_xml::de::FromXmlFlatten>::Fields::default();
+        let mut #seen = false;
+    ));
+
+    elements.r#enum.extend(quote!(#enum_name,));
+    elements.branches.push(Branch {
+        name: None,
+        field: field_str.clone(),
+        condition: quote!(
+            <#no_lifetime_type as ::instant_xml::de::FromXmlFlatten>::matches_element(id)
+        ),
+        variant: quote!(__Elements::#enum_name),
+    });
+    elements.r#match.extend(quote!(
+        __Elements::#enum_name => {
+            #seen = true;
+            let mut nested = deserializer.nested(data);
+            <#no_lifetime_type as ::instant_xml::de::FromXmlFlatten>::deserialize_element(
+                &mut #val_name,
+                #field_str,
+                &mut nested,
+            )?;
+        },
+    ));
+
+    attributes.r#enum.extend(quote!(#enum_name,));
+    attributes.branches.push(Branch {
+        name: None,
+        field: field_str.clone(),
+        condition: quote!(
+            <#no_lifetime_type as ::instant_xml::de::FromXmlFlatten>::matches_attribute(id)
+        ),
+        variant: quote!(__Attributes::#enum_name),
+    });
+    attributes.r#match.extend(quote!(
+        __Attributes::#enum_name => {
+            #seen = true;
+            let mut nested = deserializer.for_node(Node::AttributeValue(attr.value));
+            <#no_lifetime_type as ::instant_xml::de::FromXmlFlatten>::deserialize_attribute(
+                &mut #val_name,
+                id,
+                #field_str,
+                &mut nested,
+            )?;
+        },
+    ));
+
+    // The flattened fields report their own names when missing, so check for the field itself
+    let value = match &field_meta.default {
+        Some(default) => quote!(match #seen {
+            true => #val_name.try_done(#field_str)?,
+            false => #default,
+        }),
+        None => quote!(#val_name.try_done(#field_str)?),
+    };
+    return_val.extend(quote!(
+        #field_name: #value,
+    ));
+
+    Ok(())
+}
+
+/// Deserialize a field from a pair of sibling elements holding the bounds of a range
+#[allow(clippy::too_many_arguments)]
+fn range_field(
+    field: &syn::Field,
+    index: usize,
+    declare_values: &mut TokenStream,
+    return_val: &mut TokenStream,
+    elements: &mut Tokens,
+    borrowed: &mut BTreeSet<syn::Lifetime>,
+    field_meta: FieldMeta,
+    type_name: &Ident,
+    container_meta: &ContainerMeta,
+) {
+    let field_name = field.ident.as_ref().unwrap();
+    let mut no_lifetime_type = field.ty.clone();
+    discard_lifetimes(&mut no_lifetime_type, borrowed, field_meta.borrow, true);
+
+    let ns = match field_meta
+        .ns
+        .uri
+        .as_ref()
+        .or(container_meta.ns.uri.as_ref())
+    {
+        Some(ns) => quote!(#ns),
+        None => quote!(""),
+    };
+
+    let bound = quote!(<#no_lifetime_type as ::instant_xml::Interval>::Bound);
+    let (start, end) = field_meta.range.unwrap();
+    let mut values = Vec::with_capacity(2);
+    for (suffix, tag) in [("Start", start), ("End", end)] {
+        let enum_name = Ident::new(&format!("__Value{index}{suffix}"), Span::call_site());
+        let val_name = Ident::new(
+            &format!("__value{index}_{}", suffix.to_lowercase()),
+            Span::call_site(),
+        );
+        let field_str = format!("{type_name}::{field_name}::{tag}");
+
+        declare_values.extend(quote!(
+            let mut #val_name = <#bound as FromXml>::Accumulator::default();
+        ));
+
+        elements.r#enum.extend(quote!(#enum_name,));
+        elements.branches.push(Branch {
+            name: Some(tag.clone()),
+            field: field_str.clone(),
+            condition: quote!(
+                <#bound as FromXml>::matches(id, Some(::instant_xml::Id { ns: #ns, name: #tag }))
+            ),
+            variant: quote!(__Elements::#enum_name),
+        });
+        elements.r#match.extend(quote!(
+            __Elements::#enum_name => {
+                let mut nested = deserializer.nested(data);
+                <#bound as FromXml>::deserialize(&mut #val_name, #field_str, &mut nested)?;
+                if let Kind::Scalar = <#bound as FromXml>::KIND {
+                    nested.ignore()?;
+                }
+            },
+        ));
+
+        values.push(quote!(#val_name.try_done(#field_str)?));
+    }
+
+    return_val.extend(quote!(
+        #field_name: <#no_lifetime_type as ::instant_xml::Interval>::from_bounds(#(#values),*),
+    ));
+}
+
+/// Finalize an accumulator, falling back to the field's default (if any) when the value is missing
+fn try_done(
+    accumulator: TokenStream,
+    field_str: &str,
+    default: Option<&TokenStream>,
+) -> TokenStream {
+    match default {
+        Some(default) => quote!(
+            match #accumulator.try_done(#field_str) {
+                Ok(value) => value,
+                // Only if the field itself is missing, not a value nested in it
+                Err(::instant_xml::Error::MissingValue(missing)) if missing == #field_str => #default,
+                Err(err) => return Err(err),
+            }
+        ),
+        None => quote!(#accumulator.try_done(#field_str)?),
+    }
+}
+
 struct FieldData<'a> {
     field_name: &'a Ident,
     no_lifetime_type: syn::Type,
     deserialize_with: Option<syn::Path>,
+    default: Option<TokenStream>,
 }
 
 fn deserialize_tuple_struct(
@@ -764,7 +1730,7 @@ fn deserialize_tuple_struct(
         impl #xml_impl_generics FromXml<'xml> for #ident #ty_generics #where_clause {
             #[inline]
             fn matches(id: ::instant_xml::Id<'_>, field: Option<::instant_xml::Id<'_>>) -> bool {
-                id == ::instant_xml::Id { ns: #default_namespace, name: #name }
+                id.name == #name && ::instant_xml::de::ns_matches(id.ns, #default_namespace)
             }
 
             fn deserialize<'cx>(
@@ -773,7 +1739,7 @@ fn deserialize_tuple_struct(
                 deserializer: &mut ::instant_xml::Deserializer<'cx, 'xml>,
             ) -> ::std::result::Result<(), ::instant_xml::Error> {
                 use ::instant_xml::de::Node;
-                use ::instant_xml::{Accumulate, Error, FromXml, Id, Kind};
+                use ::instant_xml::{Accumulate, Error, FromXml, Kind};
 
                 #declare_values
                 deserializer.ignore()?;
@@ -797,6 +1763,11 @@ fn unnamed_field(
     borrowed: &mut BTreeSet<syn::Lifetime>,
     type_name: &Ident,
 ) {
+    if is_phantom_data(&field.ty) {
+        return_val.extend(quote!(::core::marker::PhantomData,));
+        return;
+    }
+
     let mut no_lifetime_type = field.ty.clone();
     discard_lifetimes(&mut no_lifetime_type, borrowed, false, true);
 
@@ -842,7 +1813,7 @@ fn deserialize_unit_struct(input: &syn::DeriveInput, meta: &ContainerMeta) -> To
         impl #xml_impl_generics FromXml<'xml> for #ident #ty_generics #where_clause {
             #[inline]
             fn matches(id: ::instant_xml::Id<'_>, field: Option<::instant_xml::Id<'_>>) -> bool {
-                id == ::instant_xml::Id { ns: #default_namespace, name: #name }
+                id.name == #name && ::instant_xml::de::ns_matches(id.ns, #default_namespace)
             }
 
             fn deserialize<'cx>(
@@ -891,6 +1862,26 @@ fn is_cow(ty: &syn::Type, elem: fn(&syn::Type) -> bool) -> bool {
         }
 }
 
+/// Whether `ty` is `wrapper<T>` (like `Option<T>`) for a `T` matching `inner`
+fn is_wrapped(ty: &syn::Type, wrapper: &str, inner: impl Fn(&syn::Type) -> bool) -> bool {
+    let seg = match ungroup(ty) {
+        syn::Type::Path(ty) => match ty.path.segments.last() {
+            Some(seg) => seg,
+            None => return false,
+        },
+        _ => return false,
+    };
+
+    match &seg.arguments {
+        syn::PathArguments::AngleBracketed(bracketed) => {
+            seg.ident == wrapper
+                && bracketed.args.len() == 1
+                && matches!(&bracketed.args[0], syn::GenericArgument::Type(arg) if inner(arg))
+        }
+        _ => false,
+    }
+}
+
 fn is_str(ty: &syn::Type) -> bool {
     is_primitive_type(ty, "str")
 }
@@ -916,6 +1907,13 @@ fn is_primitive_path(path: &syn::Path, primitive: &str) -> bool {
         && path.segments[0].arguments.is_empty()
 }
 
+/// Get the value of a tag given as a string literal
+fn tag_name(tag: &TokenStream) -> Option<String> {
+    syn::parse2::<syn::LitStr>(tag.clone())
+        .ok()
+        .map(|lit| lit.value())
+}
+
 pub fn ungroup(mut ty: &syn::Type) -> &syn::Type {
     while let syn::Type::Group(group) = ty {
         ty = &group.elem;
@@ -926,6 +1924,107 @@ pub fn ungroup(mut ty: &syn::Type) -> &syn::Type {
 #[derive(Default)]
 struct Tokens {
     r#enum: TokenStream,
-    branches: TokenStream,
+    branches: Vec<Branch>,
     r#match: TokenStream,
 }
+
+impl Tokens {
+    /// Generate an expression naming the field selected by the enum variant in `value`
+    fn field(&self, value: TokenStream) -> TokenStream {
+        let arms = self.branches.iter().map(|branch| {
+            let Branch { field, variant, .. } = branch;
+            quote!(#variant => #field,)
+        });
+
+        quote!(match &#value {
+            #(#arms)*
+            _ => "",
+        })
+    }
+
+    /// Generate an expression selecting the enum variant for the current `id`
+    ///
+    /// Fields with a tag known at compile time are looked up by name, dispatching on the name
+    /// length first, so wide structs don't compare every field name. This only covers the
+    /// leading run of such fields, so fields without a known tag (like `any` or flattened
+    /// fields) keep their priority over the fields declared after them. Everything else, or
+    /// fields whose type rejects the name, go through the remaining conditions in field order.
+    fn dispatch(&self, ignore: TokenStream) -> TokenStream {
+        let switched = self
+            .branches
+            .iter()
+            .take_while(|branch| branch.name.is_some())
+            .count();
+
+        let mut cascade = TokenStream::new();
+        for (index, branch) in self.branches.iter().enumerate() {
+            let Branch {
+                name,
+                condition,
+                variant,
+                ..
+            } = branch;
+            match name {
+                // Already tried in the length switch if the name matches
+                Some(name) if index < switched => {
+                    cascade.extend(quote!(if id.name != #name && #condition { #variant } else))
+                }
+                _ => cascade.extend(quote!(if #condition { #variant } else)),
+            }
+        }
+        cascade.extend(quote!({ #ignore }));
+
+        let mut lengths = BTreeMap::<usize, TokenStream>::new();
+        for branch in &self.branches[..switched] {
+            let Branch {
+                name,
+                condition,
+                variant,
+                ..
+            } = branch;
+            let name = name.as_ref().unwrap();
+            lengths
+                .entry(name.len())
+                .or_default()
+                .extend(quote!(#name if #condition => Some(#variant),));
+        }
+
+        if lengths.is_empty() {
+            return cascade;
+        }
+
+        let lengths = lengths.into_iter().map(|(len, arms)| {
+            quote!(#len => match id.name {
+                #arms
+                _ => None,
+            },)
+        });
+
+        quote!(match match id.name.len() {
+            #(#lengths)*
+            _ => None,
+        } {
+            Some(variant) => variant,
+            None => #cascade,
+        })
+    }
+
+    /// List the tags known at compile time, as a comma-separated list of string literals
+    fn known(&self) -> TokenStream {
+        let names = self
+            .branches
+            .iter()
+            .filter_map(|branch| branch.name.as_ref());
+        quote!(#(#names),*)
+    }
+}
+
+/// Selects `variant` when `condition` holds for the current `id`
+struct Branch {
+    /// The field's tag, if known at compile time
+    name: Option<String>,
+    /// The field's name as used in errors, like `Type::field`
+    field: String,
+    condition: TokenStream,
+    variant: TokenStream,
+}
@@ -3,7 +3,7 @@ extern crate proc_macro;
 use std::collections::BTreeSet;
 use std::mem;
 
-use proc_macro2::{Literal, Span, TokenStream};
+use proc_macro2::{Ident, Literal, Span, TokenStream};
 use quote::{quote, ToTokens};
 use syn::spanned::Spanned;
 use syn::{parse_macro_input, DeriveInput, Generics};
@@ -13,6 +13,7 @@ use case::RenameRule;
 mod de;
 mod meta;
 use meta::{meta_items, MetaItem, Namespace, NamespaceMeta};
+mod schema;
 mod ser;
 
 #[proc_macro_derive(ToXml, attributes(xml))]
@@ -27,23 +28,57 @@ pub fn from_xml(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     proc_macro::TokenStream::from(de::from_xml(&ast))
 }
 
+#[proc_macro_derive(Schema, attributes(xml))]
+pub fn schema(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+    schema::to_schema(&ast).into()
+}
+
 struct ContainerMeta<'input> {
     input: &'input DeriveInput,
     ns: NamespaceMeta,
     rename: Option<Literal>,
+    name_from: Option<Ident>,
     rename_all: RenameRule,
     mode: Option<Mode>,
+    type_attr: Option<TypeAttr>,
+    deny_unknown: bool,
+    borrow: bool,
+    skip_empty: bool,
+    soap_action: Option<Literal>,
 }
 
 impl<'input> ContainerMeta<'input> {
     fn from_derive(input: &'input syn::DeriveInput) -> Result<Self, syn::Error> {
         let mut ns = NamespaceMeta::default();
         let mut rename = Default::default();
+        let mut name_from = None;
         let mut rename_all = Default::default();
         let mut mode = None;
+        let mut type_attr = None;
+        let mut deny_unknown = false;
+        let mut borrow = false;
+        let mut skip_empty = false;
+        let mut soap_action = None;
 
         for (item, span) in meta_items(&input.attrs) {
             match item {
+                MetaItem::Borrow => borrow = true,
+                MetaItem::SkipEmpty => skip_empty = true,
+                MetaItem::DenyUnknown => deny_unknown = true,
+                MetaItem::SoapAction(lit) => match lit.to_string().starts_with('"') {
+                    true => soap_action = Some(lit),
+                    false => {
+                        return Err(syn::Error::new(
+                            span,
+                            "soap_action must be a string literal",
+                        ))
+                    }
+                },
+                MetaItem::NameFrom(lit) => {
+                    let name = lit.to_string();
+                    name_from = Some(Ident::new(name.trim_matches('"'), lit.span()));
+                }
                 MetaItem::Ns(namespace) => ns = namespace,
                 MetaItem::Rename(lit) => rename = Some(lit),
                 MetaItem::RenameAll(lit) => {
@@ -56,6 +91,12 @@ impl<'input> ContainerMeta<'input> {
                     None => mode = Some(new),
                     Some(_) => return Err(syn::Error::new(span, "cannot have two modes")),
                 },
+                MetaItem::Tag(lit) => {
+                    type_attr = Some(match lit {
+                        Some(lit) => TypeAttr::Attribute(lit),
+                        None => TypeAttr::Xsi,
+                    })
+                }
                 _ => {
                     return Err(syn::Error::new(
                         span,
@@ -65,27 +106,66 @@ impl<'input> ContainerMeta<'input> {
             }
         }
 
+        if type_attr.is_some() && (mode.is_some() || !matches!(input.data, syn::Data::Enum(_))) {
+            return Err(syn::Error::new(
+                input.span(),
+                "tag is only supported on enums without a mode",
+            ));
+        }
+
+        if let Some(name_from) = &name_from {
+            let fields = match &input.data {
+                syn::Data::Struct(syn::DataStruct {
+                    fields: syn::Fields::Named(fields),
+                    ..
+                }) if mode.is_none() && rename.is_none() => fields,
+                _ => {
+                    return Err(syn::Error::new(
+                        name_from.span(),
+                        "name_from is only supported on structs with named fields, without a mode or rename",
+                    ))
+                }
+            };
+
+            if !fields
+                .named
+                .iter()
+                .any(|field| field.ident.as_ref() == Some(name_from))
+            {
+                return Err(syn::Error::new(
+                    name_from.span(),
+                    format!("no field named `{name_from}`"),
+                ));
+            }
+        }
+
+        if deny_unknown && matches!(mode, Some(Mode::Scalar | Mode::Transparent)) {
+            return Err(syn::Error::new(
+                input.span(),
+                "deny_unknown is not supported on scalar or transparent types",
+            ));
+        }
+
         Ok(Self {
             input,
             ns,
             rename,
+            name_from,
             rename_all,
             mode,
+            type_attr,
+            deny_unknown,
+            borrow,
+            skip_empty,
+            soap_action,
         })
     }
 
     fn xml_generics(&self, borrowed: BTreeSet<syn::Lifetime>) -> Generics {
-        let mut xml_generics = self.input.generics.clone();
+        let mut xml_generics = bounded_generics(self.input, "::instant_xml::FromXml<'xml>");
         let mut xml = syn::LifetimeParam::new(syn::Lifetime::new("'xml", Span::call_site()));
         xml.bounds.extend(borrowed);
         xml_generics.params.push(xml.into());
-
-        for param in xml_generics.type_params_mut() {
-            param
-                .bounds
-                .push(syn::parse_str("::instant_xml::FromXml<'xml>").unwrap());
-        }
-
         xml_generics
     }
 
@@ -104,15 +184,51 @@ impl<'input> ContainerMeta<'input> {
     }
 }
 
+/// The attribute selecting the variant of a tagged enum
+enum TypeAttr {
+    /// `xsi:type`, with the type name as a (possibly prefixed) QName
+    Xsi,
+    /// An unqualified attribute with the given name
+    Attribute(Literal),
+}
+
+impl TypeAttr {
+    fn name(&self) -> TokenStream {
+        match self {
+            Self::Xsi => quote!("type"),
+            Self::Attribute(name) => quote!(#name),
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 struct FieldMeta {
+    any: bool,
+    any_attribute: bool,
+    any_ns: bool,
     attribute: bool,
     borrow: bool,
+    cdata: bool,
+    default: Option<TokenStream>,
     direct: bool,
+    fixed: Option<Literal>,
+    flatten: bool,
+    max: Option<usize>,
+    min: Option<usize>,
+    mixed: bool,
+    nil: bool,
     ns: NamespaceMeta,
+    null: Option<Literal>,
+    preserve_space: bool,
+    range: Option<(String, String)>,
+    raw: bool,
+    skip_empty: bool,
+    sorted: bool,
     tag: TokenStream,
     serialize_with: Option<Literal>,
     deserialize_with: Option<Literal>,
+    with: Option<TokenStream>,
+    wrapper: Option<Literal>,
 }
 
 impl FieldMeta {
@@ -123,30 +239,403 @@ impl FieldMeta {
                 .rename_all
                 .apply_to_field(field_name)
                 .into_token_stream(),
+            borrow: container.borrow,
             ..Default::default()
         };
 
+        let mut no_ns = false;
+        let mut keep_empty = false;
+        let mut adapter = None;
         for (item, span) in meta_items(&input.attrs) {
             match item {
+                MetaItem::Any => meta.any = true,
+                MetaItem::AnyAttribute => meta.any_attribute = true,
+                MetaItem::AnyNs => meta.any_ns = true,
                 MetaItem::Attribute => meta.attribute = true,
+                MetaItem::Base64 => {
+                    adapter = Some(("base64", quote!(::instant_xml::adapters::base64), span))
+                }
                 MetaItem::Borrow => meta.borrow = true,
+                MetaItem::Cdata => meta.cdata = true,
+                MetaItem::Default(None) => {
+                    meta.default = Some(quote!(::core::default::Default::default()))
+                }
+                MetaItem::Default(Some(lit)) => {
+                    let path = lit.to_string();
+                    let path =
+                        syn::parse_str::<syn::Path>(path.trim_matches('"')).map_err(|err| {
+                            syn::Error::new(
+                                lit.span(),
+                                format!("failed to parse default as path: {err}"),
+                            )
+                        })?;
+                    meta.default = Some(quote!(#path()));
+                }
                 MetaItem::Direct => meta.direct = true,
+                MetaItem::Fixed(lit) => meta.fixed = Some(lit),
+                MetaItem::Flatten => meta.flatten = true,
+                MetaItem::Hex => {
+                    adapter = Some(("hex", quote!(::instant_xml::adapters::hex), span))
+                }
+                MetaItem::List => {
+                    adapter = Some(("list", quote!(::instant_xml::adapters::list), span))
+                }
+                MetaItem::Max(lit) => meta.max = Some(occurrences(&lit)?),
+                MetaItem::Min(lit) => meta.min = Some(occurrences(&lit)?),
+                MetaItem::Mixed => meta.mixed = true,
+                MetaItem::Nil => meta.nil = true,
+                MetaItem::NoNs => no_ns = true,
                 MetaItem::Ns(ns) => meta.ns = ns,
+                MetaItem::Null(lit) => meta.null = Some(lit),
+                MetaItem::PreserveSpace => meta.preserve_space = true,
+                MetaItem::Range(None) => meta.range = Some(("start".into(), "end".into())),
+                MetaItem::Range(Some(lit)) => meta.range = Some(range_names(&lit)?),
+                MetaItem::Raw => meta.raw = true,
                 MetaItem::Rename(lit) => meta.tag = quote!(#lit),
+                MetaItem::KeepEmpty => keep_empty = true,
+                MetaItem::SkipEmpty => meta.skip_empty = true,
+                MetaItem::Sorted => meta.sorted = true,
                 MetaItem::SerializeWith(lit) => meta.serialize_with = Some(lit),
                 MetaItem::DeserializeWith(lit) => meta.deserialize_with = Some(lit),
+                MetaItem::With(lit) => {
+                    let path = lit.to_string();
+                    let path =
+                        syn::parse_str::<syn::Path>(path.trim_matches('"')).map_err(|err| {
+                            syn::Error::new(
+                                lit.span(),
+                                format!("failed to parse with as path: {err}"),
+                            )
+                        })?;
+                    meta.with = Some(quote!(#path));
+                }
+                MetaItem::Wrapper(lit) => meta.wrapper = Some(lit),
                 MetaItem::RenameAll(_) => {
                     return Err(syn::Error::new(
                         span,
                         "attribute 'rename_all' invalid in field xml attribute",
                     ))
                 }
-                MetaItem::Mode(_) => {
+                MetaItem::DenyUnknown
+                | MetaItem::Mode(_)
+                | MetaItem::NameFrom(_)
+                | MetaItem::Other
+                | MetaItem::SoapAction(_)
+                | MetaItem::Tag(_) => {
                     return Err(syn::Error::new(span, "invalid attribute for struct field"));
                 }
             }
         }
 
+        if no_ns {
+            if meta.ns.uri.is_some() || !meta.ns.prefixes.is_empty() || meta.any_ns {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "no_ns cannot be combined with ns or any_ns",
+                ));
+            } else if meta.attribute {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "no_ns can only be used on element fields; attributes have no namespace by default",
+                ));
+            }
+
+            meta.ns.uri = Some(Namespace::Literal(Literal::string("")));
+        }
+
+        if meta.any_ns && meta.ns.uri.is_some() {
+            return Err(syn::Error::new(
+                input.span(),
+                "any_ns cannot be combined with a namespace",
+            ));
+        } else if meta.any_ns
+            && (meta.attribute
+                || meta.direct
+                || meta.flatten
+                || meta.any
+                || meta.any_attribute
+                || meta.mixed
+                || meta.range.is_some())
+        {
+            return Err(syn::Error::new(
+                input.span(),
+                "any_ns can only be used on element fields",
+            ));
+        }
+
+        // Empty collections only produce output as a list or in a wrapper, so the container's
+        // skip_empty is limited to those fields
+        let list = matches!(adapter, Some(("list", _, _)));
+        if keep_empty {
+            if meta.skip_empty || !container.skip_empty {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "keep_empty can only be used to override skip_empty on the container",
+                ));
+            }
+        } else if container.skip_empty && (list || meta.wrapper.is_some()) {
+            meta.skip_empty = true;
+        }
+
+        if let Some((adapter, module, span)) = adapter {
+            if meta.with.is_some() {
+                return Err(syn::Error::new(
+                    span,
+                    format!("{adapter} cannot be combined with with"),
+                ));
+            }
+
+            meta.with = Some(module);
+        }
+
+        if meta.with.is_some()
+            && (meta.serialize_with.is_some()
+                || meta.deserialize_with.is_some()
+                || meta.direct
+                || meta.flatten)
+        {
+            return Err(syn::Error::new(
+                input.span(),
+                "with cannot be combined with serialize_with, deserialize_with, direct or flatten",
+            ));
+        }
+
+        if meta.preserve_space && (meta.attribute || meta.flatten || meta.any_attribute) {
+            return Err(syn::Error::new(
+                input.span(),
+                "preserve_space can only be used on element and direct fields",
+            ));
+        }
+
+        if meta.wrapper.is_some()
+            && (meta.attribute
+                || meta.direct
+                || meta.flatten
+                || meta.any
+                || meta.any_attribute
+                || meta.mixed
+                || meta.cdata
+                || meta.raw
+                || meta.fixed.is_some()
+                || meta.range.is_some()
+                || meta.with.is_some()
+                || meta.serialize_with.is_some()
+                || meta.deserialize_with.is_some())
+        {
+            return Err(syn::Error::new(
+                input.span(),
+                "wrapper can only be used on element fields without other (de)serialization options",
+            ));
+        }
+
+        if meta.nil
+            && (meta.attribute
+                || meta.direct
+                || meta.flatten
+                || meta.any
+                || meta.any_attribute
+                || meta.mixed
+                || meta.cdata
+                || meta.raw
+                || meta.fixed.is_some()
+                || meta.range.is_some()
+                || meta.with.is_some()
+                || meta.serialize_with.is_some()
+                || meta.wrapper.is_some())
+        {
+            return Err(syn::Error::new(
+                input.span(),
+                "nil can only be used on element fields without other serialization options",
+            ));
+        }
+
+        if meta.skip_empty {
+            if meta.direct || meta.flatten || meta.any || meta.any_attribute || meta.mixed {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "skip_empty cannot be combined with direct, flatten, any or mixed",
+                ));
+            }
+
+            meta.default
+                .get_or_insert_with(|| quote!(::core::default::Default::default()));
+        }
+
+        if meta.min.is_some() || meta.max.is_some() {
+            if meta.attribute
+                || meta.direct
+                || meta.flatten
+                || meta.any_attribute
+                || meta.with.is_some()
+                || meta.deserialize_with.is_some()
+            {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "min and max can only be used on collections of elements",
+                ));
+            } else if let (Some(min), Some(max)) = (meta.min, meta.max) {
+                if min > max {
+                    return Err(syn::Error::new(input.span(), "min cannot exceed max"));
+                }
+            }
+        }
+
+        if let Some(fixed) = &meta.fixed {
+            if !fixed.to_string().starts_with('"') {
+                return Err(syn::Error::new(
+                    fixed.span(),
+                    "fixed value must be a string literal",
+                ));
+            } else if meta.any
+                || meta.any_attribute
+                || meta.direct
+                || meta.flatten
+                || meta.default.is_some()
+                || meta.null.is_some()
+                || meta.with.is_some()
+                || meta.serialize_with.is_some()
+                || meta.deserialize_with.is_some()
+            {
+                return Err(syn::Error::new(
+                    fixed.span(),
+                    "fixed can only be combined with attribute, ns and rename",
+                ));
+            }
+        }
+
+        if meta.range.is_some()
+            && (meta.any
+                || meta.any_attribute
+                || meta.attribute
+                || meta.default.is_some()
+                || meta.direct
+                || meta.fixed.is_some()
+                || meta.flatten
+                || meta.max.is_some()
+                || meta.min.is_some()
+                || meta.null.is_some()
+                || meta.with.is_some()
+                || meta.serialize_with.is_some()
+                || meta.deserialize_with.is_some())
+        {
+            return Err(syn::Error::new(
+                input.span(),
+                "range can only be combined with ns",
+            ));
+        }
+
+        if meta.cdata
+            && (meta.any
+                || meta.any_attribute
+                || meta.attribute
+                || meta.fixed.is_some()
+                || meta.flatten
+                || meta.mixed
+                || meta.null.is_some()
+                || meta.range.is_some()
+                || meta.raw
+                || meta.with.is_some()
+                || meta.serialize_with.is_some())
+        {
+            return Err(syn::Error::new(
+                input.span(),
+                "cdata can only be used on element or direct text fields",
+            ));
+        }
+
+        if meta.raw
+            && (meta.any
+                || meta.any_attribute
+                || meta.attribute
+                || meta.fixed.is_some()
+                || meta.flatten
+                || meta.null.is_some()
+                || meta.with.is_some()
+                || meta.serialize_with.is_some()
+                || meta.deserialize_with.is_some())
+        {
+            return Err(syn::Error::new(
+                input.span(),
+                "raw can only be combined with borrow, direct, default, ns and rename",
+            ));
+        }
+
+        if meta.mixed {
+            if meta.any
+                || meta.any_attribute
+                || meta.attribute
+                || meta.direct
+                || meta.fixed.is_some()
+                || meta.flatten
+                || meta.max.is_some()
+                || meta.min.is_some()
+                || meta.null.is_some()
+                || meta.range.is_some()
+                || meta.raw
+                || meta.with.is_some()
+                || meta.serialize_with.is_some()
+                || meta.deserialize_with.is_some()
+            {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "mixed cannot be combined with other field attributes except default",
+                ));
+            } else if container.mode == Some(Mode::Transparent) {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "inline structs cannot have mixed fields",
+                ));
+            }
+        }
+
+        if meta.sorted && !meta.any_attribute {
+            return Err(syn::Error::new(
+                input.span(),
+                "sorted can only be used on any_attribute fields",
+            ));
+        }
+
+        if meta.any || meta.any_attribute {
+            if meta.any && meta.any_attribute {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "any and any_attribute cannot be combined",
+                ));
+            } else if meta.attribute
+                || meta.direct
+                || meta.flatten
+                || meta.with.is_some()
+                || meta.serialize_with.is_some()
+                || meta.deserialize_with.is_some()
+            {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "any and any_attribute cannot be combined with other field attributes except default",
+                ));
+            } else if container.mode == Some(Mode::Transparent) {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "inline structs cannot have any or any_attribute fields",
+                ));
+            }
+        }
+
+        if let Some(null) = &meta.null {
+            if !meta.attribute {
+                return Err(syn::Error::new(
+                    null.span(),
+                    "null can only be used on attribute fields",
+                ));
+            } else if meta.with.is_some()
+                || meta.serialize_with.is_some()
+                || meta.deserialize_with.is_some()
+            {
+                return Err(syn::Error::new(
+                    null.span(),
+                    "null cannot be combined with with, serialize_with or deserialize_with",
+                ));
+            }
+        }
+
         Ok(meta)
     }
 }
@@ -224,6 +713,109 @@ impl VariantMeta {
     }
 }
 
+fn occurrences(lit: &Literal) -> Result<usize, syn::Error> {
+    lit.to_string()
+        .parse()
+        .map_err(|_| syn::Error::new(lit.span(), "expected an unsigned integer"))
+}
+
+/// Parse the element names of a range field, written as `"min..max"`
+fn range_names(lit: &Literal) -> Result<(String, String), syn::Error> {
+    let value = lit.to_string();
+    let names = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .and_then(|value| value.split_once(".."));
+
+    match names {
+        Some((start, end)) if !start.is_empty() && !end.is_empty() => {
+            Ok((start.to_owned(), end.to_owned()))
+        }
+        _ => Err(syn::Error::new(
+            lit.span(),
+            "expected range element names like \"min..max\"",
+        )),
+    }
+}
+
+/// Whether the fields of a struct with its own element can also be flattened into another struct
+///
+/// Flattened fields are matched by name among the parent's own nodes, so this rules out fields
+/// that rely on the struct having an element of its own.
+fn flattenable(fields: &syn::FieldsNamed, meta: &ContainerMeta) -> bool {
+    if !meta.ns.prefixes.is_empty() || meta.name_from.is_some() {
+        return false;
+    }
+
+    fields.named.iter().all(|field| {
+        if is_phantom_data(&field.ty) {
+            return true;
+        }
+
+        match FieldMeta::from_field(field, meta) {
+            Ok(meta) => {
+                !(meta.any
+                    || meta.any_attribute
+                    || meta.mixed
+                    || meta.direct
+                    || meta.flatten
+                    || meta.raw
+                    || meta.nil
+                    || meta.with.is_some()
+                    || meta.range.is_some()
+                    || meta.wrapper.is_some()
+                    || meta.fixed.is_some()
+                    || meta.min.is_some()
+                    || meta.max.is_some())
+            }
+            Err(_) => false,
+        }
+    })
+}
+
+/// Whether a newtype variant in a forward enum is the catch-all for unknown elements
+fn is_other(variant: &syn::Variant) -> Result<bool, syn::Error> {
+    let mut other = false;
+    for (item, span) in meta_items(&variant.attrs) {
+        match item {
+            MetaItem::Other => other = true,
+            _ => {
+                return Err(syn::Error::new(
+                    span,
+                    "only 'other' attribute is permitted on wrapped enum variants",
+                ))
+            }
+        }
+    }
+
+    match (&variant.fields, other) {
+        (syn::Fields::Unnamed(fields), true) if fields.unnamed.len() == 1 => Ok(true),
+        (_, true) => Err(syn::Error::new(
+            variant.span(),
+            "'other' variant must have 1 unnamed field to hold the element's name",
+        )),
+        _ => Ok(false),
+    }
+}
+
+/// The element name for a struct variant in a forward enum
+fn variant_tag(variant: &syn::Variant) -> Result<TokenStream, syn::Error> {
+    let mut tag = Literal::string(&variant.ident.to_string()).into_token_stream();
+    for (item, span) in meta_items(&variant.attrs) {
+        match item {
+            MetaItem::Rename(lit) => tag = lit.into_token_stream(),
+            _ => {
+                return Err(syn::Error::new(
+                    span,
+                    "only 'rename' attribute is permitted on struct variants",
+                ))
+            }
+        }
+    }
+
+    Ok(tag)
+}
+
 fn discard_lifetimes(
     ty: &mut syn::Type,
     borrowed: &mut BTreeSet<syn::Lifetime>,
@@ -296,6 +888,57 @@ fn discard_path_lifetimes(
     }
 }
 
+/// Clone the generics of `input`, adding `bound` to its type parameters
+///
+/// Type parameters that are only used in `PhantomData` fields are left alone, since those fields
+/// are never (de)serialized.
+fn bounded_generics(input: &DeriveInput, bound: &str) -> Generics {
+    let fields = match &input.data {
+        syn::Data::Struct(data) => data.fields.iter().collect(),
+        syn::Data::Enum(data) => data.variants.iter().flat_map(|v| &v.fields).collect(),
+        syn::Data::Union(_) => Vec::new(),
+    };
+
+    let mut used = BTreeSet::new();
+    for field in fields {
+        if !is_phantom_data(&field.ty) {
+            collect_idents(field.ty.to_token_stream(), &mut used);
+        }
+    }
+
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        if used.contains(&param.ident) {
+            param.bounds.push(syn::parse_str(bound).unwrap());
+        }
+    }
+
+    generics
+}
+
+fn collect_idents(tokens: TokenStream, idents: &mut BTreeSet<proc_macro2::Ident>) {
+    for tree in tokens {
+        match tree {
+            proc_macro2::TokenTree::Ident(ident) => {
+                idents.insert(ident);
+            }
+            proc_macro2::TokenTree::Group(group) => collect_idents(group.stream(), idents),
+            _ => {}
+        }
+    }
+}
+
+fn is_phantom_data(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Group(group) => is_phantom_data(&group.elem),
+        syn::Type::Path(ty) => match ty.path.segments.last() {
+            Some(seg) => ty.qself.is_none() && seg.ident == "PhantomData",
+            None => false,
+        },
+        _ => false,
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum Mode {
     Forward,
@@ -293,20 +293,81 @@ pub(crate) fn meta_items(attrs: &[syn::Attribute]) -> Vec<(MetaItem, Span)> {
         let span = tree.span();
         state = match (state, tree) {
             (MetaState::Start, TokenTree::Ident(id)) => {
-                if id == "attribute" {
+                if id == "any" {
+                    items.push((MetaItem::Any, span));
+                    MetaState::Comma
+                } else if id == "any_attribute" {
+                    items.push((MetaItem::AnyAttribute, span));
+                    MetaState::Comma
+                } else if id == "any_ns" {
+                    items.push((MetaItem::AnyNs, span));
+                    MetaState::Comma
+                } else if id == "attribute" {
                     items.push((MetaItem::Attribute, span));
                     MetaState::Comma
+                } else if id == "base64" {
+                    items.push((MetaItem::Base64, span));
+                    MetaState::Comma
                 } else if id == "borrow" {
                     items.push((MetaItem::Borrow, span));
                     MetaState::Comma
-                } else if id == "direct" {
+                } else if id == "cdata" {
+                    items.push((MetaItem::Cdata, span));
+                    MetaState::Comma
+                } else if id == "default" {
+                    MetaState::Default(span)
+                } else if id == "deny_unknown" {
+                    items.push((MetaItem::DenyUnknown, span));
+                    MetaState::Comma
+                } else if id == "direct" || id == "text" {
                     items.push((MetaItem::Direct, span));
                     MetaState::Comma
+                } else if id == "fixed" {
+                    MetaState::Fixed
+                } else if id == "flatten" {
+                    items.push((MetaItem::Flatten, span));
+                    MetaState::Comma
+                } else if id == "tag" {
+                    MetaState::Tag(span)
                 } else if id == "transparent" {
                     items.push((MetaItem::Mode(Mode::Transparent), span));
                     MetaState::Comma
+                } else if id == "hex" {
+                    items.push((MetaItem::Hex, span));
+                    MetaState::Comma
+                } else if id == "list" {
+                    items.push((MetaItem::List, span));
+                    MetaState::Comma
+                } else if id == "max" {
+                    MetaState::Max
+                } else if id == "min" {
+                    MetaState::Min
+                } else if id == "mixed" {
+                    items.push((MetaItem::Mixed, span));
+                    MetaState::Comma
+                } else if id == "no_ns" {
+                    items.push((MetaItem::NoNs, span));
+                    MetaState::Comma
                 } else if id == "ns" {
                     MetaState::Ns
+                } else if id == "name_from" {
+                    MetaState::NameFrom
+                } else if id == "nil" {
+                    items.push((MetaItem::Nil, span));
+                    MetaState::Comma
+                } else if id == "null" {
+                    MetaState::Null
+                } else if id == "other" {
+                    items.push((MetaItem::Other, span));
+                    MetaState::Comma
+                } else if id == "preserve_space" {
+                    items.push((MetaItem::PreserveSpace, span));
+                    MetaState::Comma
+                } else if id == "range" {
+                    MetaState::Range(span)
+                } else if id == "raw" {
+                    items.push((MetaItem::Raw, span));
+                    MetaState::Comma
                 } else if id == "rename" {
                     MetaState::Rename
                 } else if id == "rename_all" {
@@ -317,10 +378,25 @@ pub(crate) fn meta_items(attrs: &[syn::Attribute]) -> Vec<(MetaItem, Span)> {
                 } else if id == "scalar" {
                     items.push((MetaItem::Mode(Mode::Scalar), span));
                     MetaState::Comma
+                } else if id == "keep_empty" {
+                    items.push((MetaItem::KeepEmpty, span));
+                    MetaState::Comma
+                } else if id == "skip_empty" {
+                    items.push((MetaItem::SkipEmpty, span));
+                    MetaState::Comma
+                } else if id == "sorted" {
+                    items.push((MetaItem::Sorted, span));
+                    MetaState::Comma
+                } else if id == "soap_action" {
+                    MetaState::SoapAction
                 } else if id == "serialize_with" {
                     MetaState::SerializeWith
                 } else if id == "deserialize_with" {
                     MetaState::DeserializeWith
+                } else if id == "with" {
+                    MetaState::With
+                } else if id == "wrapper" {
+                    MetaState::Wrapper
                 } else {
                     panic!("unexpected key in xml attribute");
                 }
@@ -328,12 +404,80 @@ pub(crate) fn meta_items(attrs: &[syn::Attribute]) -> Vec<(MetaItem, Span)> {
             (MetaState::Comma, TokenTree::Punct(punct)) if punct.as_char() == ',' => {
                 MetaState::Start
             }
+            (MetaState::Default(span), TokenTree::Punct(punct)) if punct.as_char() == ',' => {
+                items.push((MetaItem::Default(None), span));
+                MetaState::Start
+            }
+            (MetaState::Default(_), TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                MetaState::DefaultValue
+            }
+            (MetaState::DefaultValue, TokenTree::Literal(lit)) => {
+                items.push((MetaItem::Default(Some(lit)), span));
+                MetaState::Comma
+            }
+            (MetaState::Tag(span), TokenTree::Punct(punct)) if punct.as_char() == ',' => {
+                items.push((MetaItem::Tag(None), span));
+                MetaState::Start
+            }
+            (MetaState::Tag(_), TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                MetaState::TagValue
+            }
+            (MetaState::TagValue, TokenTree::Literal(lit)) => {
+                items.push((MetaItem::Tag(Some(lit)), span));
+                MetaState::Comma
+            }
+            (MetaState::Range(span), TokenTree::Punct(punct)) if punct.as_char() == ',' => {
+                items.push((MetaItem::Range(None), span));
+                MetaState::Start
+            }
+            (MetaState::Range(_), TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                MetaState::RangeValue
+            }
+            (MetaState::RangeValue, TokenTree::Literal(lit)) => {
+                items.push((MetaItem::Range(Some(lit)), span));
+                MetaState::Comma
+            }
             (MetaState::Ns, TokenTree::Group(group))
                 if group.delimiter() == Delimiter::Parenthesis =>
             {
                 items.push((MetaItem::Ns(NamespaceMeta::from_tokens(group)), span));
                 MetaState::Comma
             }
+            (MetaState::Fixed, TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                MetaState::FixedValue
+            }
+            (MetaState::FixedValue, TokenTree::Literal(lit)) => {
+                items.push((MetaItem::Fixed(lit), span));
+                MetaState::Comma
+            }
+            (MetaState::Max, TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                MetaState::MaxValue
+            }
+            (MetaState::MaxValue, TokenTree::Literal(lit)) => {
+                items.push((MetaItem::Max(lit), span));
+                MetaState::Comma
+            }
+            (MetaState::Min, TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                MetaState::MinValue
+            }
+            (MetaState::MinValue, TokenTree::Literal(lit)) => {
+                items.push((MetaItem::Min(lit), span));
+                MetaState::Comma
+            }
+            (MetaState::NameFrom, TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                MetaState::NameFromValue
+            }
+            (MetaState::NameFromValue, TokenTree::Literal(lit)) => {
+                items.push((MetaItem::NameFrom(lit), span));
+                MetaState::Comma
+            }
+            (MetaState::Null, TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                MetaState::NullValue
+            }
+            (MetaState::NullValue, TokenTree::Literal(lit)) => {
+                items.push((MetaItem::Null(lit), span));
+                MetaState::Comma
+            }
             (MetaState::Rename, TokenTree::Punct(punct)) if punct.as_char() == '=' => {
                 MetaState::RenameValue
             }
@@ -341,6 +485,13 @@ pub(crate) fn meta_items(attrs: &[syn::Attribute]) -> Vec<(MetaItem, Span)> {
                 items.push((MetaItem::Rename(lit), span));
                 MetaState::Comma
             }
+            (MetaState::SoapAction, TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                MetaState::SoapActionValue
+            }
+            (MetaState::SoapActionValue, TokenTree::Literal(lit)) => {
+                items.push((MetaItem::SoapAction(lit), span));
+                MetaState::Comma
+            }
             (MetaState::RenameAll, TokenTree::Punct(punct)) if punct.as_char() == '=' => {
                 MetaState::RenameAllValue
             }
@@ -362,6 +513,20 @@ pub(crate) fn meta_items(attrs: &[syn::Attribute]) -> Vec<(MetaItem, Span)> {
                 items.push((MetaItem::DeserializeWith(lit), span));
                 MetaState::Comma
             }
+            (MetaState::With, TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                MetaState::WithValue
+            }
+            (MetaState::WithValue, TokenTree::Literal(lit)) => {
+                items.push((MetaItem::With(lit), span));
+                MetaState::Comma
+            }
+            (MetaState::Wrapper, TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                MetaState::WrapperValue
+            }
+            (MetaState::WrapperValue, TokenTree::Literal(lit)) => {
+                items.push((MetaItem::Wrapper(lit), span));
+                MetaState::Comma
+            }
             (state, tree) => {
                 panic!(
                     "invalid state transition while parsing xml attribute ({}, {tree})",
@@ -371,6 +536,13 @@ pub(crate) fn meta_items(attrs: &[syn::Attribute]) -> Vec<(MetaItem, Span)> {
         };
     }
 
+    match state {
+        MetaState::Default(span) => items.push((MetaItem::Default(None), span)),
+        MetaState::Range(span) => items.push((MetaItem::Range(None), span)),
+        MetaState::Tag(span) => items.push((MetaItem::Tag(None), span)),
+        _ => {}
+    }
+
     items
 }
 
@@ -378,15 +550,37 @@ pub(crate) fn meta_items(attrs: &[syn::Attribute]) -> Vec<(MetaItem, Span)> {
 enum MetaState {
     Start,
     Comma,
+    Default(Span),
+    DefaultValue,
+    Fixed,
+    FixedValue,
+    Max,
+    MaxValue,
+    Min,
+    MinValue,
+    NameFrom,
+    NameFromValue,
     Ns,
+    Null,
+    NullValue,
+    Range(Span),
+    RangeValue,
     Rename,
     RenameValue,
     RenameAll,
     RenameAllValue,
+    SoapAction,
+    SoapActionValue,
+    Tag(Span),
+    TagValue,
     SerializeWith,
     SerializeWithValue,
     DeserializeWith,
     DeserializeWithValue,
+    With,
+    WithValue,
+    Wrapper,
+    WrapperValue,
 }
 
 impl MetaState {
@@ -394,15 +588,37 @@ impl MetaState {
         match self {
             MetaState::Start => "Start",
             MetaState::Comma => "Comma",
+            MetaState::Default(_) => "Default",
+            MetaState::DefaultValue => "DefaultValue",
+            MetaState::Fixed => "Fixed",
+            MetaState::FixedValue => "FixedValue",
+            MetaState::Max => "Max",
+            MetaState::MaxValue => "MaxValue",
+            MetaState::Min => "Min",
+            MetaState::MinValue => "MinValue",
             MetaState::Ns => "Ns",
+            MetaState::NameFrom => "NameFrom",
+            MetaState::NameFromValue => "NameFromValue",
+            MetaState::Null => "Null",
+            MetaState::NullValue => "NullValue",
+            MetaState::Range(_) => "Range",
+            MetaState::RangeValue => "RangeValue",
             MetaState::Rename => "Rename",
             MetaState::RenameValue => "RenameValue",
             MetaState::RenameAll => "RenameAll",
             MetaState::RenameAllValue => "RenameAllValue",
+            MetaState::SoapAction => "SoapAction",
+            MetaState::SoapActionValue => "SoapActionValue",
+            MetaState::Tag(_) => "Tag",
+            MetaState::TagValue => "TagValue",
             MetaState::SerializeWith => "SerializeWith",
             MetaState::SerializeWithValue => "SerializeWithValue",
             MetaState::DeserializeWith => "DeserializeWith",
             MetaState::DeserializeWithValue => "DeserializeWithValue",
+            MetaState::With => "With",
+            MetaState::WithValue => "WithValue",
+            MetaState::Wrapper => "Wrapper",
+            MetaState::WrapperValue => "WrapperValue",
         }
     }
 }
@@ -485,13 +701,42 @@ impl fmt::Debug for Namespace {
 
 #[derive(Debug)]
 pub(crate) enum MetaItem {
+    Any,
+    AnyAttribute,
+    AnyNs,
     Attribute,
+    Base64,
     Borrow,
+    Cdata,
+    Default(Option<Literal>),
+    DenyUnknown,
     Direct,
+    Fixed(Literal),
+    Flatten,
+    Hex,
+    KeepEmpty,
+    List,
+    Max(Literal),
+    Min(Literal),
+    Mixed,
+    NameFrom(Literal),
+    Nil,
+    NoNs,
     Ns(NamespaceMeta),
+    Null(Literal),
+    Other,
+    PreserveSpace,
+    Range(Option<Literal>),
+    Raw,
     Rename(Literal),
     Mode(Mode),
     RenameAll(Literal),
+    SkipEmpty,
+    Sorted,
+    SoapAction(Literal),
+    Tag(Option<Literal>),
     SerializeWith(Literal),
     DeserializeWith(Literal),
+    With(Literal),
+    Wrapper(Literal),
 }
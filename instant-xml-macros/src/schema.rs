@@ -0,0 +1,357 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+
+use super::{
+    bounded_generics, is_other, is_phantom_data, variant_tag, ContainerMeta, FieldMeta, Mode,
+    Namespace, VariantMeta,
+};
+
+pub(crate) fn to_schema(input: &syn::DeriveInput) -> TokenStream {
+    let meta = match ContainerMeta::from_derive(input) {
+        Ok(meta) => meta,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    let body = match (&input.data, meta.mode) {
+        (syn::Data::Struct(data), None) if meta.name_from.is_none() => {
+            describe_struct(input, data, &meta)
+        }
+        (syn::Data::Enum(data), Some(Mode::Scalar)) => describe_scalar_enum(input, data, &meta),
+        (syn::Data::Enum(data), Some(Mode::Forward)) => describe_forward_enum(input, data, &meta),
+        _ => Err(syn::Error::new(
+            input.span(),
+            "Schema can only be derived for structs without a mode or name_from, and scalar or forward enums",
+        )),
+    };
+
+    let body = match body {
+        Ok(body) => body,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    let generics = bounded_generics(input, "::instant_xml::schema::Schema");
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ident = &input.ident;
+    quote!(
+        impl #impl_generics ::instant_xml::schema::Schema for #ident #ty_generics #where_clause {
+            #body
+        }
+    )
+}
+
+fn describe_struct(
+    input: &syn::DeriveInput,
+    data: &syn::DataStruct,
+    meta: &ContainerMeta,
+) -> Result<TokenStream, syn::Error> {
+    let definition = match &data.fields {
+        syn::Fields::Named(fields) => complex_type(&fields.named, meta)?,
+        syn::Fields::Unit => complex_type(None, meta)?,
+        syn::Fields::Unnamed(_) => {
+            return Err(syn::Error::new(
+                input.span(),
+                "Schema cannot be derived for tuple structs",
+            ))
+        }
+    };
+
+    let ns = meta.default_namespace();
+    let name = input.ident.to_string();
+    let tag = meta.tag();
+    Ok(quote!(
+        fn describe(schemas: &mut ::instant_xml::schema::Schemas) -> ::instant_xml::schema::Type {
+            schemas.define::<Self>(::instant_xml::Id { ns: #ns, name: #name }, |schemas| #definition)
+        }
+
+        fn particle(
+            schemas: &mut ::instant_xml::schema::Schemas,
+            _: ::instant_xml::Id<'static>,
+            occurs: ::instant_xml::schema::Occurs,
+        ) -> ::instant_xml::schema::Particle {
+            ::instant_xml::schema::Particle::Element {
+                id: ::instant_xml::Id { ns: #ns, name: #tag },
+                ty: <Self as ::instant_xml::schema::Schema>::describe(schemas),
+                occurs,
+                nillable: false,
+            }
+        }
+    ))
+}
+
+fn describe_scalar_enum(
+    input: &syn::DeriveInput,
+    data: &syn::DataEnum,
+    meta: &ContainerMeta,
+) -> Result<TokenStream, syn::Error> {
+    let mut values = TokenStream::new();
+    for variant in data.variants.iter() {
+        let serialize_as = VariantMeta::from_variant(variant, meta)?.serialize_as;
+        values.extend(quote!(#serialize_as,));
+    }
+
+    let ns = meta.default_namespace();
+    let name = input.ident.to_string();
+    let describe = quote!(
+        fn describe(schemas: &mut ::instant_xml::schema::Schemas) -> ::instant_xml::schema::Type {
+            schemas.define::<Self>(::instant_xml::Id { ns: #ns, name: #name }, |_| {
+                ::instant_xml::schema::Definition::Enumeration(::std::vec![#values])
+            })
+        }
+    );
+
+    // Without a namespace of its own, the enum takes the namespace of the field
+    Ok(match meta.ns.uri {
+        Some(_) => quote!(
+            #describe
+
+            fn particle(
+                schemas: &mut ::instant_xml::schema::Schemas,
+                field: ::instant_xml::Id<'static>,
+                occurs: ::instant_xml::schema::Occurs,
+            ) -> ::instant_xml::schema::Particle {
+                ::instant_xml::schema::Particle::Element {
+                    id: ::instant_xml::Id { ns: #ns, name: field.name },
+                    ty: <Self as ::instant_xml::schema::Schema>::describe(schemas),
+                    occurs,
+                    nillable: false,
+                }
+            }
+        ),
+        None => describe,
+    })
+}
+
+fn describe_forward_enum(
+    input: &syn::DeriveInput,
+    data: &syn::DataEnum,
+    meta: &ContainerMeta,
+) -> Result<TokenStream, syn::Error> {
+    let ns = meta.default_namespace();
+    let mut particles = TokenStream::new();
+    for variant in data.variants.iter() {
+        let fields = match &variant.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                particles.extend(match is_other(variant)? {
+                    true => quote!(::instant_xml::schema::Particle::Any {
+                        occurs: ::instant_xml::schema::Occurs::ONCE,
+                    },),
+                    false => quote!(<#ty as ::instant_xml::schema::Schema>::particle(
+                        schemas,
+                        field,
+                        ::instant_xml::schema::Occurs::ONCE,
+                    ),),
+                });
+                continue;
+            }
+            syn::Fields::Unit => {
+                is_other(variant)?;
+                continue;
+            }
+            syn::Fields::Named(fields) => fields,
+            _ => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "wrapped enum variants must have 1 unnamed field or named fields",
+                ))
+            }
+        };
+
+        let tag = variant_tag(variant)?;
+        let name = format!("{}{}", input.ident, variant.ident);
+        let definition = complex_type(&fields.named, meta)?;
+        particles.extend(quote!(::instant_xml::schema::Particle::Element {
+            id: ::instant_xml::Id { ns: #ns, name: #tag },
+            ty: schemas.define::<Self>(::instant_xml::Id { ns: #ns, name: #name }, |schemas| #definition),
+            occurs: ::instant_xml::schema::Occurs::ONCE,
+            nillable: false,
+        },));
+    }
+
+    Ok(quote!(
+        fn describe(_: &mut ::instant_xml::schema::Schemas) -> ::instant_xml::schema::Type {
+            ::instant_xml::schema::Type::Builtin("anyType")
+        }
+
+        fn particle(
+            schemas: &mut ::instant_xml::schema::Schemas,
+            field: ::instant_xml::Id<'static>,
+            occurs: ::instant_xml::schema::Occurs,
+        ) -> ::instant_xml::schema::Particle {
+            ::instant_xml::schema::Particle::Choice {
+                particles: ::std::vec![#particles],
+                occurs,
+            }
+        }
+    ))
+}
+
+/// An expression for the `Definition` of an element with `fields`, using `schemas`
+fn complex_type<'a>(
+    fields: impl IntoIterator<Item = &'a syn::Field>,
+    meta: &ContainerMeta,
+) -> Result<TokenStream, syn::Error> {
+    let default_ns = meta.default_namespace();
+    let mut body = TokenStream::new();
+    for field in fields {
+        if is_phantom_data(&field.ty) {
+            continue;
+        }
+
+        let field_meta = FieldMeta::from_field(field, meta)?;
+        if field_meta.any_attribute {
+            body.extend(quote!(complex.any_attribute = true;));
+            continue;
+        } else if field_meta.any {
+            body.extend(quote!(
+                particles.push(::instant_xml::schema::Particle::Any {
+                    occurs: ::instant_xml::schema::Occurs::MANY,
+                });
+            ));
+            continue;
+        } else if field_meta.flatten
+            || field_meta.mixed
+            || field_meta.raw
+            || field_meta.range.is_some()
+            || field_meta.wrapper.is_some()
+        {
+            return Err(syn::Error::new(
+                field.span(),
+                "Schema does not support flatten, mixed, raw, range or wrapper fields",
+            ));
+        }
+
+        let field_ty = &field.ty;
+        let custom = field_meta.with.is_some() || field_meta.serialize_with.is_some();
+        let ty = match custom {
+            true => quote!(::instant_xml::schema::Type::Builtin("string")),
+            false => quote!(<#field_ty as ::instant_xml::schema::Schema>::describe(schemas)),
+        };
+
+        if field_meta.direct {
+            body.extend(quote!(
+                complex.content = ::instant_xml::schema::Content::Simple(#ty);
+            ));
+            continue;
+        }
+
+        let tag = field_meta.tag;
+        let default = field_meta.default.is_some() || field_meta.skip_empty;
+        if field_meta.attribute {
+            // Unprefixed attributes have no namespace, whatever the element's namespace
+            let ns = match &field_meta.ns.uri {
+                Some(Namespace::Path(path)) => match path
+                    .get_ident()
+                    .and_then(|prefix| meta.ns.prefixes.get(&prefix.to_string()))
+                {
+                    Some(ns) => quote!(#ns),
+                    None => {
+                        return Err(syn::Error::new(
+                            field_meta.ns.uri.span(),
+                            "attribute namespace must be a prefix defined on the field's type",
+                        ))
+                    }
+                },
+                Some(Namespace::Literal(_)) => {
+                    return Err(syn::Error::new(
+                        field_meta.ns.uri.span(),
+                        "attribute namespace must be a prefix identifier",
+                    ))
+                }
+                None => quote!(""),
+            };
+
+            let required = match (default, custom) {
+                (true, _) => quote!(false),
+                (false, true) => quote!(true),
+                (false, false) => quote!(
+                    <#field_ty as ::instant_xml::schema::Schema>::particle(
+                        schemas,
+                        ::instant_xml::Id { ns: #ns, name: #tag },
+                        ::instant_xml::schema::Occurs::ONCE,
+                    )
+                    .occurs()
+                    .min > 0
+                ),
+            };
+
+            body.extend(quote!(
+                complex.attributes.push(::instant_xml::schema::Attribute {
+                    id: ::instant_xml::Id { ns: #ns, name: #tag },
+                    ty: #ty,
+                    required: #required,
+                });
+            ));
+            continue;
+        }
+
+        let ns = match &field_meta.ns.uri {
+            Some(ns) => quote!(#ns),
+            None => default_ns.clone(),
+        };
+
+        let particle = match custom {
+            true => quote!(::instant_xml::schema::Particle::Element {
+                id: ::instant_xml::Id { ns: #ns, name: #tag },
+                ty: #ty,
+                occurs: ::instant_xml::schema::Occurs::ONCE,
+                nillable: false,
+            }),
+            false => quote!(<#field_ty as ::instant_xml::schema::Schema>::particle(
+                schemas,
+                ::instant_xml::Id { ns: #ns, name: #tag },
+                ::instant_xml::schema::Occurs::ONCE,
+            )),
+        };
+
+        let mut adjust = TokenStream::new();
+        if default {
+            adjust.extend(quote!(occurs.min = 0;));
+        }
+        if let Some(min) = field_meta.min {
+            let min = min as u32;
+            adjust.extend(quote!(occurs.min = #min;));
+        }
+        if let Some(max) = field_meta.max {
+            let max = max as u32;
+            adjust.extend(quote!(occurs.max = ::core::option::Option::Some(#max);));
+        }
+
+        let nillable = match field_meta.nil {
+            true => quote!(
+                if let ::instant_xml::schema::Particle::Element { nillable, .. } = &mut particle {
+                    *nillable = true;
+                }
+            ),
+            false => quote!(),
+        };
+
+        if !adjust.is_empty() {
+            adjust = quote!(
+                let mut occurs = particle.occurs();
+                #adjust
+                particle = particle.with_occurs(occurs);
+            );
+        }
+
+        body.extend(quote!({
+            #[allow(unused_mut)]
+            let mut particle = #particle;
+            #adjust
+            #nillable
+            particles.push(particle);
+        }));
+    }
+
+    Ok(quote!({
+        let mut complex = ::instant_xml::schema::ComplexType::default();
+        let mut particles = ::std::vec::Vec::new();
+        #body
+        if !particles.is_empty() {
+            complex.content = ::instant_xml::schema::Content::Sequence(particles);
+        }
+
+        ::instant_xml::schema::Definition::Complex(complex)
+    }))
+}
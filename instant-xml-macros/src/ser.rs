@@ -4,7 +4,10 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::spanned::Spanned;
 
-use super::{discard_lifetimes, meta_items, ContainerMeta, FieldMeta, Mode, VariantMeta};
+use super::{
+    bounded_generics, discard_lifetimes, flattenable, is_other, is_phantom_data, variant_tag,
+    ContainerMeta, FieldMeta, Mode, TypeAttr, VariantMeta,
+};
 use crate::{case::RenameRule, Namespace};
 
 pub fn to_xml(input: &syn::DeriveInput) -> proc_macro2::TokenStream {
@@ -13,13 +16,32 @@ pub fn to_xml(input: &syn::DeriveInput) -> proc_macro2::TokenStream {
         Err(e) => return e.to_compile_error(),
     };
 
-    match (&input.data, meta.mode) {
-        (syn::Data::Struct(data), None) => serialize_struct(input, data, meta),
-        (syn::Data::Struct(data), Some(Mode::Transparent)) => {
-            serialize_inline_struct(input, data, meta)
+    let soap_action = match &meta.soap_action {
+        Some(action) => {
+            let ident = &input.ident;
+            let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+            quote!(
+                impl #impl_generics ::instant_xml::SoapAction for #ident #ty_generics #where_clause {
+                    const SOAP_ACTION: &'static str = #action;
+                }
+            )
         }
+        None => TokenStream::new(),
+    };
+
+    let body = match (&input.data, meta.mode) {
+        (syn::Data::Struct(data), None) => serialize_struct(input, data, meta),
+        (syn::Data::Struct(data), Some(Mode::Transparent)) => match &data.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                serialize_newtype(input, &fields.unnamed[0], meta)
+            }
+            _ => serialize_inline_struct(input, data, meta),
+        },
         (syn::Data::Enum(data), Some(Mode::Scalar)) => serialize_scalar_enum(input, data, meta),
         (syn::Data::Enum(data), Some(Mode::Forward)) => serialize_forward_enum(input, data, meta),
+        (syn::Data::Enum(data), None) if meta.type_attr.is_some() => {
+            serialize_tagged_enum(input, data, meta)
+        }
         (syn::Data::Struct(_), Some(mode)) => syn::Error::new(
             input.span(),
             format_args!("{mode:?} mode not allowed on struct type"),
@@ -34,7 +56,9 @@ pub fn to_xml(input: &syn::DeriveInput) -> proc_macro2::TokenStream {
             syn::Error::new(input.span(), "missing mode").to_compile_error()
         }
         _ => todo!(),
-    }
+    };
+
+    quote!(#body #soap_action)
 }
 
 fn serialize_scalar_enum(
@@ -56,7 +80,11 @@ fn serialize_scalar_enum(
         variants.extend(quote!(#ident::#v_ident => #serialize_as,));
     }
 
-    let default_namespace = meta.default_namespace();
+    // Without a namespace of its own, the enum takes the namespace of the field
+    let ns = match &meta.ns.uri {
+        Some(_) => meta.default_namespace(),
+        None => quote!(id.ns),
+    };
 
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
     quote!(
@@ -68,7 +96,7 @@ fn serialize_scalar_enum(
             ) -> ::std::result::Result<(), instant_xml::Error> {
                 let prefix = match field {
                     Some(id) => {
-                        let prefix = serializer.write_start(id.name, #default_namespace)?;
+                        let prefix = serializer.write_start(id.name, #ns)?;
                         serializer.end_start()?;
                         Some((prefix, id.name))
                     }
@@ -77,7 +105,7 @@ fn serialize_scalar_enum(
 
                 serializer.write_str(match self { #variants })?;
                 if let Some((prefix, name)) = prefix {
-                    serializer.write_close(prefix, name)?;
+                    serializer.write_close(prefix.as_deref(), name)?;
                 }
 
                 Ok(())
@@ -99,37 +127,140 @@ fn serialize_forward_enum(
         .to_compile_error();
     }
 
+    let default_namespace = meta.default_namespace();
+    let cx_len = meta.ns.prefixes.len();
+    let mut context = quote!(
+        let mut new = ::instant_xml::ser::Context::<#cx_len>::default();
+        // An element written with a prefix leaves the default namespace unchanged
+        new.default_ns = match prefix {
+            Some(_) => serializer.default_ns(),
+            None => #default_namespace,
+        };
+    );
+
+    for (i, (prefix, ns)) in meta.ns.prefixes.iter().enumerate() {
+        context.extend(quote!(
+            new.prefixes[#i] = ::instant_xml::ser::Prefix { ns: #ns, prefix: #prefix };
+        ));
+    }
+
     let ident = &input.ident;
     let mut variants = TokenStream::new();
+    let mut borrowed = BTreeSet::new();
     for variant in data.variants.iter() {
-        match &variant.fields {
-            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {}
+        let v_ident = &variant.ident;
+        let fields = match &variant.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                match is_other(variant) {
+                    // Write an empty element with the original name
+                    Ok(true) => variants.extend(quote!(#ident::#v_ident(name) => {
+                        let id = ::instant_xml::Id::parse(::core::convert::AsRef::<str>::as_ref(name))?;
+                        serializer.write_start(id.name, id.ns)?;
+                        serializer.end_empty()?;
+                    })),
+                    Ok(false) => variants.extend(
+                        quote!(#ident::#v_ident(inner) => ::instant_xml::ToXml::serialize(inner, None, serializer)?,),
+                    ),
+                    Err(err) => return err.to_compile_error(),
+                }
+                continue;
+            }
+            syn::Fields::Named(fields) => fields,
             _ => {
                 return syn::Error::new(
                     input.span(),
-                    "wrapped enum variants must have 1 unnamed field",
+                    "wrapped enum variants must have 1 unnamed field or named fields",
                 )
                 .to_compile_error()
             }
+        };
+
+        let tag = match variant_tag(variant) {
+            Ok(tag) => tag,
+            Err(err) => return err.to_compile_error(),
+        };
+
+        let mut bindings = TokenStream::new();
+        let mut body = TokenStream::new();
+        let mut attributes = TokenStream::new();
+        for field in &fields.named {
+            let field_name = field.ident.as_ref().unwrap();
+            bindings.extend(quote!(#field_name,));
+            let value = quote!((*#field_name));
+            if let Err(err) = named_field(
+                field,
+                &mut body,
+                &mut attributes,
+                &mut borrowed,
+                &meta,
+                &value,
+            ) {
+                return err.to_compile_error();
+            }
         }
 
-        if !meta_items(&variant.attrs).is_empty() {
-            return syn::Error::new(
-                input.span(),
-                "attributes not allowed on wrapped enum variants",
-            )
-            .to_compile_error();
+        variants.extend(quote!(#ident::#v_ident { #bindings } => {
+            let prefix = serializer.write_start(#tag, #default_namespace)?;
+            #context
+            let old = serializer.push(new)?;
+
+            #attributes
+            serializer.end_start()?;
+            #body
+            serializer.write_close(prefix.as_deref(), #tag)?;
+            serializer.pop(old);
+        }));
+    }
+
+    let generics = bounded_generics(input, "::instant_xml::ToXml");
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote!(
+        impl #impl_generics ToXml for #ident #ty_generics #where_clause {
+            fn serialize<W: ::core::fmt::Write + ?::core::marker::Sized>(
+                &self,
+                field: Option<::instant_xml::Id<'_>>,
+                serializer: &mut instant_xml::Serializer<W>,
+            ) -> ::std::result::Result<(), instant_xml::Error> {
+                match self {
+                    #variants
+                }
+
+                Ok(())
+            }
         }
+    )
+}
 
-        let v_ident = &variant.ident;
-        variants.extend(quote!(#ident::#v_ident(inner) => inner.serialize(None, serializer)?,));
+fn serialize_tagged_enum(
+    input: &syn::DeriveInput,
+    data: &syn::DataEnum,
+    meta: ContainerMeta,
+) -> TokenStream {
+    if meta.rename_all != RenameRule::None {
+        return syn::Error::new(
+            input.span(),
+            "rename_all is not allowed on tagged enum type",
+        )
+        .to_compile_error();
     }
 
     let default_namespace = meta.default_namespace();
-    let cx_len = meta.ns.prefixes.len();
+    // A plain tag attribute is unqualified, as the deserializer expects it
+    let (type_ns, xsi) = match &meta.type_attr {
+        Some(TypeAttr::Xsi) => (quote!(::instant_xml::xsd::XSI), 1),
+        _ => (quote!(""), 0),
+    };
+    let type_name = meta.type_attr.as_ref().unwrap().name();
+
+    let cx_len = meta.ns.prefixes.len() + xsi;
     let mut context = quote!(
         let mut new = ::instant_xml::ser::Context::<#cx_len>::default();
-        new.default_ns = #default_namespace;
+        // An element written with a prefix leaves the default namespace unchanged
+        new.default_ns = match prefix {
+            Some(_) => serializer.default_ns(),
+            None => #default_namespace,
+        };
     );
 
     for (i, (prefix, ns)) in meta.ns.prefixes.iter().enumerate() {
@@ -138,14 +269,77 @@ fn serialize_forward_enum(
         ));
     }
 
-    let mut generics = input.generics.clone();
-    for param in generics.type_params_mut() {
-        param
-            .bounds
-            .push(syn::parse_str("::instant_xml::ToXml").unwrap());
+    if xsi == 1 {
+        let i = meta.ns.prefixes.len();
+        context.extend(quote!(
+            new.prefixes[#i] = ::instant_xml::ser::Prefix { ns: ::instant_xml::xsd::XSI, prefix: "xsi" };
+        ));
+    }
+
+    let ident = &input.ident;
+    let mut variants = TokenStream::new();
+    let mut borrowed = BTreeSet::new();
+    for variant in data.variants.iter() {
+        let value = match variant_tag(variant) {
+            Ok(tag) => tag,
+            Err(err) => return err.to_compile_error(),
+        };
+
+        let v_ident = &variant.ident;
+        let mut bindings = TokenStream::new();
+        let mut body = TokenStream::new();
+        let mut attributes = TokenStream::new();
+        match &variant.fields {
+            syn::Fields::Named(fields) => {
+                for field in &fields.named {
+                    let field_name = field.ident.as_ref().unwrap();
+                    bindings.extend(quote!(#field_name,));
+                    let value = quote!((*#field_name));
+                    if let Err(err) = named_field(
+                        field,
+                        &mut body,
+                        &mut attributes,
+                        &mut borrowed,
+                        &meta,
+                        &value,
+                    ) {
+                        return err.to_compile_error();
+                    }
+                }
+            }
+            syn::Fields::Unit => {}
+            syn::Fields::Unnamed(_) => {
+                return syn::Error::new(
+                    variant.span(),
+                    "tagged enum variants must be unit variants or have named fields",
+                )
+                .to_compile_error()
+            }
+        }
+
+        let write_type = match xsi {
+            // The type name is a QName in the enum's namespace, so it takes the element's prefix
+            1 => quote!(match &prefix {
+                Some(prefix) => {
+                    serializer.write_attr(#type_name, #type_ns, &format!("{}:{}", prefix, #value))?
+                }
+                None => serializer.write_attr(#type_name, #type_ns, #value)?,
+            }),
+            _ => quote!(serializer.write_attr(#type_name, #type_ns, #value)?;),
+        };
+
+        variants.extend(quote!(#ident::#v_ident { #bindings } => {
+            #write_type
+            #attributes
+            serializer.end_start()?;
+            #body
+        }));
     }
 
+    let generics = bounded_generics(input, "::instant_xml::ToXml");
+
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let tag = meta.tag();
     quote!(
         impl #impl_generics ToXml for #ident #ty_generics #where_clause {
             fn serialize<W: ::core::fmt::Write + ?::core::marker::Sized>(
@@ -153,13 +347,19 @@ fn serialize_forward_enum(
                 field: Option<::instant_xml::Id<'_>>,
                 serializer: &mut instant_xml::Serializer<W>,
             ) -> ::std::result::Result<(), instant_xml::Error> {
+                let prefix = serializer.write_start(#tag, #default_namespace)?;
+                #context
+                let old = serializer.push(new)?;
+
                 match self {
                     #variants
                 }
 
+                serializer.write_close(prefix.as_deref(), #tag)?;
+                serializer.pop(old);
                 Ok(())
             }
-        };
+        }
     )
 }
 
@@ -168,21 +368,40 @@ fn serialize_struct(
     data: &syn::DataStruct,
     meta: ContainerMeta,
 ) -> proc_macro2::TokenStream {
-    let tag = meta.tag();
+    let tag = match &meta.name_from {
+        Some(field) => quote!(::core::convert::AsRef::<str>::as_ref(&self.#field)),
+        None => meta.tag(),
+    };
     let mut body = TokenStream::new();
     let mut attributes = TokenStream::new();
     let mut borrowed = BTreeSet::new();
+    let mut flatten = None;
     match &data.fields {
         syn::Fields::Named(fields) => {
-            body.extend(quote!(serializer.end_start()?;));
+            let mut content = TokenStream::new();
             for field in &fields.named {
-                if let Err(err) =
-                    named_field(field, &mut body, &mut attributes, &mut borrowed, &meta)
-                {
+                let field_name = field.ident.as_ref().unwrap();
+                let value = quote!(self.#field_name);
+                if let Err(err) = named_field(
+                    field,
+                    &mut content,
+                    &mut attributes,
+                    &mut borrowed,
+                    &meta,
+                    &value,
+                ) {
                     return err.to_compile_error();
                 }
             }
-            body.extend(quote!(serializer.write_close(prefix, #tag)?;));
+
+            body.extend(quote!(
+                serializer.end_start()?;
+                #content
+                serializer.write_close(prefix.as_deref(), #tag)?;
+            ));
+            if flattenable(fields, &meta) {
+                flatten = Some(content);
+            }
         }
         syn::Fields::Unnamed(fields) => {
             body.extend(quote!(serializer.end_start()?;));
@@ -191,7 +410,7 @@ fn serialize_struct(
                     return err.to_compile_error();
                 }
             }
-            body.extend(quote!(serializer.write_close(prefix, #tag)?;));
+            body.extend(quote!(serializer.write_close(prefix.as_deref(), #tag)?;));
         }
         syn::Fields::Unit => body.extend(quote!(serializer.end_empty()?;)),
     }
@@ -200,7 +419,11 @@ fn serialize_struct(
     let cx_len = meta.ns.prefixes.len();
     let mut context = quote!(
         let mut new = ::instant_xml::ser::Context::<#cx_len>::default();
-        new.default_ns = #default_namespace;
+        // An element written with a prefix leaves the default namespace unchanged
+        new.default_ns = match prefix {
+            Some(_) => serializer.default_ns(),
+            None => #default_namespace,
+        };
     );
 
     for (i, (prefix, ns)) in meta.ns.prefixes.iter().enumerate() {
@@ -209,17 +432,17 @@ fn serialize_struct(
         ));
     }
 
-    let mut generics = input.generics.clone();
-    for param in generics.type_params_mut() {
-        param
-            .bounds
-            .push(syn::parse_str("::instant_xml::ToXml").unwrap());
-    }
+    let generics = bounded_generics(input, "::instant_xml::ToXml");
+    let flatten = match flatten {
+        Some(content) => flatten_impl(input, &generics, &attributes, &content),
+        None => TokenStream::new(),
+    };
 
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-    let tag = meta.tag();
     let ident = &input.ident;
     quote!(
+        #flatten
+
         impl #impl_generics ToXml for #ident #ty_generics #where_clause {
             fn serialize<W: ::core::fmt::Write + ?::core::marker::Sized>(
                 &self,
@@ -240,7 +463,7 @@ fn serialize_struct(
                 serializer.pop(old);
                 Ok(())
             }
-        };
+        }
     )
 }
 
@@ -272,19 +495,18 @@ fn serialize_inline_struct(
     match &data.fields {
         syn::Fields::Named(fields) => {
             for field in &fields.named {
-                if let Err(err) =
-                    named_field(field, &mut body, &mut attributes, &mut borrowed, &meta)
-                {
+                let field_name = field.ident.as_ref().unwrap();
+                let value = quote!(self.#field_name);
+                if let Err(err) = named_field(
+                    field,
+                    &mut body,
+                    &mut attributes,
+                    &mut borrowed,
+                    &meta,
+                    &value,
+                ) {
                     return err.to_compile_error();
                 }
-
-                if !attributes.is_empty() {
-                    return syn::Error::new(
-                        input.span(),
-                        "no attributes allowed on inline structs",
-                    )
-                    .to_compile_error();
-                }
             }
         }
         syn::Fields::Unnamed(fields) => {
@@ -294,18 +516,15 @@ fn serialize_inline_struct(
                 }
             }
         }
-        syn::Fields::Unit => body.extend(quote!(serializer.end_empty()?;)),
+        // An inline unit struct has no content of its own
+        syn::Fields::Unit => {}
     }
 
-    let mut generics = input.generics.clone();
-    for param in generics.type_params_mut() {
-        param
-            .bounds
-            .push(syn::parse_str("::instant_xml::ToXml").unwrap());
-    }
+    let generics = bounded_generics(input, "::instant_xml::ToXml");
 
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let ident = &input.ident;
+    let flatten = flatten_impl(input, &generics, &attributes, &body);
     quote!(
         impl #impl_generics ToXml for #ident #ty_generics #where_clause {
             fn serialize<W: ::core::fmt::Write + ?::core::marker::Sized>(
@@ -313,10 +532,88 @@ fn serialize_inline_struct(
                 field: Option<::instant_xml::Id<'_>>,
                 serializer: &mut instant_xml::Serializer<W>,
             ) -> ::std::result::Result<(), instant_xml::Error> {
-                #body
+                ::instant_xml::ser::ToXmlFlatten::serialize_content(self, serializer)
+            }
+        }
+
+        #flatten
+    )
+}
+
+/// Generate the `ToXmlFlatten` impl writing the struct's fields into its parent's element
+fn flatten_impl(
+    input: &syn::DeriveInput,
+    generics: &syn::Generics,
+    attributes: &TokenStream,
+    content: &TokenStream,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ident = &input.ident;
+    quote!(
+        impl #impl_generics ::instant_xml::ser::ToXmlFlatten for #ident #ty_generics #where_clause {
+            fn serialize_attributes<W: ::core::fmt::Write + ?::core::marker::Sized>(
+                &self,
+                serializer: &mut instant_xml::Serializer<W>,
+            ) -> ::std::result::Result<(), instant_xml::Error> {
+                #attributes
                 Ok(())
             }
-        };
+
+            fn serialize_content<W: ::core::fmt::Write + ?::core::marker::Sized>(
+                &self,
+                serializer: &mut instant_xml::Serializer<W>,
+            ) -> ::std::result::Result<(), instant_xml::Error> {
+                #content
+                Ok(())
+            }
+        }
+    )
+}
+
+fn serialize_newtype(
+    input: &syn::DeriveInput,
+    field: &syn::Field,
+    meta: ContainerMeta,
+) -> TokenStream {
+    if !field.attrs.is_empty() {
+        return syn::Error::new(field.span(), "unnamed fields cannot have attributes")
+            .to_compile_error();
+    } else if !meta.ns.prefixes.is_empty() {
+        return syn::Error::new(
+            input.span(),
+            "transparent newtypes cannot have namespace prefixes",
+        )
+        .to_compile_error();
+    }
+
+    // Without a field name, a renamed newtype uses its own name for the element
+    let field_id = match meta.rename.is_some() || meta.ns.uri.is_some() {
+        true => {
+            let name = meta.tag();
+            let ns = meta.default_namespace();
+            quote!(field.or(Some(::instant_xml::Id { ns: #ns, name: #name })))
+        }
+        false => quote!(field),
+    };
+
+    let generics = bounded_generics(input, "::instant_xml::ToXml");
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ident = &input.ident;
+    quote!(
+        impl #impl_generics ToXml for #ident #ty_generics #where_clause {
+            fn serialize<W: ::core::fmt::Write + ?::core::marker::Sized>(
+                &self,
+                field: Option<::instant_xml::Id<'_>>,
+                serializer: &mut instant_xml::Serializer<W>,
+            ) -> ::std::result::Result<(), instant_xml::Error> {
+                ToXml::serialize(&self.0, #field_id, serializer)
+            }
+
+            fn present(&self) -> bool {
+                self.0.present()
+            }
+        }
     )
 }
 
@@ -326,8 +623,12 @@ fn named_field(
     attributes: &mut TokenStream,
     borrowed: &mut BTreeSet<syn::Lifetime>,
     meta: &ContainerMeta,
+    value: &TokenStream,
 ) -> Result<(), syn::Error> {
-    let field_name = field.ident.as_ref().unwrap();
+    if is_phantom_data(&field.ty) || field.ident.is_some() && field.ident == meta.name_from {
+        return Ok(());
+    }
+
     let field_meta = match FieldMeta::from_field(field, meta) {
         Ok(meta) => meta,
         Err(err) => {
@@ -336,6 +637,93 @@ fn named_field(
         }
     };
 
+    if !field_meta.skip_empty {
+        return field_tokens(field, field_meta, body, attributes, borrowed, meta, value);
+    }
+
+    let (mut inner_body, mut inner_attributes) = (TokenStream::new(), TokenStream::new());
+    field_tokens(
+        field,
+        field_meta,
+        &mut inner_body,
+        &mut inner_attributes,
+        borrowed,
+        meta,
+        value,
+    )?;
+
+    if !inner_body.is_empty() {
+        body.extend(quote!(if !#value.is_empty() { #inner_body }));
+    }
+
+    if !inner_attributes.is_empty() {
+        attributes.extend(quote!(if !#value.is_empty() { #inner_attributes }));
+    }
+
+    Ok(())
+}
+
+fn field_tokens(
+    field: &syn::Field,
+    field_meta: FieldMeta,
+    body: &mut TokenStream,
+    attributes: &mut TokenStream,
+    borrowed: &mut BTreeSet<syn::Lifetime>,
+    meta: &ContainerMeta,
+    value: &TokenStream,
+) -> Result<(), syn::Error> {
+    if field_meta.any || field_meta.mixed {
+        body.extend(quote!(::instant_xml::ToXml::serialize(&#value, None, serializer)?;));
+        return Ok(());
+    } else if field_meta.any_attribute {
+        // Attributes are written in the map's iteration order, which is already deterministic for
+        // ordered maps like `BTreeMap`; `sorted` orders them by namespace and name instead, for
+        // maps like `HashMap`
+        attributes.extend(match field_meta.sorted {
+            true => quote!(
+                let mut extra = ::std::vec::Vec::new();
+                for (name, value) in &#value {
+                    let id = ::instant_xml::Id::parse(::core::convert::AsRef::<str>::as_ref(name))?;
+                    extra.push((id, ::core::convert::AsRef::<str>::as_ref(value)));
+                }
+
+                extra.sort_by(|a, b| (a.0.ns, a.0.name).cmp(&(b.0.ns, b.0.name)));
+                for (id, value) in extra {
+                    serializer.write_attr_ns(id.name, id.ns, value)?;
+                }
+            ),
+            false => quote!(
+                for (name, value) in &#value {
+                    let id = ::instant_xml::Id::parse(::core::convert::AsRef::<str>::as_ref(name))?;
+                    serializer.write_attr_ns(id.name, id.ns, ::core::convert::AsRef::<str>::as_ref(value))?;
+                }
+            ),
+        });
+        return Ok(());
+    }
+
+    if field_meta.flatten {
+        if field_meta.attribute || field_meta.direct || field_meta.serialize_with.is_some() {
+            return Err(syn::Error::new(
+                field.span(),
+                "flatten cannot be combined with attribute, direct or serialize_with",
+            ));
+        } else if meta.mode == Some(Mode::Transparent) {
+            return Err(syn::Error::new(
+                field.span(),
+                "inline structs cannot have flattened fields",
+            ));
+        }
+
+        attributes.extend(quote!(
+            ::instant_xml::ser::ToXmlFlatten::serialize_attributes(&#value, serializer)?;
+        ));
+        body.extend(quote!(
+            ::instant_xml::ser::ToXmlFlatten::serialize_content(&#value, serializer)?;
+        ));
+        return Ok(());
+    }
+
     let tag = field_meta.tag;
     let default_ns = match &meta.ns.uri {
         Some(ns) => quote!(#ns),
@@ -383,12 +771,37 @@ fn named_field(
             None => (default_ns, quote!()),
         };
 
-        attributes.extend(quote!(
-            #error
-            if self.#field_name.present() {
-                serializer.write_attr(#tag, #ns, &self.#field_name)?;
+        if let Some(fixed) = &field_meta.fixed {
+            attributes.extend(quote!(
+                #error
+                serializer.write_attr(#tag, #ns, #fixed)?;
+            ));
+            return Ok(());
+        }
+
+        match &field_meta.with {
+            Some(with) => attributes.extend(quote!(
+                #error
+                serializer.write_attr_with(#tag, #ns, |serializer| {
+                    #with::serialize(&#value, None, serializer)
+                })?;
+            )),
+            None => {
+                let absent = match &field_meta.null {
+                    Some(null) => quote!(else {
+                        serializer.write_attr(#tag, #ns, &#null)?;
+                    }),
+                    None => quote!(),
+                };
+
+                attributes.extend(quote!(
+                    #error
+                    if #value.present() {
+                        serializer.write_attr(#tag, #ns, &#value)?;
+                    } #absent
+                ))
             }
-        ));
+        }
         return Ok(());
     }
 
@@ -397,9 +810,36 @@ fn named_field(
         None => default_ns,
     };
 
+    if let Some((start, end)) = &field_meta.range {
+        if meta.mode == Some(Mode::Transparent) {
+            return Err(syn::Error::new(
+                field.span(),
+                "inline structs cannot have range fields",
+            ));
+        }
+
+        body.extend(quote!({
+            let (start, end) = ::instant_xml::Interval::bounds(&#value);
+            ::instant_xml::ToXml::serialize(start, Some(::instant_xml::Id { ns: #ns, name: #start }), serializer)?;
+            ::instant_xml::ToXml::serialize(end, Some(::instant_xml::Id { ns: #ns, name: #end }), serializer)?;
+        }));
+        return Ok(());
+    }
+
+    if let Some(fixed) = &field_meta.fixed {
+        body.extend(quote!(
+            ::instant_xml::ToXml::serialize(#fixed, Some(::instant_xml::Id { ns: #ns, name: #tag }), serializer)?;
+        ));
+        return Ok(());
+    }
+
     let mut no_lifetime_type = field.ty.clone();
     discard_lifetimes(&mut no_lifetime_type, borrowed, false, true);
-    if let Some(with) = field_meta.serialize_with {
+    if let Some(with) = field_meta.with {
+        body.extend(quote!(
+            #with::serialize(&#value, Some(::instant_xml::Id { ns: #ns, name: #tag }), serializer)?;
+        ));
+    } else if let Some(with) = field_meta.serialize_with {
         if field_meta.direct {
             return Err(syn::Error::new(
                 field.span(),
@@ -415,15 +855,60 @@ fn named_field(
             )
         })?;
 
-        body.extend(quote!(#path(&self.#field_name, serializer)?;));
+        body.extend(quote!(#path(&#value, serializer)?;));
         return Ok(());
+    } else if field_meta.cdata {
+        let value = quote!(::core::convert::AsRef::<str>::as_ref(&#value));
+        body.extend(match field_meta.direct {
+            true => quote!(serializer.write_cdata(#value)?;),
+            false => quote!({
+                let prefix = serializer.write_start(#tag, #ns)?;
+                serializer.end_start()?;
+                serializer.write_cdata(#value)?;
+                serializer.write_close(prefix.as_deref(), #tag)?;
+            }),
+        });
+    } else if field_meta.raw {
+        let field = match field_meta.direct {
+            true => quote!(None),
+            false => quote!(Some(::instant_xml::Id { ns: #ns, name: #tag })),
+        };
+
+        body.extend(quote!(
+            ::instant_xml::Raw::from(::core::convert::AsRef::<str>::as_ref(&#value))
+                .serialize(#field, serializer)?;
+        ));
     } else if field_meta.direct {
         body.extend(quote!(
-            self.#field_name.serialize(None, serializer)?;
+            ::instant_xml::ToXml::serialize(&#value, None, serializer)?;
+        ));
+    } else if field_meta.nil {
+        body.extend(quote!(
+            match ::instant_xml::ToXml::present(&#value) {
+                true => ::instant_xml::ToXml::serialize(&#value, Some(::instant_xml::Id { ns: #ns, name: #tag }), serializer)?,
+                false => serializer.write_nil(#tag, #ns)?,
+            }
         ));
+    } else if let Some(wrapper) = &field_meta.wrapper {
+        body.extend(quote!({
+            let prefix = serializer.write_start(#wrapper, #ns)?;
+            let new = ::instant_xml::ser::Context::<0> {
+                default_ns: match prefix {
+                    Some(_) => serializer.default_ns(),
+                    None => #ns,
+                },
+                ..::core::default::Default::default()
+            };
+
+            let old = serializer.push(new)?;
+            serializer.end_start()?;
+            ::instant_xml::ToXml::serialize(&#value, Some(::instant_xml::Id { ns: #ns, name: #tag }), serializer)?;
+            serializer.write_close(prefix.as_deref(), #wrapper)?;
+            serializer.pop(old);
+        }));
     } else {
         body.extend(quote!(
-            self.#field_name.serialize(Some(::instant_xml::Id { ns: #ns, name: #tag }), serializer)?;
+            ::instant_xml::ToXml::serialize(&#value, Some(::instant_xml::Id { ns: #ns, name: #tag }), serializer)?;
         ));
     }
 
@@ -443,11 +928,15 @@ fn unnamed_field(
         ));
     }
 
+    if is_phantom_data(&field.ty) {
+        return Ok(());
+    }
+
     let mut no_lifetime_type = field.ty.clone();
     discard_lifetimes(&mut no_lifetime_type, borrowed, false, true);
     let index = syn::Index::from(index);
     body.extend(quote!(
-        self.#index.serialize(None, serializer)?;
+        ::instant_xml::ToXml::serialize(&self.#index, None, serializer)?;
     ));
 
     Ok(())
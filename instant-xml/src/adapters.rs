@@ -0,0 +1,604 @@
+//! Wrapper types that map common schema idioms onto plain Rust types
+
+use std::any::type_name;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::{Accumulate, Deserializer, Error, FromXml, Id, Kind, Position, Serializer, ToXml};
+
+/// (De)serializes `T` through its `Display` and `FromStr` implementations
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DisplayFromStr<T>(pub T);
+
+impl<T: fmt::Display> ToXml for DisplayFromStr<T> {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        self.0.to_string().serialize(field, serializer)
+    }
+}
+
+impl<'xml, T: FromStr> FromXml<'xml> for DisplayFromStr<T> {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        scalar_matches(id, field)
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        deserialize_scalar(into, field, deserializer, |value| {
+            T::from_str(value).map(Self).map_err(|_| {
+                Error::UnexpectedValue(format!(
+                    "unable to parse {} from `{value}` for {field}",
+                    type_name::<T>()
+                ))
+            })
+        })
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Scalar;
+}
+
+/// A `bool` represented as `1` or `0`
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct BoolFromInt(pub bool);
+
+impl ToXml for BoolFromInt {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        let value = match self.0 {
+            true => "1",
+            false => "0",
+        };
+
+        value.serialize(field, serializer)
+    }
+}
+
+impl<'xml> FromXml<'xml> for BoolFromInt {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        scalar_matches(id, field)
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        deserialize_scalar(into, field, deserializer, |value| match value {
+            "1" => Ok(Self(true)),
+            "0" => Ok(Self(false)),
+            _ => Err(Error::UnexpectedValue(format!(
+                "unable to parse bool from `{value}` for {field}"
+            ))),
+        })
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Scalar;
+}
+
+/// A list of values in a single comma-separated string
+///
+/// Whitespace around each item is ignored when deserializing, and an empty string yields an
+/// empty list.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CommaSeparated<T>(pub Vec<T>);
+
+impl<T: fmt::Display> ToXml for CommaSeparated<T> {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        let mut value = String::new();
+        for (i, item) in self.0.iter().enumerate() {
+            if i > 0 {
+                value.push(',');
+            }
+
+            fmt::Write::write_fmt(&mut value, format_args!("{item}"))?;
+        }
+
+        value.serialize(field, serializer)
+    }
+}
+
+impl<'xml, T: FromStr> FromXml<'xml> for CommaSeparated<T> {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        scalar_matches(id, field)
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        deserialize_scalar(into, field, deserializer, |value| {
+            if value.trim().is_empty() {
+                return Ok(Self(Vec::new()));
+            }
+
+            value
+                .split(',')
+                .map(|item| {
+                    let item = item.trim();
+                    T::from_str(item).map_err(|_| {
+                        Error::UnexpectedValue(format!(
+                            "unable to parse {} from `{item}` for {field}",
+                            type_name::<T>()
+                        ))
+                    })
+                })
+                .collect::<Result<_, _>>()
+                .map(Self)
+        })
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Scalar;
+}
+
+/// A list of values in a single whitespace-separated string, like an xs:list
+///
+/// Any amount of whitespace separates items when deserializing; items are written separated by
+/// a single space.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SpaceSeparated<T>(pub Vec<T>);
+
+impl<T: fmt::Display> ToXml for SpaceSeparated<T> {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        list::serialize(&self.0, field, serializer)
+    }
+}
+
+impl<'xml, T: FromStr> FromXml<'xml> for SpaceSeparated<T> {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        scalar_matches(id, field)
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        deserialize_scalar(into, field, deserializer, |value| {
+            list::parse(value, field).map(Self)
+        })
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Scalar;
+}
+
+/// Functions for `#[xml(with = "...")]` that (de)serialize a `Vec<T>` as an xs:list
+///
+/// This is what `#[xml(list)]` uses.
+pub mod list {
+    use std::any::type_name;
+    use std::fmt;
+    use std::str::FromStr;
+
+    use crate::{Deserializer, Error, Id, Serializer, ToXml};
+
+    pub fn serialize<T: fmt::Display, W: fmt::Write + ?Sized>(
+        value: &[T],
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        let mut out = String::new();
+        for (i, item) in value.iter().enumerate() {
+            if i > 0 {
+                out.push(' ');
+            }
+
+            fmt::Write::write_fmt(&mut out, format_args!("{item}"))?;
+        }
+
+        out.serialize(field, serializer)
+    }
+
+    pub fn deserialize<T: FromStr>(
+        into: &mut Option<Vec<T>>,
+        field: &'static str,
+        deserializer: &mut Deserializer<'_, '_>,
+    ) -> Result<(), Error> {
+        super::deserialize_scalar(into, field, deserializer, |value| parse(value, field))
+    }
+
+    pub(super) fn parse<T: FromStr>(value: &str, field: &'static str) -> Result<Vec<T>, Error> {
+        value
+            .split_ascii_whitespace()
+            .map(|item| {
+                T::from_str(item).map_err(|_| {
+                    Error::UnexpectedValue(format!(
+                        "unable to parse {} from `{item}` for {field}",
+                        type_name::<T>()
+                    ))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Bytes encoded as standard, padded base64
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Base64(pub Vec<u8>);
+
+impl ToXml for Base64 {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        base64::serialize(&self.0, field, serializer)
+    }
+}
+
+impl<'xml> FromXml<'xml> for Base64 {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        scalar_matches(id, field)
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        deserialize_scalar(into, field, deserializer, |value| {
+            base64::decode(value, field).map(Self)
+        })
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Scalar;
+}
+
+/// Functions for `#[xml(with = "...")]` that encode bytes as standard, padded base64
+///
+/// This is what `#[xml(base64)]` uses. Any `AsRef<[u8]>` type can be serialized, and any type
+/// that implements `From<Vec<u8>>` can be deserialized.
+pub mod base64 {
+    use std::fmt;
+
+    use crate::{Deserializer, Error, Id, Serializer, ToXml};
+
+    pub fn serialize<T: AsRef<[u8]> + ?Sized, W: fmt::Write + ?Sized>(
+        value: &T,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        encode(value.as_ref()).serialize(field, serializer)
+    }
+
+    pub fn deserialize<T: From<Vec<u8>>>(
+        into: &mut Option<T>,
+        field: &'static str,
+        deserializer: &mut Deserializer<'_, '_>,
+    ) -> Result<(), Error> {
+        super::deserialize_scalar(into, field, deserializer, |value| {
+            decode(value, field).map(T::from)
+        })
+    }
+
+    fn encode(input: &[u8]) -> String {
+        let mut value = String::with_capacity((input.len() + 2) / 3 * 4);
+        for chunk in input.chunks(3) {
+            let bytes = [
+                chunk[0],
+                chunk.get(1).copied().unwrap_or(0),
+                chunk.get(2).copied().unwrap_or(0),
+            ];
+
+            let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+            for i in 0..4 {
+                match i <= chunk.len() {
+                    true => value.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char),
+                    false => value.push('='),
+                }
+            }
+        }
+
+        value
+    }
+
+    pub(crate) fn decode(value: &str, field: &'static str) -> Result<Vec<u8>, Error> {
+        let invalid = || Error::UnexpectedValue(format!("invalid base64 `{value}` for {field}"));
+
+        let input = value
+            .bytes()
+            .filter(|b| !b.is_ascii_whitespace())
+            .collect::<Vec<_>>();
+        if input.len() % 4 != 0 {
+            return Err(invalid());
+        }
+
+        let mut bytes = Vec::with_capacity(input.len() / 4 * 3);
+        for (i, chunk) in input.chunks(4).enumerate() {
+            let last = i == input.len() / 4 - 1;
+            let padding = match (chunk[2], chunk[3]) {
+                (b'=', b'=') if last => 2,
+                (_, b'=') if last => 1,
+                _ => 0,
+            };
+
+            let mut n = 0u32;
+            for &b in &chunk[..4 - padding] {
+                let digit = ALPHABET.iter().position(|&c| c == b).ok_or_else(invalid)?;
+                n = (n << 6) | digit as u32;
+            }
+
+            n <<= 6 * padding;
+            bytes.extend_from_slice(&n.to_be_bytes()[1..4 - padding]);
+        }
+
+        Ok(bytes)
+    }
+
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+}
+
+/// Bytes encoded as xs:hexBinary, written in upper case and parsed case-insensitively
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Hex(pub Vec<u8>);
+
+impl ToXml for Hex {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        hex::serialize(&self.0, field, serializer)
+    }
+}
+
+impl<'xml> FromXml<'xml> for Hex {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        scalar_matches(id, field)
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        deserialize_scalar(into, field, deserializer, |value| {
+            hex::decode(value, field).map(Self)
+        })
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Scalar;
+}
+
+/// Functions for `#[xml(with = "...")]` that encode bytes as xs:hexBinary
+///
+/// This is what `#[xml(hex)]` uses. Output is upper case, input may use either case.
+pub mod hex {
+    use std::fmt;
+
+    use crate::{Deserializer, Error, Id, Serializer, ToXml};
+
+    pub fn serialize<T: AsRef<[u8]> + ?Sized, W: fmt::Write + ?Sized>(
+        value: &T,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        let input = value.as_ref();
+        let mut value = String::with_capacity(input.len() * 2);
+        for b in input {
+            value.push(DIGITS[(b >> 4) as usize] as char);
+            value.push(DIGITS[(b & 0xf) as usize] as char);
+        }
+
+        value.serialize(field, serializer)
+    }
+
+    pub fn deserialize<T: From<Vec<u8>>>(
+        into: &mut Option<T>,
+        field: &'static str,
+        deserializer: &mut Deserializer<'_, '_>,
+    ) -> Result<(), Error> {
+        super::deserialize_scalar(into, field, deserializer, |value| {
+            decode(value, field).map(T::from)
+        })
+    }
+
+    pub(crate) fn decode(value: &str, field: &'static str) -> Result<Vec<u8>, Error> {
+        let invalid = || Error::UnexpectedValue(format!("invalid hex `{value}` for {field}"));
+
+        let input = value.trim().as_bytes();
+        if input.len() % 2 != 0 {
+            return Err(invalid());
+        }
+
+        input
+            .chunks(2)
+            .map(|pair| {
+                let high = (pair[0] as char).to_digit(16).ok_or_else(invalid)?;
+                let low = (pair[1] as char).to_digit(16).ok_or_else(invalid)?;
+                Ok((high << 4 | low) as u8)
+            })
+            .collect()
+    }
+
+    const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+}
+
+/// A value along with the namespace and name of the element it was read from
+///
+/// This is mostly useful for fields that accept elements with different names, like `#[xml(any)]`
+/// fields. Serializing writes the value under the stored name, unless the value's type picks its
+/// own name (as derived structs do).
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct WithName<T> {
+    pub ns: String,
+    pub name: String,
+    pub value: T,
+}
+
+impl<T> WithName<T> {
+    pub fn new(id: Id<'_>, value: T) -> Self {
+        Self {
+            ns: id.ns.to_owned(),
+            name: id.name.to_owned(),
+            value,
+        }
+    }
+
+    pub fn id(&self) -> Id<'_> {
+        Id {
+            ns: &self.ns,
+            name: &self.name,
+        }
+    }
+}
+
+impl<T: ToXml> ToXml for WithName<T> {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        _: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        self.value.serialize(Some(self.id()), serializer)
+    }
+
+    fn present(&self) -> bool {
+        self.value.present()
+    }
+}
+
+impl<'xml, T: FromXml<'xml>> FromXml<'xml> for WithName<T> {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        T::matches(id, field)
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        let id = deserializer.parent()?;
+        let mut value = T::Accumulator::default();
+        T::deserialize(&mut value, field, deserializer)?;
+        if let Kind::Scalar = T::KIND {
+            deserializer.ignore()?;
+        }
+
+        *into = Some(Self::new(id, value.try_done(field)?));
+        Ok(())
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = T::KIND;
+}
+
+/// A value along with the byte range in the input of the element it was deserialized from
+///
+/// The span runs from the start of the element's start tag through the end of its end tag.
+/// Serializing writes only the value.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Spanned<T> {
+    pub span: Range<usize>,
+    pub value: T,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(span: Range<usize>, value: T) -> Self {
+        Self { span, value }
+    }
+
+    /// The line and column of the start of the element in `input`
+    pub fn position(&self, input: &str) -> Position {
+        Position::from_offset(input, self.span.start)
+    }
+}
+
+impl<T: ToXml> ToXml for Spanned<T> {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        self.value.serialize(field, serializer)
+    }
+
+    fn present(&self) -> bool {
+        self.value.present()
+    }
+}
+
+impl<'xml, T: FromXml<'xml>> FromXml<'xml> for Spanned<T> {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        T::matches(id, field)
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        let start = deserializer.element_offset();
+        let mut value = T::Accumulator::default();
+        T::deserialize(&mut value, field, deserializer)?;
+        deserializer.ignore()?;
+
+        let span = start..deserializer.offset();
+        *into = Some(Self::new(span, value.try_done(field)?));
+        Ok(())
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = T::KIND;
+}
+
+fn scalar_matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+    match field {
+        Some(field) => id == field,
+        None => false,
+    }
+}
+
+fn deserialize_scalar<T>(
+    into: &mut Option<T>,
+    field: &'static str,
+    deserializer: &mut Deserializer<'_, '_>,
+    parse: impl FnOnce(&str) -> Result<T, Error>,
+) -> Result<(), Error> {
+    if into.is_some() {
+        return Err(Error::DuplicateValue(field));
+    }
+
+    let value = deserializer.take_str()?.unwrap_or_default();
+    *into = Some(parse(&value)?);
+    Ok(())
+}
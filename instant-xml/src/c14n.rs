@@ -0,0 +1,377 @@
+//! Exclusive XML Canonicalization (without comments), as used to sign XML with XML-DSig
+//!
+//! See <https://www.w3.org/TR/xml-exc-c14n/>. Namespace declarations are only written on the
+//! elements that use them (or whose prefix is in the `InclusiveNamespaces` prefix list), unless an
+//! output ancestor already declared the same namespace. Namespace declarations and attributes are
+//! sorted, empty elements are written with a start and end tag, and character and entity
+//! references are replaced by the characters they stand for.
+
+use xmlparser::{ElementEnd, Token, Tokenizer};
+
+use crate::de::decode;
+use crate::ser::XML_NS;
+use crate::{Error, Id};
+
+/// Canonicalize the document element of `input`
+///
+/// `inclusive_prefixes` is the `InclusiveNamespaces` prefix list: declarations of these prefixes
+/// are written wherever they are in scope, even if not used. `#default` names the default
+/// namespace.
+pub fn exclusive(input: &str, inclusive_prefixes: &[&str]) -> Result<String, Error> {
+    let mut canonicalizer = Canonicalizer::new(inclusive_prefixes, None);
+    canonicalizer.run(input)?;
+    Ok(canonicalizer.output)
+}
+
+/// Canonicalize the first element named `id` in `input`, such as the SOAP `Body` of an envelope
+///
+/// Namespaces declared on the element's ancestors are in scope for the element, so its output
+/// includes the declarations it uses. Returns `None` if there is no such element.
+pub fn exclusive_element(
+    input: &str,
+    id: Id<'_>,
+    inclusive_prefixes: &[&str],
+) -> Result<Option<String>, Error> {
+    let mut canonicalizer = Canonicalizer::new(inclusive_prefixes, Some(id));
+    match canonicalizer.run(input)? {
+        true => Ok(Some(canonicalizer.output)),
+        false => Ok(None),
+    }
+}
+
+struct Canonicalizer<'a> {
+    inclusive_prefixes: &'a [&'a str],
+    select: Option<Id<'a>>,
+    output: String,
+    /// The open elements, innermost last
+    stack: Vec<Frame<'a>>,
+    /// The stack depth of the outermost element being written, if any
+    start: Option<usize>,
+    /// Whether the selected element (or the document element) has been written
+    done: bool,
+    /// The element that was started but of which the attributes are still being read
+    element: Option<Start<'a>>,
+}
+
+impl<'a> Canonicalizer<'a> {
+    fn new(inclusive_prefixes: &'a [&'a str], select: Option<Id<'a>>) -> Self {
+        Self {
+            inclusive_prefixes,
+            select,
+            output: String::new(),
+            stack: Vec::new(),
+            start: None,
+            done: false,
+            element: None,
+        }
+    }
+
+    fn run(&mut self, input: &'a str) -> Result<bool, Error> {
+        for token in Tokenizer::from(input) {
+            match token? {
+                Token::ElementStart { prefix, local, .. } => {
+                    self.element = Some(Start {
+                        prefix: prefix.as_str(),
+                        local: local.as_str(),
+                        declared: Vec::new(),
+                        attributes: Vec::new(),
+                    });
+                }
+                Token::Attribute {
+                    prefix,
+                    local,
+                    value,
+                    ..
+                } => {
+                    let element = self.element.as_mut().ok_or(Error::UnexpectedState(
+                        "attribute outside of an element start",
+                    ))?;
+
+                    match (prefix.as_str(), local.as_str()) {
+                        ("xmlns", prefix) => element.declared.push((prefix, value.as_str())),
+                        ("", "xmlns") => element.declared.push(("", value.as_str())),
+                        (prefix, local) => element.attributes.push((prefix, local, value.as_str())),
+                    }
+                }
+                Token::ElementEnd {
+                    end: ElementEnd::Open,
+                    ..
+                } => self.open()?,
+                Token::ElementEnd {
+                    end: ElementEnd::Empty,
+                    ..
+                } => {
+                    self.open()?;
+                    self.close();
+                }
+                Token::ElementEnd {
+                    end: ElementEnd::Close(..),
+                    ..
+                } => self.close(),
+                Token::Text { text } if self.writing() => {
+                    let text = normalize_newlines(text.as_str());
+                    escape_text(&decode(&text)?, &mut self.output);
+                }
+                Token::Cdata { text, .. } if self.writing() => {
+                    escape_text(&normalize_newlines(text.as_str()), &mut self.output);
+                }
+                Token::ProcessingInstruction {
+                    target, content, ..
+                } if self.writing() || (self.select.is_none() && self.stack.is_empty()) => {
+                    // Outside the document element, processing instructions are separated from it
+                    // by a line break
+                    let outside = self.stack.is_empty();
+                    if outside && self.done {
+                        self.output.push('\n');
+                    }
+
+                    self.output.push_str("<?");
+                    self.output.push_str(target.as_str());
+                    if let Some(content) = content {
+                        self.output.push(' ');
+                        self.output.push_str(&normalize_newlines(content.as_str()));
+                    }
+
+                    self.output.push_str("?>");
+                    if outside && !self.done {
+                        self.output.push('\n');
+                    }
+                }
+                _ => {}
+            }
+
+            if self.done && self.select.is_some() {
+                break;
+            }
+        }
+
+        Ok(self.done)
+    }
+
+    fn open(&mut self) -> Result<(), Error> {
+        let start = self
+            .element
+            .take()
+            .ok_or(Error::UnexpectedState("element end without a start"))?;
+
+        self.stack.push(Frame {
+            prefix: start.prefix,
+            local: start.local,
+            declared: start.declared,
+            rendered: Vec::new(),
+        });
+
+        if self.start.is_none() && !self.done {
+            let selected = match self.select {
+                Some(id) => id.name == start.local && id.ns == self.lookup(start.prefix)?,
+                None => true,
+            };
+
+            if selected {
+                self.start = Some(self.stack.len());
+            }
+        }
+
+        if !self.writing() {
+            return Ok(());
+        }
+
+        // The namespaces visibly utilized by the element, and the inclusive prefixes
+        let mut prefixes = vec![start.prefix];
+        for (prefix, ..) in &start.attributes {
+            if !prefix.is_empty() && *prefix != "xml" && !prefixes.contains(prefix) {
+                prefixes.push(prefix);
+            }
+        }
+
+        for &prefix in self.inclusive_prefixes {
+            let prefix = match prefix {
+                "#default" => "",
+                prefix => prefix,
+            };
+
+            if !prefixes.contains(&prefix) && self.declared(prefix).is_some() {
+                prefixes.push(prefix);
+            }
+        }
+
+        let mut rendered = Vec::new();
+        for prefix in prefixes {
+            if prefix == "xml" {
+                continue;
+            }
+
+            let ns = self.lookup(prefix)?;
+            let previous = self.rendered(prefix);
+            match (previous, prefix) {
+                (Some(previous), _) if previous == ns => continue,
+                (None, "") if ns.is_empty() => continue,
+                _ => rendered.push((prefix, ns)),
+            }
+        }
+
+        rendered.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+        let mut attributes = Vec::with_capacity(start.attributes.len());
+        for (prefix, local, value) in &start.attributes {
+            let ns = match *prefix {
+                "" => "",
+                prefix => self.lookup(prefix)?,
+            };
+
+            attributes.push((ns, *prefix, *local, *value));
+        }
+
+        attributes.sort_unstable_by(|a, b| (a.0, a.2).cmp(&(b.0, b.2)));
+
+        self.output.push('<');
+        write_name(start.prefix, start.local, &mut self.output);
+        for (prefix, ns) in &rendered {
+            match *prefix {
+                "" => self.output.push_str(" xmlns=\""),
+                prefix => {
+                    self.output.push_str(" xmlns:");
+                    self.output.push_str(prefix);
+                    self.output.push_str("=\"");
+                }
+            }
+
+            escape_attribute(ns, &mut self.output);
+            self.output.push('"');
+        }
+
+        for (_, prefix, local, value) in attributes {
+            self.output.push(' ');
+            write_name(prefix, local, &mut self.output);
+            self.output.push_str("=\"");
+            let value = normalize_attribute(value);
+            escape_attribute(&decode(&value)?, &mut self.output);
+            self.output.push('"');
+        }
+
+        self.output.push('>');
+        if let Some(frame) = self.stack.last_mut() {
+            frame.rendered = rendered;
+        }
+
+        Ok(())
+    }
+
+    fn close(&mut self) {
+        if self.writing() {
+            if let Some(frame) = self.stack.last() {
+                self.output.push_str("</");
+                write_name(frame.prefix, frame.local, &mut self.output);
+                self.output.push('>');
+            }
+        }
+
+        if self.start == Some(self.stack.len()) {
+            self.start = None;
+            self.done = true;
+        }
+
+        self.stack.pop();
+    }
+
+    fn writing(&self) -> bool {
+        self.start.is_some()
+    }
+
+    /// The namespace bound to `prefix` in the input, if it is declared
+    fn declared(&self, prefix: &str) -> Option<&'a str> {
+        self.stack.iter().rev().find_map(|frame| {
+            frame
+                .declared
+                .iter()
+                .find(|(declared, _)| *declared == prefix)
+                .map(|(_, ns)| *ns)
+        })
+    }
+
+    /// The namespace bound to `prefix` in the input, which must be declared unless it is empty
+    fn lookup(&self, prefix: &str) -> Result<&'a str, Error> {
+        match (prefix, self.declared(prefix)) {
+            ("xml", _) => Ok(XML_NS),
+            (_, Some(ns)) => Ok(ns),
+            ("", None) => Ok(""),
+            (prefix, None) => Err(Error::UnknownPrefix(prefix.to_owned())),
+        }
+    }
+
+    /// The namespace bound to `prefix` by the nearest output ancestor that declares it
+    fn rendered(&self, prefix: &str) -> Option<&'a str> {
+        let start = self.start?;
+        self.stack[start - 1..self.stack.len() - 1]
+            .iter()
+            .rev()
+            .find_map(|frame| {
+                frame
+                    .rendered
+                    .iter()
+                    .find(|(rendered, _)| *rendered == prefix)
+                    .map(|(_, ns)| *ns)
+            })
+    }
+}
+
+struct Start<'a> {
+    prefix: &'a str,
+    local: &'a str,
+    declared: Vec<(&'a str, &'a str)>,
+    attributes: Vec<(&'a str, &'a str, &'a str)>,
+}
+
+struct Frame<'a> {
+    prefix: &'a str,
+    local: &'a str,
+    /// Namespace declarations in the input, as `(prefix, namespace)`
+    declared: Vec<(&'a str, &'a str)>,
+    /// Namespace declarations written to the output
+    rendered: Vec<(&'a str, &'a str)>,
+}
+
+fn write_name(prefix: &str, local: &str, output: &mut String) {
+    if !prefix.is_empty() {
+        output.push_str(prefix);
+        output.push(':');
+    }
+
+    output.push_str(local);
+}
+
+/// Replace line breaks in `input` by `\n`, as an XML processor does when reading
+fn normalize_newlines(input: &str) -> String {
+    input.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Normalize line breaks and whitespace in an attribute value, before replacing references
+fn normalize_attribute(input: &str) -> String {
+    normalize_newlines(input).replace(['\t', '\n'], " ")
+}
+
+fn escape_text(input: &str, output: &mut String) {
+    for c in input.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '\r' => output.push_str("&#xD;"),
+            c => output.push(c),
+        }
+    }
+}
+
+fn escape_attribute(input: &str, output: &mut String) {
+    for c in input.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '"' => output.push_str("&quot;"),
+            '\t' => output.push_str("&#x9;"),
+            '\n' => output.push_str("&#xA;"),
+            '\r' => output.push_str("&#xD;"),
+            c => output.push(c),
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Mutex, RwLock};
+
+use crate::{Accumulate, Deserializer, Error, FromXml, Id, Kind, Serializer, ToXml};
+
+/// Accumulates the inner value of a `RefCell`, `Mutex` or `RwLock`, wrapping it when done
+pub struct CellAccumulator<T, A: Accumulate<T>> {
+    value: A,
+    marker: PhantomData<T>,
+}
+
+impl<T, A: Accumulate<T>> CellAccumulator<T, A> {
+    pub fn get_mut(&mut self) -> &mut A {
+        &mut self.value
+    }
+}
+
+impl<T, A: Accumulate<T>> Default for CellAccumulator<T, A> {
+    fn default() -> Self {
+        Self {
+            value: A::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+macro_rules! cell_impls {
+    ($cell:ident, |$this:ident| $inner:expr) => {
+        impl<T: ToXml + ?Sized> ToXml for $cell<T> {
+            fn serialize<W: fmt::Write + ?Sized>(
+                &self,
+                field: Option<Id<'_>>,
+                serializer: &mut Serializer<W>,
+            ) -> Result<(), Error> {
+                let $this = self;
+                let inner = $inner?;
+                inner.serialize(field, serializer)
+            }
+
+            fn present(&self) -> bool {
+                let $this = self;
+                match $inner {
+                    Ok(inner) => inner.present(),
+                    Err(_) => true,
+                }
+            }
+        }
+
+        impl<'xml, T: FromXml<'xml>> FromXml<'xml> for $cell<T> {
+            #[inline]
+            fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+                T::matches(id, field)
+            }
+
+            fn deserialize<'cx>(
+                into: &mut Self::Accumulator,
+                field: &'static str,
+                deserializer: &mut Deserializer<'cx, 'xml>,
+            ) -> Result<(), Error> {
+                T::deserialize(&mut into.value, field, deserializer)
+            }
+
+            type Accumulator = CellAccumulator<T, T::Accumulator>;
+            const KIND: Kind = T::KIND;
+        }
+
+        impl<T, A: Accumulate<T>> Accumulate<$cell<T>> for CellAccumulator<T, A> {
+            fn try_done(self, field: &'static str) -> Result<$cell<T>, Error> {
+                Ok($cell::new(self.value.try_done(field)?))
+            }
+        }
+    };
+}
+
+// Serializing borrows or locks the value for the duration of the call; a `Mutex` or `RwLock`
+// already locked (for writing) by the current thread will deadlock.
+cell_impls!(RefCell, |cell| cell.try_borrow().map_err(|_| Error::Other(
+    "value is already mutably borrowed".into()
+)));
+cell_impls!(Mutex, |cell| cell
+    .lock()
+    .map_err(|_| Error::Other("mutex is poisoned".into())));
+cell_impls!(RwLock, |cell| cell
+    .read()
+    .map_err(|_| Error::Other("lock is poisoned".into())));
@@ -1,42 +1,72 @@
 use std::borrow::Cow;
 use std::collections::{BTreeMap, VecDeque};
+use std::marker::PhantomData;
+use std::ops::Range;
 use std::str::{self, FromStr};
 
 use xmlparser::{ElementEnd, Token, Tokenizer};
 
 use crate::impls::CowStrAccumulator;
-use crate::{Error, Id};
+use crate::xsd::XSI;
+use crate::{Accumulate, Error, FromXml, Id, OptionAccumulator, Position};
 
 pub struct Deserializer<'cx, 'xml> {
     pub(crate) local: &'xml str,
     prefix: Option<&'xml str>,
     level: usize,
     done: bool,
+    /// Keep whitespace around text, regardless of [`Options::trim_text`]
+    preserve_space: bool,
     context: &'cx mut Context<'xml>,
 }
 
 impl<'cx, 'xml> Deserializer<'cx, 'xml> {
     pub(crate) fn new(element: Element<'xml>, context: &'cx mut Context<'xml>) -> Self {
         let level = context.stack.len();
-        if !element.empty {
-            context.stack.push(element.level);
-        }
+        context.stack.push(element.level);
 
         Self {
             local: element.local,
             prefix: element.prefix,
             level,
             done: false,
+            preserve_space: false,
             context,
         }
     }
 
+    /// Continue reading the content of the root element, which `new()` entered before
+    fn resume(
+        local: &'xml str,
+        prefix: Option<&'xml str>,
+        context: &'cx mut Context<'xml>,
+    ) -> Self {
+        Self {
+            local,
+            prefix,
+            level: 0,
+            done: false,
+            preserve_space: false,
+            context,
+        }
+    }
+
+    /// The options this document is deserialized with
+    pub fn options(&self) -> &Options {
+        &self.context.options
+    }
+
     pub fn take_str(&mut self) -> Result<Option<Cow<'xml, str>>, Error> {
         loop {
             match self.next() {
                 Some(Ok(Node::AttributeValue(s))) => return Ok(Some(s)),
-                Some(Ok(Node::Text(s))) => return Ok(Some(s)),
-                Some(Ok(Node::Attribute(_))) => continue,
+                Some(Ok(Node::Text(s))) => return Ok(Some(self.trim(s))),
+                Some(Ok(Node::Attribute(attr))) => match self.context.attribute_id(&attr) {
+                    Ok(id) if id == NIL && is_true(&attr.value) => {
+                        return Err(Error::UnexpectedNil)
+                    }
+                    _ => continue,
+                },
                 Some(Ok(node)) => return Err(Error::ExpectedScalar(format!("{node:?}"))),
                 Some(Err(e)) => return Err(e),
                 None => return Ok(None),
@@ -48,7 +78,30 @@ impl<'cx, 'xml> Deserializer<'cx, 'xml> {
     where
         'cx: 'a,
     {
-        Deserializer::new(element, self.context)
+        let preserve_space = self.preserve_space;
+        let mut nested = Deserializer::new(element, self.context);
+        nested.preserve_space = preserve_space;
+        nested
+    }
+
+    /// Keep leading and trailing whitespace in text read from this element and its children
+    ///
+    /// This overrides [`Options::trim_text`], for fields marked `#[xml(preserve_space)]`.
+    pub fn preserve_space(&mut self) {
+        self.preserve_space = true;
+    }
+
+    /// Trim whitespace around `text` if the options ask for it, unless it is preserved here
+    fn trim(&self, text: Cow<'xml, str>) -> Cow<'xml, str> {
+        if !self.context.options.trim_text || self.preserve_space || self.context.preserve_space() {
+            return text;
+        }
+
+        match text {
+            Cow::Borrowed(text) => Cow::Borrowed(text.trim()),
+            Cow::Owned(text) if text.trim().len() == text.len() => Cow::Owned(text),
+            Cow::Owned(text) => Cow::Owned(text.trim().to_owned()),
+        }
     }
 
     pub fn ignore(&mut self) -> Result<(), Error> {
@@ -75,18 +128,25 @@ impl<'cx, 'xml> Deserializer<'cx, 'xml> {
             prefix: self.prefix,
             level: self.level,
             done: self.done,
+            preserve_space: self.preserve_space,
             context: self.context,
         }
     }
 
-    pub fn parent(&self) -> Id<'xml> {
-        Id {
+    /// The name of the element being deserialized
+    ///
+    /// Fails with [`Error::UnknownPrefix`] if the element's prefix is not declared.
+    pub fn parent(&self) -> Result<Id<'xml>, Error> {
+        Ok(Id {
             ns: match self.prefix {
-                Some(ns) => self.context.lookup(ns).unwrap(),
+                Some(prefix) => self
+                    .context
+                    .lookup(prefix)
+                    .ok_or_else(|| Error::UnknownPrefix(prefix.to_owned()))?,
                 None => self.context.default_ns(),
             },
             name: self.local,
-        }
+        })
     }
 
     #[inline]
@@ -98,6 +158,236 @@ impl<'cx, 'xml> Deserializer<'cx, 'xml> {
     pub fn attribute_id(&self, attr: &Attribute<'xml>) -> Result<Id<'xml>, Error> {
         self.context.attribute_id(attr)
     }
+
+    /// Resolve a qualified name like `xs:string` in the current element's content or attributes
+    ///
+    /// The prefix is looked up in the namespace declarations in scope; unprefixed names are in
+    /// the default namespace.
+    pub fn resolve_qname<'a>(&self, qname: &'a str) -> Result<Id<'a>, Error>
+    where
+        'xml: 'a,
+    {
+        Ok(match qname.split_once(':') {
+            Some((prefix, name)) => Id {
+                ns: self
+                    .context
+                    .lookup(prefix)
+                    .ok_or_else(|| Error::UnknownPrefix(prefix.to_owned()))?,
+                name,
+            },
+            None => Id {
+                ns: self.context.default_ns(),
+                name: qname,
+            },
+        })
+    }
+
+    /// Take the value of the attribute `id` of the current element
+    ///
+    /// Attributes are queued when an element is opened, so this must be called before reading
+    /// any other nodes. The attribute is not yielded again by [`Deserializer::next()`].
+    pub fn take_attribute(&mut self, id: Id<'_>) -> Result<Option<Cow<'xml, str>>, Error> {
+        for i in 0..self.context.records.len() {
+            let attr = match &self.context.records[i] {
+                Node::Attribute(attr) => attr,
+                _ => break,
+            };
+
+            if self.context.attribute_id(attr)? == id {
+                return match self.context.records.remove(i) {
+                    Some(Node::Attribute(attr)) => Ok(Some(attr.value)),
+                    _ => unreachable!(),
+                };
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Take the `xsi:nil` attribute of the current element, returning whether it is true
+    ///
+    /// Like [`Deserializer::take_attribute()`], this must be called before reading any other
+    /// nodes.
+    pub fn nil(&mut self) -> Result<bool, Error> {
+        Ok(match self.take_attribute(NIL)? {
+            Some(value) => is_true(&value),
+            None => false,
+        })
+    }
+
+    /// The byte offset in the input of the start tag of the current element
+    pub fn element_offset(&self) -> usize {
+        self.context
+            .stack
+            .get(self.level)
+            .map_or(0, |level| level.offset)
+    }
+
+    /// The byte offset in the input just past the last token read
+    pub fn offset(&self) -> usize {
+        self.context.span.end
+    }
+
+    /// Take the inner XML of the current element, exactly as it appears in the input
+    ///
+    /// This must be called before reading any other nodes; it consumes the element through its
+    /// closing tag.
+    pub fn take_raw(&mut self) -> Result<&'xml str, Error> {
+        let start = self.context.span.end;
+        self.ignore()?;
+        let end = self.context.span.start;
+        Ok(self.context.input.get(start..end).unwrap_or_default())
+    }
+
+    /// Iterate over the child elements matching `T`, deserializing each one as it is read
+    ///
+    /// Child elements that `T` does not match are skipped, as are text and attributes, so only
+    /// one value is held in memory at a time. Iteration ends after the first error.
+    pub fn iter<'a, T: FromXml<'xml>>(&'a mut self) -> Iter<'a, 'cx, 'xml, T> {
+        Iter {
+            deserializer: self,
+            failed: false,
+            marker: PhantomData,
+        }
+    }
+
+    /// Skip to the first descendant at `path` (a list of local names) and deserialize it as `T`
+    ///
+    /// Returns `None` if there is no such element. Reading stops as soon as the value is
+    /// deserialized, so the rest of the input is not parsed.
+    pub(crate) fn extract<T: FromXml<'xml>>(&mut self, path: &[&str]) -> Result<Option<T>, Error> {
+        let (first, rest) = match path.split_first() {
+            Some(split) => split,
+            None => {
+                let mut value = T::Accumulator::default();
+                T::deserialize(&mut value, EXTRACTED, self)?;
+                return value.try_done(EXTRACTED).map(Some);
+            }
+        };
+
+        while let Some(node) = self.next() {
+            let element = match node? {
+                Node::Open(element) => element,
+                _ => continue,
+            };
+
+            let matched = element.local == *first;
+            let mut nested = self.nested(element);
+            match matched {
+                true => match nested.extract(rest)? {
+                    Some(value) => return Ok(Some(value)),
+                    None => continue,
+                },
+                false => nested.ignore()?,
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Read up to the next child element matching `T` and deserialize it
+    fn next_element<T: FromXml<'xml>>(&mut self) -> Option<Result<T, Error>> {
+        loop {
+            let element = match self.next()? {
+                Ok(Node::Open(element)) => element,
+                Ok(_) => continue,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let id = match self.element_id(&element) {
+                Ok(id) => id,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let mut nested = self.nested(element);
+            if !T::matches(id, None) {
+                match nested.ignore() {
+                    Ok(()) => continue,
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+
+            let mut value = T::Accumulator::default();
+            let result = T::deserialize(&mut value, CHILD, &mut nested)
+                .and_then(|()| nested.ignore())
+                .and_then(|()| value.try_done(CHILD));
+            return Some(result);
+        }
+    }
+
+    /// Record a child element that is skipped, if a report is being collected
+    ///
+    /// `known` lists the names of the fields that could have matched, to tell apart elements in
+    /// an unexpected namespace from unknown ones.
+    pub fn skip_element(&mut self, id: Id<'_>, known: &[&str]) {
+        let reason = match known.contains(&id.name) {
+            true => SkipReason::Namespace,
+            false => SkipReason::Unknown,
+        };
+
+        self.skip(id.name, id.ns, reason);
+    }
+
+    /// Record an attribute that is skipped, if a report is being collected
+    pub fn skip_attribute(&mut self, id: Id<'_>, known: &[&str]) {
+        let reason = match known.contains(&id.name) {
+            true => SkipReason::Namespace,
+            false => SkipReason::Unknown,
+        };
+
+        self.skip(&format!("@{}", id.name), id.ns, reason);
+    }
+
+    /// Record `error` from reading `field` and skip the rest of the field's element, if
+    /// collecting errors
+    ///
+    /// Otherwise, or if the error is not recoverable, `error` is returned.
+    pub fn recover(&mut self, error: Error, field: &'static str) -> Result<(), Error> {
+        self.context.record(error)?;
+        let offset = self.element_offset();
+        self.context.failed.push((offset, field));
+        while self.context.stack.len() > self.level + 1 {
+            match self.context.next() {
+                Some(Ok(Node::Open(element))) => self.context.stack.push(element.level),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e),
+                None => return Err(Error::UnexpectedEndOfStream),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Record text content that is skipped, if a report is being collected
+    ///
+    /// Whitespace-only text is not reported.
+    pub fn skip_text(&mut self, text: &str) {
+        if !text.trim().is_empty() {
+            self.skip("text()", "", SkipReason::Text);
+        }
+    }
+
+    fn skip(&mut self, name: &str, ns: &str, reason: SkipReason) {
+        let report = match &mut self.context.report {
+            Some(report) => report,
+            None => return,
+        };
+
+        let mut path = String::new();
+        for level in &self.context.stack[..self.level] {
+            path.push_str(level.local);
+            path.push('/');
+        }
+
+        path.push_str(self.local);
+        path.push('/');
+        path.push_str(name);
+        report.push(Skipped {
+            path,
+            ns: ns.to_owned(),
+            reason,
+        });
+    }
 }
 
 impl<'xml> Iterator for Deserializer<'_, 'xml> {
@@ -122,29 +412,361 @@ impl<'xml> Iterator for Deserializer<'_, 'xml> {
     }
 }
 
+/// Iterator over the child elements of an element, from [`Deserializer::iter()`]
+pub struct Iter<'a, 'cx, 'xml, T> {
+    deserializer: &'a mut Deserializer<'cx, 'xml>,
+    failed: bool,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'xml, T: FromXml<'xml>> Iterator for Iter<'_, '_, 'xml, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let item = self.deserializer.next_element();
+        self.failed = matches!(item, Some(Err(_)));
+        item
+    }
+}
+
+/// Iterator over the children of the root element of a document, from
+/// [`from_str_iter()`](crate::from_str_iter)
+pub struct DocumentIter<'xml, T> {
+    context: Context<'xml>,
+    /// The local name and prefix of the root element, once it has been read
+    root: Option<(&'xml str, Option<&'xml str>)>,
+    done: bool,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'xml, T> DocumentIter<'xml, T> {
+    pub(crate) fn new(input: &'xml str) -> Self {
+        Self {
+            context: Context::new(input),
+            root: None,
+            done: false,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'xml, T: FromXml<'xml>> DocumentIter<'xml, T> {
+    fn read(&mut self) -> Option<Result<T, Error>> {
+        let mut deserializer = match self.root {
+            Some((local, prefix)) => Deserializer::resume(local, prefix, &mut self.context),
+            None => {
+                let element = match self.context.root() {
+                    Ok(element) => element,
+                    Err(e) => return Some(Err(e)),
+                };
+
+                self.root = Some((element.local, element.prefix));
+                Deserializer::new(element, &mut self.context)
+            }
+        };
+
+        deserializer.next_element()
+    }
+}
+
+impl<'xml, T: FromXml<'xml>> Iterator for DocumentIter<'xml, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let item = self.read();
+        self.done = !matches!(item, Some(Ok(_)));
+        item.map(|result| result.map_err(|error| self.context.locate(error)))
+    }
+}
+
+/// Iterator over the root elements of a fragment, from
+/// [`from_str_fragments()`](crate::from_str_fragments)
+pub struct Fragments<'xml, T> {
+    context: Context<'xml>,
+    done: bool,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'xml, T> Fragments<'xml, T> {
+    pub(crate) fn new(input: &'xml str) -> Self {
+        Self {
+            context: Context::fragment(input),
+            done: false,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'xml, T: FromXml<'xml>> Fragments<'xml, T> {
+    fn read(&mut self) -> Option<Result<T, Error>> {
+        loop {
+            return Some(match self.context.next()? {
+                Ok(Node::Open(element)) => crate::deserialize_element(&mut self.context, element),
+                Ok(Node::Text(text)) if text.trim().is_empty() => continue,
+                Ok(node) => Err(Error::UnexpectedNode(format!("{node:?}"))),
+                Err(e) => Err(e),
+            });
+        }
+    }
+}
+
+impl<'xml, T: FromXml<'xml>> Iterator for Fragments<'xml, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let item = self.read();
+        self.done = !matches!(item, Some(Ok(_)));
+        item.map(|result| result.map_err(|error| self.context.locate(error)))
+    }
+}
+
+/// The field name used in errors for the element read by [`extract()`](crate::extract)
+const EXTRACTED: &str = "<extracted element>";
+
+/// The field name used in errors for elements read by [`Iter`] and [`DocumentIter`]
+const CHILD: &str = "<child element>";
+
+const NIL: Id<'static> = Id {
+    ns: XSI,
+    name: "nil",
+};
+
+/// Whether `value` is a true `xs:boolean`
+fn is_true(value: &str) -> bool {
+    matches!(value.trim(), "true" | "1")
+}
+
+/// Deserializes the fields of a struct flattened into its parent element
+///
+/// Derived for transparent structs with named fields, and for other structs with named fields
+/// unless they declare namespace prefixes, take their name from a field, or have fields that
+/// need an element of their own (like `direct`, `any`, `flatten`, `range` or `wrapper`).
+pub trait FromXmlFlatten<'xml>: FromXml<'xml> {
+    /// Accumulates the flattened fields found in the parent element
+    type Fields: Accumulate<Self>;
+
+    fn matches_element(id: Id<'_>) -> bool;
+
+    fn deserialize_element<'cx>(
+        into: &mut Self::Fields,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error>;
+
+    fn matches_attribute(id: Id<'_>) -> bool;
+
+    fn deserialize_attribute<'cx>(
+        into: &mut Self::Fields,
+        id: Id<'_>,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error>;
+}
+
+/// Options for [`from_str_with_options()`](crate::from_str_with_options)
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Options {
+    /// Accept booleans in any case (like `True` or `FALSE`) and surrounded by whitespace
+    pub lenient_bools: bool,
+    /// Trim leading and trailing whitespace from text content
+    ///
+    /// Text inside an element with `xml:space="preserve"` (or a descendant of one) is kept
+    /// as-is, as is the text of fields marked `#[xml(preserve_space)]`. Attribute values are
+    /// never trimmed.
+    pub trim_text: bool,
+    /// Wrap errors in [`Error::Located`], with their position and the path to the element
+    pub positions: bool,
+}
+
+/// Limits on the input accepted by [`from_str_with_limits()`](crate::from_str_with_limits)
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Limits {
+    /// The maximum length in bytes of element and attribute local names
+    pub max_name_len: usize,
+    /// The maximum length in bytes of namespace prefixes
+    pub max_prefix_len: usize,
+    /// The maximum nesting depth of elements
+    ///
+    /// Deserialization recurses for every level, so this defaults to [`Limits::MAX_DEPTH`] to
+    /// keep deeply nested input from overflowing the stack.
+    pub max_depth: usize,
+    /// Reject documents with a document type declaration, rather than skipping it
+    pub reject_dtd: bool,
+    /// The maximum length in bytes of the input
+    pub max_input_len: usize,
+    /// The maximum number of attributes on an element, including namespace declarations
+    pub max_attributes: usize,
+    /// The maximum length in bytes of text content, after decoding entities
+    pub max_text_len: usize,
+    /// The maximum number of distinct names counted in [`NameStats::names`]
+    ///
+    /// This defaults to 0, so only the totals are kept. Names beyond it are still included in
+    /// the totals; it does not reject the input.
+    pub max_counted_names: usize,
+}
+
+impl Limits {
+    /// The default maximum nesting depth, also enforced by [`from_str()`](crate::from_str)
+    pub const MAX_DEPTH: usize = 128;
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_name_len: usize::MAX,
+            max_prefix_len: usize::MAX,
+            max_depth: Self::MAX_DEPTH,
+            reject_dtd: false,
+            max_input_len: usize::MAX,
+            max_attributes: usize::MAX,
+            max_text_len: usize::MAX,
+            max_counted_names: 0,
+        }
+    }
+}
+
+/// Counters for the names read from a document
+///
+/// Names are borrowed from the input, so every distinct name is only stored once.
+/// [`NameStats::names`] is only filled in up to [`Limits::max_counted_names`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct NameStats<'xml> {
+    /// The number of elements
+    pub elements: usize,
+    /// The number of attributes, including namespace declarations
+    pub attributes: usize,
+    /// The length in bytes of the longest local name
+    pub longest: usize,
+    /// The number of times each local name occurs, for the first
+    /// [`Limits::max_counted_names`] distinct names
+    pub names: BTreeMap<&'xml str, usize>,
+}
+
+/// Content ignored during deserialization
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Skipped {
+    /// Path to the skipped node, like `Order/extra`, `Order/@id` or `Order/text()`
+    pub path: String,
+    /// Namespace of the skipped element or attribute
+    pub ns: String,
+    pub reason: SkipReason,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SkipReason {
+    /// No field has this name
+    Unknown,
+    /// A field has this name, but in a different namespace
+    Namespace,
+    /// The type has no field for text content
+    Text,
+}
+
 pub(crate) struct Context<'xml> {
+    input: &'xml str,
     parser: Tokenizer<'xml>,
+    /// The position of the last token read from the input
+    span: Range<usize>,
+    /// The number of attributes read on the current start tag
+    attributes: usize,
+    /// The name and offset of the element closed by the last end tag, if no start tag followed
+    closed: Option<(&'xml str, usize)>,
     stack: Vec<Level<'xml>>,
     records: VecDeque<Node<'xml>>,
+    pub(crate) report: Option<Vec<Skipped>>,
+    /// Errors recovered from, if collecting them
+    pub(crate) errors: Option<Vec<Error>>,
+    /// Fields that failed to deserialize, with the offset of the element they belong to
+    failed: Vec<(usize, &'static str)>,
+    pub(crate) limits: Limits,
+    pub(crate) options: Options,
+    pub(crate) stats: Option<NameStats<'xml>>,
 }
 
 impl<'xml> Context<'xml> {
-    pub(crate) fn new(input: &'xml str) -> Result<(Self, Element<'xml>), Error> {
-        let mut new = Self {
+    pub(crate) fn new(input: &'xml str) -> Self {
+        Self {
+            input,
             parser: Tokenizer::from(input),
+            span: 0..0,
+            attributes: 0,
+            closed: None,
             stack: Vec::new(),
             records: VecDeque::new(),
-        };
+            report: None,
+            errors: None,
+            failed: Vec::new(),
+            limits: Limits::default(),
+            options: Options::default(),
+            stats: None,
+        }
+    }
+
+    /// Read `input` as a document fragment, which may hold any number of root elements
+    pub(crate) fn fragment(input: &'xml str) -> Self {
+        Self {
+            parser: Tokenizer::from_fragment(input, 0..input.len()),
+            ..Self::new(input)
+        }
+    }
+
+    /// Read up to and including the start of the root element
+    pub(crate) fn root(&mut self) -> Result<Element<'xml>, Error> {
+        if self.input.len() > self.limits.max_input_len {
+            return Err(Error::InputTooLong);
+        }
 
-        let root = match new.next() {
+        match self.next() {
             Some(result) => match result? {
-                Node::Open(element) => element,
-                _ => return Err(Error::UnexpectedState("first node does not open element")),
+                Node::Open(element) => Ok(element),
+                _ => Err(Error::UnexpectedState("first node does not open element")),
             },
-            None => return Err(Error::UnexpectedEndOfStream),
+            None => Err(Error::UnexpectedEndOfStream),
+        }
+    }
+
+    /// Check the length of a name read from the input and count it, if collecting statistics
+    fn name(&mut self, prefix: &'xml str, local: &'xml str, attribute: bool) -> Result<(), Error> {
+        // A namespace declaration binds its local name as a prefix
+        let declaration = attribute && prefix == "xmlns";
+        let declared = match declaration {
+            true => local,
+            false => prefix,
         };
 
-        Ok((new, root))
+        if declared.len() > self.limits.max_prefix_len {
+            return Err(Error::PrefixTooLong(self.token_position()));
+        } else if !declaration && local.len() > self.limits.max_name_len {
+            return Err(Error::NameTooLong(self.token_position()));
+        }
+
+        if let Some(stats) = &mut self.stats {
+            match attribute {
+                true => stats.attributes += 1,
+                false => stats.elements += 1,
+            }
+
+            stats.longest = stats.longest.max(local.len());
+            if let Some(count) = stats.names.get_mut(local) {
+                *count += 1;
+            } else if stats.names.len() < self.limits.max_counted_names {
+                stats.names.insert(local, 1);
+            }
+        }
+
+        Ok(())
     }
 
     pub(crate) fn element_id(&self, element: &Element<'xml>) -> Result<Id<'xml>, Error> {
@@ -176,6 +798,111 @@ impl<'xml> Context<'xml> {
         })
     }
 
+    /// Record a recoverable `error` with its position, if collecting errors
+    ///
+    /// A missing value is not recorded if the field failed to deserialize before.
+    pub(crate) fn record(&mut self, error: Error) -> Result<(), Error> {
+        if self.errors.is_none() {
+            return Err(error);
+        }
+
+        match error {
+            error if error.is_fatal() => Err(error),
+            // Missing values are reported once the element they belong to is closed
+            Error::MissingValue(field)
+                if self
+                    .closed
+                    .map_or(false, |(_, offset)| self.failed.contains(&(offset, field))) =>
+            {
+                Ok(())
+            }
+            error => {
+                let error = self.locate(error);
+                if let Some(errors) = &mut self.errors {
+                    errors.push(error);
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Add the position of the last token read to `error`, if the options or collecting errors
+    /// ask for it and the error doesn't already say where it happened
+    pub(crate) fn locate(&self, error: Error) -> Error {
+        if !self.options.positions && self.errors.is_none() || !error.locatable() {
+            return error;
+        }
+
+        Error::Located {
+            error: Box::new(error),
+            position: self.token_position(),
+            path: self.path(),
+        }
+    }
+
+    /// The path to the element being read, like `Envelope > Body > item[3]`
+    ///
+    /// Elements are listed with their index among siblings of the same name, unless they are
+    /// the first. Indices are only needed for errors, so rather than counting the children of
+    /// every element while deserializing, the input leading up to the element is read again.
+    fn path(&self) -> String {
+        let levels = self.stack.iter().map(|level| (level.local, level.offset));
+        let elements = levels.chain(self.closed).collect::<Vec<_>>();
+        let (start, end) = match (elements.first(), elements.last()) {
+            (Some((_, start)), Some((_, end))) => (*start, *end),
+            _ => return String::new(),
+        };
+
+        let mut indices = vec![0; elements.len()];
+        let mut depth = 0;
+        for token in Tokenizer::from_fragment(self.input, start..end) {
+            match token {
+                Ok(Token::ElementStart { local, span, .. }) => {
+                    // Elements at this depth after the start of the current parent are siblings
+                    let parent = match depth {
+                        0 => None,
+                        _ => elements.get(depth - 1),
+                    };
+
+                    if let (Some((_, parent)), Some((name, offset))) = (parent, elements.get(depth))
+                    {
+                        let start = span.start();
+                        if start > *parent && start < *offset && local.as_str() == *name {
+                            indices[depth] += 1;
+                        }
+                    }
+                }
+                Ok(Token::ElementEnd { end, .. }) => match end {
+                    ElementEnd::Open => depth += 1,
+                    ElementEnd::Close(..) => depth -= 1,
+                    ElementEnd::Empty => {}
+                },
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+
+        let mut path = String::new();
+        for ((local, _), index) in elements.into_iter().zip(indices) {
+            if !path.is_empty() {
+                path.push_str(" > ");
+            }
+
+            path.push_str(local);
+            if index > 0 {
+                path.push_str(&format!("[{index}]"));
+            }
+        }
+
+        path
+    }
+
+    /// The position of the last token read from the input
+    fn token_position(&self) -> Position {
+        Position::from_offset(self.input, self.span.start)
+    }
+
     fn default_ns(&self) -> &'xml str {
         self.stack
             .iter()
@@ -184,6 +911,40 @@ impl<'xml> Context<'xml> {
             .unwrap_or("")
     }
 
+    /// Join text split up by comments or processing instructions
+    fn text(&mut self, mut text: Cow<'xml, str>) -> Result<Cow<'xml, str>, Error> {
+        loop {
+            if text.len() > self.limits.max_text_len {
+                return Err(Error::TextTooLong(self.token_position()));
+            }
+
+            let mut parser = self.parser.clone();
+            let (span, next) = match parser.next() {
+                Some(Ok(
+                    Token::Comment { span, .. } | Token::ProcessingInstruction { span, .. },
+                )) => (span, None),
+                Some(Ok(Token::Text { text })) => (text, Some(decode(text.as_str())?)),
+                Some(Ok(Token::Cdata { span, text })) => (span, Some(Cow::Borrowed(text.as_str()))),
+                _ => return Ok(text),
+            };
+
+            self.parser = parser;
+            self.span.end = span.end();
+            if let Some(next) = next {
+                text.to_mut().push_str(&next);
+            }
+        }
+    }
+
+    /// Whether the innermost `xml:space` attribute in scope asks to preserve whitespace
+    fn preserve_space(&self) -> bool {
+        self.stack
+            .iter()
+            .rev()
+            .find_map(|level| level.preserve_space)
+            .unwrap_or(false)
+    }
+
     fn lookup(&self, prefix: &str) -> Option<&'xml str> {
         // The prefix xml is by definition bound to the namespace
         // name http://www.w3.org/XML/1998/namespace
@@ -203,28 +964,51 @@ impl<'xml> Iterator for Context<'xml> {
     type Item = Result<Node<'xml>, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        // Missing values in the element closed last have been checked by now, so the failures
+        // recorded for it and its descendants are no longer needed
+        if let (Some((_, offset)), false) = (self.closed, self.failed.is_empty()) {
+            self.failed.retain(|(start, _)| *start < offset);
+        }
+
         if let Some(record) = self.records.pop_front() {
+            // The close node of an empty element is queued when it is opened
+            if let Node::Close { .. } = record {
+                self.closed = self.stack.pop().map(|level| (level.local, level.offset));
+            }
+
             return Some(Ok(record));
         }
 
         let mut current = None;
         loop {
-            let token = match self.parser.next() {
-                Some(v) => v,
-                None => return None,
-            };
+            let token = self.parser.next()?;
+            if let Ok(token) = &token {
+                self.span = token.span().range();
+            }
 
             match token {
                 Ok(Token::ElementStart { prefix, local, .. }) => {
+                    if self.stack.len() >= self.limits.max_depth {
+                        return Some(Err(Error::NestingTooDeep(self.token_position())));
+                    }
+
                     let prefix = prefix.as_str();
+                    if let Err(e) = self.name(prefix, local.as_str(), false) {
+                        return Some(Err(e));
+                    }
+
+                    self.closed = None;
+                    self.attributes = 0;
                     current = Some(Level {
                         local: local.as_str(),
+                        offset: self.span.start,
                         prefix: match prefix.is_empty() {
                             true => None,
                             false => Some(prefix),
                         },
                         default_ns: None,
                         prefixes: BTreeMap::new(),
+                        preserve_space: None,
                     });
                 }
                 Ok(Token::ElementEnd { end, .. }) => match end {
@@ -243,7 +1027,6 @@ impl<'xml> Iterator for Context<'xml> {
                             prefix: level.prefix,
                             default_ns: level.default_ns,
                             level,
-                            empty: false,
                         };
 
                         return Some(Ok(Node::Open(element)));
@@ -258,6 +1041,8 @@ impl<'xml> Iterator for Context<'xml> {
                             }
                         };
 
+                        self.closed = Some((level.local, level.offset));
+
                         let prefix = match prefix.is_empty() {
                             true => None,
                             false => Some(prefix.as_str()),
@@ -295,7 +1080,6 @@ impl<'xml> Iterator for Context<'xml> {
                             prefix: level.prefix,
                             default_ns: level.default_ns,
                             level,
-                            empty: true,
                         };
 
                         return Some(Ok(Node::Open(element)));
@@ -307,6 +1091,15 @@ impl<'xml> Iterator for Context<'xml> {
                     value,
                     ..
                 }) => {
+                    if let Err(e) = self.name(prefix.as_str(), local.as_str(), true) {
+                        return Some(Err(e));
+                    }
+
+                    self.attributes += 1;
+                    if self.attributes > self.limits.max_attributes {
+                        return Some(Err(Error::TooManyAttributes(self.token_position())));
+                    }
+
                     if prefix.is_empty() && local.as_str() == "xmlns" {
                         match &mut current {
                             Some(level) => level.default_ns = Some(value.as_str()),
@@ -333,6 +1126,12 @@ impl<'xml> Iterator for Context<'xml> {
                             Err(e) => return Some(Err(e)),
                         };
 
+                        if prefix.as_str() == "xml" && local.as_str() == "space" {
+                            if let Some(level) = &mut current {
+                                level.preserve_space = Some(value == "preserve");
+                            }
+                        }
+
                         self.records.push_back(Node::Attribute(Attribute {
                             prefix: match prefix.is_empty() {
                                 true => None,
@@ -344,23 +1143,58 @@ impl<'xml> Iterator for Context<'xml> {
                     }
                 }
                 Ok(Token::Text { text }) => {
-                    return Some(decode(text.as_str()).map(Node::Text));
+                    return Some(
+                        decode(text.as_str())
+                            .and_then(|text| self.text(text))
+                            .map(Node::Text),
+                    );
                 }
                 Ok(Token::Cdata { text, .. }) => {
-                    return Some(Ok(Node::Text(Cow::Borrowed(text.as_str()))));
+                    return Some(self.text(Cow::Borrowed(text.as_str())).map(Node::Text));
                 }
-                Ok(Token::Declaration { .. }) => match self.stack.is_empty() {
-                    false => return Some(Err(Error::UnexpectedToken(format!("{token:?}")))),
-                    true => {}
-                },
-                Ok(Token::Comment { .. }) => continue,
-                Ok(token) => return Some(Err(Error::UnexpectedToken(format!("{token:?}")))),
+                Ok(Token::Declaration { .. }) => {
+                    if !self.stack.is_empty() {
+                        return Some(Err(Error::MisplacedDeclaration(self.token_position())));
+                    }
+                }
+                Ok(Token::Comment { .. } | Token::ProcessingInstruction { .. }) => continue,
+                Ok(Token::DtdStart { external_id, .. } | Token::EmptyDtd { external_id, .. })
+                    if self.limits.reject_dtd =>
+                {
+                    return Some(Err(match external_id {
+                        Some(_) => Error::ExternalDtdNotSupported(self.token_position()),
+                        None => Error::DtdNotSupported(self.token_position()),
+                    }))
+                }
+                Ok(Token::EntityDeclaration { .. }) if self.limits.reject_dtd => {
+                    return Some(Err(Error::EntityDeclarationNotSupported(
+                        self.token_position(),
+                    )))
+                }
+                // The document type declaration is skipped without loading any external subset;
+                // entities it declares are not expanded.
+                Ok(
+                    Token::DtdStart { .. }
+                    | Token::EmptyDtd { .. }
+                    | Token::DtdEnd { .. }
+                    | Token::EntityDeclaration { .. },
+                ) => continue,
                 Err(e) => return Some(Err(Error::Parse(e))),
             }
         }
     }
 }
 
+/// Namespace passed to [`FromXml::matches()`] for `#[xml(any_ns)]` fields
+///
+/// NUL cannot occur in an XML document, so this never collides with a real namespace.
+pub const ANY_NS: &str = "\0";
+
+/// Whether namespace `ns` is `expected`, or the [`ANY_NS`] wildcard
+pub fn ns_matches(ns: &str, expected: &str) -> bool {
+    ns == expected || ns == ANY_NS
+}
+
 pub fn borrow_cow_str<'a, 'xml: 'a>(
     into: &mut CowStrAccumulator<'xml, 'a>,
     field: &'static str,
@@ -370,12 +1204,43 @@ pub fn borrow_cow_str<'a, 'xml: 'a>(
         return Err(Error::DuplicateValue(field));
     }
 
-    match deserializer.take_str()? {
-        Some(value) => into.inner = Some(value),
-        None => return Ok(()),
-    };
+    if let Some(value) = take_value(deserializer)? {
+        into.inner = Some(value);
+    }
+
+    Ok(())
+}
+
+pub fn borrow_option_cow_str<'a, 'xml: 'a>(
+    into: &mut OptionAccumulator<Cow<'a, str>, CowStrAccumulator<'xml, 'a>>,
+    field: &'static str,
+    deserializer: &mut Deserializer<'_, 'xml>,
+) -> Result<(), Error> {
+    borrow_cow_str(into.get_mut(), field, deserializer)
+}
 
-    deserializer.ignore()?;
+pub fn borrow_vec_cow_str<'a, 'xml: 'a>(
+    into: &mut Vec<Cow<'a, str>>,
+    field: &'static str,
+    deserializer: &mut Deserializer<'_, 'xml>,
+) -> Result<(), Error> {
+    let mut value = CowStrAccumulator::default();
+    borrow_cow_str(&mut value, field, deserializer)?;
+    into.push(value.inner.unwrap_or_default());
+    Ok(())
+}
+
+/// Deserialize the inner XML of an element as-is, for `#[xml(raw)]` fields
+pub fn raw<'xml, T: From<&'xml str>>(
+    into: &mut Option<T>,
+    field: &'static str,
+    deserializer: &mut Deserializer<'_, 'xml>,
+) -> Result<(), Error> {
+    if into.is_some() {
+        return Err(Error::DuplicateValue(field));
+    }
+
+    *into = Some(T::from(deserializer.take_raw()?));
     Ok(())
 }
 
@@ -388,18 +1253,102 @@ pub fn borrow_cow_slice_u8<'xml>(
         return Err(Error::DuplicateValue(field));
     }
 
-    if let Some(value) = deserializer.take_str()? {
+    if let Some(value) = take_value(deserializer)? {
         *into = Some(match value {
             Cow::Borrowed(v) => Cow::Borrowed(v.as_bytes()),
             Cow::Owned(v) => Cow::Owned(v.into_bytes()),
         });
     }
 
-    deserializer.ignore()?;
     Ok(())
 }
 
-fn decode(input: &str) -> Result<Cow<'_, str>, Error> {
+/// Take the scalar value for a field, skipping the rest of its element
+///
+/// Attribute values are read through the deserializer of the element they are on, which has
+/// further content to read.
+fn take_value<'xml>(
+    deserializer: &mut Deserializer<'_, 'xml>,
+) -> Result<Option<Cow<'xml, str>>, Error> {
+    let attribute = matches!(
+        deserializer.context.records.front(),
+        Some(Node::AttributeValue(_))
+    );
+
+    let value = deserializer.take_str()?;
+    if value.is_some() && !attribute {
+        deserializer.ignore()?;
+    }
+
+    Ok(value)
+}
+
+/// Decode a UTF-8 or UTF-16 document, as detected from its first bytes
+///
+/// See <https://www.w3.org/TR/xml/#sec-guessing>.
+#[cfg(feature = "utf16")]
+pub(crate) fn decode_bytes(input: &[u8]) -> Result<Cow<'_, str>, Error> {
+    let (input, big_endian) = match input {
+        [0xff, 0xfe, rest @ ..] => (rest, false),
+        [0xfe, 0xff, rest @ ..] => (rest, true),
+        [0x3c, 0x00, 0x3f, 0x00, ..] => (input, false),
+        [0x00, 0x3c, 0x00, 0x3f, ..] => (input, true),
+        _ => return utf8(input).map(Cow::Borrowed),
+    };
+
+    if input.len() % 2 != 0 {
+        return Err(Error::InvalidEncoding(
+            "odd number of bytes in UTF-16 input",
+        ));
+    }
+
+    let units = input.chunks_exact(2).map(|pair| match big_endian {
+        true => u16::from_be_bytes([pair[0], pair[1]]),
+        false => u16::from_le_bytes([pair[0], pair[1]]),
+    });
+
+    let decoded = char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map_err(|_| Error::InvalidEncoding("invalid UTF-16"))?;
+    check_encoding(&decoded, &["UTF-16", "UTF-16LE", "UTF-16BE"])?;
+    Ok(Cow::Owned(decoded))
+}
+
+/// Read a UTF-8 document, skipping a byte order mark
+///
+/// Documents in any other encoding, as detected from their first bytes or declared in the XML
+/// declaration, result in [`Error::UnsupportedEncoding`].
+pub(crate) fn utf8(input: &[u8]) -> Result<&str, Error> {
+    let input = match input {
+        [0xef, 0xbb, 0xbf, rest @ ..] => rest,
+        [0xff, 0xfe, ..] | [0xfe, 0xff, ..] | [0x3c, 0x00, ..] | [0x00, 0x3c, ..] => {
+            return Err(Error::UnsupportedEncoding("UTF-16".to_owned()))
+        }
+        _ => input,
+    };
+
+    let input = str::from_utf8(input).map_err(|_| Error::InvalidEncoding("invalid UTF-8"))?;
+    check_encoding(input, &["UTF-8", "US-ASCII"])?;
+    Ok(input)
+}
+
+/// Check that the encoding named in the XML declaration of `input`, if any, is `supported`
+fn check_encoding(input: &str, supported: &[&str]) -> Result<(), Error> {
+    let encoding = match Tokenizer::from(input).next() {
+        Some(Ok(Token::Declaration {
+            encoding: Some(encoding),
+            ..
+        })) => encoding.as_str(),
+        _ => return Ok(()),
+    };
+
+    match supported.iter().any(|s| s.eq_ignore_ascii_case(encoding)) {
+        true => Ok(()),
+        false => Err(Error::UnsupportedEncoding(encoding.to_owned())),
+    }
+}
+
+pub(crate) fn decode(input: &str) -> Result<Cow<'_, str>, Error> {
     let mut result = String::with_capacity(input.len());
     let (mut state, mut last_end) = (DecodeState::Normal, 0);
     for (i, &b) in input.as_bytes().iter().enumerate() {
@@ -449,8 +1398,12 @@ fn decode(input: &str) -> Result<Cow<'_, str>, Error> {
 
                 let start = i - (len + 1); // current position - (length of entity characters + 1 for '&')
                 if last_end < start {
-                    // Unwrap should be safe: `last_end` and `start` must be at character boundaries.
-                    result.push_str(input.get(last_end..start).unwrap());
+                    // `last_end` and `start` must be at character boundaries.
+                    result.push_str(
+                        input
+                            .get(last_end..start)
+                            .ok_or(Error::UnexpectedState("entity not at character boundary"))?,
+                    );
                 }
 
                 last_end = i + 1;
@@ -483,8 +1436,12 @@ fn decode(input: &str) -> Result<Cow<'_, str>, Error> {
     Ok(match result.is_empty() {
         true => Cow::Borrowed(input),
         false => {
-            // Unwrap should be safe: `last_end` and `input.len()` must be at character boundaries.
-            result.push_str(input.get(last_end..input.len()).unwrap());
+            // `last_end` and `input.len()` must be at character boundaries.
+            result.push_str(
+                input
+                    .get(last_end..)
+                    .ok_or(Error::UnexpectedState("entity not at character boundary"))?,
+            );
             Cow::Owned(result)
         }
     })
@@ -515,19 +1472,22 @@ pub enum Node<'xml> {
 
 #[derive(Debug)]
 pub struct Element<'xml> {
-    local: &'xml str,
+    pub(crate) local: &'xml str,
     default_ns: Option<&'xml str>,
     prefix: Option<&'xml str>,
     level: Level<'xml>,
-    empty: bool,
 }
 
 #[derive(Debug)]
 struct Level<'xml> {
     local: &'xml str,
+    /// The byte offset of the start tag in the input
+    offset: usize,
     prefix: Option<&'xml str>,
     default_ns: Option<&'xml str>,
     prefixes: BTreeMap<&'xml str, &'xml str>,
+    /// Set by an `xml:space` attribute: `preserve` keeps whitespace, `default` resets it
+    preserve_space: Option<bool>,
 }
 
 #[derive(Debug)]
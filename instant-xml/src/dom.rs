@@ -0,0 +1,247 @@
+//! An untyped representation of XML content
+
+use std::fmt;
+
+use crate::de::Node as DeNode;
+use crate::ser::XML_NS;
+use crate::{Deserializer, Error, FromXml, Id, Kind, Serializer, ToXml};
+
+/// An element with its namespace, attributes and content
+///
+/// Deserializing an `Element` accepts any element, so it can be used to hold content that is not
+/// modelled by other types.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Element {
+    /// The namespace, empty if the element is not in a namespace
+    pub ns: String,
+    pub name: String,
+    pub attributes: Vec<Attribute>,
+    pub children: Vec<Node>,
+}
+
+impl Element {
+    pub fn new(ns: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            ns: ns.into(),
+            name: name.into(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn id(&self) -> Id<'_> {
+        Id {
+            ns: &self.ns,
+            name: &self.name,
+        }
+    }
+
+    /// The value of the attribute identified by `id`, if present
+    pub fn attribute(&self, id: Id<'_>) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attr| attr.id() == id)
+            .map(|attr| attr.value.as_str())
+    }
+
+    /// The child elements, skipping any text
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// The first child element identified by `id`
+    pub fn element(&self, id: Id<'_>) -> Option<&Element> {
+        self.elements().find(|element| element.id() == id)
+    }
+
+    /// The concatenated text of this element and all its descendants
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        self.collect_text(&mut text);
+        text
+    }
+
+    fn collect_text(&self, text: &mut String) {
+        for node in &self.children {
+            match node {
+                Node::Element(element) => element.collect_text(text),
+                Node::Text(value) => text.push_str(value),
+            }
+        }
+    }
+
+    fn read(deserializer: &mut Deserializer<'_, '_>) -> Result<Self, Error> {
+        let id = deserializer.parent()?;
+        let mut element = Self::new(id.ns, id.name);
+        while let Some(node) = deserializer.next() {
+            match node? {
+                DeNode::Attribute(attr) => {
+                    let id = deserializer.attribute_id(&attr)?;
+                    element.attributes.push(Attribute {
+                        ns: id.ns.to_owned(),
+                        name: id.name.to_owned(),
+                        value: attr.value.into_owned(),
+                    });
+                }
+                DeNode::Open(data) => {
+                    let mut nested = deserializer.nested(data);
+                    let child = Self::read(&mut nested)?;
+                    element.children.push(Node::Element(child));
+                }
+                DeNode::Text(text) => element.children.push(Node::Text(text.into_owned())),
+                node => return Err(Error::UnexpectedNode(format!("{node:?} in element"))),
+            }
+        }
+
+        Ok(element)
+    }
+
+    fn write<W: fmt::Write + ?Sized>(
+        &self,
+        prefixes: &mut Vec<(String, String)>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        serializer.write_start_dynamic(&self.name, &self.ns)?;
+
+        // Declare prefixes for attribute namespaces that don't have one yet
+        let scope = prefixes.len();
+        for attr in &self.attributes {
+            if attr.ns.is_empty()
+                || attr.ns == XML_NS
+                || prefixes.iter().any(|(ns, _)| *ns == attr.ns)
+                || serializer.prefix(&attr.ns).is_some()
+            {
+                continue;
+            }
+
+            let mut i = prefixes.len();
+            let prefix = loop {
+                let prefix = format!("ns{i}");
+                match prefixes.iter().any(|(_, used)| *used == prefix)
+                    || serializer.prefix_in_use(&prefix)
+                {
+                    true => i += 1,
+                    false => break prefix,
+                }
+            };
+
+            serializer.write_attr_dynamic(&format!("xmlns:{prefix}"), &attr.ns)?;
+            prefixes.push((attr.ns.clone(), prefix));
+        }
+
+        for attr in &self.attributes {
+            let prefix = match attr.ns.as_str() {
+                "" => None,
+                XML_NS => Some("xml"),
+                ns => match prefixes.iter().rev().find(|(used, _)| used == ns) {
+                    Some((_, prefix)) => Some(prefix.as_str()),
+                    None => serializer.prefix(ns),
+                },
+            };
+
+            match prefix {
+                Some(prefix) => serializer
+                    .write_attr_dynamic(&format!("{prefix}:{}", attr.name), &attr.value)?,
+                None => serializer.write_attr_dynamic(&attr.name, &attr.value)?,
+            }
+        }
+
+        if self.children.is_empty() {
+            serializer.end_empty()?;
+        } else {
+            serializer.end_start()?;
+            for child in &self.children {
+                match child {
+                    Node::Element(element) => element.write(prefixes, serializer)?,
+                    Node::Text(text) => serializer.write_text(text)?,
+                }
+            }
+            serializer.write_close(None, &self.name)?;
+        }
+
+        prefixes.truncate(scope);
+        Ok(())
+    }
+}
+
+impl ToXml for Element {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        _: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        self.write(&mut Vec::new(), serializer)
+    }
+}
+
+impl<'xml> FromXml<'xml> for Element {
+    #[inline]
+    fn matches(_: Id<'_>, _: Option<Id<'_>>) -> bool {
+        true
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        *into = Some(Self::read(deserializer)?);
+        Ok(())
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Element;
+}
+
+/// An attribute of an [`Element`]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Attribute {
+    /// The namespace, empty for unprefixed attributes
+    pub ns: String,
+    pub name: String,
+    pub value: String,
+}
+
+impl Attribute {
+    pub fn new(ns: impl Into<String>, name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            ns: ns.into(),
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
+    pub fn id(&self) -> Id<'_> {
+        Id {
+            ns: &self.ns,
+            name: &self.name,
+        }
+    }
+}
+
+/// Content of an [`Element`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl ToXml for Node {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        _: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Element(element) => element.serialize(None, serializer),
+            Self::Text(text) => serializer.write_text(text),
+        }
+    }
+}
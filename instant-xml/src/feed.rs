@@ -0,0 +1,253 @@
+use std::marker::PhantomData;
+
+use crate::de::{self, Context, Limits};
+use crate::{deserialize_root, Error, FromXmlOwned};
+
+/// Deserializes values from input that arrives in chunks, like frames read from a socket
+///
+/// Input is buffered until it holds a complete root element, which is then deserialized as a
+/// `T` and removed from the buffer. The buffer is scanned incrementally, so every byte is only
+/// looked at once to find the end of the element. Whitespace, comments and XML declarations
+/// before each element are part of the value that follows them.
+///
+/// A value that fails to deserialize is still removed from the buffer, so the next one can be
+/// read after an error. Errors finding the end of a value ([`Error::InputTooLong`]) leave the
+/// buffer as it is.
+#[derive(Debug)]
+pub struct FeedDeserializer<T> {
+    buffer: Vec<u8>,
+    /// How far `buffer` has been scanned
+    pos: usize,
+    state: State,
+    /// The number of open elements
+    depth: usize,
+    /// Whether the root element of the current value has been opened
+    root: bool,
+    limits: Limits,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> FeedDeserializer<T> {
+    pub fn new() -> Self {
+        Self::with_limits(Limits::default())
+    }
+
+    /// Create a deserializer that rejects values exceeding `limits`
+    ///
+    /// [`Limits::max_input_len`] applies to every value separately, including anything
+    /// buffered before it is complete.
+    pub fn with_limits(limits: Limits) -> Self {
+        Self {
+            buffer: Vec::new(),
+            pos: 0,
+            state: State::Content,
+            depth: 0,
+            root: false,
+            limits,
+            marker: PhantomData,
+        }
+    }
+
+    /// Input that has been fed but not yet deserialized
+    pub fn buffered(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Scan the buffer for the end of the current value, returning its length if found
+    fn scan(&mut self) -> Option<usize> {
+        while self.pos < self.buffer.len() {
+            let byte = self.buffer[self.pos];
+            match self.state {
+                State::Content => {
+                    if byte == b'<' {
+                        let (state, opener) = classify(&self.buffer[self.pos + 1..])?;
+                        self.pos += 1 + opener;
+                        self.state = state;
+                        continue;
+                    }
+                }
+                State::Tag {
+                    end,
+                    quote: Some(quote),
+                } => {
+                    if byte == quote {
+                        self.state = State::Tag { end, quote: None };
+                    }
+                }
+                State::Tag { end, quote: None } => match byte {
+                    b'"' | b'\'' => {
+                        self.state = State::Tag {
+                            end,
+                            quote: Some(byte),
+                        }
+                    }
+                    b'>' => {
+                        self.pos += 1;
+                        self.state = State::Content;
+                        if let Some(len) = self.close_tag(end) {
+                            return Some(len);
+                        }
+
+                        continue;
+                    }
+                    _ => {}
+                },
+                State::Until(terminator) => {
+                    match find(&self.buffer[self.pos..], terminator) {
+                        Some(i) => {
+                            self.pos += i + terminator.len();
+                            self.state = State::Content;
+                            continue;
+                        }
+                        // The terminator may start in the bytes not yet received
+                        None => {
+                            let keep = (terminator.len() - 1).min(self.buffer.len() - self.pos);
+                            self.pos = self.buffer.len() - keep;
+                            return None;
+                        }
+                    }
+                }
+                State::Doctype { subset } => match (byte, subset) {
+                    (b'[', false) => self.state = State::Doctype { subset: true },
+                    (b']', true) => self.state = State::Doctype { subset: false },
+                    (b'>', false) => self.state = State::Content,
+                    _ => {}
+                },
+            }
+
+            self.pos += 1;
+        }
+
+        None
+    }
+
+    /// Update the depth after the `>` just before `pos`, returning the length of the value if
+    /// the tag completes it
+    fn close_tag(&mut self, end: bool) -> Option<usize> {
+        let empty = self.buffer[..self.pos].ends_with(b"/>");
+        match (end, empty) {
+            // An end tag without a start tag is left for the parser to reject
+            (true, _) if self.depth == 0 => return Some(self.pos),
+            (true, _) => self.depth -= 1,
+            (false, true) => {}
+            (false, false) => self.depth += 1,
+        }
+
+        if !end {
+            self.root = true;
+        }
+
+        match self.root && self.depth == 0 {
+            true => Some(self.pos),
+            false => None,
+        }
+    }
+}
+
+impl<T: FromXmlOwned> FeedDeserializer<T> {
+    /// Add `chunk` to the input, then deserialize the next value if it is complete
+    ///
+    /// One chunk may complete several values; call [`FeedDeserializer::next_value()`] to get
+    /// the others.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Feed<T>, Error> {
+        self.buffer.extend_from_slice(chunk);
+        self.next_value()
+    }
+
+    /// Deserialize the next value from the input fed so far, if it is complete
+    pub fn next_value(&mut self) -> Result<Feed<T>, Error> {
+        let len = match self.scan() {
+            Some(len) if len <= self.limits.max_input_len => len,
+            Some(_) => return Err(Error::InputTooLong),
+            None if self.buffer.len() > self.limits.max_input_len => {
+                return Err(Error::InputTooLong)
+            }
+            None => return Ok(Feed::NeedMoreData),
+        };
+
+        let frame = &self.buffer[..len];
+        let start = frame
+            .iter()
+            .position(|byte| !byte.is_ascii_whitespace())
+            .unwrap_or(len);
+
+        let result = de::utf8(&frame[start..]).and_then(|input| {
+            let mut context = Context::new(input);
+            context.limits = self.limits;
+            deserialize_root(&mut context)
+        });
+
+        self.buffer.drain(..len);
+        self.pos = 0;
+        self.state = State::Content;
+        self.depth = 0;
+        self.root = false;
+        result.map(Feed::Value)
+    }
+}
+
+impl<T> Default for FeedDeserializer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The result of [`FeedDeserializer::feed()`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Feed<T> {
+    /// A complete value
+    Value(T),
+    /// The input does not hold a complete value yet
+    NeedMoreData,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum State {
+    /// Text between tags, or whitespace between values
+    Content,
+    /// In a start or end tag, possibly in an attribute value delimited by `quote`
+    Tag { end: bool, quote: Option<u8> },
+    /// In a comment, processing instruction or CDATA section, which ends with the terminator
+    Until(&'static [u8]),
+    /// In a document type declaration, possibly in its internal subset
+    Doctype { subset: bool },
+}
+
+/// Classify the markup starting with `rest` after a `<`, with the length of the rest of its
+/// opener, or `None` if more input is needed
+fn classify(rest: &[u8]) -> Option<(State, usize)> {
+    Some(match rest.first()? {
+        b'/' => (
+            State::Tag {
+                end: true,
+                quote: None,
+            },
+            1,
+        ),
+        b'?' => (State::Until(b"?>"), 1),
+        b'!' => {
+            for (opener, terminator) in [(&b"!--"[..], &b"-->"[..]), (b"![CDATA[", b"]]>")] {
+                if rest.starts_with(opener) {
+                    return Some((State::Until(terminator), opener.len()));
+                } else if opener.starts_with(rest) {
+                    return None;
+                }
+            }
+
+            (State::Doctype { subset: false }, 1)
+        }
+        _ => (
+            State::Tag {
+                end: false,
+                quote: None,
+            },
+            0,
+        ),
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
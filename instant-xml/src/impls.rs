@@ -1,12 +1,22 @@
 use std::borrow::Cow;
+use std::collections::{BinaryHeap, VecDeque};
 use std::fmt;
-use std::net::IpAddr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::num::{
+    NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU16, NonZeroU32,
+    NonZeroU64, NonZeroU8, NonZeroUsize,
+};
+use std::path::{Path, PathBuf};
 use std::str;
 use std::str::FromStr;
 use std::{any::type_name, marker::PhantomData};
 
 #[cfg(feature = "chrono")]
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+#[cfg(feature = "time")]
+use time::format_description::{well_known::Rfc3339, BorrowedFormatItem};
+#[cfg(feature = "time")]
+use time::macros::format_description;
 
 use crate::{Accumulate, Deserializer, Error, FromXml, Id, Kind, Serializer, ToXml};
 
@@ -102,6 +112,11 @@ impl<'xml> FromXml<'xml> for bool {
             None => return Ok(()),
         };
 
+        let value = match deserializer.options().lenient_bools {
+            true => Cow::Owned(value.trim().to_ascii_lowercase()),
+            false => value,
+        };
+
         let value = match value.as_ref() {
             "true" | "1" => true,
             "false" | "0" => false,
@@ -141,22 +156,75 @@ where
         field: Option<Id<'_>>,
         serializer: &mut Serializer<W>,
     ) -> Result<(), Error> {
-        let prefix = match field {
-            Some(id) => {
-                let prefix = serializer.write_start(id.name, id.ns)?;
-                serializer.end_start()?;
-                Some((prefix, id.name))
-            }
-            None => None,
-        };
+        scalar_to_xml(field, serializer, |serializer| serializer.write_str(self.0))
+    }
+}
+
+/// Serializes unescaped text, escaping it in the serializer
+struct TextToXml<'a>(&'a str);
+
+impl ToXml for TextToXml<'_> {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        scalar_to_xml(field, serializer, |serializer| {
+            serializer.write_text(self.0)
+        })
+    }
+}
+
+/// XML content that is written to the output verbatim, without escaping
+///
+/// The content must be well-formed; it is not checked.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Raw<'a>(pub Cow<'a, str>);
 
-        serializer.write_str(self.0)?;
-        if let Some((prefix, name)) = prefix {
-            serializer.write_close(prefix, name)?;
+impl<'a> From<&'a str> for Raw<'a> {
+    fn from(value: &'a str) -> Self {
+        Self(Cow::Borrowed(value))
+    }
+}
+
+impl From<String> for Raw<'_> {
+    fn from(value: String) -> Self {
+        Self(Cow::Owned(value))
+    }
+}
+
+impl ToXml for Raw<'_> {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        scalar_to_xml(field, serializer, |serializer| {
+            serializer.write_raw(&self.0)
+        })
+    }
+}
+
+fn scalar_to_xml<W: fmt::Write + ?Sized>(
+    field: Option<Id<'_>>,
+    serializer: &mut Serializer<W>,
+    write: impl FnOnce(&mut Serializer<W>) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let prefix = match field {
+        Some(id) => {
+            let prefix = serializer.write_start(id.name, id.ns)?;
+            serializer.end_start()?;
+            Some((prefix, id.name))
         }
+        None => None,
+    };
 
-        Ok(())
+    write(serializer)?;
+    if let Some((prefix, name)) = prefix {
+        serializer.write_close(prefix.as_deref(), name)?;
     }
+
+    Ok(())
 }
 
 macro_rules! to_xml_for_number {
@@ -220,6 +288,16 @@ from_xml_for_number!(u64);
 from_xml_for_number!(usize);
 from_xml_for_number!(f32);
 from_xml_for_number!(f64);
+from_xml_for_number!(NonZeroI8);
+from_xml_for_number!(NonZeroI16);
+from_xml_for_number!(NonZeroI32);
+from_xml_for_number!(NonZeroI64);
+from_xml_for_number!(NonZeroIsize);
+from_xml_for_number!(NonZeroU8);
+from_xml_for_number!(NonZeroU16);
+from_xml_for_number!(NonZeroU32);
+from_xml_for_number!(NonZeroU64);
+from_xml_for_number!(NonZeroUsize);
 
 impl<'xml> FromXml<'xml> for char {
     #[inline]
@@ -365,6 +443,10 @@ impl<'xml, T: FromXml<'xml>> FromXml<'xml> for Option<T> {
         field: &'static str,
         deserializer: &mut Deserializer<'cx, 'xml>,
     ) -> Result<(), Error> {
+        if deserializer.nil()? {
+            return deserializer.ignore();
+        }
+
         <T>::deserialize(&mut into.value, field, deserializer)?;
         Ok(())
     }
@@ -414,6 +496,16 @@ to_xml_for_number!(u64);
 to_xml_for_number!(usize);
 to_xml_for_number!(f32);
 to_xml_for_number!(f64);
+to_xml_for_number!(NonZeroI8);
+to_xml_for_number!(NonZeroI16);
+to_xml_for_number!(NonZeroI32);
+to_xml_for_number!(NonZeroI64);
+to_xml_for_number!(NonZeroIsize);
+to_xml_for_number!(NonZeroU8);
+to_xml_for_number!(NonZeroU16);
+to_xml_for_number!(NonZeroU32);
+to_xml_for_number!(NonZeroU64);
+to_xml_for_number!(NonZeroUsize);
 
 impl ToXml for bool {
     fn serialize<W: fmt::Write + ?Sized>(
@@ -421,9 +513,11 @@ impl ToXml for bool {
         field: Option<Id<'_>>,
         serializer: &mut Serializer<W>,
     ) -> Result<(), Error> {
-        let value = match self {
-            true => "true",
-            false => "false",
+        let value = match (self, serializer.numeric_bools()) {
+            (true, false) => "true",
+            (false, false) => "false",
+            (true, true) => "1",
+            (false, true) => "0",
         };
 
         DisplayToXml(&value).serialize(field, serializer)
@@ -436,7 +530,7 @@ impl ToXml for String {
         field: Option<Id<'_>>,
         serializer: &mut Serializer<W>,
     ) -> Result<(), Error> {
-        DisplayToXml(&encode(self)?).serialize(field, serializer)
+        TextToXml(self).serialize(field, serializer)
     }
 }
 
@@ -447,7 +541,7 @@ impl ToXml for char {
         serializer: &mut Serializer<W>,
     ) -> Result<(), Error> {
         let mut tmp = [0u8; 4];
-        DisplayToXml(&encode(&*self.encode_utf8(&mut tmp))?).serialize(field, serializer)
+        TextToXml(self.encode_utf8(&mut tmp)).serialize(field, serializer)
     }
 }
 
@@ -457,7 +551,7 @@ impl ToXml for str {
         field: Option<Id<'_>>,
         serializer: &mut Serializer<W>,
     ) -> Result<(), Error> {
-        DisplayToXml(&encode(self)?).serialize(field, serializer)
+        TextToXml(self).serialize(field, serializer)
     }
 }
 
@@ -467,7 +561,19 @@ impl ToXml for Cow<'_, str> {
         field: Option<Id<'_>>,
         serializer: &mut Serializer<W>,
     ) -> Result<(), Error> {
-        DisplayToXml(&encode(self)?).serialize(field, serializer)
+        TextToXml(self).serialize(field, serializer)
+    }
+}
+
+// Derived impls call `ToXml::serialize()` on fields without auto-deref, so this can't rely on
+// method resolution reaching the `[T]` impl
+impl<T: ToXml + Clone> ToXml for Cow<'_, [T]> {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        self.as_ref().serialize(field, serializer)
     }
 }
 
@@ -488,7 +594,7 @@ impl<T: ToXml> ToXml for Option<T> {
     }
 }
 
-fn encode(input: &str) -> Result<Cow<'_, str>, Error> {
+pub(crate) fn encode(input: &str) -> Result<Cow<'_, str>, Error> {
     let mut result = String::with_capacity(input.len());
     let mut last_end = 0;
     for (start, c) in input.char_indices() {
@@ -544,7 +650,28 @@ impl<T: ToXml> ToXml for Vec<T> {
     }
 }
 
-impl<T: ToXml> ToXml for [T] {
+impl<'xml, T: FromXml<'xml>> FromXml<'xml> for VecDeque<T> {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        T::matches(id, field)
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        let mut value = T::Accumulator::default();
+        T::deserialize(&mut value, field, deserializer)?;
+        into.push_back(value.try_done(field)?);
+        Ok(())
+    }
+
+    type Accumulator = VecDeque<T>;
+    const KIND: Kind = T::KIND;
+}
+
+impl<T: ToXml> ToXml for VecDeque<T> {
     fn serialize<W: fmt::Write + ?Sized>(
         &self,
         field: Option<Id<'_>>,
@@ -558,31 +685,118 @@ impl<T: ToXml> ToXml for [T] {
     }
 }
 
-#[cfg(feature = "chrono")]
-impl ToXml for DateTime<Utc> {
+// Items are written in ascending order (like `BinaryHeap::into_sorted_vec()`) rather than in
+// the heap's internal order, which is unspecified.
+impl<T: Ord + ToXml> ToXml for BinaryHeap<T> {
     fn serialize<W: fmt::Write + ?Sized>(
         &self,
         field: Option<Id<'_>>,
         serializer: &mut Serializer<W>,
     ) -> Result<(), Error> {
-        let prefix = match field {
-            Some(id) => {
-                let prefix = serializer.write_start(id.name, id.ns)?;
-                serializer.end_start()?;
-                Some((prefix, id.name))
-            }
-            None => None,
-        };
+        let mut items = self.iter().collect::<Vec<_>>();
+        items.sort();
+        for i in items {
+            i.serialize(field, serializer)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'xml, T: FromXml<'xml>, const N: usize> FromXml<'xml> for [T; N] {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        T::matches(id, field)
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        // Count surplus elements without buffering them, to report the length in `try_done()`
+        if into.items.len() == N {
+            into.surplus += 1;
+            return deserializer.ignore();
+        }
+
+        let mut value = T::Accumulator::default();
+        T::deserialize(&mut value, field, deserializer)?;
+        into.items.push(value.try_done(field)?);
+        Ok(())
+    }
+
+    type Accumulator = ArrayAccumulator<T, N>;
+    const KIND: Kind = T::KIND;
+}
+
+impl<T: ToXml, const N: usize> ToXml for [T; N] {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        self.as_slice().serialize(field, serializer)
+    }
+}
+
+pub struct ArrayAccumulator<T, const N: usize> {
+    items: Vec<T>,
+    surplus: usize,
+}
+
+impl<T, const N: usize> Default for ArrayAccumulator<T, N> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            surplus: 0,
+        }
+    }
+}
+
+impl<T, const N: usize> Accumulate<[T; N]> for ArrayAccumulator<T, N> {
+    fn try_done(self, field: &'static str) -> Result<[T; N], Error> {
+        let found = self.items.len() + self.surplus;
+        match self.surplus {
+            0 => <[T; N]>::try_from(self.items).ok(),
+            _ => None,
+        }
+        .ok_or(Error::LengthMismatch {
+            field,
+            expected: N,
+            found,
+        })
+    }
+}
 
-        serializer.write_str(&self.to_rfc3339())?;
-        if let Some((prefix, name)) = prefix {
-            serializer.write_close(prefix, name)?;
+impl<T: ToXml> ToXml for [T] {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        for i in self {
+            i.serialize(field, serializer)?;
         }
 
         Ok(())
     }
 }
 
+#[cfg(feature = "chrono")]
+impl<Tz: TimeZone> ToXml for DateTime<Tz>
+where
+    Tz::Offset: fmt::Display,
+{
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        DisplayToXml(&self.to_rfc3339()).serialize(field, serializer)
+    }
+}
+
 #[cfg(feature = "chrono")]
 impl<'xml> FromXml<'xml> for DateTime<Utc> {
     #[inline]
@@ -621,27 +835,87 @@ impl<'xml> FromXml<'xml> for DateTime<Utc> {
 }
 
 #[cfg(feature = "chrono")]
-impl ToXml for NaiveDate {
-    fn serialize<W: fmt::Write + ?Sized>(
-        &self,
-        field: Option<Id<'_>>,
-        serializer: &mut Serializer<W>,
+impl<'xml> FromXml<'xml> for DateTime<FixedOffset> {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        match field {
+            Some(field) => id == field,
+            None => false,
+        }
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
     ) -> Result<(), Error> {
-        let prefix = match field {
-            Some(id) => {
-                let prefix = serializer.write_start(id.name, id.ns)?;
-                serializer.end_start()?;
-                Some((prefix, id.name))
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        let value = match deserializer.take_str()? {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+
+        match DateTime::parse_from_rfc3339(value.as_ref()) {
+            Ok(dt) => {
+                *into = Some(dt);
+                Ok(())
             }
-            None => None,
+            _ => Err(Error::Other("invalid date/time".into())),
+        }
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Scalar;
+}
+
+#[cfg(feature = "chrono")]
+impl<'xml> FromXml<'xml> for DateTime<Local> {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        match field {
+            Some(field) => id == field,
+            None => false,
+        }
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        let value = match deserializer.take_str()? {
+            Some(value) => value,
+            None => return Ok(()),
         };
 
-        serializer.write_str(&self)?;
-        if let Some((prefix, name)) = prefix {
-            serializer.write_close(prefix, name)?;
+        match DateTime::parse_from_rfc3339(value.as_ref()) {
+            Ok(dt) => {
+                *into = Some(dt.with_timezone(&Local));
+                Ok(())
+            }
+            _ => Err(Error::Other("invalid date/time".into())),
         }
+    }
 
-        Ok(())
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Scalar;
+}
+
+#[cfg(feature = "chrono")]
+impl ToXml for NaiveDate {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        DisplayToXml(self).serialize(field, serializer)
     }
 }
 
@@ -682,7 +956,19 @@ impl<'xml> FromXml<'xml> for NaiveDate {
     const KIND: Kind = Kind::Scalar;
 }
 
-impl<'xml> FromXml<'xml> for () {
+#[cfg(feature = "chrono")]
+impl ToXml for NaiveDateTime {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        DisplayToXml(&self.format("%Y-%m-%dT%H:%M:%S%.f")).serialize(field, serializer)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<'xml> FromXml<'xml> for NaiveDateTime {
     #[inline]
     fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
         match field {
@@ -693,28 +979,44 @@ impl<'xml> FromXml<'xml> for () {
 
     fn deserialize<'cx>(
         into: &mut Self::Accumulator,
-        _: &'static str,
-        _: &mut Deserializer<'cx, 'xml>,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
     ) -> Result<(), Error> {
-        *into = Some(());
-        Ok(())
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        let value = match deserializer.take_str()? {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+
+        match NaiveDateTime::parse_from_str(value.as_ref(), "%Y-%m-%dT%H:%M:%S%.f") {
+            Ok(dt) => {
+                *into = Some(dt);
+                Ok(())
+            }
+            _ => Err(Error::Other("invalid date/time".into())),
+        }
     }
 
     type Accumulator = Option<Self>;
     const KIND: Kind = Kind::Scalar;
 }
 
-impl ToXml for IpAddr {
+#[cfg(feature = "chrono")]
+impl ToXml for NaiveTime {
     fn serialize<W: fmt::Write + ?Sized>(
         &self,
         field: Option<Id<'_>>,
         serializer: &mut Serializer<W>,
     ) -> Result<(), Error> {
-        DisplayToXml(self).serialize(field, serializer)
+        DisplayToXml(&self.format("%H:%M:%S%.f")).serialize(field, serializer)
     }
 }
 
-impl<'xml> FromXml<'xml> for IpAddr {
+#[cfg(feature = "chrono")]
+impl<'xml> FromXml<'xml> for NaiveTime {
     #[inline]
     fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
         match field {
@@ -732,19 +1034,461 @@ impl<'xml> FromXml<'xml> for IpAddr {
             return Err(Error::DuplicateValue(field));
         }
 
-        let mut value = None;
-        FromXmlStr::<Self>::deserialize(&mut value, field, deserializer)?;
-        if let Some(value) = value {
-            *into = Some(value.0);
-        }
+        let value = match deserializer.take_str()? {
+            Some(value) => value,
+            None => return Ok(()),
+        };
 
-        Ok(())
+        match NaiveTime::parse_from_str(value.as_ref(), "%H:%M:%S%.f") {
+            Ok(t) => {
+                *into = Some(t);
+                Ok(())
+            }
+            _ => Err(Error::Other("invalid date/time".into())),
+        }
     }
 
     type Accumulator = Option<Self>;
     const KIND: Kind = Kind::Scalar;
 }
 
+#[cfg(feature = "time")]
+const TIME_DATE: &[BorrowedFormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+#[cfg(feature = "time")]
+const TIME_TIME: &[BorrowedFormatItem<'static>] = format_description!("[hour]:[minute]:[second]");
+
+#[cfg(feature = "time")]
+const TIME_TIME_FRACTION: &[BorrowedFormatItem<'static>] =
+    format_description!("[hour]:[minute]:[second].[subsecond]");
+
+#[cfg(feature = "time")]
+const TIME_TIME_PARSE: &[BorrowedFormatItem<'static>] =
+    format_description!("[hour]:[minute]:[second][optional [.[subsecond]]]");
+
+#[cfg(feature = "time")]
+const TIME_DATE_TIME: &[BorrowedFormatItem<'static>] =
+    format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
+
+#[cfg(feature = "time")]
+const TIME_DATE_TIME_FRACTION: &[BorrowedFormatItem<'static>] =
+    format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond]");
+
+#[cfg(feature = "time")]
+const TIME_DATE_TIME_PARSE: &[BorrowedFormatItem<'static>] =
+    format_description!("[year]-[month]-[day]T[hour]:[minute]:[second][optional [.[subsecond]]]");
+
+#[cfg(feature = "time")]
+impl ToXml for time::OffsetDateTime {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        let value = self
+            .format(&Rfc3339)
+            .map_err(|err| Error::Other(err.to_string()))?;
+        DisplayToXml(&value).serialize(field, serializer)
+    }
+}
+
+#[cfg(feature = "time")]
+impl<'xml> FromXml<'xml> for time::OffsetDateTime {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        match field {
+            Some(field) => id == field,
+            None => false,
+        }
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        let value = match deserializer.take_str()? {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+
+        match time::OffsetDateTime::parse(value.as_ref(), &Rfc3339) {
+            Ok(v) => {
+                *into = Some(v);
+                Ok(())
+            }
+            _ => Err(Error::Other("invalid date/time".into())),
+        }
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Scalar;
+}
+
+#[cfg(feature = "time")]
+impl ToXml for time::PrimitiveDateTime {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        let format = match self.nanosecond() {
+            0 => TIME_DATE_TIME,
+            _ => TIME_DATE_TIME_FRACTION,
+        };
+
+        let value = self
+            .format(format)
+            .map_err(|err| Error::Other(err.to_string()))?;
+        DisplayToXml(&value).serialize(field, serializer)
+    }
+}
+
+#[cfg(feature = "time")]
+impl<'xml> FromXml<'xml> for time::PrimitiveDateTime {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        match field {
+            Some(field) => id == field,
+            None => false,
+        }
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        let value = match deserializer.take_str()? {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+
+        match time::PrimitiveDateTime::parse(value.as_ref(), TIME_DATE_TIME_PARSE) {
+            Ok(v) => {
+                *into = Some(v);
+                Ok(())
+            }
+            _ => Err(Error::Other("invalid date/time".into())),
+        }
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Scalar;
+}
+
+#[cfg(feature = "time")]
+impl ToXml for time::Date {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        let value = self
+            .format(TIME_DATE)
+            .map_err(|err| Error::Other(err.to_string()))?;
+        DisplayToXml(&value).serialize(field, serializer)
+    }
+}
+
+#[cfg(feature = "time")]
+impl<'xml> FromXml<'xml> for time::Date {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        match field {
+            Some(field) => id == field,
+            None => false,
+        }
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        let value = match deserializer.take_str()? {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+
+        match time::Date::parse(value.as_ref(), TIME_DATE) {
+            Ok(v) => {
+                *into = Some(v);
+                Ok(())
+            }
+            _ => Err(Error::Other("invalid date/time".into())),
+        }
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Scalar;
+}
+
+#[cfg(feature = "time")]
+impl ToXml for time::Time {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        let format = match self.nanosecond() {
+            0 => TIME_TIME,
+            _ => TIME_TIME_FRACTION,
+        };
+
+        let value = self
+            .format(format)
+            .map_err(|err| Error::Other(err.to_string()))?;
+        DisplayToXml(&value).serialize(field, serializer)
+    }
+}
+
+#[cfg(feature = "time")]
+impl<'xml> FromXml<'xml> for time::Time {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        match field {
+            Some(field) => id == field,
+            None => false,
+        }
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        let value = match deserializer.take_str()? {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+
+        match time::Time::parse(value.as_ref(), TIME_TIME_PARSE) {
+            Ok(v) => {
+                *into = Some(v);
+                Ok(())
+            }
+            _ => Err(Error::Other("invalid date/time".into())),
+        }
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Scalar;
+}
+
+#[cfg(feature = "jiff")]
+to_xml_for_number!(jiff::Timestamp);
+#[cfg(feature = "jiff")]
+from_xml_for_number!(jiff::Timestamp);
+#[cfg(feature = "jiff")]
+to_xml_for_number!(jiff::civil::Date);
+#[cfg(feature = "jiff")]
+from_xml_for_number!(jiff::civil::Date);
+#[cfg(feature = "jiff")]
+to_xml_for_number!(jiff::civil::DateTime);
+#[cfg(feature = "jiff")]
+from_xml_for_number!(jiff::civil::DateTime);
+
+#[cfg(feature = "jiff")]
+impl ToXml for jiff::Zoned {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        // xs:dateTime has room for the offset, but not for the name of the time zone
+        let value = self.timestamp().display_with_offset(self.offset());
+        DisplayToXml(&value).serialize(field, serializer)
+    }
+}
+
+#[cfg(feature = "jiff")]
+impl<'xml> FromXml<'xml> for jiff::Zoned {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        match field {
+            Some(field) => id == field,
+            None => false,
+        }
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        let value = match deserializer.take_str()? {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+
+        // The value is in the fixed time zone of its offset
+        let offset = jiff::fmt::temporal::Pieces::parse(value.as_ref())
+            .ok()
+            .and_then(|pieces| pieces.to_numeric_offset());
+        match (value.parse::<jiff::Timestamp>(), offset) {
+            (Ok(timestamp), Some(offset)) => {
+                *into = Some(timestamp.to_zoned(offset.to_time_zone()));
+                Ok(())
+            }
+            _ => Err(Error::Other("invalid date/time".into())),
+        }
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Scalar;
+}
+
+#[cfg(feature = "uuid")]
+to_xml_for_number!(uuid::Uuid);
+#[cfg(feature = "uuid")]
+from_xml_for_number!(uuid::Uuid);
+
+// Not `to_xml_for_number!()`: URLs may contain `&` (in the query), so they need escaping
+#[cfg(feature = "url")]
+impl ToXml for url::Url {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        TextToXml(self.as_str()).serialize(field, serializer)
+    }
+}
+
+#[cfg(feature = "url")]
+from_xml_for_number!(url::Url);
+
+#[cfg(feature = "rust_decimal")]
+to_xml_for_number!(rust_decimal::Decimal);
+#[cfg(feature = "rust_decimal")]
+from_xml_for_number!(rust_decimal::Decimal);
+
+#[cfg(feature = "bigdecimal")]
+impl ToXml for bigdecimal::BigDecimal {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        // xs:decimal has no exponent notation
+        DisplayToXml(&self.to_plain_string()).serialize(field, serializer)
+    }
+}
+
+#[cfg(feature = "bigdecimal")]
+from_xml_for_number!(bigdecimal::BigDecimal);
+
+impl<'xml> FromXml<'xml> for () {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        match field {
+            Some(field) => id == field,
+            None => false,
+        }
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        _: &'static str,
+        _: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        *into = Some(());
+        Ok(())
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Scalar;
+}
+
+impl ToXml for Path {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        match self.to_str() {
+            Some(path) => TextToXml(path).serialize(field, serializer),
+            None => Err(Error::UnexpectedValue(format!(
+                "path `{}` is not valid UTF-8",
+                self.display()
+            ))),
+        }
+    }
+}
+
+impl ToXml for PathBuf {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        self.as_path().serialize(field, serializer)
+    }
+}
+
+impl<'xml> FromXml<'xml> for PathBuf {
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        match field {
+            Some(field) => id == field,
+            None => false,
+        }
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        if let Some(value) = deserializer.take_str()? {
+            *into = Some(PathBuf::from(value.as_ref()));
+        }
+
+        Ok(())
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Scalar;
+}
+
+to_xml_for_number!(IpAddr);
+from_xml_for_number!(IpAddr);
+to_xml_for_number!(Ipv4Addr);
+from_xml_for_number!(Ipv4Addr);
+to_xml_for_number!(Ipv6Addr);
+from_xml_for_number!(Ipv6Addr);
+to_xml_for_number!(SocketAddr);
+from_xml_for_number!(SocketAddr);
+to_xml_for_number!(SocketAddrV4);
+from_xml_for_number!(SocketAddrV4);
+to_xml_for_number!(SocketAddrV6);
+from_xml_for_number!(SocketAddrV6);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,18 +1,51 @@
+use std::collections::VecDeque;
+use std::ops::{Range, RangeInclusive};
 use std::{borrow::Cow, fmt};
 
 use thiserror::Error;
 
-pub use macros::{FromXml, ToXml};
+pub use macros::{FromXml, Schema, ToXml};
 
+pub mod c14n;
 #[doc(hidden)]
 pub mod de;
+pub mod dom;
+mod feed;
+pub use dom::Element;
+pub use feed::{Feed, FeedDeserializer};
 mod impls;
 use de::Context;
-pub use de::Deserializer;
-pub use impls::{display_to_xml, from_xml_str, OptionAccumulator};
+pub use de::{
+    Deserializer, DocumentIter, Fragments, Iter, Limits, NameStats, Options, SkipReason, Skipped,
+};
+pub use impls::{display_to_xml, from_xml_str, ArrayAccumulator, OptionAccumulator, Raw};
 #[doc(hidden)]
 pub mod ser;
-pub use ser::Serializer;
+pub use ser::{Checkpoint, Separator, Serializer, SerializerBuffers};
+mod wrapped;
+pub use wrapped::{Wrapped, WrappedAccumulator, Wrapper};
+mod map;
+pub use map::{EntryKeyValue, MapEntry, XmlMap};
+mod repeated;
+pub use repeated::Repeated;
+mod set;
+pub use set::Unique;
+mod stream;
+pub use stream::Stream;
+mod tuple;
+pub use tuple::TupleAccumulator;
+mod cell;
+pub use cell::CellAccumulator;
+pub mod adapters;
+mod pattern;
+pub mod schema;
+pub use schema::Schema;
+pub mod soap;
+pub mod validate;
+pub use validate::{Validator, Violation};
+pub mod wsdl;
+pub mod xmlrpc;
+pub mod xsd;
 
 pub trait ToXml {
     fn serialize<W: fmt::Write + ?Sized>(
@@ -26,7 +59,7 @@ pub trait ToXml {
     }
 }
 
-impl<'a, T: ToXml + ?Sized> ToXml for &'a T {
+impl<T: ToXml + ?Sized> ToXml for &T {
     fn serialize<W: fmt::Write + ?Sized>(
         &self,
         field: Option<Id<'_>>,
@@ -36,6 +69,80 @@ impl<'a, T: ToXml + ?Sized> ToXml for &'a T {
     }
 }
 
+impl<T: ToXml + ?Sized> ToXml for Box<T> {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        (**self).serialize(field, serializer)
+    }
+
+    fn present(&self) -> bool {
+        (**self).present()
+    }
+}
+
+/// An object-safe version of [`ToXml`], implemented for every type that implements it
+///
+/// [`ToXml::serialize()`] is generic over the output, so `dyn ToXml` is not possible. Trait
+/// objects of this trait do implement [`ToXml`], so values of different types can be stored as
+/// `Box<dyn ErasedToXml>` and serialized like any other value.
+pub trait ErasedToXml {
+    fn erased_serialize(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<'_, dyn fmt::Write + '_>,
+    ) -> Result<(), Error>;
+
+    fn erased_present(&self) -> bool;
+}
+
+impl<T: ToXml + ?Sized> ErasedToXml for T {
+    fn erased_serialize(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<'_, dyn fmt::Write + '_>,
+    ) -> Result<(), Error> {
+        self.serialize(field, serializer)
+    }
+
+    fn erased_present(&self) -> bool {
+        self.present()
+    }
+}
+
+macro_rules! to_xml_for_erased {
+    ($($ty:ty),*) => {
+        $(
+            impl ToXml for $ty {
+                fn serialize<W: fmt::Write + ?Sized>(
+                    &self,
+                    field: Option<Id<'_>>,
+                    serializer: &mut Serializer<W>,
+                ) -> Result<(), Error> {
+                    serializer.erased(|serializer| self.erased_serialize(field, serializer))
+                }
+
+                fn present(&self) -> bool {
+                    self.erased_present()
+                }
+            }
+        )*
+    };
+}
+
+to_xml_for_erased!(
+    dyn ErasedToXml + '_,
+    dyn ErasedToXml + Send + '_,
+    dyn ErasedToXml + Send + Sync + '_
+);
+
+/// The SOAPAction of a request type, set with `#[xml(soap_action = "...")]` on a `ToXml` derive
+pub trait SoapAction {
+    const SOAP_ACTION: &'static str;
+}
+
 pub trait FromXml<'xml>: Sized {
     fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool;
 
@@ -66,6 +173,12 @@ impl<T> Accumulate<Vec<T>> for Vec<T> {
     }
 }
 
+impl<T> Accumulate<VecDeque<T>> for VecDeque<T> {
+    fn try_done(self, _: &'static str) -> Result<VecDeque<T>, Error> {
+        Ok(self)
+    }
+}
+
 impl<'a, T> Accumulate<Cow<'a, [T]>> for Vec<T>
 where
     [T]: ToOwned<Owned = Vec<T>>,
@@ -81,8 +194,216 @@ impl<T> Accumulate<Option<T>> for Option<T> {
     }
 }
 
+/// A range that can be (de)serialized from two sibling elements with `#[xml(range)]`
+pub trait Interval {
+    type Bound;
+
+    fn from_bounds(start: Self::Bound, end: Self::Bound) -> Self;
+    fn bounds(&self) -> (&Self::Bound, &Self::Bound);
+}
+
+impl<T> Interval for Range<T> {
+    type Bound = T;
+
+    fn from_bounds(start: T, end: T) -> Self {
+        start..end
+    }
+
+    fn bounds(&self) -> (&T, &T) {
+        (&self.start, &self.end)
+    }
+}
+
+impl<T> Interval for RangeInclusive<T> {
+    type Bound = T;
+
+    fn from_bounds(start: T, end: T) -> Self {
+        start..=end
+    }
+
+    fn bounds(&self) -> (&T, &T) {
+        (self.start(), self.end())
+    }
+}
+
+/// Deserialize a document from `input`
+///
+/// This never panics, whatever the input: malformed or hostile documents result in an error.
+/// Elements nested deeper than [`Limits::MAX_DEPTH`](de::Limits::MAX_DEPTH) are rejected with
+/// [`Error::NestingTooDeep`].
+///
+/// This is a breaking change: `from_str()` used to accept any depth, overflowing the stack on
+/// deeply nested input. Trusted documents that need more levels can be read with
+/// [`from_str_with_limits()`] and a higher [`Limits::max_depth`](de::Limits::max_depth).
 pub fn from_str<'xml, T: FromXml<'xml>>(input: &'xml str) -> Result<T, Error> {
-    let (mut context, root) = Context::new(input)?;
+    let mut context = Context::new(input);
+    deserialize_root(&mut context)
+}
+
+/// Iterate over the children of the root element of `input` that match `T`
+///
+/// Each element is deserialized as it is read, so documents with many repeated elements can be
+/// processed without collecting them into a `Vec<T>`. Other children of the root are skipped;
+/// the root element itself is not checked. Iteration ends after the first error.
+pub fn from_str_iter<'xml, T: FromXml<'xml>>(input: &'xml str) -> DocumentIter<'xml, T> {
+    DocumentIter::new(input)
+}
+
+/// Deserialize each of the sibling root elements in `input`, like records concatenated in a log
+///
+/// `input` is read as a document fragment, so it may hold any number of root elements with
+/// whitespace, comments and processing instructions between them, but no document type
+/// declaration. Every root element must match `T`. Iteration ends after the first error.
+pub fn from_str_fragments<'xml, T: FromXml<'xml>>(input: &'xml str) -> Fragments<'xml, T> {
+    Fragments::new(input)
+}
+
+/// Deserialize only the element at `path` in `input`, skipping the rest of the document
+///
+/// `path` lists the local names of the elements leading to the one to deserialize, starting
+/// with the root element, like `&["Envelope", "Body", "GetResponse"]`; namespaces are not
+/// checked. The first element at that path is deserialized as `T`, and the input after it is
+/// not parsed at all.
+pub fn extract<'xml, T: FromXml<'xml>>(input: &'xml str, path: &[&str]) -> Result<T, Error> {
+    let mut context = Context::new(input);
+    let result = extract_root(&mut context, path);
+    result.map_err(|error| context.locate(error))
+}
+
+fn extract_root<'xml, T: FromXml<'xml>>(
+    context: &mut Context<'xml>,
+    path: &[&str],
+) -> Result<T, Error> {
+    let not_found = || Error::PathNotFound(path.join(" > "));
+    let (first, rest) = path.split_first().ok_or_else(not_found)?;
+    let root = context.root()?;
+    if root.local != *first {
+        return Err(not_found());
+    }
+
+    Deserializer::new(root, context)
+        .extract(rest)?
+        .ok_or_else(not_found)
+}
+
+/// Deserialize a document from bytes, decoding UTF-16 input as needed
+///
+/// UTF-16 is detected from a byte order mark or from the start of an XML declaration; anything
+/// else is read as UTF-8 (after skipping a UTF-8 byte order mark, if present).
+#[cfg(feature = "utf16")]
+pub fn from_bytes<T: FromXmlOwned>(input: &[u8]) -> Result<T, Error> {
+    from_str(&de::decode_bytes(input)?)
+}
+
+/// Deserialize a UTF-8 document from bytes, skipping a byte order mark
+///
+/// Documents that declare an encoding other than UTF-8 (or its subset US-ASCII) are rejected with
+/// [`Error::UnsupportedEncoding`]; use `from_bytes()` from the `utf16` feature for UTF-16 input.
+pub fn from_slice<'xml, T: FromXml<'xml>>(input: &'xml [u8]) -> Result<T, Error> {
+    from_str(de::utf8(input)?)
+}
+
+/// Deserialize `input`, also returning a report of the content that was skipped
+pub fn from_str_with_report<'xml, T: FromXml<'xml>>(
+    input: &'xml str,
+) -> Result<(T, Vec<Skipped>), Error> {
+    let mut context = Context::new(input);
+    context.report = Some(Vec::new());
+    let value = deserialize_root(&mut context)?;
+    Ok((value, context.report.unwrap_or_default()))
+}
+
+/// Deserialize untrusted `input`, rejecting names that exceed `limits`
+///
+/// Also returns counters for the element and attribute names in the document.
+pub fn from_str_with_limits<'xml, T: FromXml<'xml>>(
+    input: &'xml str,
+    limits: Limits,
+) -> Result<(T, NameStats<'xml>), Error> {
+    let mut context = Context::new(input);
+    context.limits = limits;
+    context.stats = Some(NameStats::default());
+    let value = deserialize_root(&mut context)?;
+    Ok((value, context.stats.unwrap_or_default()))
+}
+
+/// Deserialize `input`, rejecting elements nested deeper than `max_depth`
+///
+/// This overrides the default of [`Limits::MAX_DEPTH`](de::Limits::MAX_DEPTH) enforced by
+/// [`from_str()`]; exceeding it fails with [`Error::NestingTooDeep`].
+pub fn from_str_with_max_depth<'xml, T: FromXml<'xml>>(
+    input: &'xml str,
+    max_depth: usize,
+) -> Result<T, Error> {
+    let mut context = Context::new(input);
+    context.limits.max_depth = max_depth;
+    deserialize_root(&mut context)
+}
+
+/// Deserialize `input` with the given `options`
+pub fn from_str_with_options<'xml, T: FromXml<'xml>>(
+    input: &'xml str,
+    options: Options,
+) -> Result<T, Error> {
+    let mut context = Context::new(input);
+    context.options = options;
+    deserialize_root(&mut context)
+}
+
+/// Deserialize `input` if it is valid according to `validator`
+///
+/// The document is checked before it is deserialized; if it does not match the schema, all
+/// violations are returned in [`Error::Invalid`].
+pub fn from_str_validated<'xml, T: FromXml<'xml>>(
+    input: &'xml str,
+    validator: &Validator,
+) -> Result<T, Error> {
+    let violations = validator.validate_str(input)?;
+    match violations.is_empty() {
+        true => from_str(input),
+        false => Err(Error::Invalid(violations)),
+    }
+}
+
+/// Deserialize `input`, continuing after errors in fields to return all of them
+///
+/// A field that fails to deserialize is skipped, so the value it belongs to is usually missing
+/// as well; such missing values are not reported separately. Errors are reported with their
+/// position, as in [`Error::Located`].
+pub fn from_str_all_errors<'xml, T: FromXml<'xml>>(input: &'xml str) -> Result<T, Vec<Error>> {
+    let mut context = Context::new(input);
+    context.errors = Some(Vec::new());
+    let (value, fatal) = match deserialize_document(&mut context) {
+        Ok(value) => (Some(value), None),
+        Err(error) => (None, context.record(error).err()),
+    };
+
+    let fatal = fatal.map(|error| context.locate(error));
+    let mut errors = context.errors.take().unwrap_or_default();
+    errors.extend(fatal);
+
+    match value {
+        Some(value) if errors.is_empty() => Ok(value),
+        _ => Err(errors),
+    }
+}
+
+/// Deserialize the document in `context`, adding the position to errors if asked for
+fn deserialize_root<'xml, T: FromXml<'xml>>(context: &mut Context<'xml>) -> Result<T, Error> {
+    let result = deserialize_document(context);
+    result.map_err(|error| context.locate(error))
+}
+
+fn deserialize_document<'xml, T: FromXml<'xml>>(context: &mut Context<'xml>) -> Result<T, Error> {
+    let root = context.root()?;
+    deserialize_element(context, root)
+}
+
+/// Deserialize `root`, the root element of a document or fragment, just opened in `context`
+fn deserialize_element<'xml, T: FromXml<'xml>>(
+    context: &mut Context<'xml>,
+    root: de::Element<'xml>,
+) -> Result<T, Error> {
     let id = context.element_id(&root)?;
 
     if !T::matches(id, None) {
@@ -99,7 +420,7 @@ pub fn from_str<'xml, T: FromXml<'xml>>(input: &'xml str) -> Result<T, Error> {
     T::deserialize(
         &mut value,
         "<root element>",
-        &mut Deserializer::new(root, &mut context),
+        &mut Deserializer::new(root, context),
     )?;
     value.try_done("<root element>")
 }
@@ -110,6 +431,14 @@ pub fn to_string(value: &(impl ToXml + ?Sized)) -> Result<String, Error> {
     Ok(output)
 }
 
+/// Serialize `value` into `output`, replacing its contents but keeping its allocation
+///
+/// If serialization fails, `output` holds whatever was written before the error.
+pub fn to_string_in(value: &(impl ToXml + ?Sized), output: &mut String) -> Result<(), Error> {
+    output.clear();
+    to_writer(value, output)
+}
+
 pub fn to_writer(
     value: &(impl ToXml + ?Sized),
     output: &mut (impl fmt::Write + ?Sized),
@@ -117,6 +446,95 @@ pub fn to_writer(
     value.serialize(None, &mut Serializer::new(output))
 }
 
+/// Serialize `value`, writing `bool` values as `1` and `0` rather than `true` and `false`
+///
+/// Both forms are accepted when deserializing.
+pub fn to_string_with_numeric_bools(value: &(impl ToXml + ?Sized)) -> Result<String, Error> {
+    let mut output = String::new();
+    {
+        let mut serializer = Serializer::new(&mut output);
+        serializer.set_numeric_bools(true);
+        value.serialize(None, &mut serializer)?;
+    }
+
+    Ok(output)
+}
+
+/// Serialize `value` after an XML declaration, with a `standalone` document declaration if given
+pub fn to_string_with_decl(
+    value: &(impl ToXml + ?Sized),
+    standalone: Option<bool>,
+) -> Result<String, Error> {
+    let mut output = String::new();
+    {
+        let mut serializer = Serializer::new(&mut output);
+        serializer.write_declaration(standalone)?;
+        value.serialize(None, &mut serializer)?;
+    }
+
+    Ok(output)
+}
+
+/// Serialize `value` with nested elements on their own lines, indented by `indent`
+///
+/// No whitespace is added inside elements that directly contain text, or inside elements named
+/// in `mixed` (like `p` for markup that may only contain child elements), so their content is
+/// preserved.
+pub fn to_string_pretty(
+    value: &(impl ToXml + ?Sized),
+    indent: &str,
+    mixed: &[&str],
+) -> Result<String, Error> {
+    let compact = to_string(value)?;
+    let mut output = String::with_capacity(compact.len());
+    ser::write_pretty(&compact, indent, mixed, &mut output)?;
+    Ok(output)
+}
+
+/// Serialize `value` in Exclusive XML Canonicalization form, see [`c14n::exclusive()`]
+pub fn to_string_exc_c14n(
+    value: &(impl ToXml + ?Sized),
+    inclusive_prefixes: &[&str],
+) -> Result<String, Error> {
+    c14n::exclusive(&to_string(value)?, inclusive_prefixes)
+}
+
+/// Serialize `values` as sibling elements without a root, one per line
+///
+/// The output can be read back with [`from_str_fragments()`].
+pub fn to_string_fragments<'a, T: ToXml + ?Sized + 'a>(
+    values: impl IntoIterator<Item = &'a T>,
+) -> Result<String, Error> {
+    let mut output = String::new();
+    to_writer_many(values, Separator::Newline, &mut output)?;
+    Ok(output)
+}
+
+/// Serialize each of `values` as a separate document into the same `output`
+///
+/// Every document gets a fresh serializer, so namespace declarations do not carry over between
+/// documents.
+pub fn to_writer_many<'a, T: ToXml + ?Sized + 'a>(
+    values: impl IntoIterator<Item = &'a T>,
+    separator: Separator,
+    output: &mut (impl fmt::Write + ?Sized),
+) -> Result<(), Error> {
+    for value in values {
+        let mut serializer = Serializer::new(output);
+        if let Separator::Declaration = separator {
+            serializer.write_declaration(None)?;
+        }
+
+        value.serialize(None, &mut serializer)?;
+        drop(serializer);
+        if let Separator::Newline | Separator::Declaration = separator {
+            output.write_char('\n')?;
+        }
+    }
+
+    Ok(())
+}
+
 pub trait FromXmlOwned: for<'xml> FromXml<'xml> {}
 
 impl<T> FromXmlOwned for T where T: for<'xml> FromXml<'xml> {}
@@ -127,6 +545,10 @@ pub enum Error {
     Format(#[from] fmt::Error),
     #[error("invalid entity: {0}")]
     InvalidEntity(String),
+    #[error("invalid encoding: {0}")]
+    InvalidEncoding(&'static str),
+    #[error("unsupported encoding: {0}")]
+    UnsupportedEncoding(String),
     #[error("parse: {0}")]
     Parse(#[from] xmlparser::Error),
     #[error("other: {0}")]
@@ -153,6 +575,179 @@ pub enum Error {
     ExpectedScalar(String),
     #[error("duplicate value for {0}")]
     DuplicateValue(&'static str),
+    #[error("element with xsi:nil=\"true\" for a value that is not optional")]
+    UnexpectedNil,
+    #[error("expected {expected} values for {field}, found {found}")]
+    LengthMismatch {
+        field: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    #[error("document type declarations are not supported (at {0})")]
+    DtdNotSupported(Position),
+    #[error("external DTDs are not supported (at {0})")]
+    ExternalDtdNotSupported(Position),
+    #[error("entity declarations are not supported (at {0})")]
+    EntityDeclarationNotSupported(Position),
+    #[error("XML declaration is only allowed at the start of the document (at {0})")]
+    MisplacedDeclaration(Position),
+    #[error("name exceeds the configured maximum length (at {0})")]
+    NameTooLong(Position),
+    #[error("prefix exceeds the configured maximum length (at {0})")]
+    PrefixTooLong(Position),
+    #[error("elements are nested too deeply (at {0})")]
+    NestingTooDeep(Position),
+    #[error("input exceeds the configured maximum length")]
+    InputTooLong,
+    #[error("element has more attributes than the configured maximum (at {0})")]
+    TooManyAttributes(Position),
+    #[error("text exceeds the configured maximum length (at {0})")]
+    TextTooLong(Position),
+    /// A SOAP envelope holds a fault instead of the expected body
+    #[error("SOAP fault: {0}")]
+    Fault(Box<soap::Fault>),
+    /// The document does not match the schema it was validated against
+    #[error("document does not match the schema ({} violations)", .0.len())]
+    Invalid(Vec<Violation>),
+    /// Two different types claim the same name in a schema, like two instances of a generic type
+    #[error("conflicting schema definitions for {0}")]
+    SchemaConflict(String),
+    /// No element was found at the path given to [`extract()`]
+    #[error("no element at path {0}")]
+    PathNotFound(String),
+    /// An error found while deserializing, with the position of the last token read and the path
+    /// to the element being read
+    ///
+    /// Only returned when asked for with [`Options::positions`], and for the errors collected by
+    /// [`from_str_all_errors()`].
+    #[error("{error} (at {position}, in {path})")]
+    Located {
+        error: Box<Error>,
+        position: Position,
+        path: String,
+    },
+}
+
+impl Error {
+    /// The underlying error, without the position added by [`Error::Located`]
+    pub fn into_inner(self) -> Self {
+        match self {
+            Self::Located { error, .. } => *error,
+            error => error,
+        }
+    }
+
+    /// Whether the error is about a particular place in the input that it doesn't give yet
+    ///
+    /// Every variant is listed, so new ones have to decide whether they are wrapped in
+    /// [`Error::Located`].
+    fn locatable(&self) -> bool {
+        match self {
+            Self::InvalidEntity(_)
+            | Self::Other(_)
+            | Self::UnexpectedEndOfStream
+            | Self::UnexpectedValue(_)
+            | Self::UnexpectedTag(_)
+            | Self::MissingTag
+            | Self::MissingValue(_)
+            | Self::UnexpectedToken(_)
+            | Self::UnknownPrefix(_)
+            | Self::UnexpectedNode(_)
+            | Self::UnexpectedState(_)
+            | Self::ExpectedScalar(_)
+            | Self::DuplicateValue(_)
+            | Self::UnexpectedNil
+            | Self::LengthMismatch { .. } => true,
+            // Already positioned
+            Self::Parse(_)
+            | Self::DtdNotSupported(_)
+            | Self::ExternalDtdNotSupported(_)
+            | Self::EntityDeclarationNotSupported(_)
+            | Self::MisplacedDeclaration(_)
+            | Self::NameTooLong(_)
+            | Self::PrefixTooLong(_)
+            | Self::NestingTooDeep(_)
+            | Self::TooManyAttributes(_)
+            | Self::TextTooLong(_)
+            | Self::Located { .. } => false,
+            // About the input or the document as a whole
+            Self::Format(_)
+            | Self::InvalidEncoding(_)
+            | Self::UnsupportedEncoding(_)
+            | Self::InputTooLong
+            | Self::Fault(_)
+            | Self::Invalid(_)
+            | Self::SchemaConflict(_)
+            | Self::PathNotFound(_) => false,
+        }
+    }
+
+    /// Whether deserialization can't continue after the error, even when collecting errors
+    ///
+    /// Every variant is listed, so new ones have to decide whether they can be recovered from.
+    pub(crate) fn is_fatal(&self) -> bool {
+        match self {
+            Self::Format(_)
+            | Self::InvalidEncoding(_)
+            | Self::UnsupportedEncoding(_)
+            | Self::Parse(_)
+            | Self::UnexpectedEndOfStream
+            | Self::UnexpectedState(_)
+            | Self::DtdNotSupported(_)
+            | Self::ExternalDtdNotSupported(_)
+            | Self::EntityDeclarationNotSupported(_)
+            | Self::MisplacedDeclaration(_)
+            | Self::NameTooLong(_)
+            | Self::PrefixTooLong(_)
+            | Self::NestingTooDeep(_)
+            | Self::InputTooLong
+            | Self::TooManyAttributes(_)
+            | Self::TextTooLong(_)
+            | Self::Fault(_)
+            | Self::Invalid(_)
+            | Self::SchemaConflict(_)
+            | Self::PathNotFound(_) => true,
+            Self::InvalidEntity(_)
+            | Self::Other(_)
+            | Self::UnexpectedValue(_)
+            | Self::UnexpectedTag(_)
+            | Self::MissingTag
+            | Self::MissingValue(_)
+            | Self::UnexpectedToken(_)
+            | Self::UnknownPrefix(_)
+            | Self::UnexpectedNode(_)
+            | Self::ExpectedScalar(_)
+            | Self::DuplicateValue(_)
+            | Self::UnexpectedNil
+            | Self::LengthMismatch { .. } => false,
+            Self::Located { error, .. } => error.is_fatal(),
+        }
+    }
+}
+
+/// A location in the input, with 1-based line and column (in characters)
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Position {
+    pub line: u32,
+    pub column: u32,
+}
+
+impl Position {
+    /// The position of the byte `offset` in `input`
+    pub(crate) fn from_offset(input: &str, offset: usize) -> Self {
+        let before = input.get(..offset).unwrap_or(input);
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            line: before.matches('\n').count() as u32 + 1,
+            column: before[line_start..].chars().count() as u32 + 1,
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -161,8 +756,66 @@ pub enum Kind {
     Element,
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+/// The identity of an element or attribute: a namespace and a local name
+///
+/// An empty `ns` means the name is not in any namespace.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Id<'a> {
     pub ns: &'a str,
     pub name: &'a str,
 }
+
+impl<'a> Id<'a> {
+    pub const fn new(ns: &'a str, name: &'a str) -> Self {
+        Self { ns, name }
+    }
+
+    /// An identifier that is not in any namespace
+    pub const fn local(name: &'a str) -> Self {
+        Self { ns: "", name }
+    }
+
+    /// Parse an identifier in Clark notation, like `{urn:example}name`, or a plain `name`
+    pub fn parse(input: &'a str) -> Result<Self, Error> {
+        let (ns, name) = match input.strip_prefix('{') {
+            Some(rest) => match rest.split_once('}') {
+                Some((ns, name)) => (ns, name),
+                None => {
+                    return Err(Error::UnexpectedValue(format!(
+                        "missing `}}` in identifier `{input}`"
+                    )))
+                }
+            },
+            None => ("", input),
+        };
+
+        match name.is_empty() || name.contains(['{', '}']) {
+            true => Err(Error::UnexpectedValue(format!(
+                "invalid identifier `{input}`"
+            ))),
+            false => Ok(Self { ns, name }),
+        }
+    }
+
+    /// Whether this identifier has the local `name` in namespace `ns`
+    pub fn is(&self, ns: &str, name: &str) -> bool {
+        self.ns == ns && self.name == name
+    }
+}
+
+/// Formats the identifier in Clark notation, omitting the braces if there is no namespace
+impl fmt::Display for Id<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.ns.is_empty() {
+            true => f.write_str(self.name),
+            false => write!(f, "{{{}}}{}", self.ns, self.name),
+        }
+    }
+}
+
+/// The identifier in Clark notation, as written by its [`Display`](fmt::Display) impl
+impl From<Id<'_>> for String {
+    fn from(id: Id<'_>) -> Self {
+        id.to_string()
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::de::Node;
+use crate::{Accumulate, Deserializer, Error, FromXml, Id, Kind, Serializer, ToXml};
+
+/// Names the elements that hold each entry of a map, and its key and value
+///
+/// Maps are (de)serialized as an element named after the field, containing an element per
+/// entry. Entries hold the key and the value as child elements, like any other field, so keys and
+/// values of types that pick their own element name (like derived structs) ignore the names here.
+/// A key that appears in more than one entry is an [`Error::DuplicateValue`].
+pub trait MapEntry {
+    const ENTRY: &'static str;
+    const KEY: &'static str;
+    const VALUE: &'static str;
+}
+
+/// The entry names used for maps by default: `<entry><key>..</key><value>..</value></entry>`
+pub struct EntryKeyValue;
+
+impl MapEntry for EntryKeyValue {
+    const ENTRY: &'static str = "entry";
+    const KEY: &'static str = "key";
+    const VALUE: &'static str = "value";
+}
+
+/// A map with entry, key and value elements named by `N`
+pub struct XmlMap<M, N = EntryKeyValue> {
+    pub inner: M,
+    marker: PhantomData<N>,
+}
+
+impl<M, N> XmlMap<M, N> {
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> M {
+        self.inner
+    }
+}
+
+impl<M, N> From<M> for XmlMap<M, N> {
+    fn from(inner: M) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<M: Clone, N> Clone for XmlMap<M, N> {
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone())
+    }
+}
+
+impl<M: Default, N> Default for XmlMap<M, N> {
+    fn default() -> Self {
+        Self::new(M::default())
+    }
+}
+
+impl<M: fmt::Debug, N> fmt::Debug for XmlMap<M, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("XmlMap").field(&self.inner).finish()
+    }
+}
+
+impl<M: PartialEq, N> PartialEq for XmlMap<M, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<M: Eq, N> Eq for XmlMap<M, N> {}
+
+impl<M, N> Accumulate<XmlMap<M, N>> for XmlMap<M, N>
+where
+    M: Default,
+{
+    fn try_done(self, _: &'static str) -> Result<XmlMap<M, N>, Error> {
+        Ok(self)
+    }
+}
+
+macro_rules! map_impls {
+    ($map:ident, $($bound:tt)+) => {
+        impl<K: ToXml, V: ToXml, N: MapEntry> ToXml for XmlMap<$map<K, V>, N> {
+            fn serialize<W: fmt::Write + ?Sized>(
+                &self,
+                field: Option<Id<'_>>,
+                serializer: &mut Serializer<W>,
+            ) -> Result<(), Error> {
+                serialize_map::<N, _, _, _>(&self.inner, field, serializer)
+            }
+        }
+
+        impl<'xml, K, V, N> FromXml<'xml> for XmlMap<$map<K, V>, N>
+        where
+            K: FromXml<'xml> + $($bound)+,
+            V: FromXml<'xml>,
+            N: MapEntry,
+        {
+            #[inline]
+            fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+                field == Some(id)
+            }
+
+            fn deserialize<'cx>(
+                into: &mut Self::Accumulator,
+                field: &'static str,
+                deserializer: &mut Deserializer<'cx, 'xml>,
+            ) -> Result<(), Error> {
+                deserialize_map::<N, _, _>(|key, value| into.inner.insert(key, value).is_none(), field, deserializer)
+            }
+
+            type Accumulator = Self;
+            const KIND: Kind = Kind::Element;
+        }
+
+        impl<K: ToXml, V: ToXml> ToXml for $map<K, V> {
+            fn serialize<W: fmt::Write + ?Sized>(
+                &self,
+                field: Option<Id<'_>>,
+                serializer: &mut Serializer<W>,
+            ) -> Result<(), Error> {
+                serialize_map::<EntryKeyValue, _, _, _>(self, field, serializer)
+            }
+        }
+
+        impl<'xml, K, V> FromXml<'xml> for $map<K, V>
+        where
+            K: FromXml<'xml> + $($bound)+,
+            V: FromXml<'xml>,
+        {
+            #[inline]
+            fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+                field == Some(id)
+            }
+
+            fn deserialize<'cx>(
+                into: &mut Self::Accumulator,
+                field: &'static str,
+                deserializer: &mut Deserializer<'cx, 'xml>,
+            ) -> Result<(), Error> {
+                deserialize_map::<EntryKeyValue, _, _>(|key, value| into.insert(key, value).is_none(), field, deserializer)
+            }
+
+            type Accumulator = Self;
+            const KIND: Kind = Kind::Element;
+        }
+
+        impl<K, V> Accumulate<$map<K, V>> for $map<K, V> {
+            fn try_done(self, _: &'static str) -> Result<$map<K, V>, Error> {
+                Ok(self)
+            }
+        }
+    };
+}
+
+// `BTreeMap` entries are written in key order, so its output is deterministic
+map_impls!(BTreeMap, Ord);
+map_impls!(HashMap, Eq + Hash);
+
+fn serialize_map<'a, N, K, V, W>(
+    entries: impl IntoIterator<Item = (&'a K, &'a V)>,
+    field: Option<Id<'_>>,
+    serializer: &mut Serializer<W>,
+) -> Result<(), Error>
+where
+    N: MapEntry,
+    K: ToXml + 'a,
+    V: ToXml + 'a,
+    W: fmt::Write + ?Sized,
+{
+    let id = match field {
+        Some(id) => id,
+        None => return Err(Error::UnexpectedState("map without field name")),
+    };
+
+    let prefix = serializer.write_start(id.name, id.ns)?;
+    serializer.end_start()?;
+    for (key, value) in entries {
+        let entry = serializer.write_start(N::ENTRY, id.ns)?;
+        serializer.end_start()?;
+        key.serialize(
+            Some(Id {
+                ns: id.ns,
+                name: N::KEY,
+            }),
+            serializer,
+        )?;
+        value.serialize(
+            Some(Id {
+                ns: id.ns,
+                name: N::VALUE,
+            }),
+            serializer,
+        )?;
+        serializer.write_close(entry.as_deref(), N::ENTRY)?;
+    }
+
+    serializer.write_close(prefix.as_deref(), id.name)?;
+    Ok(())
+}
+
+/// Deserialize the entries of a map, passing each to `insert`
+///
+/// `insert` returns `false` if the map already had the key, which is an error.
+fn deserialize_map<'xml, N, K, V>(
+    mut insert: impl FnMut(K, V) -> bool,
+    field: &'static str,
+    deserializer: &mut Deserializer<'_, 'xml>,
+) -> Result<(), Error>
+where
+    N: MapEntry,
+    K: FromXml<'xml>,
+    V: FromXml<'xml>,
+{
+    let ns = deserializer.parent()?.ns;
+    let (key_id, value_id) = (Id { ns, name: N::KEY }, Id { ns, name: N::VALUE });
+    loop {
+        let element = match deserializer.next() {
+            Some(Ok(Node::Open(element))) => element,
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => return Err(e),
+            None => return Ok(()),
+        };
+
+        let id = deserializer.element_id(&element)?;
+        let mut entry = deserializer.nested(element);
+        if id != (Id { ns, name: N::ENTRY }) {
+            entry.ignore()?;
+            continue;
+        }
+
+        let mut key = K::Accumulator::default();
+        let mut value = V::Accumulator::default();
+        loop {
+            let element = match entry.next() {
+                Some(Ok(Node::Open(element))) => element,
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e),
+                None => break,
+            };
+
+            let id = entry.element_id(&element)?;
+            let mut nested = entry.nested(element);
+            if K::matches(id, Some(key_id)) {
+                K::deserialize(&mut key, field, &mut nested)?;
+                if K::KIND == Kind::Scalar {
+                    nested.ignore()?;
+                }
+            } else if V::matches(id, Some(value_id)) {
+                V::deserialize(&mut value, field, &mut nested)?;
+                if V::KIND == Kind::Scalar {
+                    nested.ignore()?;
+                }
+            } else {
+                nested.ignore()?;
+            }
+        }
+
+        if !insert(key.try_done(field)?, value.try_done(field)?) {
+            return Err(Error::DuplicateValue(field));
+        }
+    }
+}
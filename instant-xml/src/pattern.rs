@@ -0,0 +1,610 @@
+//! Regular expressions as used by the `pattern` facet of XML Schema
+//!
+//! See <https://www.w3.org/TR/xmlschema-2/#regexs>. Patterns always match the whole value, and
+//! `^` and `$` are ordinary characters. Patterns are compiled to a program that is run on all
+//! alternatives at once, so matching takes time linear in the length of the value, whatever the
+//! pattern.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::Error;
+
+/// A compiled `pattern` facet
+#[derive(Clone, Debug)]
+pub(crate) struct Pattern {
+    program: Vec<Inst>,
+}
+
+impl Pattern {
+    pub(crate) fn new(pattern: &str) -> Result<Self, Error> {
+        let mut parser = Parser {
+            pattern,
+            chars: pattern.chars().peekable(),
+        };
+
+        let ast = parser.alternation()?;
+        if parser.chars.next().is_some() {
+            return Err(parser.error("unbalanced `)`"));
+        }
+
+        let mut program = Vec::new();
+        compile(&ast, &mut program)?;
+        program.push(Inst::Match);
+        Ok(Self { program })
+    }
+
+    /// Whether the whole of `value` matches
+    pub(crate) fn is_match(&self, value: &str) -> bool {
+        let mut current = Threads::new(self.program.len());
+        let mut next = Threads::new(self.program.len());
+        current.add(&self.program, 0);
+        for c in value.chars() {
+            for &pc in &current.list {
+                if let Inst::Set(set) = &self.program[pc] {
+                    if set.contains(c) {
+                        next.add(&self.program, pc + 1);
+                    }
+                }
+            }
+
+            std::mem::swap(&mut current, &mut next);
+            next.clear();
+            if current.list.is_empty() {
+                return false;
+            }
+        }
+
+        current
+            .list
+            .iter()
+            .any(|&pc| matches!(self.program[pc], Inst::Match))
+    }
+}
+
+/// The set of instructions that are active at a position in the value
+struct Threads {
+    list: Vec<usize>,
+    /// Every instruction added since the last clear, including jumps and splits
+    visited: Vec<usize>,
+    seen: Vec<bool>,
+}
+
+impl Threads {
+    fn new(len: usize) -> Self {
+        Self {
+            list: Vec::new(),
+            visited: Vec::new(),
+            seen: vec![false; len],
+        }
+    }
+
+    /// Add `pc` and the instructions it jumps to without consuming a character
+    fn add(&mut self, program: &[Inst], pc: usize) {
+        let mut stack = vec![pc];
+        while let Some(pc) = stack.pop() {
+            if self.seen[pc] {
+                continue;
+            }
+
+            self.seen[pc] = true;
+            self.visited.push(pc);
+            match &program[pc] {
+                Inst::Jump(to) => stack.push(*to),
+                Inst::Split(first, second) => {
+                    stack.push(*second);
+                    stack.push(*first);
+                }
+                Inst::Set(_) | Inst::Match => self.list.push(pc),
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.list.clear();
+        for pc in self.visited.drain(..) {
+            self.seen[pc] = false;
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Inst {
+    /// Consume a character in the set
+    Set(Set),
+    /// Continue at both instructions
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+/// Patterns that expand to more instructions than this are rejected
+const MAX_PROGRAM: usize = 100_000;
+
+fn compile(ast: &Ast, program: &mut Vec<Inst>) -> Result<(), Error> {
+    if program.len() > MAX_PROGRAM {
+        return Err(Error::UnexpectedValue("pattern is too large".to_owned()));
+    }
+
+    match ast {
+        Ast::Set(set) => program.push(Inst::Set(set.clone())),
+        Ast::Sequence(items) => {
+            for item in items {
+                compile(item, program)?;
+            }
+        }
+        Ast::Alternation(branches) => {
+            let mut jumps = Vec::new();
+            for (i, branch) in branches.iter().enumerate() {
+                if i + 1 == branches.len() {
+                    compile(branch, program)?;
+                    break;
+                }
+
+                let split = program.len();
+                program.push(Inst::Split(split + 1, 0));
+                compile(branch, program)?;
+                jumps.push(program.len());
+                program.push(Inst::Jump(0));
+                program[split] = Inst::Split(split + 1, program.len());
+            }
+
+            let end = program.len();
+            for jump in jumps {
+                program[jump] = Inst::Jump(end);
+            }
+        }
+        Ast::Repeat { item, min, max } => {
+            for _ in 0..*min {
+                compile(item, program)?;
+            }
+
+            match max {
+                None => {
+                    let split = program.len();
+                    program.push(Inst::Split(split + 1, 0));
+                    compile(item, program)?;
+                    program.push(Inst::Jump(split));
+                    program[split] = Inst::Split(split + 1, program.len());
+                }
+                Some(max) => {
+                    let mut splits = Vec::new();
+                    for _ in *min..*max {
+                        splits.push(program.len());
+                        program.push(Inst::Split(program.len() + 1, 0));
+                        compile(item, program)?;
+                    }
+
+                    let end = program.len();
+                    for split in splits {
+                        program[split] = Inst::Split(split + 1, end);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+enum Ast {
+    Set(Set),
+    Sequence(Vec<Ast>),
+    Alternation(Vec<Ast>),
+    Repeat {
+        item: Box<Ast>,
+        min: u32,
+        max: Option<u32>,
+    },
+}
+
+struct Parser<'a> {
+    pattern: &'a str,
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    /// `regExp ::= branch ( '|' branch )*`
+    fn alternation(&mut self) -> Result<Ast, Error> {
+        let mut branches = vec![self.branch()?];
+        while self.chars.next_if_eq(&'|').is_some() {
+            branches.push(self.branch()?);
+        }
+
+        Ok(match branches.len() {
+            1 => branches.remove(0),
+            _ => Ast::Alternation(branches),
+        })
+    }
+
+    /// `branch ::= piece*`, where `piece ::= atom quantifier?`
+    fn branch(&mut self) -> Result<Ast, Error> {
+        let mut pieces = Vec::new();
+        while let Some(&c) = self.chars.peek() {
+            let atom = match c {
+                '|' | ')' => break,
+                '(' => {
+                    self.chars.next();
+                    let inner = self.alternation()?;
+                    if self.chars.next() != Some(')') {
+                        return Err(self.error("unbalanced `(`"));
+                    }
+
+                    inner
+                }
+                '[' => {
+                    self.chars.next();
+                    Ast::Set(self.class()?)
+                }
+                '.' => {
+                    self.chars.next();
+                    Ast::Set(Set::not(vec![
+                        Item::Range('\n', '\n'),
+                        Item::Range('\r', '\r'),
+                    ]))
+                }
+                '\\' => {
+                    self.chars.next();
+                    Ast::Set(self.escape()?)
+                }
+                '?' | '*' | '+' | '{' | '}' | ']' => {
+                    return Err(self.error(&format!("unexpected `{c}`")))
+                }
+                c => {
+                    self.chars.next();
+                    Ast::Set(Set::char(c))
+                }
+            };
+
+            pieces.push(self.quantifier(atom)?);
+        }
+
+        Ok(match pieces.len() {
+            1 => pieces.remove(0),
+            _ => Ast::Sequence(pieces),
+        })
+    }
+
+    fn quantifier(&mut self, atom: Ast) -> Result<Ast, Error> {
+        let (min, max) = match self.chars.peek() {
+            Some('?') => (0, Some(1)),
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('{') => {
+                self.chars.next();
+                let min = self.number()?;
+                let max = match self.chars.next() {
+                    Some('}') => Some(min),
+                    Some(',') if self.chars.next_if_eq(&'}').is_some() => None,
+                    Some(',') => {
+                        let max = self.number()?;
+                        match self.chars.next() {
+                            Some('}') if max >= min => Some(max),
+                            _ => return Err(self.error("invalid quantifier")),
+                        }
+                    }
+                    _ => return Err(self.error("invalid quantifier")),
+                };
+
+                return Ok(Ast::Repeat {
+                    item: Box::new(atom),
+                    min,
+                    max,
+                });
+            }
+            _ => return Ok(atom),
+        };
+
+        self.chars.next();
+        Ok(Ast::Repeat {
+            item: Box::new(atom),
+            min,
+            max,
+        })
+    }
+
+    fn number(&mut self) -> Result<u32, Error> {
+        let mut digits = String::new();
+        while let Some(c) = self.chars.next_if(char::is_ascii_digit) {
+            digits.push(c);
+        }
+
+        digits.parse().map_err(|_| self.error("invalid quantifier"))
+    }
+
+    /// A character class expression, after the opening `[`
+    fn class(&mut self) -> Result<Set, Error> {
+        let negated = self.chars.next_if_eq(&'^').is_some();
+        let mut items = Vec::new();
+        let mut subtract = None;
+        loop {
+            let c = match self.chars.next() {
+                Some(c) => c,
+                None => return Err(self.error("unbalanced `[`")),
+            };
+
+            let start = match c {
+                ']' if !items.is_empty() => break,
+                '-' if self.chars.next_if_eq(&'[').is_some() => {
+                    subtract = Some(Box::new(self.class()?));
+                    match self.chars.next() {
+                        Some(']') => break,
+                        _ => return Err(self.error("subtraction must end a character class")),
+                    }
+                }
+                '[' => return Err(self.error("unescaped `[` in character class")),
+                '\\' => match self.escape()? {
+                    Set {
+                        negated: false,
+                        items: mut escaped,
+                        subtract: None,
+                    } if escaped.len() == 1 => match escaped.remove(0) {
+                        Item::Range(start, end) if start == end => start,
+                        item => {
+                            items.push(item);
+                            continue;
+                        }
+                    },
+                    set => {
+                        items.push(Item::Set(Box::new(set)));
+                        continue;
+                    }
+                },
+                c => c,
+            };
+
+            // A `-` is a range separator unless it is the last character of the class
+            let mut lookahead = self.chars.clone();
+            let end = match (lookahead.next(), lookahead.next()) {
+                (Some('-'), Some(']' | '[')) | (Some('-'), None) => start,
+                (Some('-'), Some(end)) => {
+                    self.chars.next();
+                    self.chars.next();
+                    match end {
+                        '\\' => match self.escape()? {
+                            Set { items, .. } if items.len() == 1 => match items[0] {
+                                Item::Range(end, other) if end == other => end,
+                                _ => return Err(self.error("invalid range in character class")),
+                            },
+                            _ => return Err(self.error("invalid range in character class")),
+                        },
+                        end => end,
+                    }
+                }
+                _ => start,
+            };
+
+            if end < start {
+                return Err(self.error("invalid range in character class"));
+            }
+
+            items.push(Item::Range(start, end));
+        }
+
+        Ok(Set {
+            negated,
+            items,
+            subtract,
+        })
+    }
+
+    /// An escape sequence, after the `\`
+    fn escape(&mut self) -> Result<Set, Error> {
+        let c = match self.chars.next() {
+            Some(c) => c,
+            None => return Err(self.error("incomplete escape")),
+        };
+
+        let (class, negated): (fn(char) -> bool, bool) = match c {
+            'n' => return Ok(Set::char('\n')),
+            'r' => return Ok(Set::char('\r')),
+            't' => return Ok(Set::char('\t')),
+            '\\' | '|' | '.' | '?' | '*' | '+' | '(' | ')' | '{' | '}' | '-' | '[' | ']' | '^' => {
+                return Ok(Set::char(c))
+            }
+            's' | 'S' => (is_space, c == 'S'),
+            'i' | 'I' => (is_initial, c == 'I'),
+            'c' | 'C' => (is_name, c == 'C'),
+            'd' | 'D' => (char::is_numeric, c == 'D'),
+            'w' | 'W' => (is_word, c == 'W'),
+            'p' | 'P' => (self.category()?, c == 'P'),
+            c => return Err(self.error(&format!("unknown escape `\\{c}`"))),
+        };
+
+        let item = Item::Class(class);
+        Ok(match negated {
+            true => Set::not(vec![item]),
+            false => Set {
+                negated: false,
+                items: vec![item],
+                subtract: None,
+            },
+        })
+    }
+
+    /// The `{name}` of a `\p` or `\P` escape
+    fn category(&mut self) -> Result<fn(char) -> bool, Error> {
+        if self.chars.next() != Some('{') {
+            return Err(self.error("expected `{` after `\\p`"));
+        }
+
+        let mut name = String::new();
+        loop {
+            match self.chars.next() {
+                Some('}') => break,
+                Some(c) => name.push(c),
+                None => return Err(self.error("unbalanced `{`")),
+            }
+        }
+
+        Ok(match name.as_str() {
+            "L" => char::is_alphabetic,
+            "Lu" => char::is_uppercase,
+            "Ll" => char::is_lowercase,
+            "N" | "Nd" => char::is_numeric,
+            "P" => is_punctuation,
+            "Z" | "Zs" => |c: char| c.is_whitespace() && !c.is_control(),
+            "C" | "Cc" => char::is_control,
+            "IsBasicLatin" => |c: char| c.is_ascii(),
+            _ => return Err(self.error(&format!("unsupported character category `{name}`"))),
+        })
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::UnexpectedValue(format!("{message} in pattern `{}`", self.pattern))
+    }
+}
+
+/// A set of characters, like `[a-z-[aeiou]]`
+#[derive(Clone, Debug)]
+struct Set {
+    negated: bool,
+    items: Vec<Item>,
+    subtract: Option<Box<Set>>,
+}
+
+impl Set {
+    fn char(c: char) -> Self {
+        Self {
+            negated: false,
+            items: vec![Item::Range(c, c)],
+            subtract: None,
+        }
+    }
+
+    fn not(items: Vec<Item>) -> Self {
+        Self {
+            negated: true,
+            items,
+            subtract: None,
+        }
+    }
+
+    fn contains(&self, c: char) -> bool {
+        let found = self.items.iter().any(|item| match item {
+            Item::Range(start, end) => (*start..=*end).contains(&c),
+            Item::Class(class) => class(c),
+            Item::Set(set) => set.contains(c),
+        });
+
+        found != self.negated && !self.subtract.as_ref().map_or(false, |set| set.contains(c))
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Item {
+    Range(char, char),
+    Class(fn(char) -> bool),
+    Set(Box<Set>),
+}
+
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r')
+}
+
+/// Characters that can start an XML name, for `\i`
+fn is_initial(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == ':'
+}
+
+/// Characters that can be part of an XML name, for `\c`
+fn is_name(c: char) -> bool {
+    is_initial(c) || c.is_numeric() || matches!(c, '-' | '.' | '\u{B7}')
+}
+
+/// Anything but punctuation, separators and other characters, for `\w`
+fn is_word(c: char) -> bool {
+    !is_punctuation(c) && !c.is_whitespace() && !c.is_control()
+}
+
+/// Approximates the Unicode punctuation category
+fn is_punctuation(c: char) -> bool {
+    matches!(
+        c,
+        '!' | '"'
+            | '#'
+            | '%'
+            | '&'
+            | '\''
+            | '('
+            | ')'
+            | '*'
+            | ','
+            | '-'
+            | '.'
+            | '/'
+            | ':'
+            | ';'
+            | '?'
+            | '@'
+            | '['
+            | '\\'
+            | ']'
+            | '_'
+            | '{'
+            | '}'
+            | '\u{A1}'
+            | '\u{A7}'
+            | '\u{AB}'
+            | '\u{B6}'
+            | '\u{B7}'
+            | '\u{BB}'
+            | '\u{BF}'
+            | '\u{2010}'..='\u{2027}'
+            | '\u{2030}'..='\u{205E}'
+            | '\u{3001}'..='\u{3003}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pattern;
+
+    #[test]
+    fn patterns() {
+        matches("[A-Z]{2}[0-9]{3}", &["AB123"], &["AB12", "ab123", "AB1234"]);
+        matches(
+            "\\d+(\\.\\d{1,2})?",
+            &["1", "12.5", "0.25"],
+            &["", "1.", "1.234"],
+        );
+        matches("a|bc|", &["a", "bc", ""], &["b", "abc"]);
+        matches("[^a-c]*", &["", "xyz"], &["xaz"]);
+        matches("[a-z-[aeiou]]+", &["xyz"], &["xaz"]);
+        matches("[+\\-]?\\d", &["+1", "-1", "1"], &["--1"]);
+        matches("\\p{Lu}\\w*", &["Hello"], &["hello", "Hi there"]);
+        matches("^.$", &["^x$"], &["x"]);
+        matches("(a*)*b", &["aaab", "b"], &["aaaa"]);
+        matches("x{2,}", &["xx", "xxxx"], &["x"]);
+    }
+
+    #[test]
+    fn invalid() {
+        for pattern in ["(a", "a)", "[a", "a{2", "*a", "\\q", "[z-a]", "a{3,1}"] {
+            assert!(Pattern::new(pattern).is_err(), "{pattern:?}");
+        }
+    }
+
+    #[test]
+    fn linear() {
+        let pattern = Pattern::new("(a|aa)*(a|aa)*(a|aa)*c").unwrap();
+        assert!(!pattern.is_match(&"a".repeat(10_000)));
+    }
+
+    fn matches(pattern: &str, valid: &[&str], invalid: &[&str]) {
+        let compiled = Pattern::new(pattern).unwrap();
+        for value in valid {
+            assert!(
+                compiled.is_match(value),
+                "{pattern:?} should match {value:?}"
+            );
+        }
+
+        for value in invalid {
+            assert!(
+                !compiled.is_match(value),
+                "{pattern:?} should not match {value:?}"
+            );
+        }
+    }
+}
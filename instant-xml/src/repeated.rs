@@ -0,0 +1,78 @@
+use std::fmt;
+use std::iter;
+
+use crate::{Accumulate, Deserializer, Error, FromXml, Id, Kind, Serializer, ToXml};
+
+/// Repeated elements collected into any collection type
+///
+/// This gives `Vec<T>` semantics to collections that don't have their own impls, like `SmallVec`
+/// or `ArrayVec`: any `C` that can be built with `Extend` and iterated by reference works.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Repeated<C> {
+    pub inner: C,
+}
+
+impl<C> Repeated<C> {
+    pub fn new(inner: C) -> Self {
+        Self { inner }
+    }
+
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C> From<C> for Repeated<C> {
+    fn from(inner: C) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<'xml, C> FromXml<'xml> for Repeated<C>
+where
+    C: IntoIterator + Extend<C::Item> + Default,
+    C::Item: FromXml<'xml>,
+{
+    #[inline]
+    fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+        C::Item::matches(id, field)
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        let mut value = <C::Item as FromXml<'xml>>::Accumulator::default();
+        C::Item::deserialize(&mut value, field, deserializer)?;
+        into.inner.extend(iter::once(value.try_done(field)?));
+        Ok(())
+    }
+
+    type Accumulator = Self;
+    const KIND: Kind = C::Item::KIND;
+}
+
+impl<C: Default> Accumulate<Repeated<C>> for Repeated<C> {
+    fn try_done(self, _: &'static str) -> Result<Repeated<C>, Error> {
+        Ok(self)
+    }
+}
+
+impl<C, T> ToXml for Repeated<C>
+where
+    for<'a> &'a C: IntoIterator<Item = &'a T>,
+    T: ToXml,
+{
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        for i in &self.inner {
+            i.serialize(field, serializer)?;
+        }
+
+        Ok(())
+    }
+}
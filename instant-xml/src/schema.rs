@@ -0,0 +1,663 @@
+//! XML Schema (XSD) documents describing the documents written for a type
+//!
+//! Derive [`Schema`](macro@crate::Schema) next to [`ToXml`](crate::ToXml) with the same `#[xml]`
+//! attributes, then use [`to_xsd()`] to get a schema for the type's namespace. Every namespace
+//! used gets its own schema document; [`Schemas`] gives access to all of them.
+
+use std::any;
+use std::borrow::Cow;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Write;
+
+use crate::{Error, Id};
+
+/// A type that can describe the XML it is (de)serialized as
+pub trait Schema {
+    /// The type of the content of elements (and attributes) holding values of this type
+    fn describe(schemas: &mut Schemas) -> Type;
+
+    /// The particle for a field of this type
+    ///
+    /// This is an element named `field`, unless the type names its own element (like derived
+    /// structs do).
+    fn particle(schemas: &mut Schemas, field: Id<'static>, occurs: Occurs) -> Particle {
+        Particle::Element {
+            id: field,
+            ty: Self::describe(schemas),
+            occurs,
+            nillable: false,
+        }
+    }
+}
+
+/// Get the schema document for the namespace of the element `T` is written as
+pub fn to_xsd<T: Schema>() -> Result<String, Error> {
+    let mut schemas = Schemas::new();
+    let ns = schemas.add::<T>()?;
+    schemas.to_xsd(ns)
+}
+
+/// A set of schema documents, one per namespace
+#[derive(Debug, Default)]
+pub struct Schemas {
+    /// Definitions of named types and the Rust types defining them, `None` while the definition
+    /// is being built
+    types: BTreeMap<(&'static str, &'static str), (&'static str, Option<Definition>)>,
+    /// Global elements and their types
+    elements: BTreeMap<(&'static str, &'static str), Type>,
+    /// The first name claimed by two different types, returned when the schemas are used
+    conflict: Option<Error>,
+}
+
+impl Schemas {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a global element for the root type `T`, returning its namespace
+    pub fn add<T: Schema>(&mut self) -> Result<&'static str, Error> {
+        let root = Id { ns: "", name: "" };
+        let particle = T::particle(self, root, Occurs::ONCE);
+        if let Some(error) = &self.conflict {
+            return Err(error.clone());
+        }
+
+        match particle {
+            Particle::Element { id, ty, .. } if id != root => {
+                declare(&mut self.elements, "element", id, &ty)?;
+                Ok(id.ns)
+            }
+            _ => Err(Error::UnexpectedState(
+                "root type for a schema must name its element",
+            )),
+        }
+    }
+
+    /// Define the type named `id` for the Rust type `T` with `f`, unless it is already defined
+    ///
+    /// If a different Rust type (like another instance of the same generic type) already defined
+    /// `id`, the conflict is returned as an error by [`Schemas::add()`] and
+    /// [`Schemas::to_xsd()`].
+    pub fn define<T: ?Sized>(
+        &mut self,
+        id: Id<'static>,
+        f: impl FnOnce(&mut Self) -> Definition,
+    ) -> Type {
+        let rust = any::type_name::<T>();
+        match self.types.get(&(id.ns, id.name)) {
+            Some((defined, _)) if *defined != rust => {
+                if self.conflict.is_none() {
+                    self.conflict = Some(Error::SchemaConflict(format!(
+                        "type {id} (defined by {defined} and {rust})"
+                    )));
+                }
+            }
+            Some(_) => {}
+            None => {
+                // Insert a placeholder first, so that recursive types refer to themselves
+                self.types.insert((id.ns, id.name), (rust, None));
+                let definition = f(self);
+                self.types
+                    .insert((id.ns, id.name), (rust, Some(definition)));
+            }
+        }
+
+        Type::Named(id)
+    }
+
+    /// The namespaces for which there is a schema document
+    pub fn namespaces(&self) -> BTreeSet<&'static str> {
+        let mut namespaces = BTreeSet::new();
+        for (ns, _) in self.types.keys().chain(self.elements.keys()) {
+            namespaces.insert(*ns);
+        }
+
+        // Attributes and elements without a namespace are declared locally
+        for (_, definition) in self.definitions() {
+            if let Definition::Complex(complex) = definition {
+                for attribute in &complex.attributes {
+                    if !attribute.id.ns.is_empty() {
+                        namespaces.insert(attribute.id.ns);
+                    }
+                }
+
+                if let Content::Sequence(particles) = &complex.content {
+                    for id in particles.iter().flat_map(Particle::elements) {
+                        if !id.ns.is_empty() {
+                            namespaces.insert(id.ns);
+                        }
+                    }
+                }
+            }
+        }
+
+        namespaces
+    }
+
+    /// Write the schema document for `ns`
+    pub fn to_xsd(&self, ns: &str) -> Result<String, Error> {
+        if let Some(error) = &self.conflict {
+            return Err(error.clone());
+        }
+
+        let mut namespaces = self.namespaces();
+        namespaces.remove("");
+        let prefixes = namespaces
+            .iter()
+            .enumerate()
+            .map(|(i, uri)| match *uri == ns {
+                true => (*uri, "tns".to_owned()),
+                false => (*uri, format!("ns{i}")),
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let document = Document {
+            ns,
+            prefixes: &prefixes,
+        };
+
+        // Elements and attributes in other namespaces are referenced, so they are declared
+        // globally in the schema for their namespace
+        let mut elements = self.elements.clone();
+        let mut attributes = BTreeMap::new();
+        for ((type_ns, _), definition) in self.definitions() {
+            let complex = match definition {
+                Definition::Complex(complex) => complex,
+                Definition::Enumeration(_) => continue,
+            };
+
+            for attribute in &complex.attributes {
+                if !attribute.id.ns.is_empty() && attribute.id.ns != type_ns {
+                    declare(&mut attributes, "attribute", attribute.id, &attribute.ty)?;
+                }
+            }
+
+            if let Content::Sequence(particles) = &complex.content {
+                for particle in particles {
+                    particle.globals(type_ns, &mut elements)?;
+                }
+            }
+        }
+
+        let mut body = String::new();
+        for ((element_ns, name), ty) in &elements {
+            if *element_ns == ns {
+                let ty = document.type_name(ty);
+                writeln!(body, r#"  <xs:element name="{name}" type="{ty}"/>"#)?;
+            }
+        }
+
+        for ((attribute_ns, name), ty) in &attributes {
+            if *attribute_ns == ns {
+                let ty = document.type_name(ty);
+                writeln!(body, r#"  <xs:attribute name="{name}" type="{ty}"/>"#)?;
+            }
+        }
+
+        for ((type_ns, name), definition) in self.definitions() {
+            if type_ns == ns {
+                document.definition(name, definition, &mut body)?;
+            }
+        }
+
+        let mut output = String::new();
+        output.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        output.push_str("\n<xs:schema xmlns:xs=\"http://www.w3.org/2001/XMLSchema\"");
+        for (uri, prefix) in &prefixes {
+            write!(output, " xmlns:{prefix}=\"{uri}\"")?;
+        }
+
+        if !ns.is_empty() {
+            write!(output, r#" targetNamespace="{ns}""#)?;
+        }
+
+        output.push_str(" elementFormDefault=\"qualified\">\n");
+        let mut imports = self.namespaces();
+        imports.remove(ns);
+        for uri in imports {
+            match uri {
+                "" => output.push_str("  <xs:import/>\n"),
+                uri => writeln!(output, r#"  <xs:import namespace="{uri}"/>"#)?,
+            }
+        }
+
+        output.push_str(&body);
+        output.push_str("</xs:schema>\n");
+        Ok(output)
+    }
+
+    fn definitions(&self) -> impl Iterator<Item = ((&'static str, &'static str), &Definition)> {
+        self.types
+            .iter()
+            .filter_map(|(key, (_, definition))| Some((*key, definition.as_ref()?)))
+    }
+}
+
+/// The namespace and prefixes of the schema document being written
+struct Document<'a> {
+    ns: &'a str,
+    prefixes: &'a BTreeMap<&'static str, String>,
+}
+
+impl Document<'_> {
+    fn definition(
+        &self,
+        name: &str,
+        definition: &Definition,
+        output: &mut String,
+    ) -> Result<(), Error> {
+        let complex = match definition {
+            Definition::Enumeration(values) => {
+                writeln!(output, r#"  <xs:simpleType name="{name}">"#)?;
+                output.push_str("    <xs:restriction base=\"xs:string\">\n");
+                for value in values {
+                    writeln!(output, r#"      <xs:enumeration value="{value}"/>"#)?;
+                }
+
+                output.push_str("    </xs:restriction>\n  </xs:simpleType>\n");
+                return Ok(());
+            }
+            Definition::Complex(complex) => complex,
+        };
+
+        if complex.content == Content::Empty
+            && complex.attributes.is_empty()
+            && !complex.any_attribute
+        {
+            writeln!(output, r#"  <xs:complexType name="{name}"/>"#)?;
+            return Ok(());
+        }
+
+        writeln!(output, r#"  <xs:complexType name="{name}">"#)?;
+        match &complex.content {
+            Content::Empty => self.attributes(complex, "    ", output)?,
+            Content::Simple(ty) => {
+                let ty = self.type_name(ty);
+                output.push_str("    <xs:simpleContent>\n");
+                writeln!(output, r#"      <xs:extension base="{ty}">"#)?;
+                self.attributes(complex, "        ", output)?;
+                output.push_str("      </xs:extension>\n    </xs:simpleContent>\n");
+            }
+            Content::Sequence(particles) => {
+                output.push_str("    <xs:sequence>\n");
+                for particle in particles {
+                    self.particle(particle, "      ", output)?;
+                }
+
+                output.push_str("    </xs:sequence>\n");
+                self.attributes(complex, "    ", output)?;
+            }
+        }
+
+        output.push_str("  </xs:complexType>\n");
+        Ok(())
+    }
+
+    fn particle(
+        &self,
+        particle: &Particle,
+        indent: &str,
+        output: &mut String,
+    ) -> Result<(), Error> {
+        match particle {
+            Particle::Element {
+                id,
+                ty,
+                occurs,
+                nillable,
+            } => {
+                output.push_str(indent);
+                match id.ns {
+                    ns if ns == self.ns => write!(
+                        output,
+                        r#"<xs:element name="{}" type="{}""#,
+                        id.name,
+                        self.type_name(ty)
+                    )?,
+                    "" => write!(
+                        output,
+                        r#"<xs:element name="{}" form="unqualified" type="{}""#,
+                        id.name,
+                        self.type_name(ty)
+                    )?,
+                    ns => write!(output, r#"<xs:element ref="{}""#, self.qname(ns, id.name))?,
+                }
+
+                occurs.write(output)?;
+                if *nillable {
+                    output.push_str(r#" nillable="true""#);
+                }
+
+                output.push_str("/>\n");
+            }
+            Particle::Choice { particles, occurs } => {
+                write!(output, "{indent}<xs:choice")?;
+                occurs.write(output)?;
+                output.push_str(">\n");
+                let inner = format!("{indent}  ");
+                for particle in particles {
+                    self.particle(particle, &inner, output)?;
+                }
+
+                writeln!(output, "{indent}</xs:choice>")?;
+            }
+            Particle::Any { occurs } => {
+                write!(output, r#"{indent}<xs:any processContents="lax""#)?;
+                occurs.write(output)?;
+                output.push_str("/>\n");
+            }
+        }
+
+        Ok(())
+    }
+
+    fn attributes(
+        &self,
+        complex: &ComplexType,
+        indent: &str,
+        output: &mut String,
+    ) -> Result<(), Error> {
+        for attribute in &complex.attributes {
+            output.push_str(indent);
+            match attribute.id.ns {
+                "" => write!(
+                    output,
+                    r#"<xs:attribute name="{}" type="{}""#,
+                    attribute.id.name,
+                    self.type_name(&attribute.ty)
+                )?,
+                ns if ns == self.ns => write!(
+                    output,
+                    r#"<xs:attribute name="{}" form="qualified" type="{}""#,
+                    attribute.id.name,
+                    self.type_name(&attribute.ty)
+                )?,
+                ns => write!(
+                    output,
+                    r#"<xs:attribute ref="{}""#,
+                    self.qname(ns, attribute.id.name)
+                )?,
+            }
+
+            if attribute.required {
+                output.push_str(r#" use="required""#);
+            }
+
+            output.push_str("/>\n");
+        }
+
+        if complex.any_attribute {
+            writeln!(
+                output,
+                r#"{indent}<xs:anyAttribute processContents="lax"/>"#
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn type_name(&self, ty: &Type) -> String {
+        match ty {
+            Type::Builtin(name) => format!("xs:{name}"),
+            Type::Named(id) => self.qname(id.ns, id.name),
+        }
+    }
+
+    fn qname(&self, ns: &str, name: &str) -> String {
+        match self.prefixes.get(ns) {
+            Some(prefix) => format!("{prefix}:{name}"),
+            None => name.to_owned(),
+        }
+    }
+}
+
+/// The type of an element's content or an attribute's value
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Type {
+    /// A built-in XSD type, by its local name (like `string`)
+    Builtin(&'static str),
+    /// A type defined with [`Schemas::define()`]
+    Named(Id<'static>),
+}
+
+/// The definition of a named type
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Definition {
+    Complex(ComplexType),
+    /// A string that must be one of the given values
+    Enumeration(Vec<&'static str>),
+}
+
+/// An element type with attributes and content
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ComplexType {
+    pub attributes: Vec<Attribute>,
+    /// Whether attributes other than the ones listed are allowed
+    pub any_attribute: bool,
+    pub content: Content,
+}
+
+/// The content of an element of a [`ComplexType`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Content {
+    Empty,
+    /// Text of the given type
+    Simple(Type),
+    /// Child elements, in order
+    Sequence(Vec<Particle>),
+}
+
+impl Default for Content {
+    fn default() -> Self {
+        Self::Empty
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attribute {
+    pub id: Id<'static>,
+    pub ty: Type,
+    pub required: bool,
+}
+
+/// Child elements of an element, see [`Content::Sequence`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Particle {
+    Element {
+        id: Id<'static>,
+        ty: Type,
+        occurs: Occurs,
+        /// Whether the element may be written with `xsi:nil="true"`
+        nillable: bool,
+    },
+    /// One of the given particles
+    Choice {
+        particles: Vec<Particle>,
+        occurs: Occurs,
+    },
+    /// Any element
+    Any { occurs: Occurs },
+}
+
+impl Particle {
+    pub fn occurs(&self) -> Occurs {
+        match self {
+            Self::Element { occurs, .. } | Self::Choice { occurs, .. } | Self::Any { occurs } => {
+                *occurs
+            }
+        }
+    }
+
+    pub fn with_occurs(mut self, new: Occurs) -> Self {
+        match &mut self {
+            Self::Element { occurs, .. } | Self::Choice { occurs, .. } | Self::Any { occurs } => {
+                *occurs = new
+            }
+        }
+
+        self
+    }
+
+    /// The ids of the elements of this particle
+    fn elements(&self) -> Vec<Id<'static>> {
+        match self {
+            Self::Element { id, .. } => vec![*id],
+            Self::Choice { particles, .. } => particles.iter().flat_map(Self::elements).collect(),
+            Self::Any { .. } => Vec::new(),
+        }
+    }
+
+    /// Collect the elements that are not in `ns` (or no namespace) into `globals`
+    fn globals(
+        &self,
+        ns: &str,
+        globals: &mut BTreeMap<(&'static str, &'static str), Type>,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Element { id, ty, .. } if !id.ns.is_empty() && id.ns != ns => {
+                declare(globals, "element", *id, ty)?;
+            }
+            Self::Element { .. } | Self::Any { .. } => {}
+            Self::Choice { particles, .. } => {
+                for particle in particles {
+                    particle.globals(ns, globals)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Add the global element or attribute `id`, unless it is already declared with the same type
+fn declare(
+    globals: &mut BTreeMap<(&'static str, &'static str), Type>,
+    kind: &str,
+    id: Id<'static>,
+    ty: &Type,
+) -> Result<(), Error> {
+    match globals.entry((id.ns, id.name)) {
+        Entry::Occupied(entry) if entry.get() != ty => Err(conflict(kind, id)),
+        Entry::Occupied(_) => Ok(()),
+        Entry::Vacant(entry) => {
+            entry.insert(ty.clone());
+            Ok(())
+        }
+    }
+}
+
+fn conflict(kind: &str, id: Id<'_>) -> Error {
+    Error::SchemaConflict(format!("{kind} {id}"))
+}
+
+/// How often a particle occurs: at least `min` times, and at most `max` times if it is set
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Occurs {
+    pub min: u32,
+    pub max: Option<u32>,
+}
+
+impl Occurs {
+    pub const ONCE: Self = Self {
+        min: 1,
+        max: Some(1),
+    };
+
+    /// Zero or more times
+    pub const MANY: Self = Self { min: 0, max: None };
+
+    fn write(self, output: &mut String) -> Result<(), Error> {
+        if self.min != 1 {
+            write!(output, r#" minOccurs="{}""#, self.min)?;
+        }
+
+        match self.max {
+            Some(1) => {}
+            Some(max) => write!(output, r#" maxOccurs="{max}""#)?,
+            None => output.push_str(r#" maxOccurs="unbounded""#),
+        }
+
+        Ok(())
+    }
+}
+
+macro_rules! builtin {
+    ($($ty:ty => $name:literal),* $(,)?) => {
+        $(
+            impl Schema for $ty {
+                fn describe(_: &mut Schemas) -> Type {
+                    Type::Builtin($name)
+                }
+            }
+        )*
+    };
+}
+
+builtin!(
+    bool => "boolean",
+    char => "string",
+    str => "string",
+    String => "string",
+    Cow<'_, str> => "string",
+    i8 => "byte",
+    i16 => "short",
+    i32 => "int",
+    i64 => "long",
+    isize => "long",
+    u8 => "unsignedByte",
+    u16 => "unsignedShort",
+    u32 => "unsignedInt",
+    u64 => "unsignedLong",
+    usize => "unsignedLong",
+    f32 => "float",
+    f64 => "double",
+);
+
+impl<T: Schema + ?Sized> Schema for &T {
+    fn describe(schemas: &mut Schemas) -> Type {
+        T::describe(schemas)
+    }
+
+    fn particle(schemas: &mut Schemas, field: Id<'static>, occurs: Occurs) -> Particle {
+        T::particle(schemas, field, occurs)
+    }
+}
+
+impl<T: Schema + ?Sized> Schema for Box<T> {
+    fn describe(schemas: &mut Schemas) -> Type {
+        T::describe(schemas)
+    }
+
+    fn particle(schemas: &mut Schemas, field: Id<'static>, occurs: Occurs) -> Particle {
+        T::particle(schemas, field, occurs)
+    }
+}
+
+impl<T: Schema> Schema for Option<T> {
+    fn describe(schemas: &mut Schemas) -> Type {
+        T::describe(schemas)
+    }
+
+    fn particle(schemas: &mut Schemas, field: Id<'static>, occurs: Occurs) -> Particle {
+        T::particle(schemas, field, Occurs { min: 0, ..occurs })
+    }
+}
+
+macro_rules! repeated {
+    ($($ty:ident),*) => {
+        $(
+            impl<T: Schema> Schema for $ty<T> {
+                fn describe(schemas: &mut Schemas) -> Type {
+                    T::describe(schemas)
+                }
+
+                fn particle(schemas: &mut Schemas, field: Id<'static>, _: Occurs) -> Particle {
+                    T::particle(schemas, field, Occurs::MANY)
+                }
+            }
+        )*
+    };
+}
+
+repeated!(Vec, VecDeque);
@@ -1,54 +1,235 @@
+use std::borrow::Cow;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::fmt::{self};
+use std::fmt::{self, Write};
 use std::mem;
+use std::ops::Range;
 
 use super::Error;
-use crate::ToXml;
+use crate::impls::encode;
+use crate::xsd::XSI;
+use crate::{Id, ToXml};
 
 pub struct Serializer<'xml, W: fmt::Write + ?Sized> {
-    output: &'xml mut W,
+    output: Output<'xml, W>,
     /// Map namespace keys to prefixes.
     ///
     /// The prefix map is updated using `Context` types that are held on the
     /// stack in the relevant `ToXml` implementation. If a prefix is already
     /// defined for a given namespace, we don't update the set the new prefix.
-    prefixes: HashMap<&'static str, &'static str>,
+    prefixes: HashMap<&'static str, Cow<'static, str>>,
     default_ns: &'static str,
     state: State,
+    hook: Option<ValueHook<'xml>>,
+    /// Prefixes to declare for namespaces, set with `prefer_prefix()`
+    preferred: HashMap<&'static str, &'static str>,
+    /// Namespace declarations on the start tags of the open elements, innermost last
+    scopes: Vec<Scope>,
+    numeric_bools: bool,
 }
 
 impl<'xml, W: fmt::Write + ?Sized> Serializer<'xml, W> {
     pub fn new(output: &'xml mut W) -> Self {
         Self {
-            output,
+            output: Output {
+                writer: output,
+                buffer: None,
+            },
             prefixes: HashMap::new(),
             default_ns: "",
             state: State::Element,
+            hook: None,
+            preferred: HashMap::new(),
+            scopes: Vec::new(),
+            numeric_bools: false,
+        }
+    }
+
+    /// Create a serializer that reuses the allocations in `buffers`
+    ///
+    /// Take them back with [`Serializer::into_buffers()`] when done, to avoid allocating for
+    /// every document when serializing many of them.
+    pub fn with_buffers(output: &'xml mut W, buffers: SerializerBuffers) -> Self {
+        let SerializerBuffers {
+            mut prefixes,
+            mut preferred,
+            mut scopes,
+        } = buffers;
+
+        prefixes.clear();
+        preferred.clear();
+        scopes.clear();
+        Self {
+            prefixes,
+            preferred,
+            scopes,
+            ..Self::new(output)
+        }
+    }
+
+    /// Take the allocations of this serializer, to reuse them with [`Serializer::with_buffers()`]
+    pub fn into_buffers(self) -> SerializerBuffers {
+        SerializerBuffers {
+            prefixes: self.prefixes,
+            preferred: self.preferred,
+            scopes: self.scopes,
         }
     }
 
-    pub fn write_start(&mut self, name: &str, ns: &str) -> Result<Option<&'static str>, Error> {
+    /// Save the current position, to be able to roll back anything written after it
+    ///
+    /// Until the checkpoint is committed, output is buffered in memory. Nested checkpoints must be
+    /// committed or rolled back before the checkpoints taken before them.
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let outermost = self.output.buffer.is_none();
+        let buffer = self.output.buffer.get_or_insert_with(String::new);
+        Checkpoint {
+            len: buffer.len(),
+            outermost,
+            state: self.state,
+            default_ns: self.default_ns,
+            prefixes: self.prefixes.clone(),
+            scopes: self.scopes.clone(),
+            hook: self
+                .hook
+                .as_ref()
+                .map(|hook| (hook.path.clone(), hook.stack.clone())),
+        }
+    }
+
+    /// Discard the output written since `checkpoint`, and restore the serializer's state
+    pub fn rollback(&mut self, checkpoint: Checkpoint) {
+        match checkpoint.outermost {
+            true => self.output.buffer = None,
+            false => {
+                if let Some(buffer) = &mut self.output.buffer {
+                    buffer.truncate(checkpoint.len);
+                }
+            }
+        }
+
+        self.state = checkpoint.state;
+        self.default_ns = checkpoint.default_ns;
+        self.prefixes = checkpoint.prefixes;
+        self.scopes = checkpoint.scopes;
+        if let (Some(hook), Some((path, stack))) = (&mut self.hook, checkpoint.hook) {
+            hook.path = path;
+            hook.stack = stack;
+        }
+    }
+
+    /// Keep the output written since `checkpoint`
+    pub fn commit(&mut self, checkpoint: Checkpoint) -> Result<(), Error> {
+        if checkpoint.outermost {
+            if let Some(buffer) = self.output.buffer.take() {
+                self.output.writer.write_str(&buffer)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Serialize `value`, leaving the output and the serializer unchanged if that fails
+    pub fn write_checked<V: ToXml + ?Sized>(
+        &mut self,
+        value: &V,
+        field: Option<Id<'_>>,
+    ) -> Result<(), Error> {
+        let checkpoint = self.checkpoint();
+        match value.serialize(field, self) {
+            Ok(()) => self.commit(checkpoint),
+            Err(err) => {
+                self.rollback(checkpoint);
+                Err(err)
+            }
+        }
+    }
+
+    /// Set a hook to inspect or replace text and attribute values before they are escaped
+    ///
+    /// The hook gets the path to the value, like `Order/Payment/card` for element content or
+    /// `Order/@id` for an attribute, and the value itself.
+    pub fn set_value_hook(&mut self, hook: impl FnMut(&str, &str) -> Option<String> + 'xml) {
+        self.hook = Some(ValueHook {
+            f: Box::new(hook),
+            path: String::new(),
+            stack: Vec::new(),
+        });
+    }
+
+    /// Use `prefix` for `ns` wherever a prefix for it is declared
+    ///
+    /// This applies to prefixes declared by derived types, which are replaced, and to namespaces
+    /// that would otherwise be declared as the default namespace of an element or with a
+    /// generated prefix for an attribute. The preference is ignored where `prefix` is already
+    /// bound to another namespace.
+    pub fn prefer_prefix(&mut self, ns: &'static str, prefix: &'static str) {
+        self.preferred.insert(ns, prefix);
+    }
+
+    /// The preferred prefix for `ns`, if there is one and it is not bound to another namespace
+    fn preferred(&self, ns: &str) -> Option<(&'static str, &'static str)> {
+        let (ns, prefix) = self.preferred.get_key_value(ns)?;
+        match self.prefix_in_use(prefix) {
+            true => None,
+            false => Some((*ns, *prefix)),
+        }
+    }
+
+    /// Write `bool` values as `1` and `0` rather than `true` and `false`
+    pub fn set_numeric_bools(&mut self, numeric: bool) {
+        self.numeric_bools = numeric;
+    }
+
+    pub fn numeric_bools(&self) -> bool {
+        self.numeric_bools
+    }
+
+    /// Start an element named `name` in `ns`
+    ///
+    /// Returns the prefix the element was written with, to be passed to
+    /// [`Serializer::write_close()`].
+    pub fn write_start(
+        &mut self,
+        name: &str,
+        ns: &str,
+    ) -> Result<Option<Cow<'static, str>>, Error> {
         if self.state != State::Element {
             return Err(Error::UnexpectedState("invalid state for element start"));
         }
 
-        let prefix = match (ns == self.default_ns, self.prefixes.get(ns)) {
+        let mut scope = Scope::default();
+        let prefix = match (self.in_default_ns(ns), self.prefixes.get(ns)) {
             (true, _) => {
                 self.output.write_fmt(format_args!("<{name}"))?;
                 None
             }
             (false, Some(prefix)) => {
                 self.output.write_fmt(format_args!("<{prefix}:{name}"))?;
-                Some(*prefix)
-            }
-            _ => {
-                self.output
-                    .write_fmt(format_args!("<{name} xmlns=\"{ns}\""))?;
-                None
+                Some(prefix.clone())
             }
+            _ => match self.preferred(ns) {
+                Some((ns, prefix)) => {
+                    self.output
+                        .write_fmt(format_args!("<{prefix}:{name} xmlns:{prefix}=\"{ns}\""))?;
+                    self.prefixes.insert(ns, Cow::Borrowed(prefix));
+                    scope.preferred = Some(ns);
+                    Some(Cow::Borrowed(prefix))
+                }
+                None => {
+                    self.output
+                        .write_fmt(format_args!("<{name} xmlns=\"{ns}\""))?;
+                    scope.default_ns = Some(ns.to_owned());
+                    None
+                }
+            },
         };
 
+        self.scopes.push(scope);
+        if let Some(hook) = &mut self.hook {
+            hook.enter(name);
+        }
+
         self.state = State::Attribute;
         Ok(prefix)
     }
@@ -58,25 +239,63 @@ impl<'xml, W: fmt::Write + ?Sized> Serializer<'xml, W> {
         name: &str,
         ns: &str,
         value: &V,
+    ) -> Result<(), Error> {
+        self.write_attr_with(name, ns, |serializer| value.serialize(None, serializer))
+    }
+
+    /// Write an attribute, using `f` to write the (escaped) value
+    pub fn write_attr_with(
+        &mut self,
+        name: &str,
+        ns: &str,
+        f: impl FnOnce(&mut Self) -> Result<(), Error>,
     ) -> Result<(), Error> {
         if self.state != State::Attribute {
             return Err(Error::UnexpectedState("invalid state for attribute"));
         }
 
-        match ns == self.default_ns {
+        match ns == self.default_ns || ns.is_empty() {
             true => self.output.write_fmt(format_args!(" {name}=\""))?,
             false => {
-                let prefix = self
-                    .prefixes
-                    .get(ns)
-                    .ok_or(Error::UnexpectedState("unknown prefix"))?;
+                let prefix = self.attr_prefix(ns)?;
                 self.output.write_fmt(format_args!(" {prefix}:{name}=\""))?;
             }
         }
 
+        if let Some(hook) = &mut self.hook {
+            hook.enter(&format!("@{name}"));
+        }
+
         self.state = State::Scalar;
-        value.serialize(None, self)?;
+        f(self)?;
         self.state = State::Attribute;
+        if let Some(hook) = &mut self.hook {
+            hook.leave();
+        }
+
+        self.output.write_char('"')?;
+        Ok(())
+    }
+
+    /// Write an attribute in a namespace that is only known at runtime
+    ///
+    /// If `ns` has no prefix in scope, a prefix for it is declared on the current element.
+    pub fn write_attr_ns(&mut self, name: &str, ns: &str, value: &str) -> Result<(), Error> {
+        if self.state != State::Attribute {
+            return Err(Error::UnexpectedState("invalid state for attribute"));
+        }
+
+        let prefix = match ns {
+            "" => None,
+            _ => Some(self.attr_prefix(ns)?),
+        };
+
+        match prefix {
+            Some(prefix) => self.output.write_fmt(format_args!(" {prefix}:{name}=\""))?,
+            None => self.output.write_fmt(format_args!(" {name}=\""))?,
+        }
+
+        self.output.write_str(&encode(value)?)?;
         self.output.write_char('"')?;
         Ok(())
     }
@@ -86,11 +305,282 @@ impl<'xml, W: fmt::Write + ?Sized> Serializer<'xml, W> {
             return Err(Error::UnexpectedState("invalid state for scalar"));
         }
 
-        self.output.write_fmt(format_args!("{value}"))?;
+        match &mut self.hook {
+            Some(hook) => {
+                let value = value.to_string();
+                match (hook.f)(&hook.path, &value) {
+                    Some(new) => self.output.write_str(&encode(&new)?)?,
+                    None => self.output.write_str(&value)?,
+                }
+            }
+            None => self.output.write_fmt(format_args!("{value}"))?,
+        }
+
+        self.state = State::Element;
+        Ok(())
+    }
+
+    /// Write unescaped text content or attribute value, escaping it as needed
+    pub fn write_text(&mut self, value: &str) -> Result<(), Error> {
+        if !matches!(self.state, State::Element | State::Scalar) {
+            return Err(Error::UnexpectedState("invalid state for scalar"));
+        }
+
+        let new = match &mut self.hook {
+            Some(hook) => (hook.f)(&hook.path, value),
+            None => None,
+        };
+
+        self.output
+            .write_str(&encode(new.as_deref().unwrap_or(value))?)?;
+        self.state = State::Element;
+        Ok(())
+    }
+
+    /// Write text content as a CDATA section, splitting it where it contains `]]>`
+    pub fn write_cdata(&mut self, value: &str) -> Result<(), Error> {
+        if !matches!(self.state, State::Element | State::Scalar) {
+            return Err(Error::UnexpectedState("invalid state for scalar"));
+        }
+
+        let new = match &mut self.hook {
+            Some(hook) => (hook.f)(&hook.path, value),
+            None => None,
+        };
+
+        let value = new.as_deref().unwrap_or(value);
+        self.output.write_str("<![CDATA[")?;
+        self.output
+            .write_str(&value.replace("]]>", "]]]]><![CDATA[>"))?;
+        self.output.write_str("]]>")?;
+        self.state = State::Element;
+        Ok(())
+    }
+
+    /// Write an empty element with `xsi:nil="true"`, declaring the `xsi` prefix if needed
+    pub fn write_nil(&mut self, name: &str, ns: &str) -> Result<(), Error> {
+        self.write_start(name, ns)?;
+        let new = Context::<1> {
+            default_ns: self.default_ns,
+            prefixes: [Prefix {
+                prefix: "xsi",
+                ns: XSI,
+            }],
+        };
+
+        let old = self.push(new)?;
+        self.write_attr("nil", XSI, "true")?;
+        self.end_empty()?;
+        self.pop(old);
+        Ok(())
+    }
+
+    /// Write an XML declaration, with a `standalone` document declaration if given
+    ///
+    /// This must be called before the root element is started.
+    pub fn write_declaration(&mut self, standalone: Option<bool>) -> Result<(), Error> {
+        if self.state != State::Element {
+            return Err(Error::UnexpectedState("invalid state for declaration"));
+        }
+
+        self.output
+            .write_str(r#"<?xml version="1.0" encoding="UTF-8""#)?;
+        match standalone {
+            Some(true) => self.output.write_str(r#" standalone="yes""#)?,
+            Some(false) => self.output.write_str(r#" standalone="no""#)?,
+            None => {}
+        }
+
+        self.output.write_str("?>")?;
+        Ok(())
+    }
+
+    /// Write XML content verbatim, without escaping it or passing it to the value hook
+    pub fn write_raw(&mut self, value: &str) -> Result<(), Error> {
+        if !matches!(self.state, State::Element | State::Scalar) {
+            return Err(Error::UnexpectedState("invalid state for scalar"));
+        }
+
+        self.output.write_str(value)?;
         self.state = State::Element;
         Ok(())
     }
 
+    /// Start an element named `name` in `ns`, to be closed with [`Serializer::end_element()`]
+    ///
+    /// This and the methods below it make up a writer API for documents (or parts of them)
+    /// written by hand: the start tag is ended as soon as content is written, and
+    /// [`ToXml`] values can be embedded with [`Serializer::value()`]. `ns` is declared as the
+    /// element's default namespace if it is not the one in scope.
+    pub fn start_element(&mut self, name: &str, ns: &str) -> Result<(), Error> {
+        self.end_pending_start()?;
+        self.write_start_dynamic(name, ns)?;
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.element = Some(name.to_owned());
+        }
+
+        Ok(())
+    }
+
+    /// Write an attribute on the element just started, escaping `value`
+    ///
+    /// If `ns` is not empty and has no prefix in scope, a prefix for it is declared on the
+    /// element. Attributes must be written before any content of the element.
+    pub fn attribute(&mut self, name: &str, ns: &str, value: &str) -> Result<(), Error> {
+        self.write_attr_ns(name, ns, value)
+    }
+
+    /// Write text content, escaping it as needed
+    pub fn text(&mut self, value: &str) -> Result<(), Error> {
+        self.end_pending_start()?;
+        self.write_text(value)
+    }
+
+    /// Write XML content verbatim, without escaping it
+    pub fn raw(&mut self, value: &str) -> Result<(), Error> {
+        self.end_pending_start()?;
+        self.write_raw(value)
+    }
+
+    /// Serialize `value` as content of the current element, as it would be serialized on its own
+    pub fn value<V: ToXml + ?Sized>(&mut self, value: &V) -> Result<(), Error> {
+        self.end_pending_start()?;
+        value.serialize(None, self)
+    }
+
+    /// Close the innermost element started with [`Serializer::start_element()`]
+    ///
+    /// An element without content is written as an empty element, like `<br />`.
+    pub fn end_element(&mut self) -> Result<(), Error> {
+        let name = match self.scopes.last_mut() {
+            Some(Scope {
+                element: Some(name),
+                ..
+            }) => mem::take(name),
+            _ => return Err(Error::UnexpectedState("no element to end")),
+        };
+
+        match self.state {
+            State::Attribute => self.end_empty(),
+            _ => self.write_close(None, &name),
+        }
+    }
+
+    /// End the start tag of the current element, if attributes can still be written to it
+    fn end_pending_start(&mut self) -> Result<(), Error> {
+        match self.state {
+            State::Attribute => self.end_start(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Start an element in a namespace that is only known at runtime
+    ///
+    /// `ns` is declared as the element's default namespace if it is not the one in scope.
+    pub(crate) fn write_start_dynamic(&mut self, name: &str, ns: &str) -> Result<(), Error> {
+        if self.state != State::Element {
+            return Err(Error::UnexpectedState("invalid state for element start"));
+        }
+
+        let mut scope = Scope::default();
+        match self.in_default_ns(ns) {
+            true => self.output.write_fmt(format_args!("<{name}"))?,
+            false => {
+                self.output
+                    .write_fmt(format_args!("<{name} xmlns=\"{}\"", encode(ns)?))?;
+                scope.default_ns = Some(ns.to_owned());
+            }
+        }
+
+        self.scopes.push(scope);
+        if let Some(hook) = &mut self.hook {
+            hook.enter(name);
+        }
+
+        self.state = State::Attribute;
+        Ok(())
+    }
+
+    /// Write an attribute with a `name` that includes its prefix, if any
+    pub(crate) fn write_attr_dynamic(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        if self.state != State::Attribute {
+            return Err(Error::UnexpectedState("invalid state for attribute"));
+        }
+
+        self.output
+            .write_fmt(format_args!(" {name}=\"{}\"", encode(value)?))?;
+        Ok(())
+    }
+
+    /// The prefix for attributes in `ns`, declaring one on the current element if none is in scope
+    fn attr_prefix(&mut self, ns: &str) -> Result<String, Error> {
+        if ns == XML_NS {
+            return Ok("xml".to_owned());
+        } else if let Some(prefix) = self.prefixes.get(ns) {
+            return Ok(prefix.to_string());
+        } else if let Some(prefix) = self.scoped_prefix(ns) {
+            return Ok(prefix.to_owned());
+        }
+
+        let prefix = match self.preferred(ns) {
+            Some((_, prefix)) => prefix.to_owned(),
+            None => self.generate_prefix(),
+        };
+
+        self.output
+            .write_fmt(format_args!(" xmlns:{prefix}=\"{}\"", encode(ns)?))?;
+        match self.scopes.last_mut() {
+            Some(scope) => scope.prefixes.push((ns.to_owned(), prefix.clone())),
+            None => return Err(Error::UnexpectedState("attribute outside of an element")),
+        }
+
+        Ok(prefix)
+    }
+
+    /// The prefix declared for attributes in `ns` by one of the open elements, if any
+    fn scoped_prefix(&self, ns: &str) -> Option<&str> {
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.prefixes.iter())
+            .find(|(used, _)| used == ns)
+            .map(|(_, prefix)| prefix.as_str())
+    }
+
+    /// The first of `ns0`, `ns1` and so on that is not bound by one of the enclosing elements
+    ///
+    /// Used for namespaces without a prefix of their own, or whose declared one is already bound
+    /// to another namespace.
+    fn generate_prefix(&self) -> String {
+        (0..)
+            .map(|i| format!("ns{i}"))
+            .find(|prefix| !self.prefix_in_use(prefix))
+            .unwrap()
+    }
+
+    /// Whether `prefix` is bound to a namespace by one of the enclosing elements
+    pub(crate) fn prefix_in_use(&self, prefix: &str) -> bool {
+        self.prefixes.values().any(|used| *used == prefix)
+            || self
+                .scopes
+                .iter()
+                .flat_map(|scope| scope.prefixes.iter())
+                .any(|(_, used)| used == prefix)
+    }
+
+    /// Whether `ns` is the default namespace in scope, so elements in it need no declaration
+    fn in_default_ns(&self, ns: &str) -> bool {
+        match self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.default_ns.as_deref())
+        {
+            Some(default_ns) => ns == default_ns,
+            None => ns == self.default_ns,
+        }
+    }
+
     pub fn end_start(&mut self) -> Result<(), Error> {
         if self.state != State::Attribute {
             return Err(Error::UnexpectedState("invalid state for element end"));
@@ -107,6 +597,11 @@ impl<'xml, W: fmt::Write + ?Sized> Serializer<'xml, W> {
         }
 
         self.output.write_str(" />")?;
+        self.leave_scope();
+        if let Some(hook) = &mut self.hook {
+            hook.leave();
+        }
+
         self.state = State::Element;
         Ok(())
     }
@@ -121,9 +616,25 @@ impl<'xml, W: fmt::Write + ?Sized> Serializer<'xml, W> {
             None => self.output.write_fmt(format_args!("</{name}>"))?,
         }
 
+        self.leave_scope();
+        if let Some(hook) = &mut self.hook {
+            hook.leave();
+        }
+
         Ok(())
     }
 
+    /// Drop the namespace declarations of the element being closed
+    fn leave_scope(&mut self) {
+        if let Some(Scope {
+            preferred: Some(ns),
+            ..
+        }) = self.scopes.pop()
+        {
+            self.prefixes.remove(ns);
+        }
+    }
+
     pub fn push<const N: usize>(&mut self, new: Context<N>) -> Result<Context<N>, Error> {
         if self.state != State::Attribute {
             return Err(Error::UnexpectedState("invalid state for attribute"));
@@ -143,20 +654,21 @@ impl<'xml, W: fmt::Write + ?Sized> Serializer<'xml, W> {
                 continue;
             }
 
-            self.output
-                .write_fmt(format_args!(" xmlns:{}=\"{}\"", prefix.prefix, prefix.ns))?;
-
-            let prev = match self.prefixes.entry(prefix.ns) {
-                Entry::Occupied(mut entry) => mem::replace(entry.get_mut(), prefix.prefix),
-                Entry::Vacant(entry) => {
-                    entry.insert(prefix.prefix);
-                    ""
-                }
+            // An enclosing scope may have bound the prefix to another namespace
+            let name = match (self.preferred(prefix.ns), self.prefix_in_use(prefix.prefix)) {
+                (Some((_, preferred)), _) => Cow::Borrowed(preferred),
+                (None, true) => Cow::Owned(self.generate_prefix()),
+                (None, false) => Cow::Borrowed(prefix.prefix),
             };
 
+            self.output
+                .write_fmt(format_args!(" xmlns:{}=\"{}\"", name, prefix.ns))?;
+
+            // The namespace had no prefix yet (see above), so it is removed again by `pop()`
+            self.prefixes.insert(prefix.ns, name);
             old.prefixes[used] = Prefix {
                 ns: prefix.ns,
-                prefix: prev,
+                prefix: "",
             };
             used += 1;
         }
@@ -181,19 +693,265 @@ impl<'xml, W: fmt::Write + ?Sized> Serializer<'xml, W> {
                     entry.remove();
                 }
                 prev => {
-                    let _ = mem::replace(entry.get_mut(), prev);
+                    *entry.get_mut() = Cow::Borrowed(prev);
                 }
             }
         }
     }
 
-    pub fn prefix(&self, ns: &str) -> Option<&'static str> {
-        self.prefixes.get(ns).copied()
+    pub fn prefix(&self, ns: &str) -> Option<&str> {
+        self.prefixes.get(ns).map(|prefix| prefix.as_ref())
     }
 
     pub fn default_ns(&self) -> &'static str {
         self.default_ns
     }
+
+    /// Continue serializing with a serializer that writes to a `dyn fmt::Write`
+    ///
+    /// The state of this serializer (namespace prefixes, the value hook and so on) is lent to the
+    /// serializer passed to `f`, and taken back afterwards. This is how
+    /// [`ErasedToXml`](crate::ErasedToXml) values are serialized.
+    pub fn erased<R>(
+        &mut self,
+        f: impl FnOnce(&mut Serializer<'_, dyn fmt::Write + '_>) -> R,
+    ) -> R {
+        let Self {
+            output,
+            prefixes,
+            default_ns,
+            state,
+            hook,
+            preferred,
+            scopes,
+            numeric_bools,
+        } = self;
+
+        let (result, erased_hook) = {
+            let mut erased = Serializer {
+                output: Output {
+                    writer: output as &mut dyn fmt::Write,
+                    buffer: None,
+                },
+                prefixes: mem::take(prefixes),
+                default_ns,
+                state: *state,
+                hook: hook.as_mut().map(|hook| ValueHook {
+                    f: Box::new(|path: &str, value: &str| (hook.f)(path, value)),
+                    path: mem::take(&mut hook.path),
+                    stack: mem::take(&mut hook.stack),
+                }),
+                preferred: mem::take(preferred),
+                scopes: mem::take(scopes),
+                numeric_bools: *numeric_bools,
+            };
+
+            let result = f(&mut erased);
+            *prefixes = erased.prefixes;
+            *default_ns = erased.default_ns;
+            *state = erased.state;
+            *preferred = erased.preferred;
+            *scopes = erased.scopes;
+            (result, erased.hook.map(|hook| (hook.path, hook.stack)))
+        };
+
+        if let (Some(hook), Some((path, stack))) = (hook, erased_hook) {
+            hook.path = path;
+            hook.stack = stack;
+        }
+
+        result
+    }
+}
+
+/// The allocations of a [`Serializer`], see [`Serializer::into_buffers()`]
+#[derive(Default)]
+pub struct SerializerBuffers {
+    prefixes: HashMap<&'static str, Cow<'static, str>>,
+    preferred: HashMap<&'static str, &'static str>,
+    scopes: Vec<Scope>,
+}
+
+/// Namespace declarations made on the start tag of an open element
+#[derive(Clone, Default)]
+struct Scope {
+    /// The default namespace declared with `xmlns`, if any
+    default_ns: Option<String>,
+    /// Prefixes declared for attribute namespaces, as `(namespace, prefix)`
+    prefixes: Vec<(String, String)>,
+    /// The namespace bound to its preferred prefix, see `Serializer::prefer_prefix()`
+    preferred: Option<&'static str>,
+    /// The name of the element, if it was started with `Serializer::start_element()`
+    element: Option<String>,
+}
+
+/// A saved position of a [`Serializer`], see [`Serializer::checkpoint()`]
+#[must_use]
+pub struct Checkpoint {
+    len: usize,
+    outermost: bool,
+    state: State,
+    default_ns: &'static str,
+    prefixes: HashMap<&'static str, Cow<'static, str>>,
+    scopes: Vec<Scope>,
+    hook: Option<(String, Vec<usize>)>,
+}
+
+/// The serializer's output, buffered while there are checkpoints
+struct Output<'a, W: ?Sized> {
+    writer: &'a mut W,
+    buffer: Option<String>,
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for Output<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match &mut self.buffer {
+            Some(buffer) => {
+                buffer.push_str(s);
+                Ok(())
+            }
+            None => self.writer.write_str(s),
+        }
+    }
+}
+
+/// How [`to_writer_many`](crate::to_writer_many) separates documents
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Separator {
+    /// Documents directly follow each other
+    None,
+    /// A newline after each document
+    Newline,
+    /// An XML declaration before and a newline after each document
+    Declaration,
+}
+
+/// Write the compact document `input` to `output`, putting nested elements on their own lines
+///
+/// Elements that directly contain text, and elements named in `mixed`, are written exactly as
+/// they are, including their descendants: whitespace there is part of the content.
+pub(crate) fn write_pretty(
+    input: &str,
+    indent: &str,
+    mixed: &[&str],
+    output: &mut (impl fmt::Write + ?Sized),
+) -> Result<(), Error> {
+    let mut nodes = Vec::new();
+    // Index in `nodes` of the open elements
+    let mut open = Vec::new();
+    let mut start = None;
+    for token in xmlparser::Tokenizer::from(input) {
+        let node = match token? {
+            xmlparser::Token::ElementStart { local, span, .. } => {
+                start = Some((span.start(), local.as_str()));
+                continue;
+            }
+            xmlparser::Token::Attribute { .. } => continue,
+            xmlparser::Token::ElementEnd { end, span } => {
+                let span = span.start()..span.end();
+                match (end, start.take()) {
+                    (xmlparser::ElementEnd::Open, Some((start, name))) => PrettyNode::Open {
+                        span: start..span.end,
+                        mixed: mixed.contains(&name),
+                        children: false,
+                    },
+                    (xmlparser::ElementEnd::Empty, Some((start, _))) => {
+                        PrettyNode::Other(start..span.end)
+                    }
+                    (xmlparser::ElementEnd::Close(..), _) => {
+                        open.pop();
+                        nodes.push(PrettyNode::Close(span));
+                        continue;
+                    }
+                    _ => return Err(Error::UnexpectedState("element end without start")),
+                }
+            }
+            xmlparser::Token::Text { text } => PrettyNode::Text(text.start()..text.end()),
+            xmlparser::Token::Cdata { span, .. } => PrettyNode::Text(span.start()..span.end()),
+            token => {
+                let span = token.span();
+                PrettyNode::Other(span.start()..span.end())
+            }
+        };
+
+        if let Some(&parent) = open.last() {
+            if let PrettyNode::Open {
+                mixed, children, ..
+            } = &mut nodes[parent]
+            {
+                *children = true;
+                *mixed |= matches!(node, PrettyNode::Text(_));
+            }
+        }
+
+        if let PrettyNode::Open { .. } = node {
+            open.push(nodes.len());
+        }
+
+        nodes.push(node);
+    }
+
+    // For each open element, whether it is written verbatim and whether it has children
+    let mut stack = Vec::<(bool, bool)>::new();
+    let mut first = true;
+    for node in nodes {
+        let inside = matches!(stack.last(), Some((true, _)));
+        let depth = stack.len();
+        let (span, newline, depth) = match node {
+            PrettyNode::Open {
+                span,
+                mixed,
+                children,
+            } => {
+                stack.push((inside || mixed, children));
+                (span, !inside, depth)
+            }
+            PrettyNode::Close(span) => {
+                let children = matches!(stack.pop(), Some((_, true)));
+                (span, !inside && children, depth.saturating_sub(1))
+            }
+            PrettyNode::Text(span) => (span, false, depth),
+            PrettyNode::Other(span) => (span, !inside, depth),
+        };
+
+        if newline && !first {
+            output.write_char('\n')?;
+            for _ in 0..depth {
+                output.write_str(indent)?;
+            }
+        }
+
+        output.write_str(&input[span])?;
+        first = false;
+    }
+
+    Ok(())
+}
+
+enum PrettyNode {
+    Open {
+        span: Range<usize>,
+        mixed: bool,
+        children: bool,
+    },
+    Close(Range<usize>),
+    Text(Range<usize>),
+    Other(Range<usize>),
+}
+
+/// Serializes the fields of a struct flattened into its parent element
+///
+/// Derived for the same structs as [`FromXmlFlatten`](crate::de::FromXmlFlatten).
+pub trait ToXmlFlatten: ToXml {
+    fn serialize_attributes<W: fmt::Write + ?Sized>(
+        &self,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error>;
+
+    fn serialize_content<W: fmt::Write + ?Sized>(
+        &self,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error>;
 }
 
 #[derive(Debug)]
@@ -217,7 +975,35 @@ pub struct Prefix {
     pub ns: &'static str,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+pub(crate) const XML_NS: &str = "http://www.w3.org/XML/1998/namespace";
+
+type HookFn<'a> = dyn FnMut(&str, &str) -> Option<String> + 'a;
+
+struct ValueHook<'a> {
+    f: Box<HookFn<'a>>,
+    /// Path to the current value, with elements separated by `/`
+    path: String,
+    /// Length of `path` before each element or attribute was entered
+    stack: Vec<usize>,
+}
+
+impl ValueHook<'_> {
+    fn enter(&mut self, name: &str) {
+        self.stack.push(self.path.len());
+        if !self.path.is_empty() {
+            self.path.push('/');
+        }
+        self.path.push_str(name);
+    }
+
+    fn leave(&mut self) {
+        if let Some(len) = self.stack.pop() {
+            self.path.truncate(len);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum State {
     Attribute,
     Element,
@@ -0,0 +1,118 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+use std::hash::Hash;
+
+use crate::{Accumulate, Deserializer, Error, FromXml, Id, Kind, Serializer, ToXml};
+
+/// A set that rejects duplicate elements on deserialization
+///
+/// Sets are (de)serialized as repeated elements, like `Vec<T>`. A plain `HashSet` or `BTreeSet`
+/// silently drops duplicates; wrapping it in `Unique` returns [`Error::DuplicateValue`] instead.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Unique<S> {
+    pub inner: S,
+}
+
+impl<S> Unique<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> From<S> for Unique<S> {
+    fn from(inner: S) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<S: Default> Accumulate<Unique<S>> for Unique<S> {
+    fn try_done(self, _: &'static str) -> Result<Unique<S>, Error> {
+        Ok(self)
+    }
+}
+
+macro_rules! set_impls {
+    ($set:ident, $($bound:tt)+) => {
+        impl<'xml, T: FromXml<'xml> + $($bound)+> FromXml<'xml> for $set<T> {
+            #[inline]
+            fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+                T::matches(id, field)
+            }
+
+            fn deserialize<'cx>(
+                into: &mut Self::Accumulator,
+                field: &'static str,
+                deserializer: &mut Deserializer<'cx, 'xml>,
+            ) -> Result<(), Error> {
+                let mut value = T::Accumulator::default();
+                T::deserialize(&mut value, field, deserializer)?;
+                into.insert(value.try_done(field)?);
+                Ok(())
+            }
+
+            type Accumulator = Self;
+            const KIND: Kind = T::KIND;
+        }
+
+        impl<T: ToXml> ToXml for $set<T> {
+            fn serialize<W: fmt::Write + ?Sized>(
+                &self,
+                field: Option<Id<'_>>,
+                serializer: &mut Serializer<W>,
+            ) -> Result<(), Error> {
+                for item in self {
+                    item.serialize(field, serializer)?;
+                }
+
+                Ok(())
+            }
+        }
+
+        impl<T> Accumulate<$set<T>> for $set<T> {
+            fn try_done(self, _: &'static str) -> Result<$set<T>, Error> {
+                Ok(self)
+            }
+        }
+
+        impl<'xml, T: FromXml<'xml> + $($bound)+> FromXml<'xml> for Unique<$set<T>> {
+            #[inline]
+            fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+                T::matches(id, field)
+            }
+
+            fn deserialize<'cx>(
+                into: &mut Self::Accumulator,
+                field: &'static str,
+                deserializer: &mut Deserializer<'cx, 'xml>,
+            ) -> Result<(), Error> {
+                let mut value = T::Accumulator::default();
+                T::deserialize(&mut value, field, deserializer)?;
+                match into.inner.insert(value.try_done(field)?) {
+                    true => Ok(()),
+                    false => Err(Error::DuplicateValue(field)),
+                }
+            }
+
+            type Accumulator = Self;
+            const KIND: Kind = T::KIND;
+        }
+
+        impl<T: ToXml> ToXml for Unique<$set<T>> {
+            fn serialize<W: fmt::Write + ?Sized>(
+                &self,
+                field: Option<Id<'_>>,
+                serializer: &mut Serializer<W>,
+            ) -> Result<(), Error> {
+                self.inner.serialize(field, serializer)
+            }
+        }
+    };
+}
+
+// `BTreeSet` items are written in order, so its output is deterministic
+set_impls!(BTreeSet, Ord);
+set_impls!(HashSet, Eq + Hash);
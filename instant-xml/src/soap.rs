@@ -0,0 +1,498 @@
+//! SOAP 1.1 and 1.2 envelopes and faults
+//!
+//! Deserializing an [`Envelope`] yields the value in its `Body`, or, if the body holds a fault,
+//! [`Error::Fault`] with the fault as a typed value. Operations generated from WSDL documents
+//! implement [`Operation`], so they can be sent with [`call()`] over any HTTP client.
+
+use std::fmt;
+
+use crate::de::Node;
+use crate::dom::Element;
+use crate::ser::{Context, XML_NS};
+use crate::{Accumulate, Deserializer, Error, FromXml, Id, Kind, Serializer, ToXml};
+
+/// The SOAP 1.1 envelope namespace
+pub const SOAP11: &str = "http://schemas.xmlsoap.org/soap/envelope/";
+/// The SOAP 1.2 envelope namespace
+pub const SOAP12: &str = "http://www.w3.org/2003/05/soap-envelope";
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Version {
+    Soap11,
+    Soap12,
+}
+
+impl Version {
+    /// The envelope namespace of this version
+    pub fn ns(self) -> &'static str {
+        match self {
+            Self::Soap11 => SOAP11,
+            Self::Soap12 => SOAP12,
+        }
+    }
+
+    /// The HTTP `Content-Type` of messages of this version
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Soap11 => "text/xml; charset=utf-8",
+            Self::Soap12 => "application/soap+xml; charset=utf-8",
+        }
+    }
+
+    fn from_ns(ns: &str) -> Option<Self> {
+        match ns {
+            SOAP11 => Some(Self::Soap11),
+            SOAP12 => Some(Self::Soap12),
+            _ => None,
+        }
+    }
+}
+
+/// A SOAP envelope with `body` as the content of its `Body`
+///
+/// Headers are skipped when deserializing, and not written when serializing.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Envelope<T> {
+    pub version: Version,
+    pub body: T,
+}
+
+impl<T> Envelope<T> {
+    pub fn new(version: Version, body: T) -> Self {
+        Self { version, body }
+    }
+}
+
+impl<T: ToXml> ToXml for Envelope<T> {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        _: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        let ns = self.version.ns();
+        let prefix = serializer.write_start("Envelope", ns)?;
+        let new = Context::<0> {
+            default_ns: match prefix {
+                Some(_) => serializer.default_ns(),
+                None => ns,
+            },
+            ..Default::default()
+        };
+
+        let old = serializer.push(new)?;
+        serializer.end_start()?;
+        let body = serializer.write_start("Body", ns)?;
+        serializer.end_start()?;
+        self.body.serialize(None, serializer)?;
+        serializer.write_close(body.as_deref(), "Body")?;
+        serializer.write_close(prefix.as_deref(), "Envelope")?;
+        serializer.pop(old);
+        Ok(())
+    }
+}
+
+impl<'xml, T: FromXml<'xml>> FromXml<'xml> for Envelope<T> {
+    #[inline]
+    fn matches(id: Id<'_>, _: Option<Id<'_>>) -> bool {
+        id.name == "Envelope" && Version::from_ns(id.ns).is_some()
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        let version = match Version::from_ns(deserializer.parent()?.ns) {
+            Some(version) => version,
+            None => return Err(Error::UnexpectedState("envelope in unknown namespace")),
+        };
+
+        let mut body = None;
+        while let Some(node) = deserializer.next() {
+            let element = match node? {
+                Node::Open(element) => element,
+                _ => continue,
+            };
+
+            let id = deserializer.element_id(&element)?;
+            let mut nested = deserializer.nested(element);
+            match id.ns == version.ns() && id.name == "Body" {
+                true if body.is_some() => return Err(Error::DuplicateValue("Body")),
+                true => body = Some(read_body::<T>(version, &mut nested)?),
+                false => nested.ignore()?,
+            }
+        }
+
+        match body {
+            Some(body) => {
+                *into = Some(Envelope::new(version, body));
+                Ok(())
+            }
+            None => Err(Error::MissingValue("Body")),
+        }
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Element;
+}
+
+/// Read the value in a `Body`, or return the fault it holds as an error
+fn read_body<'xml, T: FromXml<'xml>>(
+    version: Version,
+    deserializer: &mut Deserializer<'_, 'xml>,
+) -> Result<T, Error> {
+    let mut value = T::Accumulator::default();
+    while let Some(node) = deserializer.next() {
+        let element = match node? {
+            Node::Open(element) => element,
+            _ => continue,
+        };
+
+        let id = deserializer.element_id(&element)?;
+        let mut nested = deserializer.nested(element);
+        if id.ns == version.ns() && id.name == "Fault" {
+            let fault = match version {
+                Version::Soap11 => Fault::Soap11(Fault11::read(&mut nested)?),
+                Version::Soap12 => Fault::Soap12(Fault12::read(&mut nested)?),
+            };
+
+            return Err(Error::Fault(Box::new(fault)));
+        }
+
+        match T::matches(id, None) {
+            true => T::deserialize(&mut value, "Body", &mut nested)?,
+            false => nested.ignore()?,
+        }
+    }
+
+    value.try_done("Body")
+}
+
+/// A SOAP fault, returned in [`Error::Fault`] when deserializing an [`Envelope`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Fault {
+    Soap11(Fault11),
+    Soap12(Fault12),
+}
+
+impl Fault {
+    /// The `faultcode`, or the top-level `Code` value
+    pub fn code(&self) -> &FaultCode {
+        match self {
+            Self::Soap11(fault) => &fault.code,
+            Self::Soap12(fault) => &fault.code,
+        }
+    }
+
+    /// The `faultstring`, or the first `Reason` text
+    pub fn reason(&self) -> &str {
+        match self {
+            Self::Soap11(fault) => &fault.string,
+            Self::Soap12(fault) => fault
+                .reasons
+                .first()
+                .map_or("", |reason| reason.text.as_str()),
+        }
+    }
+
+    pub fn detail(&self) -> Option<&Element> {
+        match self {
+            Self::Soap11(fault) => fault.detail.as_ref(),
+            Self::Soap12(fault) => fault.detail.as_ref(),
+        }
+    }
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code(), self.reason())
+    }
+}
+
+impl<'xml> FromXml<'xml> for Fault {
+    #[inline]
+    fn matches(id: Id<'_>, _: Option<Id<'_>>) -> bool {
+        id.name == "Fault" && Version::from_ns(id.ns).is_some()
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        *into = Some(match Version::from_ns(deserializer.parent()?.ns) {
+            Some(Version::Soap11) => Self::Soap11(Fault11::read(deserializer)?),
+            Some(Version::Soap12) => Self::Soap12(Fault12::read(deserializer)?),
+            None => return Err(Error::UnexpectedState("fault in unknown namespace")),
+        });
+
+        Ok(())
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Element;
+}
+
+/// A SOAP 1.1 `Fault`
+///
+/// The child elements are matched by their local name only, since some implementations put them
+/// in the envelope namespace.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Fault11 {
+    /// The `faultcode`, like `{http://schemas.xmlsoap.org/soap/envelope/}Server`
+    pub code: FaultCode,
+    /// The `faultstring`
+    pub string: String,
+    /// The `faultactor`
+    pub actor: Option<String>,
+    pub detail: Option<Element>,
+}
+
+impl Fault11 {
+    fn read(deserializer: &mut Deserializer<'_, '_>) -> Result<Self, Error> {
+        let (mut code, mut string, mut actor, mut detail) = (None, None, None, None);
+        while let Some(node) = deserializer.next() {
+            let element = match node? {
+                Node::Open(element) => element,
+                _ => continue,
+            };
+
+            let id = deserializer.element_id(&element)?;
+            let mut nested = deserializer.nested(element);
+            match id.name {
+                "faultcode" => code = Some(FaultCode::read(&mut nested)?),
+                "faultstring" => string = Some(text(&mut nested)?),
+                "faultactor" => actor = Some(text(&mut nested)?),
+                "detail" => Element::deserialize(&mut detail, "detail", &mut nested)?,
+                _ => nested.ignore()?,
+            }
+        }
+
+        Ok(Self {
+            code: code.ok_or(Error::MissingValue("faultcode"))?,
+            string: string.ok_or(Error::MissingValue("faultstring"))?,
+            actor,
+            detail,
+        })
+    }
+}
+
+/// A SOAP 1.2 `Fault`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Fault12 {
+    /// The `Code` value, like `{http://www.w3.org/2003/05/soap-envelope}Receiver`
+    pub code: FaultCode,
+    /// The values of the nested `Subcode` elements, outermost first
+    pub subcodes: Vec<FaultCode>,
+    /// The `Reason` texts, one per language
+    pub reasons: Vec<Reason>,
+    pub node: Option<String>,
+    pub role: Option<String>,
+    pub detail: Option<Element>,
+}
+
+impl Fault12 {
+    fn read(deserializer: &mut Deserializer<'_, '_>) -> Result<Self, Error> {
+        let mut fault = Self {
+            code: FaultCode::default(),
+            subcodes: Vec::new(),
+            reasons: Vec::new(),
+            node: None,
+            role: None,
+            detail: None,
+        };
+
+        let mut code = false;
+        while let Some(node) = deserializer.next() {
+            let element = match node? {
+                Node::Open(element) => element,
+                _ => continue,
+            };
+
+            let id = deserializer.element_id(&element)?;
+            let mut nested = deserializer.nested(element);
+            match (id.ns, id.name) {
+                (SOAP12, "Code") => {
+                    let mut codes = Vec::new();
+                    read_code(&mut nested, &mut codes)?;
+                    if codes.is_empty() {
+                        return Err(Error::MissingValue("Value"));
+                    }
+
+                    fault.code = codes.remove(0);
+                    fault.subcodes = codes;
+                    code = true;
+                }
+                (SOAP12, "Reason") => {
+                    while let Some(node) = nested.next() {
+                        let element = match node? {
+                            Node::Open(element) => element,
+                            _ => continue,
+                        };
+
+                        let mut text_element = nested.nested(element);
+                        let lang = text_element.take_attribute(Id::new(XML_NS, "lang"))?;
+                        fault.reasons.push(Reason {
+                            lang: lang.map(|lang| lang.into_owned()).unwrap_or_default(),
+                            text: text(&mut text_element)?,
+                        });
+                    }
+                }
+                (SOAP12, "Node") => fault.node = Some(text(&mut nested)?),
+                (SOAP12, "Role") => fault.role = Some(text(&mut nested)?),
+                (SOAP12, "Detail") => {
+                    Element::deserialize(&mut fault.detail, "Detail", &mut nested)?
+                }
+                _ => nested.ignore()?,
+            }
+        }
+
+        match code {
+            true => Ok(fault),
+            false => Err(Error::MissingValue("Code")),
+        }
+    }
+}
+
+/// Read the `Value` of a `Code` or `Subcode` element, followed by those of its subcodes
+fn read_code(
+    deserializer: &mut Deserializer<'_, '_>,
+    codes: &mut Vec<FaultCode>,
+) -> Result<(), Error> {
+    while let Some(node) = deserializer.next() {
+        let element = match node? {
+            Node::Open(element) => element,
+            _ => continue,
+        };
+
+        let id = deserializer.element_id(&element)?;
+        let mut nested = deserializer.nested(element);
+        match (id.ns, id.name) {
+            (SOAP12, "Value") => codes.push(FaultCode::read(&mut nested)?),
+            (SOAP12, "Subcode") => read_code(&mut nested, codes)?,
+            _ => nested.ignore()?,
+        }
+    }
+
+    Ok(())
+}
+
+/// A `Reason` text of a SOAP 1.2 fault
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Reason {
+    /// The `xml:lang` of the text
+    pub lang: String,
+    pub text: String,
+}
+
+/// A fault code, resolved from a qualified name like `soap:Server`
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct FaultCode {
+    /// The namespace, empty if the code has no prefix and there is no default namespace
+    pub ns: String,
+    pub name: String,
+}
+
+impl FaultCode {
+    pub fn id(&self) -> Id<'_> {
+        Id::new(&self.ns, &self.name)
+    }
+
+    fn read(deserializer: &mut Deserializer<'_, '_>) -> Result<Self, Error> {
+        let value = text(deserializer)?;
+        let id = deserializer.resolve_qname(value.trim())?;
+        Ok(Self {
+            ns: id.ns.to_owned(),
+            name: id.name.to_owned(),
+        })
+    }
+}
+
+/// Formats the code in Clark notation, like [`Id`]
+impl fmt::Display for FaultCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.id().fmt(f)
+    }
+}
+
+/// The text content of the current element, consuming the element
+fn text(deserializer: &mut Deserializer<'_, '_>) -> Result<String, Error> {
+    let value = deserializer.take_str()?.unwrap_or_default().into_owned();
+    deserializer.ignore()?;
+    Ok(value)
+}
+
+/// Deserialize the body of the SOAP envelope in `input`
+///
+/// If the body holds a fault, it is returned as [`Error::Fault`].
+pub fn from_str<'xml, T: FromXml<'xml>>(input: &'xml str) -> Result<T, Error> {
+    crate::from_str::<Envelope<T>>(input).map(|envelope| envelope.body)
+}
+
+/// A SOAP operation, as generated by [`wsdl::generate()`](crate::wsdl::generate)
+pub trait Operation {
+    /// The value sent in the request `Body`
+    type Request: ToXml;
+    /// The value expected in the response `Body`
+    type Response: for<'xml> FromXml<'xml>;
+    /// The `soapAction` of the operation, empty if it has none
+    const ACTION: &'static str;
+    const VERSION: Version;
+}
+
+/// A serialized request envelope with the HTTP headers to send it with
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HttpRequest {
+    /// The `Content-Type` header, and for SOAP 1.1, the `SOAPAction` header
+    pub headers: Vec<(&'static str, String)>,
+    pub body: String,
+}
+
+impl HttpRequest {
+    /// Wrap `request` in an envelope for the operation `O`
+    pub fn new<O: Operation>(request: &O::Request) -> Result<Self, Error> {
+        let mut headers = Vec::with_capacity(2);
+        let content_type = O::VERSION.content_type();
+        match O::VERSION {
+            Version::Soap11 => {
+                headers.push(("Content-Type", content_type.to_owned()));
+                headers.push(("SOAPAction", format!("\"{}\"", O::ACTION)));
+            }
+            Version::Soap12 if O::ACTION.is_empty() => {
+                headers.push(("Content-Type", content_type.to_owned()));
+            }
+            Version::Soap12 => {
+                headers.push((
+                    "Content-Type",
+                    format!("{content_type}; action=\"{}\"", O::ACTION),
+                ));
+            }
+        }
+
+        Ok(Self {
+            headers,
+            body: crate::to_string(&Envelope::new(O::VERSION, request))?,
+        })
+    }
+}
+
+/// Call the operation `O` with `request`, sending it with `transport`
+///
+/// `transport` posts the request to the endpoint and returns the response body. Servers report
+/// faults with a `500` status, so the body should be returned for that status as well: the
+/// fault is then returned as [`Error::Fault`].
+pub fn call<O: Operation, E: From<Error>>(
+    request: &O::Request,
+    transport: impl FnOnce(HttpRequest) -> Result<String, E>,
+) -> Result<O::Response, E> {
+    let response = transport(HttpRequest::new::<O>(request)?)?;
+    Ok(from_str::<O::Response>(&response)?)
+}
@@ -0,0 +1,61 @@
+use std::cell::Cell;
+use std::fmt;
+
+use crate::{Error, Id, Serializer, ToXml};
+
+/// Repeated elements serialized from an iterator, without collecting them first
+///
+/// Each item is written as it is produced, like the items of a `Vec<T>` field. Serializing
+/// consumes the iterator, so a `Stream` can only be serialized once; serializing it again fails
+/// with an error.
+pub struct Stream<I> {
+    inner: Cell<Option<I>>,
+}
+
+impl<I> Stream<I> {
+    pub fn new(inner: I) -> Self {
+        Self {
+            inner: Cell::new(Some(inner)),
+        }
+    }
+
+    /// Take back the iterator, unless it was already serialized
+    pub fn into_inner(self) -> Option<I> {
+        self.inner.into_inner()
+    }
+}
+
+impl<I> From<I> for Stream<I> {
+    fn from(inner: I) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<I> fmt::Debug for Stream<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stream").finish_non_exhaustive()
+    }
+}
+
+impl<I> ToXml for Stream<I>
+where
+    I: IntoIterator,
+    I::Item: ToXml,
+{
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        let inner = self
+            .inner
+            .take()
+            .ok_or_else(|| Error::Other("stream was already serialized".into()))?;
+
+        for item in inner {
+            item.serialize(field, serializer)?;
+        }
+
+        Ok(())
+    }
+}
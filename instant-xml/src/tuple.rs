@@ -0,0 +1,91 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::{Accumulate, Deserializer, Error, FromXml, Id, Kind, Serializer, ToXml};
+
+/// Accumulates a tuple, whose items are (de)serialized as a sequence of sibling elements
+///
+/// Each element is assigned to the first remaining item (in order) whose type matches it, so
+/// every item takes exactly one element and items can't be reordered in the document.
+pub struct TupleAccumulator<T, A> {
+    items: A,
+    next: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T, A: Default> Default for TupleAccumulator<T, A> {
+    fn default() -> Self {
+        Self {
+            items: A::default(),
+            next: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+macro_rules! tuple_impls {
+    ($(($typ:ident, $acc:ident, $idx:tt)),+) => {
+        impl<'xml, $($typ: FromXml<'xml>),+> FromXml<'xml> for ($($typ,)+) {
+            #[inline]
+            fn matches(id: Id<'_>, field: Option<Id<'_>>) -> bool {
+                $($typ::matches(id, field))||+
+            }
+
+            fn deserialize<'cx>(
+                into: &mut Self::Accumulator,
+                field: &'static str,
+                deserializer: &mut Deserializer<'cx, 'xml>,
+            ) -> Result<(), Error> {
+                let id = deserializer.parent()?;
+                $(
+                    if into.next <= $idx && $typ::matches(id, Some(id)) {
+                        into.next = $idx + 1;
+                        $typ::deserialize(&mut into.items.$idx, field, deserializer)?;
+                        if $typ::KIND == Kind::Scalar {
+                            deserializer.ignore()?;
+                        }
+
+                        return Ok(());
+                    }
+                )+
+
+                Err(Error::DuplicateValue(field))
+            }
+
+            type Accumulator = TupleAccumulator<Self, ($($typ::Accumulator,)+)>;
+            const KIND: Kind = Kind::Element;
+        }
+
+        impl<$($typ, $acc: Accumulate<$typ>),+> Accumulate<($($typ,)+)>
+            for TupleAccumulator<($($typ,)+), ($($acc,)+)>
+        {
+            fn try_done(self, field: &'static str) -> Result<($($typ,)+), Error> {
+                Ok(($(self.items.$idx.try_done(field)?,)+))
+            }
+        }
+
+        impl<$($typ: ToXml),+> ToXml for ($($typ,)+) {
+            fn serialize<W: fmt::Write + ?Sized>(
+                &self,
+                field: Option<Id<'_>>,
+                serializer: &mut Serializer<W>,
+            ) -> Result<(), Error> {
+                $(self.$idx.serialize(field, serializer)?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+tuple_impls!((A, AA, 0), (B, BA, 1));
+tuple_impls!((A, AA, 0), (B, BA, 1), (C, CA, 2));
+tuple_impls!((A, AA, 0), (B, BA, 1), (C, CA, 2), (D, DA, 3));
+tuple_impls!((A, AA, 0), (B, BA, 1), (C, CA, 2), (D, DA, 3), (E, EA, 4));
+tuple_impls!(
+    (A, AA, 0),
+    (B, BA, 1),
+    (C, CA, 2),
+    (D, DA, 3),
+    (E, EA, 4),
+    (F, FA, 5)
+);
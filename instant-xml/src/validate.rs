@@ -0,0 +1,1827 @@
+//! Validation of documents against XML Schema (XSD) documents
+//!
+//! Load one or more schema documents into a [`Validator`], then check a parsed [`Element`] with
+//! [`Validator::validate()`], or deserialize only valid input with
+//! [`from_str_validated()`](crate::from_str_validated). Every way in which the document does not
+//! match the schema is returned as a [`Violation`].
+//!
+//! Supported are global and local element and attribute declarations, named and anonymous
+//! simple and complex types (including extension and restriction), `sequence`, `choice`, `all`
+//! and named groups with their cardinality, wildcards, nillable elements, and the facets of
+//! simple types (`length`, `minLength`, `maxLength`, `pattern`, `enumeration`, bounds,
+//! `totalDigits` and `fractionDigits`). Identity constraints, substitution groups and `xsi:type`
+//! are not checked. Schema documents are not fetched: `xs:import` is ignored, so add the schema
+//! for every namespace used, and `xs:include` is rejected.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::de::Node as DeNode;
+use crate::dom::{Attribute, Element, Node};
+use crate::pattern::Pattern;
+use crate::schema::{Occurs, Schemas};
+use crate::wsdl::WSDL;
+use crate::xsd::{self, XSI};
+use crate::{Deserializer, Error, FromXml, Id, Kind};
+
+/// The XML Schema namespace
+pub const XS: &str = "http://www.w3.org/2001/XMLSchema";
+
+/// Checks documents against the declarations of a set of schema documents
+#[derive(Clone, Debug, Default)]
+pub struct Validator {
+    /// Global declarations and definitions, by their name in Clark notation
+    elements: BTreeMap<String, ElementDecl>,
+    attributes: BTreeMap<String, AttributeDecl>,
+    types: BTreeMap<String, TypeDef>,
+    pub(crate) groups: BTreeMap<String, Term>,
+    attribute_groups: BTreeMap<String, Attributes>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a validator for the schema document `xsd`
+    pub fn from_xsd(xsd: &str) -> Result<Self, Error> {
+        let mut validator = Self::new();
+        validator.add_xsd(xsd)?;
+        Ok(validator)
+    }
+
+    /// Create a validator for the documents in `schemas`, as generated from derived types
+    pub fn from_schemas(schemas: &Schemas) -> Result<Self, Error> {
+        let mut validator = Self::new();
+        for ns in schemas.namespaces() {
+            validator.add_xsd(&schemas.to_xsd(ns)?)?;
+        }
+
+        Ok(validator)
+    }
+
+    /// Add the declarations of the schema document `xsd`
+    ///
+    /// References between schema documents are resolved when validating, so documents can be
+    /// added in any order.
+    pub fn add_xsd(&mut self, xsd: &str) -> Result<(), Error> {
+        self.add_schema(&crate::from_str::<SchemaDocument>(xsd)?.0)
+    }
+
+    /// Add the declarations of an `xs:schema` element, read as a [`SchemaDocument`]
+    pub(crate) fn add_schema(&mut self, root: &Element) -> Result<(), Error> {
+        if !root.id().is(XS, "schema") {
+            return Err(Error::UnexpectedValue(format!(
+                "expected xs:schema, found {}",
+                root.id()
+            )));
+        }
+
+        let loader = Loader {
+            target: attribute(root, "targetNamespace").unwrap_or_default(),
+            qualified_elements: attribute(root, "elementFormDefault") == Some("qualified"),
+            qualified_attributes: attribute(root, "attributeFormDefault") == Some("qualified"),
+        };
+
+        for child in schema_elements(root)? {
+            match child.name.as_str() {
+                "annotation" | "import" => {}
+                "element" => {
+                    let decl = loader.element(child, true)?;
+                    self.elements.insert(decl.id().to_string(), decl);
+                }
+                "attribute" => {
+                    let decl = loader.attribute(child, true)?;
+                    self.attributes.insert(decl.id().to_string(), decl);
+                }
+                "complexType" => {
+                    let key = loader.key(child)?;
+                    let ty = loader.complex_type(child)?;
+                    self.types.insert(key, TypeDef::Complex(ty));
+                }
+                "simpleType" => {
+                    let key = loader.key(child)?;
+                    let ty = loader.simple_type(child)?;
+                    self.types.insert(key, TypeDef::Simple(ty));
+                }
+                "group" => {
+                    let key = loader.key(child)?;
+                    let term = schema_elements(child)?
+                        .find_map(|element| loader.particle(element).transpose())
+                        .transpose()?;
+                    match term {
+                        Some(particle) => self.groups.insert(key, particle.term),
+                        None => return Err(unsupported("xs:group without a model group")),
+                    };
+                }
+                "attributeGroup" => {
+                    let key = loader.key(child)?;
+                    let attributes = loader.attributes(child)?;
+                    self.attribute_groups.insert(key, attributes);
+                }
+                name => return Err(unsupported(&format!("xs:{name} in xs:schema"))),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check `element` against the global element declaration with the same name
+    ///
+    /// Returns the violations found, which is empty if the document is valid. Errors are only
+    /// returned for problems in the schema, like references to undefined types.
+    pub fn validate(&self, element: &Element) -> Result<Vec<Violation>, Error> {
+        let mut check = Check {
+            validator: self,
+            violations: Vec::new(),
+        };
+
+        let path = element.name.clone();
+        match self.elements.get(&element.id().to_string()) {
+            Some(decl) => check.element(decl, element, &path)?,
+            None => check.push(&path, Problem::UndeclaredElement(element.id().to_string())),
+        }
+
+        Ok(check.violations)
+    }
+
+    /// Parse and check the document `input`, see [`Validator::validate()`]
+    pub fn validate_str(&self, input: &str) -> Result<Vec<Violation>, Error> {
+        self.validate(&crate::from_str(input)?)
+    }
+
+    pub(crate) fn element_decl(&self, key: &str) -> Result<&ElementDecl, Error> {
+        self.elements
+            .get(key)
+            .ok_or_else(|| undefined("element", key))
+    }
+
+    pub(crate) fn attribute_decl(&self, key: &str) -> Result<&AttributeDecl, Error> {
+        self.attributes
+            .get(key)
+            .ok_or_else(|| undefined("attribute", key))
+    }
+
+    fn type_def<'a>(&'a self, key: &'a str) -> Result<Resolved<'a>, Error> {
+        match Id::parse(key)? {
+            Id { ns: XS, name } => match name {
+                "anyType" => Ok(Resolved::Any),
+                name => match builtin(name) {
+                    Some((whitespace, valid)) => Ok(Resolved::Builtin {
+                        name,
+                        whitespace,
+                        valid,
+                    }),
+                    None => Err(undefined("type", key)),
+                },
+            },
+            _ => match self.types.get(key) {
+                Some(TypeDef::Simple(ty)) => Ok(Resolved::Simple(ty)),
+                Some(TypeDef::Complex(ty)) => Ok(Resolved::Complex(ty)),
+                None => Err(undefined("type", key)),
+            },
+        }
+    }
+
+    pub(crate) fn resolve<'a>(&'a self, ty: &'a TypeRef) -> Result<Resolved<'a>, Error> {
+        match ty {
+            TypeRef::Named(key) => self.type_def(key),
+            TypeRef::Simple(ty) => Ok(Resolved::Simple(ty)),
+            TypeRef::Complex(ty) => Ok(Resolved::Complex(ty)),
+        }
+    }
+
+    /// The attributes, content and mixedness of a complex type, including those it derives
+    pub(crate) fn effective<'a>(&'a self, ty: &'a ComplexType) -> Result<Effective<'a>, Error> {
+        let mut effective = match &ty.base {
+            Some((base, derivation)) => match self.type_def(base)? {
+                Resolved::Complex(base) => {
+                    let mut effective = self.effective(base)?;
+                    if *derivation == Derivation::Restriction {
+                        effective.content = Content::Empty;
+                    }
+
+                    effective
+                }
+                Resolved::Any => Effective::default(),
+                Resolved::Builtin { .. } | Resolved::Simple(_) => Effective::default(),
+            },
+            None => Effective::default(),
+        };
+
+        self.collect_attributes(&ty.attributes, &mut effective)?;
+        effective.mixed |= ty.mixed;
+        match &ty.content {
+            Content::Empty => {}
+            Content::Simple(simple) => effective.content = Content::Simple(simple),
+            Content::Elements(particle) => match &mut effective.content {
+                Content::Elements(particles) => particles.push(particle),
+                content => *content = Content::Elements(vec![particle]),
+            },
+        }
+
+        Ok(effective)
+    }
+
+    fn collect_attributes<'a>(
+        &'a self,
+        attributes: &'a Attributes,
+        effective: &mut Effective<'a>,
+    ) -> Result<(), Error> {
+        for attribute in &attributes.uses {
+            let id = attribute.id();
+            effective.attributes.retain(|existing| existing.id() != id);
+            effective.attributes.push(attribute);
+        }
+
+        for group in &attributes.groups {
+            match self.attribute_groups.get(group) {
+                Some(group) => self.collect_attributes(group, effective)?,
+                None => return Err(undefined("attribute group", group)),
+            }
+        }
+
+        if attributes.wildcard.is_some() {
+            effective.wildcard = attributes.wildcard.as_ref();
+        }
+
+        Ok(())
+    }
+
+    /// Match `particle` against `children` from `pos`, recording the declaration of each child
+    fn particle<'a>(
+        &'a self,
+        particle: &'a Particle,
+        children: &[&Element],
+        pos: usize,
+        assigned: &mut Vec<Assigned<'a>>,
+    ) -> Result<Matched, Fail> {
+        let Particle { term, occurs } = particle;
+        let (decl, name) = match term {
+            Term::Element(decl) => (Assigned::Element(decl), decl.id().to_string()),
+            Term::Ref(key) => {
+                let decl = self.element_decl(key)?;
+                (Assigned::Element(decl), decl.id().to_string())
+            }
+            Term::Any(wildcard) => (Assigned::Wildcard(wildcard), "*".to_owned()),
+            _ => return self.repeat(term, *occurs, children, pos, assigned),
+        };
+
+        let mut count = 0;
+        while occurs.max.map_or(true, |max| count < max) {
+            match children.get(pos + count as usize) {
+                Some(child) if decl.matches(child) => {}
+                _ => break,
+            }
+
+            assigned.push(decl);
+            count += 1;
+        }
+
+        let pos = pos + count as usize;
+        if count < occurs.min {
+            return Err(Fail::Mismatch {
+                pos,
+                expected: vec![name],
+            });
+        }
+
+        Ok(Matched {
+            pos,
+            open: match occurs.max.map_or(true, |max| count < max) {
+                true => vec![name],
+                false => Vec::new(),
+            },
+        })
+    }
+
+    /// Match a model group `occurs` times
+    fn repeat<'a>(
+        &'a self,
+        term: &'a Term,
+        occurs: Occurs,
+        children: &[&Element],
+        start: usize,
+        assigned: &mut Vec<Assigned<'a>>,
+    ) -> Result<Matched, Fail> {
+        let (mut pos, mut count, mut open) = (start, 0, Vec::new());
+        while occurs.max.map_or(true, |max| count < max) {
+            let mark = assigned.len();
+            match self.group(term, children, pos, assigned) {
+                // A group that matches nothing can be repeated as often as needed
+                Ok(matched) if matched.pos == pos => {
+                    open.extend(matched.open);
+                    break;
+                }
+                Ok(matched) => {
+                    pos = matched.pos;
+                    open = matched.open;
+                    count += 1;
+                }
+                Err(Fail::Mismatch {
+                    pos: failed,
+                    expected,
+                }) if failed == pos => {
+                    assigned.truncate(mark);
+                    open.extend(expected);
+                    if count < occurs.min {
+                        return Err(Fail::Mismatch {
+                            pos,
+                            expected: open,
+                        });
+                    }
+
+                    break;
+                }
+                Err(fail) => return Err(fail),
+            }
+        }
+
+        Ok(Matched { pos, open })
+    }
+
+    /// Match a single occurrence of a model group
+    fn group<'a>(
+        &'a self,
+        term: &'a Term,
+        children: &[&Element],
+        start: usize,
+        assigned: &mut Vec<Assigned<'a>>,
+    ) -> Result<Matched, Fail> {
+        match term {
+            Term::Sequence(particles) => self.sequence(particles.iter(), children, start, assigned),
+            Term::Choice(particles) => {
+                let (mut expected, mut empty) = (Vec::new(), false);
+                for particle in particles {
+                    let mark = assigned.len();
+                    match self.particle(particle, children, start, assigned) {
+                        Ok(matched) if matched.pos > start => return Ok(matched),
+                        Ok(matched) => {
+                            assigned.truncate(mark);
+                            expected.extend(matched.open);
+                            empty = true;
+                        }
+                        Err(Fail::Mismatch {
+                            pos,
+                            expected: more,
+                        }) if pos == start => {
+                            assigned.truncate(mark);
+                            expected.extend(more);
+                        }
+                        Err(fail) => return Err(fail),
+                    }
+                }
+
+                match empty {
+                    true => Ok(Matched {
+                        pos: start,
+                        open: expected,
+                    }),
+                    false => Err(Fail::Mismatch {
+                        pos: start,
+                        expected,
+                    }),
+                }
+            }
+            Term::All(particles) => {
+                let (mut pos, mut used) = (start, vec![false; particles.len()]);
+                'children: while pos < children.len() {
+                    for (particle, used) in particles.iter().zip(used.iter_mut()) {
+                        if *used {
+                            continue;
+                        }
+
+                        let mark = assigned.len();
+                        match self.particle(particle, children, pos, assigned) {
+                            Ok(matched) if matched.pos > pos => {
+                                *used = true;
+                                pos = matched.pos;
+                                continue 'children;
+                            }
+                            Ok(_) | Err(Fail::Mismatch { .. }) => assigned.truncate(mark),
+                            Err(fail) => return Err(fail),
+                        }
+                    }
+
+                    break;
+                }
+
+                let mut open = Vec::new();
+                let mut missing = false;
+                for (particle, used) in particles.iter().zip(used) {
+                    if !used {
+                        open.push(self.name(&particle.term)?);
+                        missing |= particle.occurs.min > 0;
+                    }
+                }
+
+                match missing {
+                    true => Err(Fail::Mismatch {
+                        pos,
+                        expected: open,
+                    }),
+                    false => Ok(Matched { pos, open }),
+                }
+            }
+            Term::Group(key) => match self.groups.get(key) {
+                Some(term) => self.group(term, children, start, assigned),
+                None => Err(Fail::Schema(undefined("group", key))),
+            },
+            Term::Element(_) | Term::Ref(_) | Term::Any(_) => Err(Fail::Schema(
+                Error::UnexpectedState("element term in group"),
+            )),
+        }
+    }
+
+    fn sequence<'a>(
+        &'a self,
+        particles: impl Iterator<Item = &'a Particle>,
+        children: &[&Element],
+        start: usize,
+        assigned: &mut Vec<Assigned<'a>>,
+    ) -> Result<Matched, Fail> {
+        let (mut pos, mut open) = (start, Vec::new());
+        for particle in particles {
+            match self.particle(particle, children, pos, assigned) {
+                Ok(matched) => {
+                    // Particles that matched nothing could still have matched here
+                    match matched.pos == pos {
+                        true => open.extend(matched.open),
+                        false => open = matched.open,
+                    }
+
+                    pos = matched.pos;
+                }
+                Err(Fail::Mismatch {
+                    pos: failed,
+                    mut expected,
+                }) => {
+                    if failed == pos {
+                        open.append(&mut expected);
+                        expected = open;
+                    }
+
+                    return Err(Fail::Mismatch {
+                        pos: failed,
+                        expected,
+                    });
+                }
+                Err(fail) => return Err(fail),
+            }
+        }
+
+        Ok(Matched { pos, open })
+    }
+
+    /// The name of an element term, for [`Problem::MissingElement`]
+    fn name(&self, term: &Term) -> Result<String, Error> {
+        Ok(match term {
+            Term::Element(decl) => decl.id().to_string(),
+            Term::Ref(key) => self.element_decl(key)?.id().to_string(),
+            _ => "*".to_owned(),
+        })
+    }
+
+    /// Check `value` against the simple type `ty`
+    fn value(&self, ty: &TypeRef, value: &str) -> Result<Option<Problem>, Error> {
+        match self.resolve(ty)? {
+            Resolved::Any => Ok(None),
+            Resolved::Builtin {
+                name,
+                whitespace,
+                valid,
+            } => {
+                let value = whitespace.apply(value);
+                Ok(match valid(&value) {
+                    true => None,
+                    false => Some(Problem::InvalidValue {
+                        value,
+                        ty: name.to_owned(),
+                    }),
+                })
+            }
+            Resolved::Simple(SimpleType::Restriction { base, facets }) => {
+                if let Some(problem) = self.value(base, value)? {
+                    return Ok(Some(problem));
+                }
+
+                let whitespace = match facets.whitespace {
+                    Some(whitespace) => whitespace,
+                    None => self.whitespace(base)?,
+                };
+
+                let list = self.is_list(base)?;
+                Ok(facets.check(&whitespace.apply(value), list))
+            }
+            Resolved::Simple(SimpleType::List(item)) => {
+                for item_value in value.split(is_space).filter(|item| !item.is_empty()) {
+                    if let Some(problem) = self.value(item, item_value)? {
+                        return Ok(Some(problem));
+                    }
+                }
+
+                Ok(None)
+            }
+            Resolved::Simple(SimpleType::Union(members)) => {
+                for member in members {
+                    if self.value(member, value)?.is_none() {
+                        return Ok(None);
+                    }
+                }
+
+                Ok(Some(Problem::InvalidValue {
+                    value: value.to_owned(),
+                    ty: "union".to_owned(),
+                }))
+            }
+            Resolved::Complex(complex) => match self.effective(complex)?.content {
+                Content::Simple(ty) => self.value(ty, value),
+                _ => Err(Error::UnexpectedValue(
+                    "complex type used as a simple type".to_owned(),
+                )),
+            },
+        }
+    }
+
+    fn whitespace(&self, ty: &TypeRef) -> Result<Whitespace, Error> {
+        Ok(match self.resolve(ty)? {
+            Resolved::Any => Whitespace::Preserve,
+            Resolved::Builtin { whitespace, .. } => whitespace,
+            Resolved::Simple(SimpleType::Restriction { base, facets }) => match facets.whitespace {
+                Some(whitespace) => whitespace,
+                None => self.whitespace(base)?,
+            },
+            Resolved::Simple(SimpleType::List(_) | SimpleType::Union(_)) => Whitespace::Collapse,
+            Resolved::Complex(complex) => match self.effective(complex)?.content {
+                Content::Simple(ty) => self.whitespace(ty)?,
+                _ => Whitespace::Preserve,
+            },
+        })
+    }
+
+    /// Whether the length facets of `ty` count list items rather than characters
+    fn is_list(&self, ty: &TypeRef) -> Result<bool, Error> {
+        Ok(match self.resolve(ty)? {
+            Resolved::Simple(SimpleType::List(_)) => true,
+            Resolved::Simple(SimpleType::Restriction { base, .. }) => self.is_list(base)?,
+            _ => false,
+        })
+    }
+}
+
+/// A way in which a document does not match the schema
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Violation {
+    /// The path to the element (or attribute) with the problem, like `Order > Line[1] > @sku`
+    ///
+    /// Elements are listed with their index among siblings of the same name, unless they are
+    /// the first.
+    pub path: String,
+    pub problem: Problem,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (in {})", self.problem, self.path)
+    }
+}
+
+/// The kind of a [`Violation`]
+///
+/// Element and attribute names are given in Clark notation, like `{urn:example}name`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Problem {
+    /// No global element is declared with this name
+    UndeclaredElement(String),
+    /// A child element that is not allowed at this point
+    ///
+    /// `expected` lists the elements that would have been allowed instead, where `*` stands for
+    /// a wildcard.
+    UnexpectedElement {
+        found: String,
+        expected: Vec<String>,
+    },
+    /// The element ended before all required child elements
+    MissingElement {
+        expected: Vec<String>,
+    },
+    UnexpectedAttribute(String),
+    MissingAttribute(String),
+    /// Text in an element that may only contain elements
+    UnexpectedText,
+    /// `xsi:nil="true"` on an element that is not nillable
+    NotNillable,
+    /// A value that is not valid for the built-in type `ty` (or a union of types)
+    InvalidValue {
+        value: String,
+        ty: String,
+    },
+    /// A value that does not satisfy a facet of its type, like `maxLength` or `pattern`
+    Facet {
+        facet: &'static str,
+        value: String,
+    },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UndeclaredElement(name) => write!(f, "element {name} is not declared"),
+            Self::UnexpectedElement { found, expected } => match expected.is_empty() {
+                true => write!(f, "unexpected element {found}"),
+                false => write!(
+                    f,
+                    "unexpected element {found}, expected one of {}",
+                    expected.join(", ")
+                ),
+            },
+            Self::MissingElement { expected } => {
+                write!(
+                    f,
+                    "missing element, expected one of {}",
+                    expected.join(", ")
+                )
+            }
+            Self::UnexpectedAttribute(name) => write!(f, "unexpected attribute {name}"),
+            Self::MissingAttribute(name) => write!(f, "missing required attribute {name}"),
+            Self::UnexpectedText => f.write_str("unexpected text"),
+            Self::NotNillable => f.write_str("element is nil but not nillable"),
+            Self::InvalidValue { value, ty } => write!(f, "invalid {ty} value `{value}`"),
+            Self::Facet { facet, value } => write!(f, "value `{value}` violates {facet} facet"),
+        }
+    }
+}
+
+/// State for checking a document
+struct Check<'a> {
+    validator: &'a Validator,
+    violations: Vec<Violation>,
+}
+
+impl Check<'_> {
+    fn element(&mut self, decl: &ElementDecl, element: &Element, path: &str) -> Result<(), Error> {
+        let nil = match element.attribute(Id::new(XSI, "nil")) {
+            Some(value) if matches!(value.trim(), "true" | "1") => match decl.nillable {
+                true => true,
+                false => {
+                    self.push(path, Problem::NotNillable);
+                    false
+                }
+            },
+            _ => false,
+        };
+
+        let validator = self.validator;
+        let complex = match validator.resolve(&decl.ty)? {
+            Resolved::Any => return Ok(()),
+            Resolved::Complex(complex) => complex,
+            Resolved::Builtin { .. } | Resolved::Simple(_) => {
+                for attribute in &element.attributes {
+                    if attribute.ns != XSI {
+                        self.push(path, Problem::UnexpectedAttribute(key(attribute.id())));
+                    }
+                }
+
+                return self.simple_content(&decl.ty, element, nil, path);
+            }
+        };
+
+        let effective = validator.effective(complex)?;
+        self.attributes(&effective, element, path)?;
+        match effective.content {
+            Content::Simple(ty) => self.simple_content(ty, element, nil, path),
+            Content::Empty => self.element_content(&[], effective.mixed, element, nil, path),
+            Content::Elements(particles) => {
+                self.element_content(&particles, effective.mixed, element, nil, path)
+            }
+        }
+    }
+
+    fn simple_content(
+        &mut self,
+        ty: &TypeRef,
+        element: &Element,
+        nil: bool,
+        path: &str,
+    ) -> Result<(), Error> {
+        if let Some(child) = element.elements().next() {
+            let found = key(child.id());
+            self.push(
+                path,
+                Problem::UnexpectedElement {
+                    found,
+                    expected: Vec::new(),
+                },
+            );
+
+            return Ok(());
+        }
+
+        let text = element.text();
+        if nil {
+            if !text.is_empty() {
+                self.push(path, Problem::UnexpectedText);
+            }
+
+            return Ok(());
+        }
+
+        if let Some(problem) = self.validator.value(ty, &text)? {
+            self.push(path, problem);
+        }
+
+        Ok(())
+    }
+
+    fn element_content(
+        &mut self,
+        particles: &[&Particle],
+        mixed: bool,
+        element: &Element,
+        nil: bool,
+        path: &str,
+    ) -> Result<(), Error> {
+        let text = element.children.iter().any(|node| match node {
+            Node::Text(text) => !text.trim_matches(is_space).is_empty(),
+            Node::Element(_) => false,
+        });
+
+        if text && (nil || !mixed) {
+            self.push(path, Problem::UnexpectedText);
+        }
+
+        let children = element.elements().collect::<Vec<_>>();
+        let particles = match nil {
+            true => &[],
+            false => particles,
+        };
+
+        let validator = self.validator;
+        let mut assigned = Vec::new();
+        let result = validator.sequence(particles.iter().copied(), &children, 0, &mut assigned);
+        let problem = match result {
+            Ok(Matched { pos, .. }) if pos == children.len() => None,
+            Ok(Matched { pos, open }) => Some((pos, open)),
+            Err(Fail::Mismatch { pos, expected }) => Some((pos, expected)),
+            Err(Fail::Schema(error)) => return Err(error),
+        };
+
+        if let Some((pos, mut expected)) = problem {
+            let mut unique = Vec::with_capacity(expected.len());
+            expected.retain(|name| match unique.contains(name) {
+                true => false,
+                false => {
+                    unique.push(name.clone());
+                    true
+                }
+            });
+
+            self.push(
+                path,
+                match children.get(pos) {
+                    Some(child) => Problem::UnexpectedElement {
+                        found: key(child.id()),
+                        expected,
+                    },
+                    None => Problem::MissingElement { expected },
+                },
+            );
+        }
+
+        let mut seen = BTreeMap::<&str, usize>::new();
+        for (child, assigned) in children.iter().zip(assigned) {
+            let index = seen.entry(&child.name).or_default();
+            let child_path = match *index {
+                0 => format!("{path} > {}", child.name),
+                index => format!("{path} > {}[{index}]", child.name),
+            };
+            *index += 1;
+
+            let decl = match assigned {
+                Assigned::Element(decl) => decl,
+                Assigned::Wildcard(wildcard) => match validator.elements.get(&key(child.id())) {
+                    Some(decl) if wildcard.process != Process::Skip => decl,
+                    None if wildcard.process == Process::Strict => {
+                        self.push(&child_path, Problem::UndeclaredElement(key(child.id())));
+                        continue;
+                    }
+                    _ => continue,
+                },
+            };
+
+            self.element(decl, child, &child_path)?;
+        }
+
+        Ok(())
+    }
+
+    fn attributes(
+        &mut self,
+        effective: &Effective<'_>,
+        element: &Element,
+        path: &str,
+    ) -> Result<(), Error> {
+        let validator = self.validator;
+        for attribute in &element.attributes {
+            if attribute.ns == XSI {
+                continue;
+            }
+
+            let id = attribute.id();
+            let attribute_path = format!("{path} > @{}", attribute.name);
+            let (decl, fixed) = match effective.attributes.iter().find(|used| used.id() == id) {
+                Some(used) => {
+                    let decl = match &used.decl {
+                        AttributeTerm::Local(decl) => decl,
+                        AttributeTerm::Ref(key) => validator.attribute_decl(key)?,
+                    };
+
+                    (decl, used.fixed.as_ref().or(decl.fixed.as_ref()))
+                }
+                None => match effective.wildcard {
+                    Some(wildcard) if wildcard.allows(&attribute.ns) => {
+                        match validator.attributes.get(&key(id)) {
+                            Some(decl) if wildcard.process != Process::Skip => {
+                                (decl, decl.fixed.as_ref())
+                            }
+                            None if wildcard.process == Process::Strict => {
+                                self.push(path, Problem::UnexpectedAttribute(key(id)));
+                                continue;
+                            }
+                            _ => continue,
+                        }
+                    }
+                    _ => {
+                        self.push(path, Problem::UnexpectedAttribute(key(id)));
+                        continue;
+                    }
+                },
+            };
+
+            self.attribute_value(decl, fixed, attribute, &attribute_path)?;
+        }
+
+        for used in &effective.attributes {
+            let id = used.id();
+            if used.required && !element.attributes.iter().any(|attr| attr.id() == id) {
+                self.push(path, Problem::MissingAttribute(key(id)));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn attribute_value(
+        &mut self,
+        decl: &AttributeDecl,
+        fixed: Option<&String>,
+        attribute: &Attribute,
+        path: &str,
+    ) -> Result<(), Error> {
+        if let Some(problem) = self.validator.value(&decl.ty, &attribute.value)? {
+            self.push(path, problem);
+        } else if let Some(fixed) = fixed {
+            let whitespace = self.validator.whitespace(&decl.ty)?;
+            if whitespace.apply(&attribute.value) != whitespace.apply(fixed) {
+                self.push(
+                    path,
+                    Problem::Facet {
+                        facet: "fixed",
+                        value: attribute.value.clone(),
+                    },
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn push(&mut self, path: &str, problem: Problem) {
+        self.violations.push(Violation {
+            path: path.to_owned(),
+            problem,
+        });
+    }
+}
+
+/// The result of matching a particle against child elements
+struct Matched {
+    /// The index of the first child that was not matched
+    pos: usize,
+    /// The elements that could have been matched at `pos`
+    open: Vec<String>,
+}
+
+enum Fail {
+    /// The child at `pos` (or the end of the element) is not one of the `expected` elements
+    Mismatch {
+        pos: usize,
+        expected: Vec<String>,
+    },
+    Schema(Error),
+}
+
+impl From<Error> for Fail {
+    fn from(error: Error) -> Self {
+        Self::Schema(error)
+    }
+}
+
+/// The declaration a child element was matched with
+#[derive(Clone, Copy)]
+enum Assigned<'a> {
+    Element(&'a ElementDecl),
+    Wildcard(&'a Wildcard),
+}
+
+impl Assigned<'_> {
+    fn matches(&self, element: &Element) -> bool {
+        match self {
+            Self::Element(decl) => element.id() == decl.id(),
+            Self::Wildcard(wildcard) => wildcard.allows(&element.ns),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Effective<'a> {
+    pub(crate) attributes: Vec<&'a AttributeUse>,
+    pub(crate) wildcard: Option<&'a Wildcard>,
+    pub(crate) content: Content<&'a TypeRef, Vec<&'a Particle>>,
+    mixed: bool,
+}
+
+pub(crate) enum Resolved<'a> {
+    /// `xs:anyType`, which allows any attributes and content
+    Any,
+    Builtin {
+        name: &'a str,
+        whitespace: Whitespace,
+        valid: Lexical,
+    },
+    Simple(&'a SimpleType),
+    Complex(&'a ComplexType),
+}
+
+#[derive(Clone, Debug)]
+enum TypeDef {
+    Simple(SimpleType),
+    Complex(ComplexType),
+}
+
+/// A reference to a type, or an anonymous type
+#[derive(Clone, Debug)]
+pub(crate) enum TypeRef {
+    /// A named type, in Clark notation
+    Named(String),
+    Simple(Box<SimpleType>),
+    Complex(Box<ComplexType>),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ElementDecl {
+    pub(crate) ns: String,
+    pub(crate) name: String,
+    pub(crate) ty: TypeRef,
+    pub(crate) nillable: bool,
+}
+
+impl ElementDecl {
+    pub(crate) fn id(&self) -> Id<'_> {
+        Id::new(&self.ns, &self.name)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct AttributeDecl {
+    pub(crate) ns: String,
+    pub(crate) name: String,
+    pub(crate) ty: TypeRef,
+    fixed: Option<String>,
+}
+
+impl AttributeDecl {
+    fn id(&self) -> Id<'_> {
+        Id::new(&self.ns, &self.name)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct AttributeUse {
+    pub(crate) decl: AttributeTerm,
+    pub(crate) required: bool,
+    fixed: Option<String>,
+}
+
+impl AttributeUse {
+    fn id(&self) -> Id<'_> {
+        match &self.decl {
+            AttributeTerm::Local(decl) => decl.id(),
+            AttributeTerm::Ref(key) => Id::parse(key).unwrap_or(Id::local(key)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum AttributeTerm {
+    Local(AttributeDecl),
+    /// A global attribute, in Clark notation
+    Ref(String),
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Attributes {
+    uses: Vec<AttributeUse>,
+    /// Attribute groups, in Clark notation
+    groups: Vec<String>,
+    wildcard: Option<Wildcard>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ComplexType {
+    /// The base type in Clark notation, if derived
+    base: Option<(String, Derivation)>,
+    mixed: bool,
+    attributes: Attributes,
+    content: Content<TypeRef, Particle>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Derivation {
+    Extension,
+    Restriction,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Content<S, E> {
+    Empty,
+    /// Text of a simple type
+    Simple(S),
+    /// Child elements
+    Elements(E),
+}
+
+impl<S, E> Default for Content<S, E> {
+    fn default() -> Self {
+        Self::Empty
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Particle {
+    pub(crate) term: Term,
+    pub(crate) occurs: Occurs,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Term {
+    Element(ElementDecl),
+    /// A global element, in Clark notation
+    Ref(String),
+    Any(Wildcard),
+    Sequence(Vec<Particle>),
+    Choice(Vec<Particle>),
+    All(Vec<Particle>),
+    /// A named model group, in Clark notation
+    Group(String),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Wildcard {
+    namespaces: Namespaces,
+    process: Process,
+}
+
+impl Wildcard {
+    fn allows(&self, ns: &str) -> bool {
+        match &self.namespaces {
+            Namespaces::Any => true,
+            Namespaces::Other(target) => ns != target && !ns.is_empty(),
+            Namespaces::List(namespaces) => namespaces.iter().any(|allowed| allowed == ns),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Namespaces {
+    Any,
+    /// Any namespace but this target namespace (and no namespace)
+    Other(String),
+    List(Vec<String>),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Process {
+    Strict,
+    Lax,
+    Skip,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum SimpleType {
+    Restriction { base: TypeRef, facets: Facets },
+    List(TypeRef),
+    Union(Vec<TypeRef>),
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Facets {
+    length: Option<usize>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    /// Any of these must match
+    patterns: Vec<Pattern>,
+    enumeration: Vec<String>,
+    min_inclusive: Option<f64>,
+    max_inclusive: Option<f64>,
+    min_exclusive: Option<f64>,
+    max_exclusive: Option<f64>,
+    total_digits: Option<usize>,
+    fraction_digits: Option<usize>,
+    whitespace: Option<Whitespace>,
+}
+
+impl Facets {
+    /// Check the normalized `value`, counting items instead of characters if it is a `list`
+    fn check(&self, value: &str, list: bool) -> Option<Problem> {
+        let length = match list {
+            true => value.split(' ').filter(|item| !item.is_empty()).count(),
+            false => value.chars().count(),
+        };
+
+        let number = || value.parse::<f64>().ok();
+        let (integer, fraction) = digits(value);
+        let facet = if self.length.map_or(false, |expected| length != expected) {
+            "length"
+        } else if self.min_length.map_or(false, |min| length < min) {
+            "minLength"
+        } else if self.max_length.map_or(false, |max| length > max) {
+            "maxLength"
+        } else if !self.patterns.is_empty() && !self.patterns.iter().any(|p| p.is_match(value)) {
+            "pattern"
+        } else if !self.enumeration.is_empty() && !self.enumeration.iter().any(|v| v == value) {
+            "enumeration"
+        } else if self.min_inclusive.map_or(false, |min| number() < Some(min)) {
+            "minInclusive"
+        } else if self
+            .max_inclusive
+            .map_or(false, |max| number().map_or(true, |n| n > max))
+        {
+            "maxInclusive"
+        } else if self
+            .min_exclusive
+            .map_or(false, |min| number().map_or(true, |n| n <= min))
+        {
+            "minExclusive"
+        } else if self
+            .max_exclusive
+            .map_or(false, |max| number().map_or(true, |n| n >= max))
+        {
+            "maxExclusive"
+        } else if self
+            .total_digits
+            .map_or(false, |max| integer + fraction > max)
+        {
+            "totalDigits"
+        } else if self.fraction_digits.map_or(false, |max| fraction > max) {
+            "fractionDigits"
+        } else {
+            return None;
+        };
+
+        Some(Problem::Facet {
+            facet,
+            value: value.to_owned(),
+        })
+    }
+}
+
+/// The number of significant integer and fraction digits in a decimal
+fn digits(value: &str) -> (usize, usize) {
+    let value = value.trim_start_matches(['+', '-']);
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+    (
+        integer.trim_start_matches('0').len(),
+        fraction.trim_end_matches('0').len(),
+    )
+}
+
+/// The `whiteSpace` facet
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Whitespace {
+    Preserve,
+    Replace,
+    Collapse,
+}
+
+impl Whitespace {
+    fn apply(self, value: &str) -> String {
+        match self {
+            Self::Preserve => value.to_owned(),
+            Self::Replace => value.replace(['\t', '\n', '\r'], " "),
+            Self::Collapse => value
+                .split(is_space)
+                .filter(|word| !word.is_empty())
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+/// Reads the declarations of a schema document
+struct Loader<'a> {
+    target: &'a str,
+    qualified_elements: bool,
+    qualified_attributes: bool,
+}
+
+impl Loader<'_> {
+    /// The name of a global definition, in Clark notation
+    fn key(&self, element: &Element) -> Result<String, Error> {
+        Ok(Id::new(self.target, required(element, "name")?).to_string())
+    }
+
+    fn element(&self, element: &Element, global: bool) -> Result<ElementDecl, Error> {
+        let qualified = match attribute(element, "form") {
+            Some(form) => form == "qualified",
+            None => self.qualified_elements,
+        };
+
+        Ok(ElementDecl {
+            ns: match global || qualified {
+                true => self.target.to_owned(),
+                false => String::new(),
+            },
+            name: required(element, "name")?.to_owned(),
+            ty: self.type_ref(element)?,
+            nillable: matches!(attribute(element, "nillable"), Some("true" | "1")),
+        })
+    }
+
+    fn attribute(&self, element: &Element, global: bool) -> Result<AttributeDecl, Error> {
+        let qualified = match attribute(element, "form") {
+            Some(form) => form == "qualified",
+            None => self.qualified_attributes,
+        };
+
+        Ok(AttributeDecl {
+            ns: match global || qualified {
+                true => self.target.to_owned(),
+                false => String::new(),
+            },
+            name: required(element, "name")?.to_owned(),
+            ty: match attribute(element, "type") {
+                Some(_) => self.type_ref(element)?,
+                None => match schema_elements(element)?.find(|child| child.name == "simpleType") {
+                    Some(simple) => TypeRef::Simple(Box::new(self.simple_type(simple)?)),
+                    None => TypeRef::Named(key(Id::new(XS, "anySimpleType"))),
+                },
+            },
+            fixed: attribute(element, "fixed").map(str::to_owned),
+        })
+    }
+
+    /// The type of an element declaration: named in the `type` attribute, or anonymous
+    fn type_ref(&self, element: &Element) -> Result<TypeRef, Error> {
+        if let Some(ty) = attribute(element, "type") {
+            return Ok(TypeRef::Named(ty.to_owned()));
+        }
+
+        for child in schema_elements(element)? {
+            match child.name.as_str() {
+                "complexType" => return Ok(TypeRef::Complex(Box::new(self.complex_type(child)?))),
+                "simpleType" => return Ok(TypeRef::Simple(Box::new(self.simple_type(child)?))),
+                _ => {}
+            }
+        }
+
+        Ok(TypeRef::Named(key(Id::new(XS, "anyType"))))
+    }
+
+    fn complex_type(&self, element: &Element) -> Result<ComplexType, Error> {
+        let mixed = matches!(attribute(element, "mixed"), Some("true" | "1"));
+        for child in schema_elements(element)? {
+            let simple = match child.name.as_str() {
+                "simpleContent" => true,
+                "complexContent" => false,
+                _ => continue,
+            };
+
+            let derivation = match schema_elements(child)?.next() {
+                Some(derivation) => derivation,
+                None => return Err(unsupported("empty xs:simpleContent or xs:complexContent")),
+            };
+
+            let base = required(derivation, "base")?.to_owned();
+            let (kind, content) = match (derivation.name.as_str(), simple) {
+                ("extension", true) => (
+                    Derivation::Extension,
+                    Content::Simple(TypeRef::Named(base.clone())),
+                ),
+                ("restriction", true) => (
+                    Derivation::Restriction,
+                    Content::Simple(TypeRef::Simple(Box::new(SimpleType::Restriction {
+                        base: TypeRef::Named(base.clone()),
+                        facets: self.facets(derivation)?,
+                    }))),
+                ),
+                ("extension", false) => (Derivation::Extension, self.content(derivation)?),
+                ("restriction", false) => (Derivation::Restriction, self.content(derivation)?),
+                (name, _) => return Err(unsupported(&format!("xs:{name} in xs:{}", child.name))),
+            };
+
+            return Ok(ComplexType {
+                base: Some((base, kind)),
+                mixed: mixed || matches!(attribute(child, "mixed"), Some("true" | "1")),
+                attributes: self.attributes(derivation)?,
+                content,
+            });
+        }
+
+        Ok(ComplexType {
+            base: None,
+            mixed,
+            attributes: self.attributes(element)?,
+            content: self.content(element)?,
+        })
+    }
+
+    /// The model group among the children of `element`, if any
+    fn content(&self, element: &Element) -> Result<Content<TypeRef, Particle>, Error> {
+        for child in schema_elements(element)? {
+            if let Some(particle) = self.particle(child)? {
+                return Ok(Content::Elements(particle));
+            }
+        }
+
+        Ok(Content::Empty)
+    }
+
+    /// Read a particle, or `None` if `element` is not one
+    fn particle(&self, element: &Element) -> Result<Option<Particle>, Error> {
+        let term = match element.name.as_str() {
+            "element" => match attribute(element, "ref") {
+                Some(key) => Term::Ref(key.to_owned()),
+                None => Term::Element(self.element(element, false)?),
+            },
+            "any" => Term::Any(self.wildcard(element)?),
+            "group" => Term::Group(required(element, "ref")?.to_owned()),
+            "sequence" | "choice" | "all" => {
+                let mut particles = Vec::new();
+                for child in schema_elements(element)? {
+                    particles.extend(self.particle(child)?);
+                }
+
+                match element.name.as_str() {
+                    "sequence" => Term::Sequence(particles),
+                    "choice" => Term::Choice(particles),
+                    _ => Term::All(particles),
+                }
+            }
+            _ => return Ok(None),
+        };
+
+        let min = match attribute(element, "minOccurs") {
+            Some(min) => number(min)?,
+            None => 1,
+        };
+
+        let max = match attribute(element, "maxOccurs") {
+            Some("unbounded") => None,
+            Some(max) => Some(number(max)?),
+            None => Some(1),
+        };
+
+        Ok(Some(Particle {
+            term,
+            occurs: Occurs { min, max },
+        }))
+    }
+
+    fn attributes(&self, element: &Element) -> Result<Attributes, Error> {
+        let mut attributes = Attributes::default();
+        for child in schema_elements(element)? {
+            match child.name.as_str() {
+                "attribute" if attribute(child, "use") == Some("prohibited") => {}
+                "attribute" => attributes.uses.push(AttributeUse {
+                    decl: match attribute(child, "ref") {
+                        Some(key) => AttributeTerm::Ref(key.to_owned()),
+                        None => AttributeTerm::Local(self.attribute(child, false)?),
+                    },
+                    required: attribute(child, "use") == Some("required"),
+                    fixed: attribute(child, "fixed").map(str::to_owned),
+                }),
+                "attributeGroup" => attributes.groups.push(required(child, "ref")?.to_owned()),
+                "anyAttribute" => attributes.wildcard = Some(self.wildcard(child)?),
+                _ => {}
+            }
+        }
+
+        Ok(attributes)
+    }
+
+    fn wildcard(&self, element: &Element) -> Result<Wildcard, Error> {
+        let namespaces = match attribute(element, "namespace").unwrap_or("##any") {
+            "##any" => Namespaces::Any,
+            "##other" => Namespaces::Other(self.target.to_owned()),
+            list => Namespaces::List(
+                list.split(is_space)
+                    .filter(|ns| !ns.is_empty())
+                    .map(|ns| match ns {
+                        "##targetNamespace" => self.target.to_owned(),
+                        "##local" => String::new(),
+                        ns => ns.to_owned(),
+                    })
+                    .collect(),
+            ),
+        };
+
+        let process = match attribute(element, "processContents") {
+            None | Some("strict") => Process::Strict,
+            Some("lax") => Process::Lax,
+            Some("skip") => Process::Skip,
+            Some(other) => return Err(unsupported(&format!("processContents=\"{other}\""))),
+        };
+
+        Ok(Wildcard {
+            namespaces,
+            process,
+        })
+    }
+
+    fn simple_type(&self, element: &Element) -> Result<SimpleType, Error> {
+        let variety = match schema_elements(element)?.next() {
+            Some(variety) => variety,
+            None => return Err(unsupported("empty xs:simpleType")),
+        };
+
+        let mut inline = schema_elements(variety)?
+            .filter(|child| child.name == "simpleType")
+            .map(|child| Ok::<_, Error>(TypeRef::Simple(Box::new(self.simple_type(child)?))));
+
+        Ok(match variety.name.as_str() {
+            "restriction" => SimpleType::Restriction {
+                base: match attribute(variety, "base") {
+                    Some(base) => TypeRef::Named(base.to_owned()),
+                    None => match inline.next() {
+                        Some(base) => base?,
+                        None => return Err(unsupported("xs:restriction without a base")),
+                    },
+                },
+                facets: self.facets(variety)?,
+            },
+            "list" => SimpleType::List(match attribute(variety, "itemType") {
+                Some(item) => TypeRef::Named(item.to_owned()),
+                None => match inline.next() {
+                    Some(item) => item?,
+                    None => return Err(unsupported("xs:list without an item type")),
+                },
+            }),
+            "union" => {
+                let mut members = attribute(variety, "memberTypes")
+                    .unwrap_or_default()
+                    .split(is_space)
+                    .filter(|member| !member.is_empty())
+                    .map(|member| TypeRef::Named(member.to_owned()))
+                    .collect::<Vec<_>>();
+                for member in inline {
+                    members.push(member?);
+                }
+
+                SimpleType::Union(members)
+            }
+            name => return Err(unsupported(&format!("xs:{name} in xs:simpleType"))),
+        })
+    }
+
+    fn facets(&self, element: &Element) -> Result<Facets, Error> {
+        let mut facets = Facets::default();
+        for child in schema_elements(element)? {
+            let value = match child.name.as_str() {
+                "annotation" | "simpleType" | "attribute" | "attributeGroup" | "anyAttribute" => {
+                    continue
+                }
+                _ => required(child, "value")?,
+            };
+
+            let bound = || {
+                value.trim().parse::<f64>().map_err(|_| {
+                    Error::UnexpectedValue(format!("invalid {} facet `{value}`", child.name))
+                })
+            };
+
+            match child.name.as_str() {
+                "length" => facets.length = Some(number(value)? as usize),
+                "minLength" => facets.min_length = Some(number(value)? as usize),
+                "maxLength" => facets.max_length = Some(number(value)? as usize),
+                "pattern" => facets.patterns.push(Pattern::new(value)?),
+                "enumeration" => facets.enumeration.push(value.to_owned()),
+                "minInclusive" => facets.min_inclusive = Some(bound()?),
+                "maxInclusive" => facets.max_inclusive = Some(bound()?),
+                "minExclusive" => facets.min_exclusive = Some(bound()?),
+                "maxExclusive" => facets.max_exclusive = Some(bound()?),
+                "totalDigits" => facets.total_digits = Some(number(value)? as usize),
+                "fractionDigits" => facets.fraction_digits = Some(number(value)? as usize),
+                "whiteSpace" => {
+                    facets.whitespace = Some(match value {
+                        "preserve" => Whitespace::Preserve,
+                        "replace" => Whitespace::Replace,
+                        "collapse" => Whitespace::Collapse,
+                        _ => return Err(unsupported(&format!("whiteSpace=\"{value}\""))),
+                    })
+                }
+                name => return Err(unsupported(&format!("xs:{name} facet"))),
+            }
+        }
+
+        Ok(facets)
+    }
+}
+
+/// A schema (or WSDL) document, read with the qualified names in attribute values like `type`
+/// resolved to Clark notation
+pub(crate) struct SchemaDocument(pub(crate) Element);
+
+impl SchemaDocument {
+    fn read(deserializer: &mut Deserializer<'_, '_>) -> Result<Element, Error> {
+        let parent = deserializer.parent()?;
+        let mut element = Element::new(parent.ns, parent.name);
+        while let Some(node) = deserializer.next() {
+            match node? {
+                DeNode::Attribute(attr) => {
+                    let id = deserializer.attribute_id(&attr)?;
+                    let value = match (parent.ns, id.ns, id.name) {
+                        (XS, "", "type" | "base" | "ref" | "itemType")
+                        | (WSDL, "", "element" | "type" | "message" | "binding") => {
+                            key(deserializer.resolve_qname(attr.value.trim())?)
+                        }
+                        (XS, "", "memberTypes") => {
+                            let mut members = Vec::new();
+                            for member in attr.value.split(is_space).filter(|m| !m.is_empty()) {
+                                members.push(key(deserializer.resolve_qname(member)?));
+                            }
+
+                            members.join(" ")
+                        }
+                        _ => attr.value.into_owned(),
+                    };
+
+                    element
+                        .attributes
+                        .push(Attribute::new(id.ns, id.name, value));
+                }
+                DeNode::Open(data) => {
+                    let mut nested = deserializer.nested(data);
+                    element
+                        .children
+                        .push(Node::Element(Self::read(&mut nested)?));
+                }
+                DeNode::Text(text) => element.children.push(Node::Text(text.into_owned())),
+                node => return Err(Error::UnexpectedNode(format!("{node:?} in schema"))),
+            }
+        }
+
+        Ok(element)
+    }
+}
+
+impl<'xml> FromXml<'xml> for SchemaDocument {
+    fn matches(_: Id<'_>, _: Option<Id<'_>>) -> bool {
+        true
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        *into = Some(Self(Self::read(deserializer)?));
+        Ok(())
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Element;
+}
+
+/// The child elements of a schema element, rejecting anything not in the XML Schema namespace
+/// and schema documents that are not self-contained
+fn schema_elements(element: &Element) -> Result<impl Iterator<Item = &Element>, Error> {
+    for child in element.elements() {
+        if child.ns != XS {
+            return Err(unsupported(&format!("{} in schema", child.id())));
+        } else if matches!(child.name.as_str(), "include" | "redefine" | "override") {
+            return Err(unsupported(&format!("xs:{}", child.name)));
+        }
+    }
+
+    Ok(element
+        .elements()
+        .filter(|child| child.name != "annotation"))
+}
+
+fn attribute<'a>(element: &'a Element, name: &str) -> Option<&'a str> {
+    element.attribute(Id::local(name))
+}
+
+fn required<'a>(element: &'a Element, name: &str) -> Result<&'a str, Error> {
+    attribute(element, name).ok_or_else(|| {
+        Error::UnexpectedValue(format!("missing {name} attribute on xs:{}", element.name))
+    })
+}
+
+fn number(value: &str) -> Result<u32, Error> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| Error::UnexpectedValue(format!("invalid number `{value}` in schema")))
+}
+
+/// A name in Clark notation
+fn key(id: Id<'_>) -> String {
+    id.to_string()
+}
+
+fn unsupported(what: &str) -> Error {
+    Error::UnexpectedValue(format!("unsupported in schema: {what}"))
+}
+
+fn undefined(kind: &str, key: &str) -> Error {
+    Error::UnexpectedValue(format!("undefined {kind} {key} in schema"))
+}
+
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r')
+}
+
+/// Checks the lexical form of a value, after whitespace is handled
+pub(crate) type Lexical = fn(&str) -> bool;
+
+/// The whitespace handling and lexical check of a built-in simple type
+fn builtin(name: &str) -> Option<(Whitespace, Lexical)> {
+    use Whitespace::*;
+    let valid: Lexical = match name {
+        "anySimpleType" | "string" => return Some((Preserve, |_| true)),
+        "normalizedString" => return Some((Replace, |_| true)),
+        "token" | "ENTITY" | "gYear" | "gYearMonth" | "gMonth" | "gMonthDay" | "gDay" => |_| true,
+        "boolean" => |s| matches!(s, "true" | "false" | "1" | "0"),
+        "decimal" => is_decimal,
+        "float" | "double" => |s| {
+            matches!(s, "INF" | "+INF" | "-INF" | "NaN")
+                || (s
+                    .bytes()
+                    .all(|b| b.is_ascii_digit() || b"+-.eE".contains(&b))
+                    && s.parse::<f64>().is_ok())
+        },
+        "integer" => |s| integer(s, None, None),
+        "nonPositiveInteger" => |s| integer(s, None, Some(0)),
+        "negativeInteger" => |s| integer(s, None, Some(-1)),
+        "nonNegativeInteger" => |s| integer(s, Some(0), None),
+        "positiveInteger" => |s| integer(s, Some(1), None),
+        "long" => |s| integer(s, Some(i64::MIN.into()), Some(i64::MAX.into())),
+        "int" => |s| integer(s, Some(i32::MIN.into()), Some(i32::MAX.into())),
+        "short" => |s| integer(s, Some(i16::MIN.into()), Some(i16::MAX.into())),
+        "byte" => |s| integer(s, Some(i8::MIN.into()), Some(i8::MAX.into())),
+        "unsignedLong" => |s| integer(s, Some(0), Some(u64::MAX.into())),
+        "unsignedInt" => |s| integer(s, Some(0), Some(u32::MAX.into())),
+        "unsignedShort" => |s| integer(s, Some(0), Some(u16::MAX.into())),
+        "unsignedByte" => |s| integer(s, Some(0), Some(u8::MAX.into())),
+        "date" => |s| is_date(s.strip_prefix('-').unwrap_or(s), true),
+        "time" => is_time,
+        "dateTime" => |s| match s.strip_prefix('-').unwrap_or(s).split_once('T') {
+            Some((date, time)) => is_date(date, false) && is_time(time),
+            None => false,
+        },
+        "duration" => |s| {
+            let s = s.strip_prefix('-').unwrap_or(s);
+            s.len() > 1
+                && s.starts_with('P')
+                && !s.ends_with('T')
+                && s[1..]
+                    .bytes()
+                    .all(|b| b.is_ascii_digit() || b"YMDTHS.".contains(&b))
+        },
+        "hexBinary" => |s| s.len() % 2 == 0 && s.bytes().all(|b| b.is_ascii_hexdigit()),
+        "base64Binary" => |s| {
+            let s = s.replace(' ', "");
+            s.len() % 4 == 0
+                && s.trim_end_matches('=').len() + 2 >= s.len()
+                && s.trim_end_matches('=')
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/')
+        },
+        "anyURI" => |s| xsd::AnyUri::new(s).is_ok(),
+        "language" => |s| xsd::Language::new(s).is_ok(),
+        "NCName" | "ID" | "IDREF" => |s| xsd::NcName::new(s).is_ok(),
+        "IDREFS" | "ENTITIES" => |s| s.split(' ').all(|s| xsd::NcName::new(s).is_ok()),
+        "Name" => |s| s.split(':').all(|part| xsd::NcName::new(part).is_ok()),
+        "QName" | "NOTATION" => |s| {
+            let mut parts = s.splitn(2, ':');
+            parts.all(|part| xsd::NcName::new(part).is_ok())
+        },
+        "NMTOKEN" => is_nmtoken,
+        "NMTOKENS" => |s| !s.is_empty() && s.split(' ').all(is_nmtoken),
+        _ => return None,
+    };
+
+    Some((Collapse, valid))
+}
+
+fn is_decimal(s: &str) -> bool {
+    let s = s.strip_prefix(['+', '-']).unwrap_or(s);
+    let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+    !(integer.is_empty() && fraction.is_empty())
+        && integer.bytes().all(|b| b.is_ascii_digit())
+        && fraction.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn integer(s: &str, min: Option<i128>, max: Option<i128>) -> bool {
+    let digits = s.strip_prefix(['+', '-']).unwrap_or(s);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+
+    match s.strip_prefix('+').unwrap_or(s).parse::<i128>() {
+        Ok(value) => min.map_or(true, |min| value >= min) && max.map_or(true, |max| value <= max),
+        // Beyond the range of `i128`, so only unbounded types in that direction
+        Err(_) => match s.starts_with('-') {
+            true => min.is_none(),
+            false => max.is_none(),
+        },
+    }
+}
+
+fn is_nmtoken(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c == ':' || xsd::NcName::new(&format!("a{c}")).is_ok())
+}
+
+/// `YYYY-MM-DD`, followed by a timezone if `zone` is set
+fn is_date(s: &str, zone: bool) -> bool {
+    let (year, rest) = match s.split_once('-') {
+        Some(split) => split,
+        None => return false,
+    };
+
+    let (month, day, rest) = match (rest.get(..2), rest.get(2..3), rest.get(3..5)) {
+        (Some(month), Some("-"), Some(day)) => (month, day, &rest[5..]),
+        _ => return false,
+    };
+
+    year.len() >= 4
+        && year.bytes().all(|b| b.is_ascii_digit())
+        && two_digits(month, 1, 12)
+        && two_digits(day, 1, 31)
+        && match zone {
+            true => is_zone(rest),
+            false => rest.is_empty(),
+        }
+}
+
+/// `hh:mm:ss`, with optional fractional seconds and timezone
+fn is_time(s: &str) -> bool {
+    let (hms, rest) = match s.get(..8) {
+        Some(hms) => (hms, &s[8..]),
+        None => return false,
+    };
+
+    let mut parts = hms.split(':');
+    let valid = matches!(
+        (parts.next(), parts.next(), parts.next()),
+        (Some(h), Some(m), Some(sec)) if two_digits(h, 0, 24) && two_digits(m, 0, 59) && two_digits(sec, 0, 60)
+    );
+
+    let rest = match rest.strip_prefix('.') {
+        Some(fraction) => {
+            let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+            match digits {
+                0 => return false,
+                digits => &fraction[digits..],
+            }
+        }
+        None => rest,
+    };
+
+    valid && is_zone(rest)
+}
+
+/// An optional timezone: `Z` or `+hh:mm`
+fn is_zone(s: &str) -> bool {
+    match s {
+        "" | "Z" => true,
+        s => match s.strip_prefix(['+', '-']).and_then(|s| s.split_once(':')) {
+            Some((hours, minutes)) => two_digits(hours, 0, 14) && two_digits(minutes, 0, 59),
+            None => false,
+        },
+    }
+}
+
+fn two_digits(s: &str, min: u8, max: u8) -> bool {
+    s.len() == 2
+        && s.bytes().all(|b| b.is_ascii_digit())
+        && s.parse().map_or(false, |n: u8| (min..=max).contains(&n))
+}
@@ -0,0 +1,152 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::de::{ns_matches, Node};
+use crate::ser::Context;
+use crate::{Accumulate, Deserializer, Error, FromXml, Id, Kind, Serializer, ToXml};
+
+/// Names the element that [`Wrapped`] puts around its inner value
+pub trait Wrapper {
+    const NAME: &'static str;
+    const NS: &'static str = "";
+}
+
+/// Wraps a value in an extra element named by `W`
+///
+/// The inner value is (de)serialized as the content of the wrapper element, without a field name.
+/// This works for types that map to an element (like derived structs, or `Vec`s of them) and for
+/// single scalar values, which become the text content of the wrapper element.
+pub struct Wrapped<W, T> {
+    pub inner: T,
+    marker: PhantomData<W>,
+}
+
+impl<W, T> Wrapped<W, T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<W, T> From<T> for Wrapped<W, T> {
+    fn from(inner: T) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<W, T: Clone> Clone for Wrapped<W, T> {
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone())
+    }
+}
+
+impl<W, T: fmt::Debug> fmt::Debug for Wrapped<W, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Wrapped").field(&self.inner).finish()
+    }
+}
+
+impl<W, T: PartialEq> PartialEq for Wrapped<W, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<W, T: Eq> Eq for Wrapped<W, T> {}
+
+impl<W: Wrapper, T: ToXml> ToXml for Wrapped<W, T> {
+    fn serialize<S: fmt::Write + ?Sized>(
+        &self,
+        _: Option<Id<'_>>,
+        serializer: &mut Serializer<S>,
+    ) -> Result<(), Error> {
+        let prefix = serializer.write_start(W::NAME, W::NS)?;
+        let new = Context::<0> {
+            default_ns: match prefix {
+                Some(_) => serializer.default_ns(),
+                None => W::NS,
+            },
+            ..Default::default()
+        };
+
+        let old = serializer.push(new)?;
+        serializer.end_start()?;
+        self.inner.serialize(None, serializer)?;
+        serializer.write_close(prefix.as_deref(), W::NAME)?;
+        serializer.pop(old);
+        Ok(())
+    }
+}
+
+impl<'xml, W: Wrapper, T: FromXml<'xml>> FromXml<'xml> for Wrapped<W, T> {
+    #[inline]
+    fn matches(id: Id<'_>, _: Option<Id<'_>>) -> bool {
+        id.name == W::NAME && ns_matches(id.ns, W::NS)
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.seen {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        into.seen = true;
+        if T::KIND == Kind::Scalar {
+            T::deserialize(&mut into.inner, field, deserializer)?;
+            return deserializer.ignore();
+        }
+
+        loop {
+            match deserializer.next() {
+                Some(Ok(Node::Open(element))) => {
+                    let id = deserializer.element_id(&element)?;
+                    let mut nested = deserializer.nested(element);
+                    match T::matches(id, None) {
+                        true => T::deserialize(&mut into.inner, field, &mut nested)?,
+                        false => nested.ignore()?,
+                    }
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e),
+                None => return Ok(()),
+            }
+        }
+    }
+
+    type Accumulator = WrappedAccumulator<W, T, T::Accumulator>;
+    const KIND: Kind = Kind::Element;
+}
+
+pub struct WrappedAccumulator<W, T, A: Accumulate<T>> {
+    inner: A,
+    seen: bool,
+    marker: PhantomData<(W, T)>,
+}
+
+impl<W, T, A: Accumulate<T>> Default for WrappedAccumulator<W, T, A> {
+    fn default() -> Self {
+        Self {
+            inner: A::default(),
+            seen: false,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<W, T, A: Accumulate<T>> Accumulate<Wrapped<W, T>> for WrappedAccumulator<W, T, A> {
+    fn try_done(self, field: &'static str) -> Result<Wrapped<W, T>, Error> {
+        match self.seen {
+            true => Ok(Wrapped::new(self.inner.try_done(field)?)),
+            false => Err(Error::MissingValue(field)),
+        }
+    }
+}
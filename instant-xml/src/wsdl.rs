@@ -0,0 +1,732 @@
+//! Client code generation from WSDL 1.1 documents
+//!
+//! [`generate()`] returns Rust source for the SOAP operations described by a WSDL document: a
+//! struct deriving [`FromXml`](crate::FromXml) and [`ToXml`](crate::ToXml) for every request and
+//! response, and a unit struct implementing [`Operation`](crate::soap::Operation) for every
+//! operation, which can then be sent with [`soap::call()`](crate::soap::call). Run it from a
+//! build script and `include!()` the output:
+//!
+//! ```ignore
+//! let source = instant_xml::wsdl::generate(&fs::read_to_string("stock.wsdl")?)?;
+//! fs::write(Path::new(&env::var("OUT_DIR")?).join("stock.rs"), source)?;
+//! ```
+//!
+//! Document/literal and rpc/literal bindings for SOAP 1.1 and 1.2 are supported. Types are read
+//! from the schemas embedded in `wsdl:types`, with the same support for XML Schema as the
+//! [`Validator`]. Simple types map to the closest Rust scalar type or `String`, and the elements
+//! in a choice become optional fields. `wsdl:import` is not followed, SOAP headers are not
+//! generated, and one-way operations are skipped.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use crate::dom::Element;
+use crate::soap::Version;
+use crate::validate::{
+    AttributeTerm, Content, ElementDecl, Resolved, SchemaDocument, SimpleType, Term, TypeRef,
+    Validator,
+};
+use crate::{Error, Id};
+
+/// The WSDL 1.1 namespace
+pub const WSDL: &str = "http://schemas.xmlsoap.org/wsdl/";
+/// The namespace of the SOAP 1.1 binding extensions
+const SOAP11_BINDING: &str = "http://schemas.xmlsoap.org/wsdl/soap/";
+/// The namespace of the SOAP 1.2 binding extensions
+const SOAP12_BINDING: &str = "http://schemas.xmlsoap.org/wsdl/soap12/";
+
+/// Generate Rust source for the SOAP operations of the WSDL document `wsdl`
+pub fn generate(wsdl: &str) -> Result<String, Error> {
+    let root = crate::from_str::<SchemaDocument>(wsdl)?.0;
+    if !root.id().is(WSDL, "definitions") {
+        return Err(Error::UnexpectedValue(format!(
+            "expected wsdl:definitions, found {}",
+            root.id()
+        )));
+    }
+
+    let mut validator = Validator::new();
+    for types in children(&root, "types") {
+        for schema in types.elements() {
+            validator.add_schema(schema)?;
+        }
+    }
+
+    let target = attribute(&root, "targetNamespace").unwrap_or_default();
+    let messages = definitions(&root, target, "message")?;
+    let port_types = definitions(&root, target, "portType")?;
+
+    let mut generator = Generator {
+        validator: &validator,
+        names: PRELUDE.iter().map(|&name| name.to_owned()).collect(),
+        structs: BTreeMap::new(),
+        pending: BTreeSet::new(),
+        out: String::new(),
+    };
+
+    generator
+        .out
+        .push_str("// Generated from a WSDL document by instant-xml\n\n");
+    generator
+        .out
+        .push_str("use ::instant_xml::{FromXml, ToXml};\n");
+
+    for binding in children(&root, "binding") {
+        let (version, extension) = match soap_extension(binding, "binding") {
+            Some(found) => found,
+            None => continue,
+        };
+
+        let name = required(binding, "name")?;
+        let port_type = required(binding, "type")?;
+        let port_type = match port_types.get(port_type) {
+            Some(port_type) => *port_type,
+            None => return Err(undefined("port type", port_type)),
+        };
+
+        let style = attribute(extension, "style").unwrap_or("document");
+        for operation in children(binding, "operation") {
+            let binding = Binding {
+                name,
+                version,
+                style,
+                operation,
+            };
+
+            generator.operation(&binding, port_type, &messages)?;
+        }
+    }
+
+    for service in children(&root, "service") {
+        for port in children(service, "port") {
+            let address = match soap_extension(port, "address") {
+                Some((_, address)) => required(address, "location")?,
+                None => continue,
+            };
+
+            let name = required(port, "name")?;
+            let ident = generator.name(snake(name).trim_end_matches('_').to_uppercase());
+            write!(
+                generator.out,
+                "\n/// The address of the `{name}` port of the `{}` service\n",
+                required(service, "name")?
+            )?;
+            writeln!(generator.out, "pub const {ident}: &str = {address:?};")?;
+        }
+    }
+
+    Ok(generator.out)
+}
+
+struct Generator<'a> {
+    validator: &'a Validator,
+    /// Names of the items generated so far
+    names: BTreeSet<String>,
+    /// Structs generated for element declarations, by the element name (and its type, for local
+    /// elements) in Clark notation
+    structs: BTreeMap<String, String>,
+    /// Keys of the structs that are being generated, to detect recursion
+    pending: BTreeSet<String>,
+    out: String,
+}
+
+impl Generator<'_> {
+    /// Generate the request and response types and the `Operation` impl for an operation
+    fn operation(
+        &mut self,
+        binding: &Binding<'_>,
+        port_type: &Element,
+        messages: &BTreeMap<String, &Element>,
+    ) -> Result<(), Error> {
+        let name = required(binding.operation, "name")?;
+        let abstract_operation = children(port_type, "operation")
+            .find(|operation| attribute(operation, "name") == Some(name));
+        let abstract_operation = match abstract_operation {
+            Some(operation) => operation,
+            None => return Err(undefined("operation", name)),
+        };
+
+        let (input, output) = match (
+            child(abstract_operation, "input"),
+            child(abstract_operation, "output"),
+        ) {
+            (Some(input), Some(output)) => (input, output),
+            _ => return Ok(()),
+        };
+
+        let (action, style) = match soap_extension(binding.operation, "operation") {
+            Some((_, operation)) => (
+                attribute(operation, "soapAction").unwrap_or_default(),
+                attribute(operation, "style").unwrap_or(binding.style),
+            ),
+            None => ("", binding.style),
+        };
+
+        let request = self.message(binding, style, input, name, messages)?;
+        let response =
+            self.message(binding, style, output, &format!("{name}Response"), messages)?;
+
+        let mut ident = format!("{}Operation", pascal(name));
+        if self.names.contains(&ident) {
+            ident = format!("{}{ident}", pascal(binding.name));
+        }
+
+        let ident = self.name(ident);
+        let version = match binding.version {
+            Version::Soap11 => "Soap11",
+            Version::Soap12 => "Soap12",
+        };
+
+        write!(
+            self.out,
+            "\n/// The `{name}` operation of the `{}` binding\n",
+            binding.name
+        )?;
+        writeln!(self.out, "pub struct {ident};\n")?;
+        writeln!(
+            self.out,
+            "impl ::instant_xml::soap::Operation for {ident} {{"
+        )?;
+        writeln!(self.out, "    type Request = {request};")?;
+        writeln!(self.out, "    type Response = {response};")?;
+        writeln!(self.out, "    const ACTION: &'static str = {action:?};")?;
+        writeln!(
+            self.out,
+            "    const VERSION: ::instant_xml::soap::Version = \
+             ::instant_xml::soap::Version::{version};"
+        )?;
+        writeln!(self.out, "}}")?;
+        Ok(())
+    }
+
+    /// Generate the type of the body of the `input` or `output` message of an operation
+    ///
+    /// For document-style operations, this is the struct for the element of the single message
+    /// part. For rpc-style operations, it is a wrapper named `wrapper` with a field per part.
+    fn message(
+        &mut self,
+        binding: &Binding<'_>,
+        style: &str,
+        io: &Element,
+        wrapper: &str,
+        messages: &BTreeMap<String, &Element>,
+    ) -> Result<String, Error> {
+        let key = required(io, "message")?;
+        let message = match messages.get(key) {
+            Some(message) => *message,
+            None => return Err(undefined("message", key)),
+        };
+
+        let body = child(binding.operation, &io.name)
+            .and_then(|io| soap_extension(io, "body"))
+            .map(|(_, body)| body);
+        if let Some(body) = body {
+            if attribute(body, "use") == Some("encoded") {
+                return Err(unsupported("encoded message parts"));
+            }
+        }
+
+        let parts = children(message, "part").collect::<Vec<_>>();
+        if style != "rpc" {
+            let element = match parts.as_slice() {
+                [part] => attribute(part, "element"),
+                _ => None,
+            };
+
+            return match element {
+                Some(key) => {
+                    let decl = self.validator.element_decl(key)?;
+                    self.element_struct(decl, Some(key.to_owned()), false)
+                }
+                None => Err(unsupported(&format!(
+                    "document-style message {key} without a single element part"
+                ))),
+            };
+        }
+
+        let ns = body
+            .and_then(|body| attribute(body, "namespace"))
+            .unwrap_or_default();
+        let mut fields = Fields::new(ns);
+        for part in parts {
+            match (attribute(part, "element"), attribute(part, "type")) {
+                (Some(key), _) => {
+                    let decl = self.validator.element_decl(key)?;
+                    self.element_field(decl, Some(key), false, false, &mut fields)?;
+                }
+                (None, Some(ty)) => {
+                    let decl = ElementDecl {
+                        ns: String::new(),
+                        name: required(part, "name")?.to_owned(),
+                        ty: TypeRef::Named(ty.to_owned()),
+                        nillable: false,
+                    };
+
+                    self.element_field(&decl, None, false, false, &mut fields)?;
+                }
+                (None, None) => return Err(unsupported("message part without element or type")),
+            }
+        }
+
+        let ident = self.name(pascal(wrapper));
+        self.write_struct(&ident, Id::new(ns, wrapper), &fields)?;
+        Ok(ident)
+    }
+
+    /// Generate the struct for an element declaration, or return the one generated before
+    ///
+    /// `key` identifies declarations with the same content, so their struct can be reused.
+    fn element_struct(
+        &mut self,
+        decl: &ElementDecl,
+        key: Option<String>,
+        repeated: bool,
+    ) -> Result<String, Error> {
+        if let Some(key) = &key {
+            if let Some(ident) = self.structs.get(key) {
+                if !repeated && self.pending.contains(key) {
+                    return Err(unsupported(&format!("recursive element {}", decl.id())));
+                }
+
+                return Ok(ident.clone());
+            }
+        }
+
+        let ident = self.name(pascal(&decl.name));
+        if let Some(key) = &key {
+            self.structs.insert(key.clone(), ident.clone());
+            self.pending.insert(key.clone());
+        }
+
+        let mut fields = Fields::new(&decl.ns);
+        match self.validator.resolve(&decl.ty)? {
+            Resolved::Any => fields.any(),
+            Resolved::Builtin { .. } | Resolved::Simple(_) => {
+                fields.push(None, false, &["direct"], None, self.scalar(&decl.ty)?)
+            }
+            Resolved::Complex(ty) => {
+                let effective = self.validator.effective(ty)?;
+                for attribute in effective.attributes {
+                    let decl = match &attribute.decl {
+                        AttributeTerm::Local(decl) => decl,
+                        AttributeTerm::Ref(key) => self.validator.attribute_decl(key)?,
+                    };
+
+                    let ty = self.scalar(&decl.ty)?;
+                    fields.push(
+                        Some(&decl.name),
+                        true,
+                        &["attribute"],
+                        match decl.ns.is_empty() {
+                            true => None,
+                            false => Some(format!("ns({:?})", decl.ns)),
+                        },
+                        match attribute.required {
+                            true => ty,
+                            false => format!("Option<{ty}>"),
+                        },
+                    );
+                }
+
+                match effective.content {
+                    Content::Empty => {}
+                    Content::Simple(ty) => {
+                        fields.push(None, false, &["direct"], None, self.scalar(ty)?)
+                    }
+                    Content::Elements(particles) => {
+                        for particle in particles {
+                            let optional = particle.occurs.min == 0;
+                            let repeated = particle.occurs.max != Some(1);
+                            self.term(&particle.term, optional, repeated, &mut fields)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        self.write_struct(&ident, decl.id(), &fields)?;
+        if let Some(key) = &key {
+            self.pending.remove(key);
+        }
+
+        Ok(ident)
+    }
+
+    /// Add the fields for the elements in `term`
+    fn term(
+        &mut self,
+        term: &Term,
+        optional: bool,
+        repeated: bool,
+        fields: &mut Fields,
+    ) -> Result<(), Error> {
+        match term {
+            Term::Element(decl) => self.element_field(decl, None, optional, repeated, fields)?,
+            Term::Ref(key) => {
+                let decl = self.validator.element_decl(key)?;
+                self.element_field(decl, Some(key), optional, repeated, fields)?;
+            }
+            Term::Any(_) => fields.any(),
+            Term::Sequence(particles) | Term::All(particles) | Term::Choice(particles) => {
+                let choice = matches!(term, Term::Choice(_));
+                for particle in particles {
+                    let optional = optional || choice || particle.occurs.min == 0;
+                    let repeated = repeated || particle.occurs.max != Some(1);
+                    self.term(&particle.term, optional, repeated, fields)?;
+                }
+            }
+            Term::Group(key) => match self.validator.groups.get(key) {
+                Some(term) => self.term(term, optional, repeated, fields)?,
+                None => return Err(undefined("group", key)),
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Add the field for a child element, generating its struct if it has a complex type
+    ///
+    /// `global` is the name of the declaration in Clark notation, if it is a global element.
+    fn element_field(
+        &mut self,
+        decl: &ElementDecl,
+        global: Option<&str>,
+        optional: bool,
+        repeated: bool,
+        fields: &mut Fields,
+    ) -> Result<(), Error> {
+        // Structs are matched by their own name, so only scalar fields are renamed
+        let (ty, scalar, ns) = match self.validator.resolve(&decl.ty)? {
+            Resolved::Any => {
+                fields.any();
+                return Ok(());
+            }
+            Resolved::Complex(_) => {
+                let key = match (global, &decl.ty) {
+                    (Some(key), _) => Some(key.to_owned()),
+                    (None, TypeRef::Named(ty)) => Some(format!("{} {ty}", decl.id())),
+                    (None, _) => None,
+                };
+
+                (self.element_struct(decl, key, repeated)?, false, None)
+            }
+            Resolved::Builtin { .. } | Resolved::Simple(_) => {
+                let ns = match (decl.ns == fields.ns, decl.ns.is_empty()) {
+                    (true, _) => None,
+                    (false, true) => Some("no_ns".to_owned()),
+                    (false, false) => Some(format!("ns({:?})", decl.ns)),
+                };
+
+                (self.scalar(&decl.ty)?, true, ns)
+            }
+        };
+
+        let nil = decl.nillable && !repeated;
+        let ty = match (repeated, optional || nil) {
+            (true, _) => format!("Vec<{ty}>"),
+            (false, true) => format!("Option<{ty}>"),
+            (false, false) => ty,
+        };
+
+        let meta: &[&str] = match nil {
+            true => &["nil"],
+            false => &[],
+        };
+
+        fields.push(Some(&decl.name), scalar, meta, ns, ty);
+        Ok(())
+    }
+
+    /// The Rust type for values of the simple type `ty`
+    fn scalar(&self, ty: &TypeRef) -> Result<String, Error> {
+        Ok(match self.validator.resolve(ty)? {
+            Resolved::Builtin { name, .. } => builtin(name).to_owned(),
+            Resolved::Simple(SimpleType::Restriction { base, .. }) => self.scalar(base)?,
+            Resolved::Simple(SimpleType::List(_) | SimpleType::Union(_)) => "String".to_owned(),
+            Resolved::Complex(ty) => match self.validator.effective(ty)?.content {
+                Content::Simple(ty) => self.scalar(ty)?,
+                _ => return Err(unsupported("complex type for a simple value")),
+            },
+            Resolved::Any => return Err(unsupported("xs:anyType for a simple value")),
+        })
+    }
+
+    fn write_struct(&mut self, ident: &str, id: Id<'_>, fields: &Fields) -> Result<(), Error> {
+        let mut meta = Vec::new();
+        if ident != id.name {
+            meta.push(format!("rename = {:?}", id.name));
+        }
+
+        if !id.ns.is_empty() {
+            meta.push(format!("ns({:?})", id.ns));
+        }
+
+        writeln!(
+            self.out,
+            "\n#[derive(Clone, Debug, FromXml, PartialEq, ToXml)]"
+        )?;
+        if !meta.is_empty() {
+            writeln!(self.out, "#[xml({})]", meta.join(", "))?;
+        }
+
+        if fields.list.is_empty() {
+            writeln!(self.out, "pub struct {ident};")?;
+            return Ok(());
+        }
+
+        writeln!(self.out, "pub struct {ident} {{")?;
+        for field in &fields.list {
+            if !field.meta.is_empty() {
+                writeln!(self.out, "    #[xml({})]", field.meta.join(", "))?;
+            }
+
+            writeln!(self.out, "    pub {}: {},", field.ident, field.ty)?;
+        }
+
+        writeln!(self.out, "}}")?;
+        Ok(())
+    }
+
+    /// Reserve a unique item name, starting from `candidate`
+    fn name(&mut self, candidate: String) -> String {
+        let mut name = candidate.clone();
+        let mut i = 2;
+        while self.names.contains(&name) {
+            name = format!("{candidate}{i}");
+            i += 1;
+        }
+
+        self.names.insert(name.clone());
+        name
+    }
+}
+
+/// A SOAP binding of an operation
+struct Binding<'a> {
+    /// The name of the `wsdl:binding`
+    name: &'a str,
+    version: Version,
+    /// The default style of the binding's operations
+    style: &'a str,
+    /// The `wsdl:operation` in the binding
+    operation: &'a Element,
+}
+
+/// The fields of a struct being generated
+struct Fields {
+    /// The namespace of the struct, which child elements inherit
+    ns: String,
+    list: Vec<Field>,
+}
+
+impl Fields {
+    fn new(ns: &str) -> Self {
+        Self {
+            ns: ns.to_owned(),
+            list: Vec::new(),
+        }
+    }
+
+    /// Add a field for the element or attribute `name`, or for the text content if `None`
+    ///
+    /// If `rename` is set, the field is renamed to `name` if its identifier is different.
+    fn push(
+        &mut self,
+        name: Option<&str>,
+        rename: bool,
+        kind: &[&str],
+        ns: Option<String>,
+        ty: String,
+    ) {
+        let mut meta = kind.iter().map(|&s| s.to_owned()).collect::<Vec<_>>();
+        let base = match name {
+            Some(name) => snake(name),
+            None => "value".to_owned(),
+        };
+
+        let mut ident = base.clone();
+        let mut i = 2;
+        while self.list.iter().any(|field| field.ident == ident) {
+            ident = format!("{}_{i}", base.trim_end_matches('_'));
+            i += 1;
+        }
+
+        match name {
+            Some(name) if rename && name != ident => meta.push(format!("rename = {name:?}")),
+            _ => {}
+        }
+
+        meta.extend(ns);
+        self.list.push(Field { ident, meta, ty });
+    }
+
+    /// Add the field for elements matching a wildcard, unless there is one already
+    fn any(&mut self) {
+        if !self
+            .list
+            .iter()
+            .any(|field| field.meta.iter().any(|m| m == "any"))
+        {
+            self.push(
+                Some("any"),
+                false,
+                &["any"],
+                None,
+                "Vec<::instant_xml::Element>".to_owned(),
+            );
+        }
+    }
+}
+
+struct Field {
+    ident: String,
+    /// The items for the field's `#[xml(...)]` attribute
+    meta: Vec<String>,
+    ty: String,
+}
+
+/// The Rust type for values of a built-in XML Schema type
+fn builtin(name: &str) -> &'static str {
+    match name {
+        "boolean" => "bool",
+        "float" => "f32",
+        "double" => "f64",
+        "byte" => "i8",
+        "short" => "i16",
+        "int" => "i32",
+        "long" | "integer" | "negativeInteger" | "nonPositiveInteger" => "i64",
+        "unsignedByte" => "u8",
+        "unsignedShort" => "u16",
+        "unsignedInt" => "u32",
+        "unsignedLong" | "nonNegativeInteger" | "positiveInteger" => "u64",
+        _ => "String",
+    }
+}
+
+/// Convert an XML name like `getPrice` or `get-price` to a type name like `GetPrice`
+fn pascal(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for part in name.split(|c: char| !c.is_alphanumeric()) {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            out.extend(first.to_uppercase());
+            out.extend(chars);
+        }
+    }
+
+    match out.chars().next() {
+        None => "Element".to_owned(),
+        Some(first) if first.is_numeric() => format!("_{out}"),
+        Some(_) if out == "Self" => "Self_".to_owned(),
+        Some(_) => out,
+    }
+}
+
+/// Convert an XML name like `firstName` or `URLValue` to a field name like `url_value`
+fn snake(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !out.is_empty() && !out.ends_with('_') {
+                out.push('_');
+            }
+
+            continue;
+        }
+
+        if c.is_uppercase() && i > 0 && !out.is_empty() && !out.ends_with('_') {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).map_or(false, |next| next.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_lower) {
+                out.push('_');
+            }
+        }
+
+        out.extend(c.to_lowercase());
+    }
+
+    let mut out = out.trim_end_matches('_').to_owned();
+    if out.is_empty() || out.starts_with(char::is_numeric) {
+        out.insert(0, '_');
+    }
+
+    if KEYWORDS.contains(&out.as_str()) {
+        out.push('_');
+    }
+
+    out
+}
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Names used by the generated code, which generated items must not shadow
+const PRELUDE: &[&str] = &[
+    "Box", "Err", "FromXml", "None", "Ok", "Option", "Result", "Some", "String", "ToXml", "Vec",
+];
+
+/// The SOAP 1.1 or 1.2 extension element `name` among the children of `element`
+fn soap_extension<'a>(element: &'a Element, name: &str) -> Option<(Version, &'a Element)> {
+    element.elements().find_map(|child| {
+        let version = match child.ns.as_str() {
+            SOAP11_BINDING => Version::Soap11,
+            SOAP12_BINDING => Version::Soap12,
+            _ => return None,
+        };
+
+        match child.name == name {
+            true => Some((version, child)),
+            false => None,
+        }
+    })
+}
+
+/// The named definitions of `kind` in `definitions`, by their name in Clark notation
+fn definitions<'a>(
+    definitions: &'a Element,
+    target: &str,
+    kind: &'a str,
+) -> Result<BTreeMap<String, &'a Element>, Error> {
+    let mut map = BTreeMap::new();
+    for element in children(definitions, kind) {
+        let key = Id::new(target, required(element, "name")?).to_string();
+        map.insert(key, element);
+    }
+
+    Ok(map)
+}
+
+/// The child elements of `element` named `name` in the WSDL namespace
+fn children<'a>(element: &'a Element, name: &'a str) -> impl Iterator<Item = &'a Element> {
+    element
+        .elements()
+        .filter(move |child| child.id().is(WSDL, name))
+}
+
+fn child<'a>(element: &'a Element, name: &str) -> Option<&'a Element> {
+    element.element(Id::new(WSDL, name))
+}
+
+fn attribute<'a>(element: &'a Element, name: &str) -> Option<&'a str> {
+    element.attribute(Id::local(name))
+}
+
+fn required<'a>(element: &'a Element, name: &str) -> Result<&'a str, Error> {
+    attribute(element, name).ok_or_else(|| {
+        Error::UnexpectedValue(format!("missing {name} attribute on wsdl:{}", element.name))
+    })
+}
+
+fn unsupported(what: &str) -> Error {
+    Error::UnexpectedValue(format!("unsupported in WSDL: {what}"))
+}
+
+fn undefined(kind: &str, key: &str) -> Error {
+    Error::UnexpectedValue(format!("undefined {kind} {key} in WSDL"))
+}
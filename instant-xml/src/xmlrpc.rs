@@ -0,0 +1,602 @@
+//! XML-RPC method calls and responses
+//!
+//! A [`MethodCall`] or [`MethodResponse`] is serialized with [`to_string()`](crate::to_string)
+//! and deserialized with [`from_str()`](crate::from_str), like any other type. Parameters are
+//! [`Value`]s, which cover the XML-RPC types and the common `nil` extension.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::adapters::base64;
+use crate::de::Node;
+use crate::ser::Context;
+use crate::{Deserializer, Error, FromXml, Id, Kind, Serializer, ToXml};
+
+/// A call of the method `method_name` with `params`
+#[derive(Clone, Debug, PartialEq)]
+pub struct MethodCall {
+    pub method_name: String,
+    pub params: Vec<Value>,
+}
+
+impl MethodCall {
+    pub fn new(method_name: impl Into<String>, params: Vec<Value>) -> Self {
+        Self {
+            method_name: method_name.into(),
+            params,
+        }
+    }
+}
+
+impl ToXml for MethodCall {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        _: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        element(serializer, "methodCall", |serializer| {
+            element(serializer, "methodName", |serializer| {
+                serializer.write_text(&self.method_name)
+            })?;
+            write_params(&self.params, serializer)
+        })
+    }
+}
+
+impl<'xml> FromXml<'xml> for MethodCall {
+    #[inline]
+    fn matches(id: Id<'_>, _: Option<Id<'_>>) -> bool {
+        id == Id::local("methodCall")
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        let (mut method_name, mut params) = (None, Vec::new());
+        while let Some(node) = deserializer.next() {
+            let element = match node? {
+                Node::Open(element) => element,
+                _ => continue,
+            };
+
+            let id = deserializer.element_id(&element)?;
+            let mut nested = deserializer.nested(element);
+            match (id.ns, id.name) {
+                ("", "methodName") => method_name = Some(text(&mut nested)?),
+                ("", "params") => params = read_params(&mut nested)?,
+                _ => nested.ignore()?,
+            }
+        }
+
+        *into = Some(Self {
+            method_name: method_name.ok_or(Error::MissingValue("methodName"))?,
+            params,
+        });
+
+        Ok(())
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Element;
+}
+
+/// The response to a [`MethodCall`]: the value returned, or a fault
+#[derive(Clone, Debug, PartialEq)]
+pub enum MethodResponse {
+    Success(Value),
+    Fault(Fault),
+}
+
+impl MethodResponse {
+    pub fn into_result(self) -> Result<Value, Fault> {
+        match self {
+            Self::Success(value) => Ok(value),
+            Self::Fault(fault) => Err(fault),
+        }
+    }
+}
+
+impl ToXml for MethodResponse {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        _: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        element(serializer, "methodResponse", |serializer| match self {
+            Self::Success(value) => write_params(std::slice::from_ref(value), serializer),
+            Self::Fault(fault) => element(serializer, "fault", |serializer| {
+                let mut members = BTreeMap::new();
+                members.insert("faultCode".to_owned(), Value::Int(fault.code));
+                members.insert(
+                    "faultString".to_owned(),
+                    Value::String(fault.string.clone()),
+                );
+                Value::Struct(members).serialize(None, serializer)
+            }),
+        })
+    }
+}
+
+impl<'xml> FromXml<'xml> for MethodResponse {
+    #[inline]
+    fn matches(id: Id<'_>, _: Option<Id<'_>>) -> bool {
+        id == Id::local("methodResponse")
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        while let Some(node) = deserializer.next() {
+            let element = match node? {
+                Node::Open(element) => element,
+                _ => continue,
+            };
+
+            let id = deserializer.element_id(&element)?;
+            let mut nested = deserializer.nested(element);
+            let response = match (id.ns, id.name) {
+                ("", "params") => match read_params(&mut nested)?.into_iter().next() {
+                    Some(value) => Self::Success(value),
+                    None => return Err(Error::MissingValue("param")),
+                },
+                ("", "fault") => Self::Fault(Fault::read(&mut nested)?),
+                _ => {
+                    nested.ignore()?;
+                    continue;
+                }
+            };
+
+            if into.is_some() {
+                return Err(Error::DuplicateValue("methodResponse"));
+            }
+
+            *into = Some(response);
+        }
+
+        match into {
+            Some(_) => Ok(()),
+            None => Err(Error::MissingValue("params")),
+        }
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Element;
+}
+
+/// A fault returned instead of a value
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Fault {
+    /// The `faultCode`
+    pub code: i32,
+    /// The `faultString`
+    pub string: String,
+}
+
+impl Fault {
+    fn read(deserializer: &mut Deserializer<'_, '_>) -> Result<Self, Error> {
+        let mut members = match read_value(deserializer)? {
+            Value::Struct(members) => members,
+            _ => return Err(Error::UnexpectedValue("fault that is not a struct".into())),
+        };
+
+        Ok(Self {
+            code: match members.remove("faultCode") {
+                Some(Value::Int(code)) => code,
+                _ => return Err(Error::MissingValue("faultCode")),
+            },
+            string: match members.remove("faultString") {
+                Some(Value::String(string)) => string,
+                _ => return Err(Error::MissingValue("faultString")),
+            },
+        })
+    }
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (fault code {})", self.string, self.code)
+    }
+}
+
+/// An XML-RPC value, serialized as a `value` element
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// `i4` or `int`
+    Int(i32),
+    Boolean(bool),
+    /// `string`, or text directly in the `value` element
+    String(String),
+    Double(f64),
+    /// `dateTime.iso8601`, like `19980717T14:08:55`, which has no time zone
+    DateTime(String),
+    Base64(Vec<u8>),
+    Struct(BTreeMap<String, Value>),
+    Array(Vec<Value>),
+    Nil,
+}
+
+impl Value {
+    pub fn as_i32(&self) -> Option<i32> {
+        match self {
+            Self::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Boolean(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Double(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_struct(&self) -> Option<&BTreeMap<String, Value>> {
+        match self {
+            Self::Struct(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Self::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Read the contents of a `value` element
+    fn read(deserializer: &mut Deserializer<'_, '_>) -> Result<Self, Error> {
+        let (mut value, mut string) = (None, None);
+        while let Some(node) = deserializer.next() {
+            let element = match node? {
+                Node::Open(element) => element,
+                Node::Text(s) => {
+                    string = Some(s.into_owned());
+                    continue;
+                }
+                _ => continue,
+            };
+
+            if value.is_some() {
+                return Err(Error::DuplicateValue("value"));
+            }
+
+            let id = deserializer.element_id(&element)?;
+            let mut nested = deserializer.nested(element);
+            value = Some(match (id.ns, id.name) {
+                ("", "i4" | "int") => Self::Int(number(&mut nested, id.name)?),
+                ("", "boolean") => match text(&mut nested)?.trim() {
+                    "1" => Self::Boolean(true),
+                    "0" => Self::Boolean(false),
+                    value => return Err(invalid(value, "boolean")),
+                },
+                ("", "string") => Self::String(text(&mut nested)?),
+                ("", "double") => Self::Double(number(&mut nested, "double")?),
+                ("", "dateTime.iso8601") => Self::DateTime(text(&mut nested)?.trim().to_owned()),
+                ("", "base64") => Self::Base64(base64::decode(&text(&mut nested)?, "base64")?),
+                ("", "struct") => Self::Struct(read_struct(&mut nested)?),
+                ("", "array") => Self::Array(read_array(&mut nested)?),
+                ("", "nil") => {
+                    nested.ignore()?;
+                    Self::Nil
+                }
+                _ => return Err(Error::UnexpectedValue(format!("unknown XML-RPC type {id}"))),
+            });
+        }
+
+        // Text around a typed value is whitespace
+        Ok(match value {
+            Some(value) => value,
+            None => Self::String(string.unwrap_or_default()),
+        })
+    }
+}
+
+impl ToXml for Value {
+    fn serialize<W: fmt::Write + ?Sized>(
+        &self,
+        _: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        element(serializer, "value", |serializer| match self {
+            Self::Int(value) => element(serializer, "int", |s| s.write_str(value)),
+            Self::Boolean(value) => element(serializer, "boolean", |s| {
+                s.write_str(match value {
+                    true => "1",
+                    false => "0",
+                })
+            }),
+            Self::String(value) => element(serializer, "string", |s| s.write_text(value)),
+            Self::Double(value) if !value.is_finite() => {
+                Err(Error::UnexpectedValue(format!("XML-RPC double {value}")))
+            }
+            Self::Double(value) => element(serializer, "double", |s| s.write_str(value)),
+            Self::DateTime(value) => {
+                element(serializer, "dateTime.iso8601", |s| s.write_text(value))
+            }
+            Self::Base64(value) => {
+                element(serializer, "base64", |s| base64::serialize(value, None, s))
+            }
+            Self::Struct(members) => element(serializer, "struct", |serializer| {
+                for (name, value) in members {
+                    element(serializer, "member", |serializer| {
+                        element(serializer, "name", |s| s.write_text(name))?;
+                        value.serialize(None, serializer)
+                    })?;
+                }
+
+                Ok(())
+            }),
+            Self::Array(values) => element(serializer, "array", |serializer| {
+                element(serializer, "data", |serializer| {
+                    for value in values {
+                        value.serialize(None, serializer)?;
+                    }
+
+                    Ok(())
+                })
+            }),
+            Self::Nil => {
+                serializer.write_start("nil", "")?;
+                serializer.end_empty()
+            }
+        })
+    }
+}
+
+impl<'xml> FromXml<'xml> for Value {
+    #[inline]
+    fn matches(id: Id<'_>, _: Option<Id<'_>>) -> bool {
+        id == Id::local("value")
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        field: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        if into.is_some() {
+            return Err(Error::DuplicateValue(field));
+        }
+
+        *into = Some(Self::read(deserializer)?);
+        Ok(())
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Element;
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Boolean(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Self::Double(value)
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(values: Vec<Value>) -> Self {
+        Self::Array(values)
+    }
+}
+
+impl From<BTreeMap<String, Value>> for Value {
+    fn from(members: BTreeMap<String, Value>) -> Self {
+        Self::Struct(members)
+    }
+}
+
+fn write_params<W: fmt::Write + ?Sized>(
+    params: &[Value],
+    serializer: &mut Serializer<W>,
+) -> Result<(), Error> {
+    element(serializer, "params", |serializer| {
+        for value in params {
+            element(serializer, "param", |serializer| {
+                value.serialize(None, serializer)
+            })?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Read the values in the `param` elements of a `params` element
+fn read_params(deserializer: &mut Deserializer<'_, '_>) -> Result<Vec<Value>, Error> {
+    let mut params = Vec::new();
+    while let Some(node) = deserializer.next() {
+        let element = match node? {
+            Node::Open(element) => element,
+            _ => continue,
+        };
+
+        let id = deserializer.element_id(&element)?;
+        let mut nested = deserializer.nested(element);
+        match (id.ns, id.name) {
+            ("", "param") => params.push(read_value(&mut nested)?),
+            _ => nested.ignore()?,
+        }
+    }
+
+    Ok(params)
+}
+
+fn read_struct(deserializer: &mut Deserializer<'_, '_>) -> Result<BTreeMap<String, Value>, Error> {
+    let mut members = BTreeMap::new();
+    while let Some(node) = deserializer.next() {
+        let element = match node? {
+            Node::Open(element) => element,
+            _ => continue,
+        };
+
+        let id = deserializer.element_id(&element)?;
+        let mut nested = deserializer.nested(element);
+        if id != Id::local("member") {
+            nested.ignore()?;
+            continue;
+        }
+
+        let (mut name, mut value) = (None, None);
+        while let Some(node) = nested.next() {
+            let element = match node? {
+                Node::Open(element) => element,
+                _ => continue,
+            };
+
+            let id = nested.element_id(&element)?;
+            let mut member = nested.nested(element);
+            match (id.ns, id.name) {
+                ("", "name") => name = Some(text(&mut member)?),
+                ("", "value") => Value::deserialize(&mut value, "value", &mut member)?,
+                _ => member.ignore()?,
+            }
+        }
+
+        members.insert(
+            name.ok_or(Error::MissingValue("name"))?,
+            value.ok_or(Error::MissingValue("value"))?,
+        );
+    }
+
+    Ok(members)
+}
+
+fn read_array(deserializer: &mut Deserializer<'_, '_>) -> Result<Vec<Value>, Error> {
+    let mut values = Vec::new();
+    while let Some(node) = deserializer.next() {
+        let element = match node? {
+            Node::Open(element) => element,
+            _ => continue,
+        };
+
+        let id = deserializer.element_id(&element)?;
+        let mut nested = deserializer.nested(element);
+        match (id.ns, id.name) {
+            ("", "data") => {
+                while let Some(node) = nested.next() {
+                    let element = match node? {
+                        Node::Open(element) => element,
+                        _ => continue,
+                    };
+
+                    let id = nested.element_id(&element)?;
+                    let mut value = nested.nested(element);
+                    match id == Id::local("value") {
+                        true => values.push(Value::read(&mut value)?),
+                        false => value.ignore()?,
+                    }
+                }
+            }
+            _ => nested.ignore()?,
+        }
+    }
+
+    Ok(values)
+}
+
+/// Read the single `value` element in `deserializer`
+fn read_value(deserializer: &mut Deserializer<'_, '_>) -> Result<Value, Error> {
+    let mut value = None;
+    while let Some(node) = deserializer.next() {
+        let element = match node? {
+            Node::Open(element) => element,
+            _ => continue,
+        };
+
+        let id = deserializer.element_id(&element)?;
+        let mut nested = deserializer.nested(element);
+        match id == Id::local("value") {
+            true => Value::deserialize(&mut value, "value", &mut nested)?,
+            false => nested.ignore()?,
+        }
+    }
+
+    value.ok_or(Error::MissingValue("value"))
+}
+
+/// Write the element `name`, in no namespace, with the content written by `content`
+fn element<W: fmt::Write + ?Sized>(
+    serializer: &mut Serializer<W>,
+    name: &str,
+    content: impl FnOnce(&mut Serializer<W>) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let prefix = serializer.write_start(name, "")?;
+    let old = serializer.push(Context::<0> {
+        default_ns: "",
+        ..Default::default()
+    })?;
+
+    serializer.end_start()?;
+    content(serializer)?;
+    serializer.write_close(prefix.as_deref(), name)?;
+    serializer.pop(old);
+    Ok(())
+}
+
+/// The text content of the current element, consuming the element
+fn text(deserializer: &mut Deserializer<'_, '_>) -> Result<String, Error> {
+    let value = deserializer.take_str()?.unwrap_or_default().into_owned();
+    deserializer.ignore()?;
+    Ok(value)
+}
+
+fn number<T: std::str::FromStr>(
+    deserializer: &mut Deserializer<'_, '_>,
+    ty: &str,
+) -> Result<T, Error> {
+    let value = text(deserializer)?;
+    value.trim().parse().map_err(|_| invalid(&value, ty))
+}
+
+fn invalid(value: &str, ty: &str) -> Error {
+    Error::UnexpectedValue(format!("invalid XML-RPC {ty} `{value}`"))
+}
@@ -0,0 +1,246 @@
+//! Validating newtypes for common XML Schema simple types
+
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use crate::{Deserializer, Error, FromXml, Kind, Serializer, ToXml};
+
+/// The XML Schema instance namespace, used for attributes like `xsi:type`
+pub const XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+macro_rules! xsd_string {
+    ($(#[$attr:meta])* $ty:ident, $name:literal, $valid:expr) => {
+        $(#[$attr])*
+        #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        pub struct $ty(String);
+
+        impl $ty {
+            /// Validate `value` after collapsing whitespace
+            pub fn new(value: &str) -> Result<Self, Error> {
+                let value = collapse(value);
+                let valid: fn(&str) -> bool = $valid;
+                match valid(&value) {
+                    true => Ok(Self(value.into_owned())),
+                    false => Err(Error::UnexpectedValue(format!(
+                        "invalid {} `{value}`",
+                        $name
+                    ))),
+                }
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            pub fn into_inner(self) -> String {
+                self.0
+            }
+        }
+
+        impl Deref for $ty {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $ty {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl FromStr for $ty {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::new(s)
+            }
+        }
+
+        impl TryFrom<String> for $ty {
+            type Error = Error;
+
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                Self::new(&value)
+            }
+        }
+
+        impl ToXml for $ty {
+            fn serialize<W: fmt::Write + ?Sized>(
+                &self,
+                field: Option<crate::Id<'_>>,
+                serializer: &mut Serializer<W>,
+            ) -> Result<(), Error> {
+                self.0.serialize(field, serializer)
+            }
+        }
+
+        impl<'xml> FromXml<'xml> for $ty {
+            #[inline]
+            fn matches(id: crate::Id<'_>, field: Option<crate::Id<'_>>) -> bool {
+                match field {
+                    Some(field) => id == field,
+                    None => false,
+                }
+            }
+
+            fn deserialize<'cx>(
+                into: &mut Self::Accumulator,
+                field: &'static str,
+                deserializer: &mut Deserializer<'cx, 'xml>,
+            ) -> Result<(), Error> {
+                if into.is_some() {
+                    return Err(Error::DuplicateValue(field));
+                }
+
+                let value = match deserializer.take_str()? {
+                    Some(value) => value,
+                    None => Cow::Borrowed(""),
+                };
+
+                *into = Some(Self::new(&value)?);
+                Ok(())
+            }
+
+            type Accumulator = Option<Self>;
+            const KIND: Kind = Kind::Scalar;
+        }
+    };
+}
+
+xsd_string!(
+    /// `xs:anyURI`: a URI reference
+    ///
+    /// XML Schema barely constrains its lexical space, so only percent escapes and control
+    /// characters are checked; this does not parse the URI.
+    AnyUri,
+    "anyURI",
+    |s| {
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '%' => {
+                    let (a, b) = (chars.next(), chars.next());
+                    if !matches!((a, b), (Some(a), Some(b)) if a.is_ascii_hexdigit() && b.is_ascii_hexdigit())
+                    {
+                        return false;
+                    }
+                }
+                c if c.is_control() => return false,
+                _ => {}
+            }
+        }
+
+        true
+    }
+);
+
+xsd_string!(
+    /// `xs:token`: any string, with its whitespace collapsed
+    ///
+    /// Collapsing removes line breaks, tabs, and leading, trailing or repeated spaces, so no
+    /// input is rejected.
+    Token,
+    "token",
+    |_| true
+);
+
+xsd_string!(
+    /// `xs:language`: a language tag like `en` or `pt-BR`
+    Language,
+    "language",
+    |s| {
+        s.split('-').enumerate().all(|(i, part)| {
+            (1..=8).contains(&part.len())
+                && match i {
+                    0 => part.bytes().all(|b| b.is_ascii_alphabetic()),
+                    _ => part.bytes().all(|b| b.is_ascii_alphanumeric()),
+                }
+        })
+    }
+);
+
+xsd_string!(
+    /// `xs:NCName`: an XML name without colons
+    NcName,
+    "NCName",
+    is_ncname
+);
+
+xsd_string!(
+    /// `xs:ID`: a document-unique identifier, lexically an `NCName`
+    Id,
+    "ID",
+    is_ncname
+);
+
+xsd_string!(
+    /// `xs:IDREF`: a reference to an `xs:ID`, lexically an `NCName`
+    IdRef,
+    "IDREF",
+    is_ncname
+);
+
+/// Apply the `collapse` whitespace facet
+fn collapse(input: &str) -> Cow<'_, str> {
+    let trimmed = input.trim_matches(is_xml_space);
+    let mut last_space = false;
+    let needs_work = trimmed.chars().any(|c| {
+        let bad = (c != ' ' && is_xml_space(c)) || (c == ' ' && last_space);
+        last_space = c == ' ';
+        bad
+    });
+
+    if !needs_work {
+        return Cow::Borrowed(trimmed);
+    }
+
+    let mut result = String::with_capacity(trimmed.len());
+    for word in trimmed.split(is_xml_space).filter(|s| !s.is_empty()) {
+        if !result.is_empty() {
+            result.push(' ');
+        }
+        result.push_str(word);
+    }
+
+    Cow::Owned(result)
+}
+
+fn is_xml_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r')
+}
+
+/// Check the `NCName` production from <https://www.w3.org/TR/xml-names/#NT-NCName>
+fn is_ncname(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) if is_name_start_char(c) => chars.all(is_name_char),
+        _ => false,
+    }
+}
+
+/// `NameStartChar` from <https://www.w3.org/TR/xml/#NT-NameStartChar>, minus `:`
+fn is_name_start_char(c: char) -> bool {
+    matches!(c,
+        'A'..='Z' | '_' | 'a'..='z' | '\u{C0}'..='\u{D6}' | '\u{D8}'..='\u{F6}'
+        | '\u{F8}'..='\u{2FF}' | '\u{370}'..='\u{37D}' | '\u{37F}'..='\u{1FFF}'
+        | '\u{200C}'..='\u{200D}' | '\u{2070}'..='\u{218F}' | '\u{2C00}'..='\u{2FEF}'
+        | '\u{3001}'..='\u{D7FF}' | '\u{F900}'..='\u{FDCF}' | '\u{FDF0}'..='\u{FFFD}'
+        | '\u{10000}'..='\u{EFFFF}')
+}
+
+/// `NameChar` from <https://www.w3.org/TR/xml/#NT-NameChar>, minus `:`
+fn is_name_char(c: char) -> bool {
+    is_name_start_char(c)
+        || matches!(c, '-' | '.' | '0'..='9' | '\u{B7}' | '\u{300}'..='\u{36F}' | '\u{203F}'..='\u{2040}')
+}
@@ -0,0 +1,211 @@
+use std::net::Ipv4Addr;
+
+use similar_asserts::assert_eq;
+
+use instant_xml::adapters::{
+    Base64, BoolFromInt, CommaSeparated, DisplayFromStr, Hex, SpaceSeparated,
+};
+use instant_xml::{from_str, to_string, Error, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Host {
+    #[xml(attribute)]
+    enabled: BoolFromInt,
+    addr: DisplayFromStr<Ipv4Addr>,
+    ports: CommaSeparated<u16>,
+    key: Base64,
+}
+
+#[test]
+fn adapters() {
+    let v = Host {
+        enabled: BoolFromInt(true),
+        addr: DisplayFromStr(Ipv4Addr::new(10, 0, 0, 1)),
+        ports: CommaSeparated(vec![80, 443]),
+        key: Base64(b"hello".to_vec()),
+    };
+
+    let xml =
+        r#"<Host enabled="1"><addr>10.0.0.1</addr><ports>80,443</ports><key>aGVsbG8=</key></Host>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let xml = r#"<Host enabled="0"><addr>10.0.0.1</addr><ports> 80 , 443 </ports><key>aGVs
+bG8=</key></Host>"#;
+    let v = from_str::<Host>(xml).unwrap();
+    assert_eq!(v.enabled, BoolFromInt(false));
+    assert_eq!(v.ports, CommaSeparated(vec![80, 443]));
+    assert_eq!(v.key, Base64(b"hello".to_vec()));
+}
+
+#[test]
+fn base64() {
+    for (bytes, encoded) in [
+        (&b""[..], ""),
+        (b"f", "Zg=="),
+        (b"fo", "Zm8="),
+        (b"foo", "Zm9v"),
+        (b"foob", "Zm9vYg=="),
+        (b"\xff\xfe", "//4="),
+    ] {
+        let v = Host {
+            enabled: BoolFromInt(false),
+            addr: DisplayFromStr(Ipv4Addr::LOCALHOST),
+            ports: CommaSeparated(Vec::new()),
+            key: Base64(bytes.to_vec()),
+        };
+
+        let xml = format!(
+            "<Host enabled=\"0\"><addr>127.0.0.1</addr><ports></ports><key>{encoded}</key></Host>"
+        );
+        assert_eq!(xml, to_string(&v).unwrap());
+        assert_eq!(v, from_str(&xml).unwrap());
+    }
+}
+
+#[test]
+fn invalid() {
+    assert_eq!(
+        from_str::<Host>(
+            r#"<Host enabled="true"><addr>10.0.0.1</addr><ports></ports><key></key></Host>"#
+        )
+        .unwrap_err(),
+        Error::UnexpectedValue("unable to parse bool from `true` for Host::enabled".to_owned())
+    );
+    assert_eq!(
+        from_str::<Host>(
+            r#"<Host enabled="1"><addr>10.0.0.1</addr><ports>1,x</ports><key></key></Host>"#
+        )
+        .unwrap_err(),
+        Error::UnexpectedValue("unable to parse u16 from `x` for Host::ports".to_owned())
+    );
+    assert_eq!(
+        from_str::<Host>(
+            r#"<Host enabled="1"><addr>10.0.0.1</addr><ports></ports><key>Zg=</key></Host>"#
+        )
+        .unwrap_err(),
+        Error::UnexpectedValue("invalid base64 `Zg=` for Host::key".to_owned())
+    );
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Attachment {
+    #[xml(attribute, base64)]
+    digest: Vec<u8>,
+    #[xml(base64)]
+    data: Vec<u8>,
+    #[xml(base64, default)]
+    thumbnail: Vec<u8>,
+}
+
+#[test]
+fn base64_field() {
+    let v = Attachment {
+        digest: b"\x00\xff".to_vec(),
+        data: b"hello".to_vec(),
+        thumbnail: Vec::new(),
+    };
+
+    let xml =
+        r#"<Attachment digest="AP8="><data>aGVsbG8=</data><thumbnail></thumbnail></Attachment>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let xml = r#"<Attachment digest="AP8="><data>aGVs
+        bG8=</data></Attachment>"#;
+    assert_eq!(v, from_str(xml).unwrap());
+
+    assert_eq!(
+        from_str::<Attachment>(r#"<Attachment digest="A"><data></data></Attachment>"#).unwrap_err(),
+        Error::UnexpectedValue("invalid base64 `A` for Attachment::digest".to_owned())
+    );
+}
+
+#[derive(Debug, ToXml)]
+struct Borrowed<'a> {
+    #[xml(base64)]
+    data: &'a [u8],
+}
+
+#[test]
+fn base64_borrowed() {
+    let v = Borrowed { data: b"foob" };
+    assert_eq!(
+        "<Borrowed><data>Zm9vYg==</data></Borrowed>",
+        to_string(&v).unwrap()
+    );
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Interface {
+    #[xml(attribute, hex)]
+    mac: Vec<u8>,
+    digest: Hex,
+}
+
+#[test]
+fn hex() {
+    let v = Interface {
+        mac: vec![0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0xfe],
+        digest: Hex(vec![0xde, 0xad, 0xbe, 0xef]),
+    };
+
+    let xml = r#"<Interface mac="001A2B3C4DFE"><digest>DEADBEEF</digest></Interface>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let xml = r#"<Interface mac="001a2B3c4dFe"><digest> deadbeef </digest></Interface>"#;
+    assert_eq!(v, from_str(xml).unwrap());
+
+    assert_eq!(
+        from_str::<Interface>(r#"<Interface mac="0g"><digest></digest></Interface>"#).unwrap_err(),
+        Error::UnexpectedValue("invalid hex `0g` for Interface::mac".to_owned())
+    );
+    assert_eq!(
+        from_str::<Interface>(r#"<Interface mac=""><digest>abc</digest></Interface>"#).unwrap_err(),
+        Error::UnexpectedValue("invalid hex `abc` for Interface::digest".to_owned())
+    );
+}
+
+#[derive(Debug, FromXml, PartialEq, ToXml)]
+struct Polyline {
+    #[xml(attribute, list)]
+    points: Vec<f32>,
+    #[xml(list, default)]
+    ids: Vec<u32>,
+    tags: SpaceSeparated<String>,
+}
+
+#[test]
+fn list() {
+    let v = Polyline {
+        points: vec![0.5, 1.0, 2.25],
+        ids: vec![1, 2, 3],
+        tags: SpaceSeparated(vec!["a".to_owned(), "b".to_owned()]),
+    };
+
+    let xml = r#"<Polyline points="0.5 1 2.25"><ids>1 2 3</ids><tags>a b</tags></Polyline>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let xml = "<Polyline points=\" 0.5\t1  2.25 \"><ids>\n  1\n  2\n  3\n</ids><tags>a b</tags></Polyline>";
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let v = Polyline {
+        points: Vec::new(),
+        ids: Vec::new(),
+        tags: SpaceSeparated(Vec::new()),
+    };
+    let xml = r#"<Polyline points=""><ids></ids><tags></tags></Polyline>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+    assert_eq!(
+        v,
+        from_str(r#"<Polyline points=""><tags></tags></Polyline>"#).unwrap()
+    );
+
+    assert_eq!(
+        from_str::<Polyline>(r#"<Polyline points="1 x"><tags></tags></Polyline>"#).unwrap_err(),
+        Error::UnexpectedValue("unable to parse f32 from `x` for Polyline::points".to_owned())
+    );
+}
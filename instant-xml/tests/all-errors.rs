@@ -0,0 +1,96 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str_all_errors, Error, FromXml, Position};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Order {
+    #[xml(attribute)]
+    id: u32,
+    customer: String,
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+#[xml(rename = "items", deny_unknown)]
+struct Item {
+    quantity: u32,
+    price: u32,
+}
+
+fn located(error: Error, line: u32, column: u32, path: &str) -> Error {
+    Error::Located {
+        error: Box::new(error),
+        position: Position { line, column },
+        path: path.to_owned(),
+    }
+}
+
+#[test]
+fn all_errors() {
+    let xml = r#"<Order id="1"><customer>Jane</customer><items><quantity>2</quantity><price>3</price></items></Order>"#;
+    assert_eq!(
+        from_str_all_errors::<Order>(xml),
+        Ok(Order {
+            id: 1,
+            customer: "Jane".to_owned(),
+            items: vec![Item {
+                quantity: 2,
+                price: 3
+            }],
+        })
+    );
+
+    let xml = r#"<Order id="x">
+<items><quantity>two</quantity><price>3</price></items>
+<items><quantity>1</quantity><color><red/></color><price>4</price></items>
+<items><quantity>1</quantity></items>
+<customer>Jane</customer>
+</Order>"#;
+    assert_eq!(
+        from_str_all_errors::<Order>(xml).unwrap_err(),
+        vec![
+            located(
+                Error::UnexpectedValue("unable to parse u32 from `x` for Order::id".to_owned()),
+                1,
+                14,
+                "Order",
+            ),
+            located(
+                Error::UnexpectedValue(
+                    "unable to parse u32 from `two` for Item::quantity".to_owned()
+                ),
+                2,
+                18,
+                "Order > items > quantity",
+            ),
+            located(
+                Error::UnexpectedTag("unknown element `color` in Item".to_owned()),
+                3,
+                36,
+                "Order > items[1] > color",
+            ),
+            located(
+                Error::MissingValue("Item::price"),
+                4,
+                30,
+                "Order > items[2]"
+            ),
+        ]
+    );
+
+    // Errors that cannot be recovered from end the document
+    let errors = from_str_all_errors::<Order>(
+        r#"<Order id="x"><customer>Jane</customer><items><quantity>1</price></items></Order>"#,
+    )
+    .unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[1],
+        located(
+            Error::UnexpectedState("close element mismatch"),
+            1,
+            58,
+            "Order > items > quantity"
+        )
+    );
+}
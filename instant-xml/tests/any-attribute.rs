@@ -0,0 +1,80 @@
+use std::collections::{BTreeMap, HashMap};
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("urn:device", dlna = "urn:dlna"))]
+struct Device {
+    #[xml(attribute)]
+    id: u32,
+    #[xml(any_attribute)]
+    extra: BTreeMap<String, String>,
+    name: String,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Service {
+    #[xml(any_attribute)]
+    extra: HashMap<String, String>,
+}
+
+#[derive(Debug, Eq, PartialEq, ToXml)]
+struct Sorted {
+    #[xml(any_attribute, sorted)]
+    extra: HashMap<String, String>,
+}
+
+#[test]
+fn any_attribute() {
+    let xml = r#"<Device xmlns="urn:device" xmlns:dlna="urn:dlna" xmlns:v="urn:vendor" id="1" dlna:caps="av" v:model="X &amp; Y" plain="yes"><name>TV</name></Device>"#;
+    let v = from_str::<Device>(xml).unwrap();
+    assert_eq!(
+        v,
+        Device {
+            id: 1,
+            extra: BTreeMap::from([
+                ("plain".to_owned(), "yes".to_owned()),
+                ("{urn:dlna}caps".to_owned(), "av".to_owned()),
+                ("{urn:vendor}model".to_owned(), "X & Y".to_owned()),
+            ]),
+            name: "TV".to_owned(),
+        }
+    );
+
+    let serialized = to_string(&v).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<Device xmlns="urn:device" xmlns:dlna="urn:dlna" id="1" plain="yes" dlna:caps="av" xmlns:ns0="urn:vendor" ns0:model="X &amp; Y"><name>TV</name></Device>"#
+    );
+    assert_eq!(v, from_str(&serialized).unwrap());
+}
+
+#[test]
+fn any_attribute_empty() {
+    let v = Service {
+        extra: HashMap::new(),
+    };
+    let xml = "<Service></Service>";
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[test]
+fn any_attribute_order() {
+    let v = Sorted {
+        extra: HashMap::from([
+            ("d".to_owned(), "4".to_owned()),
+            ("{urn:b}b".to_owned(), "2".to_owned()),
+            ("a".to_owned(), "1".to_owned()),
+            ("{urn:a}c".to_owned(), "3".to_owned()),
+            ("e".to_owned(), "5".to_owned()),
+        ]),
+    };
+
+    assert_eq!(
+        to_string(&v).unwrap(),
+        r#"<Sorted a="1" d="4" e="5" xmlns:ns0="urn:a" ns0:c="3" xmlns:ns1="urn:b" ns1:b="2"></Sorted>"#
+    );
+}
@@ -0,0 +1,92 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/"))]
+struct Item {
+    title: String,
+    #[xml(any_ns)]
+    album: Vec<String>,
+    #[xml(any_ns, rename = "albumArtURI")]
+    art: Option<String>,
+}
+
+#[test]
+fn any_ns() {
+    let xml = r#"<Item xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/" xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/" xmlns:x="urn:vendor">
+        <title>Song</title>
+        <upnp:album>First</upnp:album>
+        <x:album>Second</x:album>
+        <album>Third</album>
+        <x:albumArtURI>http://art</x:albumArtURI>
+    </Item>"#;
+    let item = from_str::<Item>(xml).unwrap();
+    assert_eq!(
+        item,
+        Item {
+            title: "Song".into(),
+            album: vec!["First".into(), "Second".into(), "Third".into()],
+            art: Some("http://art".into()),
+        }
+    );
+
+    // Fields without any_ns stay strict
+    let xml = r#"<Item xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/" xmlns:x="urn:vendor"><x:title>Song</x:title></Item>"#;
+    assert!(from_str::<Item>(xml).is_err());
+
+    // Serializing uses the container's namespace
+    assert_eq!(
+        to_string(&item).unwrap(),
+        concat!(
+            r#"<Item xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/"><title>Song</title>"#,
+            "<album>First</album><album>Second</album><album>Third</album>",
+            "<albumArtURI>http://art</albumArtURI></Item>"
+        )
+    );
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("urn:container"))]
+struct Container {
+    #[xml(any_ns)]
+    res: Vec<Res>,
+    #[xml(any_ns)]
+    desc: Option<Desc>,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("urn:container"))]
+struct Res {
+    #[xml(attribute)]
+    size: u32,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("urn:desc"))]
+struct Desc {
+    #[xml(direct)]
+    text: String,
+}
+
+#[test]
+fn any_ns_struct() {
+    let xml = r#"<Container xmlns="urn:container" xmlns:x="urn:vendor">
+        <Res size="1" />
+        <x:Res size="2" />
+        <x:Desc>vendor</x:Desc>
+    </Container>"#;
+    assert_eq!(
+        from_str::<Container>(xml).unwrap(),
+        Container {
+            res: vec![Res { size: 1 }, Res { size: 2 }],
+            desc: Some(Desc {
+                text: "vendor".into()
+            }),
+        }
+    );
+
+    // The nested types stay strict when used in a field without any_ns
+    let xml = r#"<Res xmlns="urn:vendor" size="1" />"#;
+    assert!(from_str::<Res>(xml).is_err());
+}
@@ -0,0 +1,67 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::dom::{Attribute, Node};
+use instant_xml::{from_str, to_string, Element, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("urn:item"))]
+struct Item {
+    title: String,
+    #[xml(any)]
+    extensions: Vec<Element>,
+}
+
+#[test]
+fn any_elements() {
+    let xml = r#"<Item xmlns="urn:item"><title>Song</title><vendor:rating xmlns:vendor="urn:vendor" vendor:scale="5">4</vendor:rating><extra a="1"><b /></extra></Item>"#;
+    let item = from_str::<Item>(xml).unwrap();
+
+    let mut rating = Element::new("urn:vendor", "rating");
+    rating.attributes.push(Attribute {
+        ns: "urn:vendor".to_owned(),
+        name: "scale".to_owned(),
+        value: "5".to_owned(),
+    });
+    rating.children.push(Node::Text("4".to_owned()));
+
+    let mut extra = Element::new("urn:item", "extra");
+    extra.attributes.push(Attribute {
+        ns: String::new(),
+        name: "a".to_owned(),
+        value: "1".to_owned(),
+    });
+    extra
+        .children
+        .push(Node::Element(Element::new("urn:item", "b")));
+
+    assert_eq!(
+        item,
+        Item {
+            title: "Song".to_owned(),
+            extensions: vec![rating, extra],
+        }
+    );
+
+    let serialized = to_string(&item).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<Item xmlns="urn:item"><title>Song</title><rating xmlns="urn:vendor" xmlns:ns0="urn:vendor" ns0:scale="5">4</rating><extra a="1"><b /></extra></Item>"#
+    );
+    assert_eq!(item, from_str(&serialized).unwrap());
+}
+
+#[test]
+fn any_empty() {
+    let xml = r#"<Item xmlns="urn:item"><title>Song</title></Item>"#;
+    let item = from_str::<Item>(xml).unwrap();
+    assert_eq!(item.extensions, vec![]);
+    assert_eq!(xml, to_string(&item).unwrap());
+}
+
+#[test]
+fn element_root() {
+    let xml = r#"<root xmlns="urn:a"><child xmlns="">text &amp; more</child></root>"#;
+    let element = from_str::<Element>(xml).unwrap();
+    assert_eq!(element.id().ns, "urn:a");
+    assert_eq!(xml, to_string(&element).unwrap());
+}
@@ -0,0 +1,61 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, Error, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Point {
+    #[xml(attribute)]
+    v: i32,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Triangle {
+    corners: [Point; 3],
+    tag: [String; 2],
+}
+
+#[test]
+fn array() {
+    let v = Triangle {
+        corners: [Point { v: 1 }, Point { v: 2 }, Point { v: 3 }],
+        tag: ["a".to_owned(), "b".to_owned()],
+    };
+
+    let xml = r#"<Triangle><Point v="1"></Point><Point v="2"></Point><Point v="3"></Point><tag>a</tag><tag>b</tag></Triangle>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[test]
+fn wrong_length() {
+    let xml = r#"<Triangle><Point v="1"></Point><Point v="2"></Point><tag>a</tag><tag>b</tag></Triangle>"#;
+    assert_eq!(
+        from_str::<Triangle>(xml),
+        Err(Error::LengthMismatch {
+            field: "Triangle::corners",
+            expected: 3,
+            found: 2,
+        })
+    );
+
+    let xml = r#"<Triangle><Point v="1"></Point><Point v="2"></Point><Point v="3"></Point><tag>a</tag><tag>b</tag><tag>c</tag></Triangle>"#;
+    assert_eq!(
+        from_str::<Triangle>(xml),
+        Err(Error::LengthMismatch {
+            field: "Triangle::tag",
+            expected: 2,
+            found: 3,
+        })
+    );
+
+    // Surplus elements are skipped, but still counted
+    let xml = r#"<Triangle><Point v="1"></Point><Point v="2"></Point><Point v="3"></Point><Point v="4"></Point><Point v="5"></Point><tag>a</tag><tag>b</tag></Triangle>"#;
+    assert_eq!(
+        from_str::<Triangle>(xml),
+        Err(Error::LengthMismatch {
+            field: "Triangle::corners",
+            expected: 3,
+            found: 5,
+        })
+    );
+}
@@ -0,0 +1,54 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{
+    from_str, from_str_with_options, to_string, to_string_with_numeric_bools, FromXml, Options,
+    ToXml,
+};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Switch {
+    #[xml(attribute)]
+    enabled: bool,
+    state: bool,
+}
+
+#[test]
+fn lenient() {
+    let options = Options {
+        lenient_bools: true,
+        ..Options::default()
+    };
+
+    for (enabled, state) in [("True", "FALSE"), (" true ", "\n  0\n"), ("1", "false")] {
+        let xml = format!(r#"<Switch enabled="{enabled}"><state>{state}</state></Switch>"#);
+        assert_eq!(
+            from_str_with_options::<Switch>(&xml, options).unwrap(),
+            Switch {
+                enabled: true,
+                state: false
+            }
+        );
+        if enabled != "1" {
+            assert!(from_str::<Switch>(&xml).is_err());
+        }
+    }
+
+    let xml = r#"<Switch enabled="yes"><state>0</state></Switch>"#;
+    assert!(from_str_with_options::<Switch>(xml, options).is_err());
+}
+
+#[test]
+fn numeric() {
+    let v = Switch {
+        enabled: true,
+        state: false,
+    };
+    assert_eq!(
+        to_string(&v).unwrap(),
+        r#"<Switch enabled="true"><state>false</state></Switch>"#
+    );
+
+    let xml = r#"<Switch enabled="1"><state>0</state></Switch>"#;
+    assert_eq!(to_string_with_numeric_bools(&v).unwrap(), xml);
+    assert_eq!(from_str::<Switch>(xml).unwrap(), v);
+}
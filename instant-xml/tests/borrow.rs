@@ -0,0 +1,69 @@
+use std::borrow::Cow;
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, FromXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+#[xml(borrow)]
+struct Message<'a> {
+    #[xml(attribute)]
+    id: Option<Cow<'a, str>>,
+    subject: Cow<'a, str>,
+    tag: Vec<Cow<'a, str>>,
+    sender: Sender<'a>,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Sender<'a> {
+    #[xml(borrow)]
+    name: Cow<'a, str>,
+    #[xml(attribute, borrow)]
+    role: Cow<'a, str>,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+#[xml(rename = "Message")]
+struct OwnedMessage<'a> {
+    #[xml(attribute)]
+    id: Option<Cow<'a, str>>,
+    subject: Cow<'a, str>,
+    tag: Vec<Cow<'a, str>>,
+}
+
+const XML: &str = r#"<Message id="m1"><subject>hi</subject><tag>a</tag><tag></tag><Sender role="admin"><name>bob</name></Sender><tag>b</tag></Message>"#;
+
+#[test]
+fn borrowed() {
+    let v = from_str::<Message>(XML).unwrap();
+    assert_eq!(
+        v,
+        Message {
+            id: Some("m1".into()),
+            subject: "hi".into(),
+            tag: vec!["a".into(), "".into(), "b".into()],
+            sender: Sender {
+                name: "bob".into(),
+                role: "admin".into(),
+            },
+        }
+    );
+
+    assert!(matches!(v.id, Some(Cow::Borrowed(_))));
+    assert!(matches!(v.subject, Cow::Borrowed(_)));
+    assert!(matches!(v.tag[0], Cow::Borrowed(_)));
+    assert!(matches!(v.sender.name, Cow::Borrowed(_)));
+    assert!(matches!(v.sender.role, Cow::Borrowed(_)));
+}
+
+#[test]
+fn owned() {
+    let v: OwnedMessage<'static> = {
+        let xml = XML.to_owned();
+        from_str(&xml).unwrap()
+    };
+
+    assert_eq!(v.id.as_deref(), Some("m1"));
+    assert!(matches!(v.subject, Cow::Owned(_)));
+    assert_eq!(v.tag, vec!["a", "", "b"]);
+}
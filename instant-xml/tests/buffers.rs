@@ -0,0 +1,65 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{to_string_in, Serializer, SerializerBuffers, ToXml};
+
+#[derive(ToXml)]
+#[xml(ns("urn:msg", m = "urn:meta"))]
+struct Message {
+    #[xml(attribute, ns(m))]
+    seq: u32,
+    body: String,
+}
+
+fn message(seq: u32) -> Message {
+    Message {
+        seq,
+        body: format!("message {seq}"),
+    }
+}
+
+#[test]
+fn string_in() {
+    let mut output = String::from("previous contents");
+    to_string_in(&message(1), &mut output).unwrap();
+    assert_eq!(
+        output,
+        r#"<Message xmlns="urn:msg" xmlns:m="urn:meta" m:seq="1"><body>message 1</body></Message>"#
+    );
+
+    let capacity = output.capacity();
+    to_string_in(&message(2), &mut output).unwrap();
+    assert_eq!(
+        output,
+        r#"<Message xmlns="urn:msg" xmlns:m="urn:meta" m:seq="2"><body>message 2</body></Message>"#
+    );
+    assert_eq!(output.capacity(), capacity);
+}
+
+#[test]
+fn buffers() {
+    let mut buffers = SerializerBuffers::default();
+    let mut output = String::new();
+    for seq in 0..3 {
+        output.clear();
+        let mut serializer = Serializer::with_buffers(&mut output, buffers);
+        message(seq).serialize(None, &mut serializer).unwrap();
+        buffers = serializer.into_buffers();
+        assert_eq!(
+            output,
+            format!(
+                r#"<Message xmlns="urn:msg" xmlns:m="urn:meta" m:seq="{seq}"><body>message {seq}</body></Message>"#
+            )
+        );
+    }
+
+    // Preferences and prefixes don't carry over to the next serializer
+    let mut serializer = Serializer::with_buffers(&mut output, buffers);
+    serializer.prefer_prefix("urn:msg", "msg");
+    let buffers = serializer.into_buffers();
+
+    output.clear();
+    let mut serializer = Serializer::with_buffers(&mut output, buffers);
+    message(3).serialize(None, &mut serializer).unwrap();
+    drop(serializer);
+    assert!(output.starts_with(r#"<Message xmlns="urn:msg""#));
+}
@@ -0,0 +1,116 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::c14n::{exclusive, exclusive_element};
+use instant_xml::{to_string_exc_c14n, Id, ToXml};
+
+#[test]
+fn document() {
+    let input = concat!(
+        "<?xml version=\"1.0\"?>\n<?pi before?>\n<!-- comment -->\n",
+        r#"<doc xmlns="urn:doc" xmlns:b="urn:b" xmlns:a="urn:a" b:y="2" z="&#x9;3" a:x='1"'>"#,
+        "<!-- inner --><e1   /><a:e2>x &amp; y &gt; &#60;z&#62;</a:e2>",
+        "<e3 xmlns=\"\"><![CDATA[<cdata> & ]]></e3>\r\n</doc>\n<?pi after?>"
+    );
+
+    assert_eq!(
+        exclusive(input, &[]).unwrap(),
+        concat!(
+            "<?pi before?>\n",
+            r#"<doc xmlns="urn:doc" xmlns:a="urn:a" xmlns:b="urn:b" z="&#x9;3" a:x="1&quot;" b:y="2">"#,
+            "<e1></e1><a:e2>x &amp; y &gt; &lt;z&gt;</a:e2>",
+            "<e3 xmlns=\"\">&lt;cdata&gt; &amp; </e3>\n</doc>",
+            "\n<?pi after?>"
+        )
+    );
+}
+
+#[test]
+fn element() {
+    // From the examples in the Exclusive XML Canonicalization recommendation
+    let input = concat!(
+        r#"<n0:local xmlns:n0="foo:bar" xmlns:n3="ftp://example.org">"#,
+        r#"<n1:elem2 xmlns:n1="http://example.net" xml:lang="en">"#,
+        r#"<n3:stuff xmlns:n3="ftp://example.org"/>"#,
+        "</n1:elem2></n0:local>"
+    );
+
+    let id = Id {
+        ns: "http://example.net",
+        name: "elem2",
+    };
+
+    assert_eq!(
+        exclusive_element(input, id, &[]).unwrap().unwrap(),
+        concat!(
+            r#"<n1:elem2 xmlns:n1="http://example.net" xml:lang="en">"#,
+            r#"<n3:stuff xmlns:n3="ftp://example.org"></n3:stuff></n1:elem2>"#,
+        )
+    );
+
+    let missing = Id {
+        ns: "urn:other",
+        name: "elem2",
+    };
+    assert_eq!(exclusive_element(input, missing, &[]).unwrap(), None);
+}
+
+#[test]
+fn inclusive_prefixes() {
+    let input = concat!(
+        r#"<s:Envelope xmlns:s="urn:soap" xmlns:wsu="urn:wsu" xmlns:x="urn:x">"#,
+        r#"<s:Body wsu:Id="body"><Ping xmlns="urn:ping"><x /></Ping></s:Body></s:Envelope>"#,
+    );
+
+    let body = Id {
+        ns: "urn:soap",
+        name: "Body",
+    };
+
+    assert_eq!(
+        exclusive_element(input, body, &[]).unwrap().unwrap(),
+        concat!(
+            r#"<s:Body xmlns:s="urn:soap" xmlns:wsu="urn:wsu" wsu:Id="body">"#,
+            r#"<Ping xmlns="urn:ping"><x></x></Ping></s:Body>"#,
+        )
+    );
+
+    assert_eq!(
+        exclusive_element(input, body, &["x", "#default"])
+            .unwrap()
+            .unwrap(),
+        concat!(
+            r#"<s:Body xmlns:s="urn:soap" xmlns:wsu="urn:wsu" xmlns:x="urn:x" wsu:Id="body">"#,
+            r#"<Ping xmlns="urn:ping"><x></x></Ping></s:Body>"#,
+        )
+    );
+}
+
+#[test]
+fn unknown_prefix() {
+    assert!(exclusive("<a:b></a:b>", &[]).is_err());
+}
+
+#[derive(ToXml)]
+#[xml(ns("urn:ping", w = "urn:wsu"))]
+struct Ping {
+    #[xml(attribute, ns(w))]
+    id: u8,
+    empty: Empty,
+}
+
+#[derive(ToXml)]
+#[xml(ns("urn:ping"))]
+struct Empty;
+
+#[test]
+fn serialized() {
+    let v = Ping {
+        id: 1,
+        empty: Empty,
+    };
+
+    assert_eq!(
+        to_string_exc_c14n(&v, &[]).unwrap(),
+        r#"<Ping xmlns="urn:ping" xmlns:w="urn:wsu" w:id="1"><Empty></Empty></Ping>"#
+    );
+}
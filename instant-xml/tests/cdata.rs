@@ -0,0 +1,38 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Page {
+    title: String,
+    #[xml(cdata)]
+    html: String,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Script {
+    #[xml(attribute)]
+    lang: String,
+    #[xml(cdata, direct)]
+    source: String,
+}
+
+#[test]
+fn cdata() {
+    let v = Page {
+        title: "a & b".to_owned(),
+        html: "<p>x &amp; y</p>".to_owned(),
+    };
+
+    let xml = "<Page><title>a &amp; b</title><html><![CDATA[<p>x &amp; y</p>]]></html></Page>";
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Page>(xml).unwrap(), v);
+
+    let v = Script {
+        lang: "js".to_owned(),
+        source: "if (a[b[0]]> 1) {}".to_owned(),
+    };
+
+    let xml = r#"<Script lang="js"><![CDATA[if (a[b[0]]]]><![CDATA[> 1) {}]]></Script>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+}
@@ -0,0 +1,49 @@
+use std::cell::RefCell;
+use std::sync::{Mutex, RwLock};
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, Error, FromXml, ToXml};
+
+#[derive(Debug, FromXml, ToXml)]
+struct State {
+    #[xml(attribute)]
+    generation: RwLock<u32>,
+    name: RefCell<String>,
+    peers: Mutex<Vec<String>>,
+    note: RefCell<Option<String>>,
+}
+
+#[test]
+fn cells() {
+    let state = State {
+        generation: RwLock::new(3),
+        name: RefCell::new("node".to_owned()),
+        peers: Mutex::new(vec!["a".to_owned(), "b".to_owned()]),
+        note: RefCell::new(None),
+    };
+
+    let xml = r#"<State generation="3"><name>node</name><peers>a</peers><peers>b</peers></State>"#;
+    assert_eq!(to_string(&state).unwrap(), xml);
+
+    let de = from_str::<State>(xml).unwrap();
+    assert_eq!(*de.generation.read().unwrap(), 3);
+    assert_eq!(*de.name.borrow(), "node");
+    assert_eq!(*de.peers.lock().unwrap(), ["a", "b"]);
+    assert_eq!(*de.note.borrow(), None);
+
+    *state.note.borrow_mut() = Some("hi".to_owned());
+    state.peers.lock().unwrap().clear();
+    let xml = r#"<State generation="3"><name>node</name><note>hi</note></State>"#;
+    assert_eq!(to_string(&state).unwrap(), xml);
+    assert_eq!(
+        *from_str::<State>(xml).unwrap().note.borrow(),
+        Some("hi".to_owned())
+    );
+
+    let _guard = state.name.borrow_mut();
+    assert_eq!(
+        to_string(&state).unwrap_err(),
+        Error::Other("value is already mutably borrowed".into())
+    );
+}
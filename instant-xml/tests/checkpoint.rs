@@ -0,0 +1,82 @@
+use std::fmt;
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{Error, Serializer, ToXml};
+
+#[derive(ToXml)]
+#[xml(ns("urn:records", r = "urn:extra"))]
+struct Record {
+    #[xml(attribute)]
+    id: u32,
+    name: String,
+    #[xml(serialize_with = "serialize_amount")]
+    amount: i64,
+}
+
+fn serialize_amount<W: fmt::Write + ?Sized>(
+    amount: &i64,
+    serializer: &mut Serializer<W>,
+) -> Result<(), Error> {
+    if *amount < 0 {
+        return Err(Error::UnexpectedValue(format!("negative amount {amount}")));
+    }
+
+    amount.serialize(
+        Some(instant_xml::Id::new("urn:records", "amount")),
+        serializer,
+    )
+}
+
+fn record(id: u32, amount: i64) -> Record {
+    Record {
+        id,
+        name: format!("record {id}"),
+        amount,
+    }
+}
+
+#[test]
+fn skip_failed_records() {
+    let records = [record(1, 10), record(2, -5), record(3, 7)];
+    let mut output = String::new();
+    let mut serializer = Serializer::new(&mut output);
+
+    let mut errors = Vec::new();
+    for record in &records {
+        if let Err(err) = serializer.write_checked(record, None) {
+            errors.push(err);
+        }
+    }
+
+    drop(serializer);
+    assert_eq!(
+        errors,
+        vec![Error::UnexpectedValue("negative amount -5".to_owned())]
+    );
+    assert_eq!(
+        output,
+        r#"<Record xmlns="urn:records" xmlns:r="urn:extra" id="1"><name>record 1</name><amount>10</amount></Record><Record xmlns="urn:records" xmlns:r="urn:extra" id="3"><name>record 3</name><amount>7</amount></Record>"#
+    );
+}
+
+#[test]
+fn nested_checkpoints() {
+    let mut output = String::new();
+    let mut serializer = Serializer::new(&mut output);
+
+    let outer = serializer.checkpoint();
+    record(1, 1).serialize(None, &mut serializer).unwrap();
+    let inner = serializer.checkpoint();
+    record(2, 2).serialize(None, &mut serializer).unwrap();
+    serializer.rollback(inner);
+    let inner = serializer.checkpoint();
+    record(3, 3).serialize(None, &mut serializer).unwrap();
+    serializer.commit(inner).unwrap();
+    serializer.commit(outer).unwrap();
+    drop(serializer);
+
+    assert!(output.contains(r#"id="1""#));
+    assert!(!output.contains(r#"id="2""#));
+    assert!(output.contains(r#"id="3""#));
+}
@@ -1,6 +1,6 @@
 #![cfg(feature = "chrono")]
 
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 use similar_asserts::assert_eq;
 
 use instant_xml::{from_str, to_string, FromXml, ToXml};
@@ -21,3 +21,104 @@ fn datetime() {
     let zulu = xml.replace("+00:00", "Z");
     assert_eq!(from_str::<Test>(&zulu).unwrap(), test);
 }
+
+#[derive(Debug, Eq, PartialEq, FromXml, ToXml)]
+struct Event {
+    #[xml(attribute)]
+    at: DateTime<Utc>,
+    #[xml(attribute)]
+    until: Option<DateTime<Utc>>,
+    #[xml(attribute)]
+    day: Option<NaiveDate>,
+    #[xml(attribute)]
+    local: Option<NaiveDateTime>,
+    #[xml(attribute)]
+    count: Option<u32>,
+}
+
+#[test]
+fn datetime_attribute() {
+    let at = Utc.with_ymd_and_hms(2022, 11, 21, 21, 17, 23).unwrap();
+    let v = Event {
+        at,
+        until: Some(at),
+        day: NaiveDate::from_ymd_opt(2022, 11, 21),
+        local: Some(at.naive_utc()),
+        count: Some(3),
+    };
+    let xml = r#"<Event at="2022-11-21T21:17:23+00:00" until="2022-11-21T21:17:23+00:00" day="2022-11-21" local="2022-11-21T21:17:23" count="3"></Event>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Event>(xml).unwrap(), v);
+
+    let v = Event {
+        at,
+        until: None,
+        day: None,
+        local: None,
+        count: None,
+    };
+    let xml = r#"<Event at="2022-11-21T21:17:23+00:00"></Event>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Event>(xml).unwrap(), v);
+}
+
+#[derive(Debug, Eq, PartialEq, FromXml, ToXml)]
+struct Zoned {
+    fixed: DateTime<FixedOffset>,
+    local: DateTime<Local>,
+}
+
+#[test]
+fn datetime_offset() {
+    let fixed = FixedOffset::east_opt(5 * 3600 + 1800)
+        .unwrap()
+        .with_ymd_and_hms(2022, 11, 21, 21, 17, 23)
+        .unwrap();
+    let v = Zoned {
+        fixed,
+        local: fixed.with_timezone(&Local),
+    };
+
+    let xml = to_string(&v).unwrap();
+    assert!(xml.starts_with("<Zoned><fixed>2022-11-21T21:17:23+05:30</fixed><local>"));
+    let de = from_str::<Zoned>(&xml).unwrap();
+    assert_eq!(de, v);
+    assert_eq!(de.fixed.offset(), fixed.offset());
+
+    let xml = "<Zoned><fixed>2022-11-21T15:47:23Z</fixed><local>2022-11-21T21:17:23+05:30</local></Zoned>";
+    let de = from_str::<Zoned>(xml).unwrap();
+    assert_eq!(de.fixed, fixed);
+    assert_eq!(de.local, fixed);
+    assert_eq!(de.fixed.offset().local_minus_utc(), 0);
+
+    let xml = "<Test><dt>2022-11-21T21:17:23+05:30</dt></Test>";
+    assert!(from_str::<Test>(xml).is_err());
+}
+
+#[derive(Debug, Eq, PartialEq, FromXml, ToXml)]
+struct Shift {
+    start: NaiveDateTime,
+    end: NaiveTime,
+}
+
+#[test]
+fn naive() {
+    let start = NaiveDate::from_ymd_opt(2022, 11, 21)
+        .unwrap()
+        .and_hms_milli_opt(9, 30, 0, 250)
+        .unwrap();
+    let v = Shift {
+        start,
+        end: NaiveTime::from_hms_opt(17, 0, 5).unwrap(),
+    };
+
+    let xml = "<Shift><start>2022-11-21T09:30:00.250</start><end>17:00:05</end></Shift>";
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Shift>(xml).unwrap(), v);
+
+    let xml = "<Shift><start>2022-11-21T09:30:00.250</start><end>17:00</end></Shift>";
+    assert!(from_str::<Shift>(xml).is_err());
+
+    let xml = "<Shift><start>2022-11-21 09:30:00</start><end>17:00:05</end></Shift>";
+    assert!(from_str::<Shift>(xml).is_err());
+}
@@ -0,0 +1,67 @@
+use std::collections::{BinaryHeap, LinkedList, VecDeque};
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, Repeated, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Queue {
+    jobs: VecDeque<u32>,
+}
+
+#[test]
+fn vec_deque() {
+    let v = Queue {
+        jobs: VecDeque::from([3, 1, 2]),
+    };
+
+    let xml = "<Queue><jobs>3</jobs><jobs>1</jobs><jobs>2</jobs></Queue>";
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[derive(ToXml)]
+struct Priorities {
+    level: BinaryHeap<u32>,
+}
+
+#[test]
+fn binary_heap() {
+    let v = Priorities {
+        level: BinaryHeap::from([2, 5, 1]),
+    };
+
+    let xml = "<Priorities><level>1</level><level>2</level><level>5</level></Priorities>";
+    assert_eq!(xml, to_string(&v).unwrap());
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Item {
+    #[xml(attribute)]
+    id: u32,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Chain {
+    items: Repeated<LinkedList<Item>>,
+    names: Repeated<LinkedList<String>>,
+}
+
+#[test]
+fn repeated() {
+    let v = Chain {
+        items: LinkedList::from([Item { id: 1 }, Item { id: 2 }]).into(),
+        names: LinkedList::from(["a".to_owned()]).into(),
+    };
+
+    let xml = r#"<Chain><Item id="1"></Item><Item id="2"></Item><names>a</names></Chain>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let v = Chain {
+        items: Repeated::default(),
+        names: Repeated::default(),
+    };
+    assert_eq!("<Chain></Chain>", to_string(&v).unwrap());
+    assert_eq!(v, from_str("<Chain></Chain>").unwrap());
+}
@@ -0,0 +1,41 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, FromXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Feed {
+    title: String,
+    #[xml(attribute)]
+    id: u32,
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Entry {
+    name: String,
+}
+
+#[test]
+fn comments() {
+    let xml = r#"<?xml version="1.0"?><!-- before --><Feed id="1"><!-- a --><title><!-- b -->x<!-- c --></title><!-- d --><Entry><name>y</name></Entry><!-- e --></Feed><!-- after -->"#;
+    assert_eq!(
+        from_str::<Feed>(xml).unwrap(),
+        Feed {
+            title: "x".to_owned(),
+            id: 1,
+            entries: vec![Entry {
+                name: "y".to_owned()
+            }],
+        }
+    );
+
+    let xml = r#"<Feed id="1"><title>foo<!-- split -->bar</title></Feed>"#;
+    assert_eq!(
+        from_str::<Feed>(xml).unwrap(),
+        Feed {
+            title: "foobar".to_owned(),
+            id: 1,
+            entries: vec![],
+        }
+    );
+}
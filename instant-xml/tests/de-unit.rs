@@ -0,0 +1,65 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Ping;
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(rename = "pong", ns("URI"))]
+struct Pong;
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Message {
+    #[xml(attribute)]
+    id: u32,
+    ping: Option<Ping>,
+    pongs: Vec<Pong>,
+}
+
+#[test]
+fn unit_round_trip() {
+    assert_eq!(to_string(&Ping).unwrap(), "<Ping />");
+    assert_eq!(from_str::<Ping>("<Ping/>").unwrap(), Ping);
+    assert_eq!(from_str::<Ping>("<Ping></Ping>").unwrap(), Ping);
+
+    assert_eq!(to_string(&Pong).unwrap(), r#"<pong xmlns="URI" />"#);
+    assert_eq!(from_str::<Pong>(r#"<pong xmlns="URI"/>"#).unwrap(), Pong);
+
+    let v = Message {
+        id: 1,
+        ping: Some(Ping),
+        pongs: vec![Pong, Pong],
+    };
+    let xml = r#"<Message id="1"><Ping /><pong xmlns="URI" /><pong xmlns="URI" /></Message>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let v = Message {
+        id: 2,
+        ping: None,
+        pongs: vec![],
+    };
+    let xml = r#"<Message id="2"></Message>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[derive(Debug, Eq, PartialEq, ToXml)]
+#[xml(transparent)]
+struct Empty;
+
+#[derive(Debug, Eq, PartialEq, ToXml)]
+struct Wrapper {
+    empty: Empty,
+    ping: Ping,
+}
+
+#[test]
+fn transparent_unit() {
+    let v = Wrapper {
+        empty: Empty,
+        ping: Ping,
+    };
+    assert_eq!(to_string(&v).unwrap(), "<Wrapper><Ping /></Wrapper>");
+}
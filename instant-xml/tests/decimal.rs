@@ -0,0 +1,64 @@
+#![cfg(any(feature = "rust_decimal", feature = "bigdecimal"))]
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[cfg(feature = "rust_decimal")]
+#[test]
+fn rust_decimal() {
+    use rust_decimal::Decimal;
+
+    #[derive(Debug, Eq, PartialEq, FromXml, ToXml)]
+    struct Line {
+        #[xml(attribute)]
+        discount: Option<Decimal>,
+        amount: Decimal,
+    }
+
+    let v = Line {
+        discount: Some(Decimal::new(5, 2)),
+        amount: Decimal::new(199_910, 2),
+    };
+    let xml = r#"<Line discount="0.05"><amount>1999.10</amount></Line>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+    let de = from_str::<Line>(xml).unwrap();
+    assert_eq!(de, v);
+    assert_eq!(de.amount.scale(), 2);
+
+    let xml = "<Line><amount>-0.1</amount></Line>";
+    assert_eq!(from_str::<Line>(xml).unwrap().amount, Decimal::new(-1, 1));
+
+    let xml = "<Line><amount>12.5.0</amount></Line>";
+    assert!(from_str::<Line>(xml).is_err());
+}
+
+#[cfg(feature = "bigdecimal")]
+#[test]
+fn bigdecimal() {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+
+    #[derive(Debug, Eq, PartialEq, FromXml, ToXml)]
+    struct Total {
+        amount: BigDecimal,
+    }
+
+    let v = Total {
+        amount: BigDecimal::from_str("123456789012345678901234567890.10").unwrap(),
+    };
+    let xml = "<Total><amount>123456789012345678901234567890.10</amount></Total>";
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Total>(xml).unwrap(), v);
+
+    let v = Total {
+        amount: BigDecimal::from_str("1e30").unwrap(),
+    };
+    let xml = "<Total><amount>1000000000000000000000000000000</amount></Total>";
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Total>(xml).unwrap(), v);
+
+    let xml = "<Total><amount>twelve</amount></Total>";
+    assert!(from_str::<Total>(xml).is_err());
+}
@@ -0,0 +1,32 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string_with_decl, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("urn:ietf:params:xml:ns:epp-1.0"))]
+struct Epp {
+    greeting: String,
+}
+
+#[test]
+fn declaration() {
+    let v = Epp {
+        greeting: "hi".to_owned(),
+    };
+    assert_eq!(
+        to_string_with_decl(&v, None).unwrap(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><Epp xmlns="urn:ietf:params:xml:ns:epp-1.0"><greeting>hi</greeting></Epp>"#
+    );
+
+    let xml = to_string_with_decl(&v, Some(true)).unwrap();
+    assert_eq!(
+        xml,
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Epp xmlns="urn:ietf:params:xml:ns:epp-1.0"><greeting>hi</greeting></Epp>"#
+    );
+    assert_eq!(from_str::<Epp>(&xml).unwrap(), v);
+
+    assert_eq!(
+        to_string_with_decl(&v, Some(false)).unwrap(),
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?><Epp xmlns="urn:ietf:params:xml:ns:epp-1.0"><greeting>hi</greeting></Epp>"#
+    );
+}
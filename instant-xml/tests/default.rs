@@ -0,0 +1,85 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, Error, FromXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Foo {
+    #[xml(default)]
+    count: u32,
+    #[xml(attribute, default = "default_flavor")]
+    flavor: String,
+    #[xml(default)]
+    tags: Vec<String>,
+    name: String,
+}
+
+fn default_flavor() -> String {
+    "vanilla".to_owned()
+}
+
+#[test]
+fn missing_uses_default() {
+    assert_eq!(
+        from_str::<Foo>("<Foo><name>foo</name></Foo>").unwrap(),
+        Foo {
+            count: 0,
+            flavor: "vanilla".to_owned(),
+            tags: Vec::new(),
+            name: "foo".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn present_overrides_default() {
+    assert_eq!(
+        from_str::<Foo>(
+            r#"<Foo flavor="mint"><count>3</count><tags>a</tags><name>foo</name></Foo>"#
+        )
+        .unwrap(),
+        Foo {
+            count: 3,
+            flavor: "mint".to_owned(),
+            tags: vec!["a".to_owned()],
+            name: "foo".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn missing_without_default() {
+    assert_eq!(
+        from_str::<Foo>("<Foo><count>3</count></Foo>").unwrap_err(),
+        Error::MissingValue("Foo::name")
+    );
+}
+
+#[derive(Debug, Default, Eq, FromXml, PartialEq)]
+struct Inner {
+    a: String,
+    b: String,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Outer {
+    #[xml(flatten, default)]
+    inner: Inner,
+    name: String,
+}
+
+#[test]
+fn nested_missing_without_default() {
+    assert_eq!(
+        from_str::<Outer>("<Outer><name>foo</name></Outer>").unwrap(),
+        Outer {
+            inner: Inner::default(),
+            name: "foo".to_owned(),
+        }
+    );
+
+    // A partially present flattened value is not replaced by the default
+    assert_eq!(
+        from_str::<Outer>("<Outer><a>x</a><name>foo</name></Outer>").unwrap_err(),
+        Error::MissingValue("Inner::b")
+    );
+}
@@ -0,0 +1,65 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, Error, FromXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+#[xml(ns("urn:config"), deny_unknown)]
+struct Config {
+    #[xml(attribute)]
+    version: u32,
+    name: String,
+    inner: Option<Inner>,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+#[xml(ns("urn:config"))]
+struct Inner {
+    value: u32,
+}
+
+#[test]
+fn known_content() {
+    assert_eq!(
+        from_str::<Config>(
+            r#"<Config xmlns="urn:config" version="2"><name>a</name><Inner><value>1</value><extra/></Inner></Config>"#
+        ),
+        Ok(Config {
+            version: 2,
+            name: "a".to_owned(),
+            inner: Some(Inner { value: 1 }),
+        })
+    );
+}
+
+#[test]
+fn unknown_element() {
+    assert_eq!(
+        from_str::<Config>(
+            r#"<Config xmlns="urn:config" version="2"><name>a</name><color>red</color></Config>"#
+        ),
+        Err(Error::UnexpectedTag(
+            "unknown element `{urn:config}color` in Config".to_owned()
+        ))
+    );
+
+    assert_eq!(
+        from_str::<Config>(
+            r#"<Config xmlns="urn:config" version="2"><name xmlns="urn:other">a</name></Config>"#
+        ),
+        Err(Error::UnexpectedTag(
+            "unknown element `{urn:other}name` in Config".to_owned()
+        ))
+    );
+}
+
+#[test]
+fn unknown_attribute() {
+    assert_eq!(
+        from_str::<Config>(
+            r#"<Config xmlns="urn:config" version="2" mode="fast"><name>a</name></Config>"#
+        ),
+        Err(Error::UnexpectedTag(
+            "unknown attribute `mode` in Config".to_owned()
+        ))
+    );
+}
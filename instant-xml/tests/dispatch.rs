@@ -0,0 +1,73 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, FromXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Inner {
+    #[xml(attribute)]
+    id: u8,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+#[xml(ns("URI", other = "OTHER"))]
+struct Wide {
+    #[xml(attribute)]
+    abc: u8,
+    #[xml(attribute)]
+    xyz: u8,
+    one: u8,
+    two: u8,
+    six: u8,
+    #[xml(ns("OTHER"))]
+    ten: u8,
+    #[xml(rename = "three")]
+    three_renamed: u8,
+    inner: Inner,
+    many: Vec<u8>,
+}
+
+#[test]
+fn dispatch() {
+    let xml = r#"<Wide xmlns="URI" xmlns:o="OTHER" xyz="2" abc="1" foo="0"><many>1</many><six>6</six><Inner xmlns="" id="9"></Inner><two>2</two><o:ten>10</o:ten><ten>0</ten><one>1</one><many>2</many><three>3</three><unknown /></Wide>"#;
+    assert_eq!(
+        from_str::<Wide>(xml).unwrap(),
+        Wide {
+            abc: 1,
+            xyz: 2,
+            one: 1,
+            two: 2,
+            six: 6,
+            ten: 10,
+            three_renamed: 3,
+            inner: Inner { id: 9 },
+            many: vec![1, 2],
+        }
+    );
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Note {
+    text: String,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Ordered {
+    #[xml(flatten)]
+    note: Note,
+    text: Option<String>,
+}
+
+#[test]
+fn declaration_order() {
+    // The flattened field comes first, so it takes the element over the named field
+    let xml = "<Ordered><text>note</text></Ordered>";
+    assert_eq!(
+        from_str::<Ordered>(xml).unwrap(),
+        Ordered {
+            note: Note {
+                text: "note".to_owned(),
+            },
+            text: None,
+        }
+    );
+}
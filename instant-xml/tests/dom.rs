@@ -0,0 +1,47 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::dom::{Attribute, Node};
+use instant_xml::{from_str, to_string, Element, Id};
+
+#[test]
+fn schemaless_document() {
+    let xml = r#"<feed xmlns="urn:feed" xmlns:m="urn:meta" version="2"><entry m:id="a"><title>First</title></entry><entry m:id="b"><title>Second <em>one</em></title></entry></feed>"#;
+    let feed = from_str::<Element>(xml).unwrap();
+
+    assert_eq!(feed.id(), Id::new("urn:feed", "feed"));
+    assert_eq!(feed.attribute(Id::local("version")), Some("2"));
+    assert_eq!(feed.attribute(Id::local("missing")), None);
+
+    let entries = feed.elements().collect::<Vec<_>>();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].attribute(Id::new("urn:meta", "id")), Some("b"));
+
+    let title = entries[1].element(Id::new("urn:feed", "title")).unwrap();
+    assert_eq!(title.text(), "Second one");
+
+    // Prefixes are not preserved, but the content is
+    let xml = to_string(&feed).unwrap();
+    assert_eq!(
+        xml,
+        r#"<feed xmlns="urn:feed" version="2"><entry xmlns:ns0="urn:meta" ns0:id="a"><title>First</title></entry><entry xmlns:ns0="urn:meta" ns0:id="b"><title>Second <em>one</em></title></entry></feed>"#
+    );
+    assert_eq!(from_str::<Element>(&xml).unwrap(), feed);
+}
+
+#[test]
+fn build_element() {
+    let mut entry = Element::new("urn:feed", "entry");
+    entry
+        .attributes
+        .push(Attribute::new("urn:meta", "id", "a & b"));
+    let mut title = Element::new("urn:feed", "title");
+    title.children.push(Node::Text("<hello>".to_owned()));
+    entry.children.push(Node::Element(title));
+
+    let xml = to_string(&entry).unwrap();
+    assert_eq!(
+        xml,
+        r#"<entry xmlns="urn:feed" xmlns:ns0="urn:meta" ns0:id="a &amp; b"><title>&lt;hello&gt;</title></entry>"#
+    );
+    assert_eq!(from_str::<Element>(&xml).unwrap(), entry);
+}
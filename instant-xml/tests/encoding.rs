@@ -0,0 +1,111 @@
+#![cfg(feature = "utf16")]
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_bytes, Error, FromXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Note {
+    #[xml(attribute)]
+    lang: String,
+    body: String,
+}
+
+const XML: &str =
+    r#"<?xml version="1.0" encoding="UTF-16"?><Note lang="fr"><body>Ça va ? 🎉</body></Note>"#;
+
+fn expected() -> Note {
+    Note {
+        lang: "fr".to_owned(),
+        body: "Ça va ? 🎉".to_owned(),
+    }
+}
+
+fn utf16(bom: &[u8], big_endian: bool) -> Vec<u8> {
+    let mut bytes = bom.to_vec();
+    for unit in XML.encode_utf16() {
+        match big_endian {
+            true => bytes.extend_from_slice(&unit.to_be_bytes()),
+            false => bytes.extend_from_slice(&unit.to_le_bytes()),
+        }
+    }
+    bytes
+}
+
+#[test]
+fn utf16_input() {
+    assert_eq!(
+        from_bytes::<Note>(&utf16(&[0xff, 0xfe], false)).unwrap(),
+        expected()
+    );
+    assert_eq!(
+        from_bytes::<Note>(&utf16(&[0xfe, 0xff], true)).unwrap(),
+        expected()
+    );
+    assert_eq!(from_bytes::<Note>(&utf16(&[], false)).unwrap(), expected());
+    assert_eq!(from_bytes::<Note>(&utf16(&[], true)).unwrap(), expected());
+}
+
+#[test]
+fn utf16_declaration() {
+    for (encoding, big_endian) in [("UTF-16LE", false), ("utf-16be", true)] {
+        let xml = XML.replace("UTF-16", encoding);
+        let mut bytes = Vec::new();
+        for unit in xml.encode_utf16() {
+            match big_endian {
+                true => bytes.extend_from_slice(&unit.to_be_bytes()),
+                false => bytes.extend_from_slice(&unit.to_le_bytes()),
+            }
+        }
+
+        assert_eq!(from_bytes::<Note>(&bytes).unwrap(), expected());
+    }
+
+    let xml = XML.replace("UTF-16", "UTF-8");
+    let bytes = xml
+        .encode_utf16()
+        .flat_map(|unit| unit.to_le_bytes())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        from_bytes::<Note>(&bytes).unwrap_err(),
+        Error::UnsupportedEncoding("UTF-8".to_owned())
+    );
+}
+
+#[test]
+fn utf8_input() {
+    let xml = XML.replace("UTF-16", "UTF-8");
+    assert_eq!(from_bytes::<Note>(xml.as_bytes()).unwrap(), expected());
+
+    let mut bytes = vec![0xef, 0xbb, 0xbf];
+    bytes.extend_from_slice(xml.as_bytes());
+    assert_eq!(from_bytes::<Note>(&bytes).unwrap(), expected());
+}
+
+#[test]
+fn invalid() {
+    let mut bytes = utf16(&[0xff, 0xfe], false);
+    bytes.pop();
+    assert_eq!(
+        from_bytes::<Note>(&bytes).unwrap_err(),
+        Error::InvalidEncoding("odd number of bytes in UTF-16 input")
+    );
+
+    let mut bytes = utf16(&[0xff, 0xfe], false);
+    bytes.extend_from_slice(&0xd800u16.to_le_bytes());
+    assert_eq!(
+        from_bytes::<Note>(&bytes).unwrap_err(),
+        Error::InvalidEncoding("invalid UTF-16")
+    );
+
+    assert_eq!(
+        from_bytes::<Note>(b"<Note lang=\"\xff\"></Note>").unwrap_err(),
+        Error::InvalidEncoding("invalid UTF-8")
+    );
+
+    let xml = XML.replace("UTF-16", "ISO-8859-1");
+    assert_eq!(
+        from_bytes::<Note>(xml.as_bytes()).unwrap_err(),
+        Error::UnsupportedEncoding("ISO-8859-1".to_owned())
+    );
+}
@@ -0,0 +1,125 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{to_string, ErasedToXml, Serializer, ToXml};
+
+#[derive(ToXml)]
+struct Image {
+    #[xml(attribute)]
+    src: String,
+}
+
+#[derive(ToXml)]
+#[xml(ns("urn:video", m = "urn:meta"))]
+struct Video {
+    #[xml(attribute, ns(m))]
+    length: u32,
+    title: String,
+}
+
+#[derive(ToXml)]
+#[xml(ns("urn:post"))]
+struct Post {
+    title: String,
+    attachments: Vec<Box<dyn ErasedToXml>>,
+    extra: Option<Box<dyn ErasedToXml + Send + Sync>>,
+}
+
+#[test]
+fn heterogeneous() {
+    let items: Vec<Box<dyn ErasedToXml>> = vec![
+        Box::new(Image {
+            src: "a.png".to_owned(),
+        }),
+        Box::new(42u8),
+        Box::new("text"),
+    ];
+
+    let xml = items
+        .iter()
+        .map(to_string)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(xml, [r#"<Image src="a.png"></Image>"#, "42", "text"]);
+}
+
+#[test]
+fn nested() {
+    let post = Post {
+        title: "Holiday".to_owned(),
+        attachments: vec![
+            Box::new(Image {
+                src: "beach.png".to_owned(),
+            }),
+            Box::new(Video {
+                length: 90,
+                title: "Waves".to_owned(),
+            }),
+        ],
+        extra: None,
+    };
+
+    assert_eq!(
+        to_string(&post).unwrap(),
+        concat!(
+            r#"<Post xmlns="urn:post"><title>Holiday</title>"#,
+            r#"<Image xmlns="" src="beach.png"></Image>"#,
+            r#"<Video xmlns="urn:video" xmlns:m="urn:meta" m:length="90"><title>Waves</title></Video>"#,
+            "</Post>"
+        )
+    );
+
+    let post = Post {
+        title: "Empty".to_owned(),
+        attachments: Vec::new(),
+        extra: Some(Box::new(Image {
+            src: "x.png".to_owned(),
+        })),
+    };
+
+    assert_eq!(
+        to_string(&post).unwrap(),
+        r#"<Post xmlns="urn:post"><title>Empty</title><Image xmlns="" src="x.png"></Image></Post>"#
+    );
+}
+
+#[derive(ToXml)]
+struct Envelope {
+    #[xml(attribute)]
+    id: u32,
+    payload: Box<dyn ErasedToXml>,
+    note: String,
+}
+
+#[test]
+fn value_hook() {
+    let envelope = Envelope {
+        id: 1,
+        payload: Box::new(Image {
+            src: "secret.png".to_owned(),
+        }),
+        note: "done".to_owned(),
+    };
+
+    let mut paths = Vec::new();
+    let mut output = String::new();
+    let mut serializer = Serializer::new(&mut output);
+    serializer.set_value_hook(|path, _| {
+        paths.push(path.to_owned());
+        match path {
+            "Envelope/Image/@src" => Some("hidden".to_owned()),
+            _ => None,
+        }
+    });
+
+    envelope.serialize(None, &mut serializer).unwrap();
+    drop(serializer);
+
+    assert_eq!(
+        output,
+        r#"<Envelope id="1"><Image src="hidden"></Image><note>done</note></Envelope>"#
+    );
+    assert_eq!(
+        paths,
+        ["Envelope/@id", "Envelope/Image/@src", "Envelope/note"]
+    );
+}
@@ -0,0 +1,62 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{extract, Error, FromXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+#[xml(ns("urn:example"))]
+struct GetResponse {
+    id: u32,
+    name: String,
+}
+
+const RESPONSE: &str = r#"<?xml version="1.0"?>
+<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/">
+    <s:Header><GetResponse>not this one</GetResponse></s:Header>
+    <s:Body>
+        <Other><GetResponse>nor this one</GetResponse></Other>
+        <GetResponse xmlns="urn:example"><id>7</id><name>seven</name></GetResponse>
+        <GetResponse xmlns="urn:example"><id>8</id><name>eight</name></GetResponse>
+    </s:Body>
+</s:Envelope>"#;
+
+#[test]
+fn path() {
+    let response = GetResponse {
+        id: 7,
+        name: "seven".to_owned(),
+    };
+
+    assert_eq!(
+        extract::<GetResponse>(RESPONSE, &["Envelope", "Body", "GetResponse"]).unwrap(),
+        response
+    );
+    assert_eq!(
+        extract::<u32>(RESPONSE, &["Envelope", "Body", "GetResponse", "id"]).unwrap(),
+        7
+    );
+}
+
+#[test]
+fn rest_not_parsed() {
+    let truncated = &RESPONSE[..RESPONSE.find("eight").unwrap()];
+    assert_eq!(
+        extract::<String>(truncated, &["Envelope", "Body", "GetResponse", "name"]).unwrap(),
+        "seven"
+    );
+}
+
+#[test]
+fn not_found() {
+    assert_eq!(
+        extract::<String>(RESPONSE, &["Envelope", "Body", "Missing"]),
+        Err(Error::PathNotFound("Envelope > Body > Missing".to_owned()))
+    );
+    assert_eq!(
+        extract::<String>(RESPONSE, &["Body"]),
+        Err(Error::PathNotFound("Body".to_owned()))
+    );
+    assert_eq!(
+        extract::<String>(RESPONSE, &[]),
+        Err(Error::PathNotFound(String::new()))
+    );
+}
@@ -0,0 +1,85 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{Error, Feed, FeedDeserializer, FromXml, Limits};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+#[xml(rename = "frame")]
+struct Frame {
+    #[xml(attribute)]
+    id: u32,
+    body: String,
+}
+
+fn frame(id: u32, body: &str) -> Feed<Frame> {
+    Feed::Value(Frame {
+        id,
+        body: body.to_owned(),
+    })
+}
+
+#[test]
+fn byte_by_byte() {
+    let input = concat!(
+        r#"<?xml version="1.0"?>"#,
+        "\n<!-- first -->",
+        r#"<frame id="1"><body>a &gt; b</body><!-- </frame> --></frame>"#,
+        "\r\n",
+        r#"<frame id='2' note="/>"><body><![CDATA[</frame>]]></body><empty/></frame>"#,
+    );
+
+    let mut deserializer = FeedDeserializer::<Frame>::new();
+    let mut values = Vec::new();
+    for byte in input.as_bytes() {
+        match deserializer.feed(&[*byte]).unwrap() {
+            Feed::Value(value) => values.push(Feed::Value(value)),
+            Feed::NeedMoreData => {}
+        }
+    }
+
+    assert_eq!(values, [frame(1, "a > b"), frame(2, "</frame>")]);
+    assert!(deserializer.buffered().is_empty());
+}
+
+#[test]
+fn several_values_in_one_chunk() {
+    let mut deserializer = FeedDeserializer::<Frame>::default();
+    let chunk = br#"<frame id="1"><body>x</body></frame><frame id="2"><body>y</body></frame><fra"#;
+
+    assert_eq!(deserializer.feed(chunk).unwrap(), frame(1, "x"));
+    assert_eq!(deserializer.next_value().unwrap(), frame(2, "y"));
+    assert_eq!(deserializer.next_value().unwrap(), Feed::NeedMoreData);
+    assert_eq!(deserializer.buffered(), b"<fra");
+
+    assert_eq!(
+        deserializer.feed(br#"me id="3"><body/></frame>"#).unwrap(),
+        frame(3, "")
+    );
+}
+
+#[test]
+fn errors() {
+    let mut deserializer = FeedDeserializer::<Frame>::new();
+    assert!(deserializer
+        .feed(br#"<frame id="x"><body>bad</body></frame>"#)
+        .is_err());
+    assert_eq!(
+        deserializer
+            .feed(br#"<frame id="4"><body>good</body></frame>"#)
+            .unwrap(),
+        frame(4, "good")
+    );
+
+    let mut deserializer = FeedDeserializer::<Frame>::with_limits(Limits {
+        max_input_len: 16,
+        ..Limits::default()
+    });
+
+    assert_eq!(
+        deserializer.feed(br#"<frame id="5">"#).unwrap(),
+        Feed::NeedMoreData
+    );
+    assert_eq!(
+        deserializer.feed(br#"<body>long</body>"#),
+        Err(Error::InputTooLong)
+    );
+}
@@ -0,0 +1,48 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, Error, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("urn:proto"))]
+struct Envelope {
+    #[xml(attribute, fixed = "1.0")]
+    version: (),
+    #[xml(fixed = "urn:schemas:device")]
+    schema: (),
+    name: String,
+}
+
+#[test]
+fn fixed() {
+    let v = Envelope {
+        version: (),
+        schema: (),
+        name: "lamp".to_owned(),
+    };
+    let xml = r#"<Envelope xmlns="urn:proto" version="1.0"><schema>urn:schemas:device</schema><name>lamp</name></Envelope>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    // Fixed values may be left out
+    let xml = r#"<Envelope xmlns="urn:proto"><name>lamp</name></Envelope>"#;
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[test]
+fn fixed_mismatch() {
+    let xml = r#"<Envelope xmlns="urn:proto" version="2.0"><name>lamp</name></Envelope>"#;
+    assert_eq!(
+        from_str::<Envelope>(xml),
+        Err(Error::UnexpectedValue(
+            "expected `1.0` for Envelope::version, found `2.0`".to_owned()
+        ))
+    );
+
+    let xml = r#"<Envelope xmlns="urn:proto"><schema>other</schema><name>lamp</name></Envelope>"#;
+    assert_eq!(
+        from_str::<Envelope>(xml),
+        Err(Error::UnexpectedValue(
+            "expected `urn:schemas:device` for Envelope::schema, found `other`".to_owned()
+        ))
+    );
+}
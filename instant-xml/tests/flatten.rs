@@ -0,0 +1,85 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, Error, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Audit {
+    #[xml(attribute)]
+    created_by: String,
+    modified: Option<u32>,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Document {
+    #[xml(attribute)]
+    id: u32,
+    title: String,
+    #[xml(flatten)]
+    audit: Audit,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Invoice {
+    #[xml(flatten)]
+    audit: Audit,
+    total: u64,
+    #[xml(flatten)]
+    revision: Revision,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(transparent)]
+struct Revision {
+    #[xml(attribute)]
+    revision: u32,
+}
+
+#[test]
+fn flatten() {
+    let v = Document {
+        id: 1,
+        title: "foo".to_owned(),
+        audit: Audit {
+            created_by: "bob".to_owned(),
+            modified: Some(42),
+        },
+    };
+
+    let xml =
+        r#"<Document id="1" created_by="bob"><title>foo</title><modified>42</modified></Document>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let v = Invoice {
+        audit: Audit {
+            created_by: "alice".to_owned(),
+            modified: None,
+        },
+        total: 10,
+        revision: Revision { revision: 3 },
+    };
+
+    let xml = r#"<Invoice created_by="alice" revision="3"><total>10</total></Invoice>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[test]
+fn standalone() {
+    let v = Audit {
+        created_by: "carol".to_owned(),
+        modified: Some(7),
+    };
+
+    let xml = r#"<Audit created_by="carol"><modified>7</modified></Audit>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[test]
+fn flatten_missing() {
+    assert_eq!(
+        from_str::<Invoice>(r#"<Invoice revision="1"><total>10</total></Invoice>"#).unwrap_err(),
+        Error::MissingValue("Audit::created_by")
+    );
+}
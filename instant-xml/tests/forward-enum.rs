@@ -26,3 +26,129 @@ fn wrapped_enum() {
     assert_eq!(xml, to_string(&v).unwrap());
     assert_eq!(v, from_str(xml).unwrap());
 }
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(forward, ns("URI"))]
+enum Shape {
+    Circle {
+        #[xml(attribute)]
+        id: u32,
+        radius: u16,
+    },
+    #[xml(rename = "rect")]
+    Rectangle {
+        width: u16,
+        height: u16,
+    },
+    Other(Bar),
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("URI"))]
+struct Drawing {
+    shapes: Vec<Shape>,
+}
+
+#[test]
+fn struct_variants() {
+    let v = Shape::Circle { id: 1, radius: 5 };
+    let xml = r#"<Circle xmlns="URI" id="1"><radius>5</radius></Circle>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let v = Shape::Rectangle {
+        width: 3,
+        height: 4,
+    };
+    let xml = r#"<rect xmlns="URI"><width>3</width><height>4</height></rect>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let v = Drawing {
+        shapes: vec![
+            Shape::Rectangle {
+                width: 1,
+                height: 2,
+            },
+            Shape::Other(Bar { bar: 7 }),
+            Shape::Circle { id: 2, radius: 9 },
+        ],
+    };
+    let xml = r#"<Drawing xmlns="URI"><rect><width>1</width><height>2</height></rect><Bar xmlns=""><bar>7</bar></Bar><Circle id="2"><radius>9</radius></Circle></Drawing>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(forward)]
+enum Item {
+    Bar(Bar),
+    #[xml(other)]
+    Unknown(String),
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+#[xml(forward)]
+enum Lenient {
+    Baz(Baz),
+    #[xml(other)]
+    Ignored(String),
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Container {
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(forward, ns("URI"))]
+enum Part {
+    Bar(Bar),
+    #[xml(other)]
+    Unknown(String),
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("URI"))]
+struct Assembly {
+    parts: Vec<Part>,
+}
+
+#[test]
+fn other_variant() {
+    // Only unknown elements in the enum's namespace go into the catch-all variant
+    let xml = r#"<Container><Bar><bar>1</bar></Bar><vendor:ext xmlns:vendor="V"><x>y</x></vendor:ext><Extra /></Container>"#;
+    let v = Container {
+        items: vec![Item::Bar(Bar { bar: 1 }), Item::Unknown("Extra".to_owned())],
+    };
+    assert_eq!(v, from_str(xml).unwrap());
+    assert_eq!(
+        r#"<Container><Bar><bar>1</bar></Bar><Extra /></Container>"#,
+        to_string(&v).unwrap()
+    );
+
+    assert_eq!(
+        Lenient::Ignored("Other".to_owned()),
+        from_str("<Other>text</Other>").unwrap()
+    );
+    assert_eq!(
+        Lenient::Baz(Baz {
+            baz: "b".to_owned()
+        }),
+        from_str("<Baz><baz>b</baz></Baz>").unwrap()
+    );
+
+    // The name is kept with its namespace, in Clark notation
+    let xml = r#"<Assembly xmlns="URI"><Bar xmlns=""><bar>1</bar></Bar><bolt size="3" /><v:nut xmlns:v="V" /></Assembly>"#;
+    let v = Assembly {
+        parts: vec![
+            Part::Bar(Bar { bar: 1 }),
+            Part::Unknown("{URI}bolt".to_owned()),
+        ],
+    };
+    assert_eq!(v, from_str(xml).unwrap());
+    assert_eq!(
+        r#"<Assembly xmlns="URI"><Bar xmlns=""><bar>1</bar></Bar><bolt /></Assembly>"#,
+        to_string(&v).unwrap()
+    );
+}
@@ -0,0 +1,79 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str_fragments, to_string_fragments, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("urn:log"))]
+struct Entry {
+    #[xml(attribute)]
+    level: String,
+    message: String,
+}
+
+fn entry(level: &str, message: &str) -> Entry {
+    Entry {
+        level: level.to_owned(),
+        message: message.to_owned(),
+    }
+}
+
+#[test]
+fn fragments() {
+    let log = r#"
+<Entry xmlns="urn:log" level="info"><message>started</message></Entry>
+<!-- restarted -->
+<l:Entry xmlns:l="urn:log" level="warn"><l:message>disk &amp; memory</l:message></l:Entry>
+<?flush?>
+<Entry xmlns="urn:log" level="error"><message>stopped</message></Entry>
+"#;
+
+    let entries = from_str_fragments::<Entry>(log)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        entries,
+        [
+            entry("info", "started"),
+            entry("warn", "disk & memory"),
+            entry("error", "stopped"),
+        ]
+    );
+
+    assert_eq!(from_str_fragments::<Entry>("").count(), 0);
+    assert_eq!(from_str_fragments::<Entry>("  \n").count(), 0);
+}
+
+#[test]
+fn errors() {
+    let mut iter = from_str_fragments::<Entry>(concat!(
+        r#"<Entry xmlns="urn:log" level="info"><message>a</message></Entry>"#,
+        "garbage",
+        r#"<Entry xmlns="urn:log" level="info"><message>b</message></Entry>"#,
+    ));
+    assert_eq!(iter.next().unwrap().unwrap(), entry("info", "a"));
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+
+    let mut iter = from_str_fragments::<Entry>(r#"<Other xmlns="urn:log" />"#);
+    assert!(iter.next().unwrap().is_err());
+}
+
+#[test]
+fn round_trip() {
+    let entries = [entry("info", "one"), entry("debug", "<two>")];
+    let log = to_string_fragments(&entries).unwrap();
+    assert_eq!(
+        log,
+        concat!(
+            r#"<Entry xmlns="urn:log" level="info"><message>one</message></Entry>"#,
+            "\n",
+            r#"<Entry xmlns="urn:log" level="debug"><message>&lt;two&gt;</message></Entry>"#,
+            "\n",
+        )
+    );
+
+    let parsed = from_str_fragments::<Entry>(&log)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(parsed, entries);
+}
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{Error, Id};
+
+#[test]
+fn construct() {
+    assert_eq!(
+        Id::new("urn:a", "item"),
+        Id {
+            ns: "urn:a",
+            name: "item"
+        }
+    );
+    assert_eq!(
+        Id::local("item"),
+        Id {
+            ns: "",
+            name: "item"
+        }
+    );
+    assert!(Id::new("urn:a", "item").is("urn:a", "item"));
+    assert!(!Id::new("urn:a", "item").is("", "item"));
+}
+
+#[test]
+fn clark_notation() {
+    let id = Id::parse("{urn:a}item").unwrap();
+    assert_eq!(id, Id::new("urn:a", "item"));
+    assert_eq!(id.to_string(), "{urn:a}item");
+
+    let id = Id::parse("item").unwrap();
+    assert_eq!(id, Id::local("item"));
+    assert_eq!(id.to_string(), "item");
+
+    assert_eq!(
+        Id::parse("{urn:a"),
+        Err(Error::UnexpectedValue(
+            "missing `}` in identifier `{urn:a`".to_owned()
+        ))
+    );
+    assert_eq!(
+        Id::parse("{urn:a}"),
+        Err(Error::UnexpectedValue(
+            "invalid identifier `{urn:a}`".to_owned()
+        ))
+    );
+}
+
+#[test]
+fn registry() {
+    let mut registry = BTreeMap::new();
+    registry.insert(Id::parse("{urn:b}second").unwrap(), 2);
+    registry.insert(Id::new("urn:a", "first"), 1);
+    assert_eq!(registry.get(&Id::new("urn:b", "second")), Some(&2));
+    assert_eq!(registry.keys().next(), Some(&Id::new("urn:a", "first")));
+}
@@ -0,0 +1,98 @@
+use std::borrow::Cow;
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, from_str_iter, Deserializer, Error, FromXml, Id, Kind};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+#[xml(rename = "record")]
+struct Record<'a> {
+    #[xml(attribute)]
+    id: u32,
+    #[xml(borrow)]
+    name: Cow<'a, str>,
+}
+
+const EXPORT: &str = r#"<?xml version="1.0"?>
+<export generated="2024-01-01">
+    <header>ignored</header>
+    <record id="1"><name>first</name></record>
+    text between records
+    <record id="2"><name>second &amp; last</name></record>
+    <record id="3"/>
+</export>"#;
+
+#[test]
+fn document() {
+    let mut iter = from_str_iter::<Record>(EXPORT);
+    assert_eq!(
+        iter.next().unwrap().unwrap(),
+        Record {
+            id: 1,
+            name: Cow::Borrowed("first"),
+        }
+    );
+    assert_eq!(
+        iter.next().unwrap().unwrap(),
+        Record {
+            id: 2,
+            name: Cow::Owned("second & last".to_owned()),
+        }
+    );
+
+    assert_eq!(
+        iter.next().unwrap(),
+        Err(Error::MissingValue("Record::name"))
+    );
+
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn empty() {
+    assert_eq!(from_str_iter::<Record>("<export/>").count(), 0);
+    assert_eq!(from_str_iter::<Record>("<export></export>").count(), 0);
+    assert!(from_str_iter::<Record>("").next().unwrap().is_err());
+
+    let mut iter = from_str_iter::<Record>("<export><record id=\"x\">");
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
+
+/// Counts the records in a document without collecting them
+#[derive(Debug, Eq, PartialEq)]
+struct Count(usize);
+
+impl<'xml> FromXml<'xml> for Count {
+    fn matches(id: Id<'_>, _: Option<Id<'_>>) -> bool {
+        id == Id {
+            ns: "",
+            name: "export",
+        }
+    }
+
+    fn deserialize<'cx>(
+        into: &mut Self::Accumulator,
+        _: &'static str,
+        deserializer: &mut Deserializer<'cx, 'xml>,
+    ) -> Result<(), Error> {
+        let mut count = 0;
+        for record in deserializer.iter::<Record>() {
+            record?;
+            count += 1;
+        }
+
+        *into = Some(Count(count));
+        Ok(())
+    }
+
+    type Accumulator = Option<Self>;
+    const KIND: Kind = Kind::Element;
+}
+
+#[test]
+fn deserializer() {
+    let xml = r#"<export><record id="1"><name>a</name></record><other/><record id="2"><name>b</name></record></export>"#;
+    assert_eq!(from_str::<Count>(xml).unwrap(), Count(2));
+    assert!(from_str::<Count>(EXPORT).is_err());
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "jiff")]
+
+use jiff::civil::{date, Date, DateTime};
+use jiff::tz::{offset, TimeZone};
+use jiff::{Timestamp, Zoned};
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[derive(Debug, Eq, PartialEq, FromXml, ToXml)]
+struct Test {
+    ts: Timestamp,
+}
+
+#[test]
+fn timestamp() {
+    let test = Test {
+        ts: Timestamp::from_second(1669065443).unwrap(),
+    };
+    let xml = "<Test><ts>2022-11-21T21:17:23Z</ts></Test>";
+    assert_eq!(to_string(&test).unwrap(), xml);
+    assert_eq!(from_str::<Test>(xml).unwrap(), test);
+
+    let offset = "<Test><ts>2022-11-22T02:47:23+05:30</ts></Test>";
+    assert_eq!(from_str::<Test>(offset).unwrap(), test);
+
+    let xml = "<Test><ts>2022-11-21T21:17:23</ts></Test>";
+    assert!(from_str::<Test>(xml).is_err());
+}
+
+#[derive(Debug, Eq, PartialEq, FromXml, ToXml)]
+struct Meeting {
+    at: Zoned,
+}
+
+#[test]
+fn zoned() {
+    let at = date(2022, 11, 21)
+        .at(21, 17, 23, 0)
+        .to_zoned(TimeZone::fixed(offset(2)))
+        .unwrap();
+    let v = Meeting { at };
+    let xml = "<Meeting><at>2022-11-21T21:17:23+02:00</at></Meeting>";
+    assert_eq!(to_string(&v).unwrap(), xml);
+    let parsed = from_str::<Meeting>(xml).unwrap();
+    assert_eq!(parsed, v);
+    assert_eq!(parsed.at.offset(), offset(2));
+
+    // Named time zones are written with their offset at the time
+    let at = date(2022, 11, 21).at(21, 17, 23, 0).in_tz("UTC").unwrap();
+    let xml = "<Meeting><at>2022-11-21T21:17:23+00:00</at></Meeting>";
+    assert_eq!(to_string(&Meeting { at }).unwrap(), xml);
+
+    let xml = "<Meeting><at>2022-11-21T19:17:23Z</at></Meeting>";
+    assert_eq!(from_str::<Meeting>(xml).unwrap(), v);
+
+    let xml = "<Meeting><at>2022-11-21T21:17:23</at></Meeting>";
+    assert!(from_str::<Meeting>(xml).is_err());
+}
+
+#[derive(Debug, Eq, PartialEq, FromXml, ToXml)]
+struct Shift {
+    #[xml(attribute)]
+    day: Option<Date>,
+    start: DateTime,
+}
+
+#[test]
+fn civil() {
+    let v = Shift {
+        day: Some(date(2022, 11, 21)),
+        start: date(2022, 11, 21).at(9, 30, 0, 250_000_000),
+    };
+
+    let xml = r#"<Shift day="2022-11-21"><start>2022-11-21T09:30:00.25</start></Shift>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Shift>(xml).unwrap(), v);
+
+    let v = Shift { day: None, ..v };
+    let xml = "<Shift><start>2022-11-21T09:30:00.25</start></Shift>";
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Shift>(xml).unwrap(), v);
+
+    let xml = r#"<Shift day="21/11/2022"><start>2022-11-21T09:30:00</start></Shift>"#;
+    assert!(from_str::<Shift>(xml).is_err());
+}
@@ -0,0 +1,169 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{
+    dom, from_str, from_str_with_limits, from_str_with_max_depth, Error, FromXml, Limits, Position,
+};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct List {
+    item: Vec<Item>,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Item {
+    #[xml(attribute)]
+    id: u32,
+}
+
+const LIMITS: Limits = Limits {
+    max_name_len: 8,
+    max_prefix_len: 2,
+    max_depth: Limits::MAX_DEPTH,
+    reject_dtd: false,
+    max_input_len: 64,
+    max_attributes: 2,
+    max_text_len: 8,
+    max_counted_names: 8,
+};
+
+#[test]
+fn stats() {
+    let (v, stats) = from_str_with_limits::<List>(
+        r#"<List><Item id="1"/><Item id="2"/><Item id="3"/></List>"#,
+        LIMITS,
+    )
+    .unwrap();
+
+    assert_eq!(v.item.len(), 3);
+    assert_eq!(stats.elements, 4);
+    assert_eq!(stats.attributes, 3);
+    assert_eq!(stats.longest, 4);
+    assert_eq!(
+        stats.names.into_iter().collect::<Vec<_>>(),
+        vec![("Item", 3), ("List", 1), ("id", 3)]
+    );
+
+    // Only the totals are kept by default, and the per-name counts are capped
+    let xml = r#"<List><Item id="1"/><Item id="2"/></List>"#;
+    let (_, stats) = from_str_with_limits::<List>(xml, Limits::default()).unwrap();
+    assert_eq!(stats.elements, 3);
+    assert!(stats.names.is_empty());
+
+    let limits = Limits {
+        max_counted_names: 2,
+        ..Limits::default()
+    };
+    let (_, stats) = from_str_with_limits::<List>(xml, limits).unwrap();
+    assert_eq!(stats.attributes, 2);
+    assert_eq!(
+        stats.names.into_iter().collect::<Vec<_>>(),
+        vec![("Item", 2), ("List", 1)]
+    );
+}
+
+#[test]
+fn limits() {
+    assert_eq!(
+        from_str_with_limits::<List>("<List><Itemmmmmmm/></List>", LIMITS),
+        Err(Error::NameTooLong(Position { line: 1, column: 7 }))
+    );
+
+    assert_eq!(
+        from_str_with_limits::<List>(r#"<List><Item idddddddd="1"/></List>"#, LIMITS),
+        Err(Error::NameTooLong(Position {
+            line: 1,
+            column: 13
+        }))
+    );
+
+    assert_eq!(
+        from_str_with_limits::<List>(r#"<List xmlns:abc="urn:x"></List>"#, LIMITS),
+        Err(Error::PrefixTooLong(Position { line: 1, column: 7 }))
+    );
+
+    assert_eq!(
+        from_str_with_limits::<List>(r#"<abc:List xmlns:abc="urn:x"></abc:List>"#, LIMITS),
+        Err(Error::PrefixTooLong(Position { line: 1, column: 1 }))
+    );
+}
+
+#[test]
+fn depth() {
+    let limits = Limits {
+        max_depth: 2,
+        ..Limits::default()
+    };
+
+    assert!(from_str_with_limits::<List>(r#"<List><Item id="1"/></List>"#, limits).is_ok());
+    assert_eq!(
+        from_str_with_limits::<List>(r#"<List><Item id="1"><x/></Item></List>"#, limits),
+        Err(Error::NestingTooDeep(Position {
+            line: 1,
+            column: 20
+        }))
+    );
+
+    // The default limit can be raised for trusted input
+    let xml = format!("{}{}", "<a>".repeat(200), "</a>".repeat(200));
+    assert!(from_str::<dom::Element>(&xml).is_err());
+    let limits = Limits {
+        max_depth: 256,
+        ..Limits::default()
+    };
+    assert!(from_str_with_limits::<dom::Element>(&xml, limits).is_ok());
+    assert!(from_str_with_max_depth::<dom::Element>(&xml, 256).is_ok());
+    assert_eq!(
+        from_str_with_max_depth::<List>(r#"<List><Item id="1"/></List>"#, 1),
+        Err(Error::NestingTooDeep(Position { line: 1, column: 7 }))
+    );
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Note {
+    body: String,
+}
+
+#[test]
+fn sizes() {
+    assert_eq!(
+        from_str_with_limits::<Note>("<Note><body>12345678</body></Note>", LIMITS)
+            .unwrap()
+            .0,
+        Note {
+            body: "12345678".to_owned()
+        }
+    );
+    assert_eq!(
+        from_str_with_limits::<Note>("<Note><body>123456789</body></Note>", LIMITS),
+        Err(Error::TextTooLong(Position {
+            line: 1,
+            column: 13
+        }))
+    );
+
+    // The limit applies to decoded text, also when split up by comments
+    assert!(
+        from_str_with_limits::<Note>("<Note><body>&amp;&amp;&amp;</body></Note>", LIMITS).is_ok()
+    );
+    assert_eq!(
+        from_str_with_limits::<Note>("<Note><body>12345<!-- -->6789</body></Note>", LIMITS),
+        Err(Error::TextTooLong(Position {
+            line: 1,
+            column: 13
+        }))
+    );
+
+    assert_eq!(
+        from_str_with_limits::<List>(&format!("<List>{}</List>", " ".repeat(60)), LIMITS),
+        Err(Error::InputTooLong)
+    );
+
+    assert!(from_str_with_limits::<List>(r#"<List a="1" b="2"></List>"#, LIMITS).is_ok());
+    assert_eq!(
+        from_str_with_limits::<List>(r#"<List a="1" xmlns:b="urn:b" c="3"></List>"#, LIMITS),
+        Err(Error::TooManyAttributes(Position {
+            line: 1,
+            column: 29
+        }))
+    );
+}
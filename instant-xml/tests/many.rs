@@ -0,0 +1,54 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{to_writer_many, Separator, ToXml};
+
+#[derive(ToXml)]
+#[xml(ns("URI", x = "OTHER"))]
+struct Record {
+    #[xml(attribute)]
+    id: u32,
+    #[xml(ns("OTHER"))]
+    name: String,
+}
+
+fn records() -> Vec<Record> {
+    vec![
+        Record {
+            id: 1,
+            name: "a".to_owned(),
+        },
+        Record {
+            id: 2,
+            name: "b".to_owned(),
+        },
+    ]
+}
+
+#[test]
+fn many() {
+    let mut output = String::new();
+    to_writer_many(&records(), Separator::None, &mut output).unwrap();
+    assert_eq!(
+        output,
+        r#"<Record xmlns="URI" xmlns:x="OTHER" id="1"><x:name>a</x:name></Record><Record xmlns="URI" xmlns:x="OTHER" id="2"><x:name>b</x:name></Record>"#
+    );
+
+    let mut output = String::new();
+    to_writer_many(&records(), Separator::Newline, &mut output).unwrap();
+    assert_eq!(
+        output,
+        "<Record xmlns=\"URI\" xmlns:x=\"OTHER\" id=\"1\"><x:name>a</x:name></Record>\n\
+         <Record xmlns=\"URI\" xmlns:x=\"OTHER\" id=\"2\"><x:name>b</x:name></Record>\n"
+    );
+
+    let mut output = String::new();
+    to_writer_many(&records()[..1], Separator::Declaration, &mut output).unwrap();
+    assert_eq!(
+        output,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Record xmlns=\"URI\" xmlns:x=\"OTHER\" id=\"1\"><x:name>a</x:name></Record>\n"
+    );
+
+    let mut output = String::new();
+    to_writer_many(&Vec::<Record>::new(), Separator::Newline, &mut output).unwrap();
+    assert_eq!(output, "");
+}
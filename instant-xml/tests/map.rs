@@ -0,0 +1,120 @@
+use std::collections::{BTreeMap, HashMap};
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, Error, FromXml, MapEntry, ToXml, XmlMap};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Config {
+    values: HashMap<String, u32>,
+}
+
+#[test]
+fn hash_map() {
+    let v = Config {
+        values: HashMap::from([("a".to_owned(), 1)]),
+    };
+
+    let xml = r#"<Config><values><entry><key>a</key><value>1</value></entry></values></Config>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let xml = r#"<Config><values><entry><value>2</value><key>b</key></entry><entry><key>c</key><value>3</value></entry></values></Config>"#;
+    let v = from_str::<Config>(xml).unwrap();
+    assert_eq!(
+        v.values,
+        HashMap::from([("b".to_owned(), 2), ("c".to_owned(), 3)])
+    );
+
+    let v = from_str::<Config>("<Config></Config>").unwrap();
+    assert_eq!(v.values, HashMap::new());
+
+    let v = Config {
+        values: HashMap::new(),
+    };
+    assert_eq!("<Config><values></values></Config>", to_string(&v).unwrap());
+}
+
+struct Property;
+
+impl MapEntry for Property {
+    const ENTRY: &'static str = "property";
+    const KEY: &'static str = "name";
+    const VALUE: &'static str = "setting";
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Item {
+    #[xml(attribute)]
+    id: u32,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Settings {
+    properties: XmlMap<HashMap<String, String>, Property>,
+    items: HashMap<u32, Item>,
+}
+
+#[test]
+fn custom_names() {
+    let v = Settings {
+        properties: HashMap::from([("color".to_owned(), "red".to_owned())]).into(),
+        items: HashMap::from([(7, Item { id: 8 })]),
+    };
+
+    let xml = r#"<Settings><properties><property><name>color</name><setting>red</setting></property></properties><items><entry><key>7</key><Item id="8"></Item></entry></items></Settings>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[test]
+fn missing_key() {
+    let xml = r#"<Config><values><entry><value>1</value></entry></values></Config>"#;
+    assert!(from_str::<Config>(xml).is_err());
+}
+
+#[test]
+fn duplicate_key() {
+    let xml = r#"<Config><values><entry><key>a</key><value>1</value></entry><entry><key>a</key><value>2</value></entry></values></Config>"#;
+    assert_eq!(
+        from_str::<Config>(xml),
+        Err(Error::DuplicateValue("Config::values"))
+    );
+
+    let xml = r#"<Ordered><values></values><properties><property><name>1</name><setting>one</setting></property><property><name>1</name><setting>uno</setting></property></properties></Ordered>"#;
+    assert_eq!(
+        from_str::<Ordered>(xml),
+        Err(Error::DuplicateValue("Ordered::properties"))
+    );
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Ordered {
+    values: BTreeMap<String, u32>,
+    properties: XmlMap<BTreeMap<u32, String>, Property>,
+}
+
+#[test]
+fn btree_map() {
+    let v = Ordered {
+        values: BTreeMap::from([
+            ("c".to_owned(), 3),
+            ("a".to_owned(), 1),
+            ("b".to_owned(), 2),
+        ]),
+        properties: BTreeMap::from([(2, "two".to_owned()), (1, "one".to_owned())]).into(),
+    };
+
+    let xml = concat!(
+        "<Ordered><values>",
+        "<entry><key>a</key><value>1</value></entry>",
+        "<entry><key>b</key><value>2</value></entry>",
+        "<entry><key>c</key><value>3</value></entry>",
+        "</values><properties>",
+        "<property><name>1</name><setting>one</setting></property>",
+        "<property><name>2</name><setting>two</setting></property>",
+        "</properties></Ordered>"
+    );
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
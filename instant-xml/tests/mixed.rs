@@ -0,0 +1,43 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::dom::Node;
+use instant_xml::{from_str, to_string, Element, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(rename = "p")]
+struct Paragraph {
+    #[xml(attribute)]
+    class: Option<String>,
+    #[xml(mixed)]
+    content: Vec<Node>,
+}
+
+#[test]
+fn mixed() {
+    let xml =
+        r#"<p class="intro">Hello <b>world</b>, &amp; <a href="/x">more <i>text</i></a>!</p>"#;
+    let v = from_str::<Paragraph>(xml).unwrap();
+
+    let mut b = Element::new("", "b");
+    b.children.push(Node::Text("world".to_owned()));
+    assert_eq!(v.content.len(), 5);
+    assert_eq!(v.content[0], Node::Text("Hello ".to_owned()));
+    assert_eq!(v.content[1], Node::Element(b));
+    assert_eq!(v.content[2], Node::Text(", & ".to_owned()));
+    assert_eq!(v.content[4], Node::Text("!".to_owned()));
+    match &v.content[3] {
+        Node::Element(a) => assert_eq!(a.text(), "more text"),
+        node => panic!("unexpected node {node:?}"),
+    }
+
+    assert_eq!(to_string(&v).unwrap(), xml);
+
+    let v = from_str::<Paragraph>("<p/>").unwrap();
+    assert_eq!(
+        v,
+        Paragraph {
+            class: None,
+            content: Vec::new(),
+        }
+    );
+}
@@ -0,0 +1,43 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("urn:schemas-upnp-org:service:AVTransport:1"), name_from = "action")]
+struct Action {
+    action: String,
+    #[xml(rename = "InstanceID")]
+    instance_id: u32,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Body {
+    #[xml(attribute)]
+    id: u32,
+    action: Action,
+}
+
+#[test]
+fn name_from() {
+    let v = Action {
+        action: "Stop".into(),
+        instance_id: 0,
+    };
+    let xml = r#"<Stop xmlns="urn:schemas-upnp-org:service:AVTransport:1"><InstanceID>0</InstanceID></Stop>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Action>(xml).unwrap(), v);
+
+    let v = Body {
+        id: 1,
+        action: Action {
+            action: "Play".into(),
+            instance_id: 2,
+        },
+    };
+    let xml = r#"<Body id="1"><Play xmlns="urn:schemas-upnp-org:service:AVTransport:1"><InstanceID>2</InstanceID></Play></Body>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Body>(xml).unwrap(), v);
+
+    // The namespace still has to match
+    assert!(from_str::<Action>("<Stop><InstanceID>0</InstanceID></Stop>").is_err());
+}
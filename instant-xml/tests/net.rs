@@ -0,0 +1,38 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[derive(Debug, Eq, PartialEq, FromXml, ToXml)]
+struct Interface {
+    #[xml(attribute)]
+    v4: Ipv4Addr,
+    #[xml(attribute)]
+    v6: Option<Ipv6Addr>,
+    ip: IpAddr,
+    listen: SocketAddr,
+    upstream: SocketAddrV4,
+    peer: SocketAddrV6,
+}
+
+#[test]
+fn addresses() {
+    let v = Interface {
+        v4: Ipv4Addr::new(192, 168, 1, 10),
+        v6: Some(Ipv6Addr::LOCALHOST),
+        ip: IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)),
+        listen: SocketAddr::from(([0, 0, 0, 0], 8080)),
+        upstream: SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 53),
+        peer: SocketAddrV6::new(Ipv6Addr::LOCALHOST, 443, 0, 0),
+    };
+    let xml = r#"<Interface v4="192.168.1.10" v6="::1"><ip>fe80::1</ip><listen>0.0.0.0:8080</listen><upstream>10.0.0.1:53</upstream><peer>[::1]:443</peer></Interface>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Interface>(xml).unwrap(), v);
+
+    let xml = r#"<Interface v4="::1"><ip>fe80::1</ip><listen>0.0.0.0:8080</listen><upstream>10.0.0.1:53</upstream><peer>[::1]:443</peer></Interface>"#;
+    assert!(from_str::<Interface>(xml).is_err());
+
+    let xml = r#"<Interface v4="192.168.1.10"><ip>fe80::1</ip><listen>0.0.0.0</listen><upstream>10.0.0.1:53</upstream><peer>[::1]:443</peer></Interface>"#;
+    assert!(from_str::<Interface>(xml).is_err());
+}
@@ -0,0 +1,57 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(transparent)]
+struct Sku(String);
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(transparent, rename = "qty", ns("URI"))]
+struct Quantity(u32);
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(transparent)]
+struct Tags(Vec<String>);
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Item {
+    #[xml(attribute)]
+    sku: Sku,
+    quantity: Quantity,
+    tag: Tags,
+    alt: Option<Sku>,
+}
+
+#[test]
+fn newtype() {
+    let v = Item {
+        sku: Sku("A1".to_owned()),
+        quantity: Quantity(3),
+        tag: Tags(vec!["x".to_owned(), "y".to_owned()]),
+        alt: Some(Sku("B2".to_owned())),
+    };
+
+    let xml =
+        r#"<Item sku="A1"><quantity>3</quantity><tag>x</tag><tag>y</tag><alt>B2</alt></Item>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let v = Item {
+        sku: Sku("A1".to_owned()),
+        quantity: Quantity(0),
+        tag: Tags(vec![]),
+        alt: None,
+    };
+
+    let xml = r#"<Item sku="A1"><quantity>0</quantity></Item>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[test]
+fn renamed_root() {
+    let xml = r#"<qty xmlns="URI">7</qty>"#;
+    assert_eq!(to_string(&Quantity(7)).unwrap(), xml);
+    assert_eq!(from_str::<Quantity>(xml).unwrap(), Quantity(7));
+}
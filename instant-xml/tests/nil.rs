@@ -0,0 +1,105 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, Error, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Item {
+    #[xml(attribute)]
+    id: u32,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Order {
+    #[xml(nil)]
+    note: Option<String>,
+    #[xml(nil)]
+    item: Option<Item>,
+    extra: Option<String>,
+}
+
+#[test]
+fn nil() {
+    let v = Order {
+        note: None,
+        item: None,
+        extra: None,
+    };
+    assert_eq!(
+        to_string(&v).unwrap(),
+        concat!(
+            "<Order>",
+            r#"<note xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:nil="true" />"#,
+            r#"<item xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:nil="true" />"#,
+            "</Order>"
+        )
+    );
+    assert_eq!(from_str::<Order>(&to_string(&v).unwrap()).unwrap(), v);
+
+    let v = Order {
+        note: Some("hi".into()),
+        item: Some(Item { id: 1 }),
+        extra: None,
+    };
+    assert_eq!(
+        to_string(&v).unwrap(),
+        r#"<Order><note>hi</note><Item id="1"></Item></Order>"#
+    );
+}
+
+#[test]
+fn deserialize() {
+    let xml = r#"<Order xmlns:i="http://www.w3.org/2001/XMLSchema-instance">
+        <note i:nil="1">ignored</note><Item i:nil="true" id="1" /><extra i:nil="true" />
+    </Order>"#;
+    assert_eq!(
+        from_str::<Order>(xml).unwrap(),
+        Order {
+            note: None,
+            item: None,
+            extra: None,
+        }
+    );
+
+    let xml = r#"<Order><note xsi:nil="false" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">hi</note></Order>"#;
+    assert_eq!(
+        from_str::<Order>(xml).unwrap(),
+        Order {
+            note: Some("hi".into()),
+            item: None,
+            extra: None,
+        }
+    );
+
+    // Values that are not optional cannot be nil
+    let xml = r#"<Item xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:nil="true" />"#;
+    assert_eq!(from_str::<Item>(xml).unwrap_err(), Error::UnexpectedNil);
+
+    let xml = r#"<Declared xmlns="URI" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><note xsi:nil="true" /></Declared>"#;
+    assert_eq!(from_str::<Declared>(xml).unwrap(), Declared { note: None });
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Required {
+    note: String,
+}
+
+#[test]
+fn required() {
+    let xml = r#"<Required><note xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:nil="true" /></Required>"#;
+    assert_eq!(from_str::<Required>(xml).unwrap_err(), Error::UnexpectedNil);
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("URI", xsi = "http://www.w3.org/2001/XMLSchema-instance"))]
+struct Declared {
+    #[xml(nil)]
+    note: Option<String>,
+}
+
+#[test]
+fn declared() {
+    assert_eq!(
+        to_string(&Declared { note: None }).unwrap(),
+        r#"<Declared xmlns="URI" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><note xsi:nil="true" /></Declared>"#
+    );
+}
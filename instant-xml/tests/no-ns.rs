@@ -0,0 +1,43 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, Error, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("URI"))]
+struct Envelope {
+    inherited: String,
+    #[xml(no_ns)]
+    local: String,
+    #[xml(attribute)]
+    id: u32,
+}
+
+#[test]
+fn no_ns() {
+    let v = Envelope {
+        inherited: "a".to_owned(),
+        local: "b".to_owned(),
+        id: 1,
+    };
+
+    let xml = r#"<Envelope xmlns="URI" id="1"><inherited>a</inherited><local xmlns="">b</local></Envelope>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Envelope>(xml).unwrap(), v);
+
+    // The local element must not be in the container namespace
+    assert_eq!(
+        from_str::<Envelope>(
+            r#"<Envelope xmlns="URI" id="1"><inherited>a</inherited><local>b</local></Envelope>"#
+        ),
+        Err(Error::MissingValue("Envelope::local"))
+    );
+
+    // A prefixed container leaves unprefixed children without namespace
+    assert_eq!(
+        from_str::<Envelope>(
+            r#"<e:Envelope xmlns:e="URI" id="1"><e:inherited>a</e:inherited><local>b</local></e:Envelope>"#
+        )
+        .unwrap(),
+        v
+    );
+}
@@ -0,0 +1,46 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Record {
+    #[xml(attribute, null = "NIL")]
+    owner: Option<String>,
+    #[xml(attribute, null = "-")]
+    count: Option<u32>,
+    #[xml(attribute)]
+    label: Option<String>,
+}
+
+#[test]
+fn null_attribute() {
+    let v = Record {
+        owner: None,
+        count: None,
+        label: None,
+    };
+    let xml = r#"<Record owner="NIL" count="-"></Record>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let v = Record {
+        owner: Some("alice".to_owned()),
+        count: Some(3),
+        label: Some("x".to_owned()),
+    };
+    let xml = r#"<Record owner="alice" count="3" label="x"></Record>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[test]
+fn missing_null_attribute() {
+    assert_eq!(
+        from_str::<Record>("<Record />").unwrap(),
+        Record {
+            owner: None,
+            count: None,
+            label: None,
+        }
+    );
+}
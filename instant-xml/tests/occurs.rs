@@ -0,0 +1,49 @@
+use std::borrow::Cow;
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, Error, FromXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Playlist<'a> {
+    #[xml(min = 1, max = 3)]
+    track: Vec<String>,
+    #[xml(max = 1)]
+    tag: Cow<'a, [u32]>,
+}
+
+#[test]
+fn occurs() {
+    let xml = "<Playlist><track>a</track><track>b</track><tag>1</tag></Playlist>";
+    assert_eq!(
+        from_str::<Playlist>(xml).unwrap(),
+        Playlist {
+            track: vec!["a".to_owned(), "b".to_owned()],
+            tag: Cow::Owned(vec![1]),
+        }
+    );
+
+    assert_eq!(
+        from_str::<Playlist>("<Playlist></Playlist>"),
+        Err(Error::UnexpectedValue(
+            "too few elements for Playlist::track: expected at least 1, found 0".to_owned()
+        ))
+    );
+
+    let xml =
+        "<Playlist><track>a</track><track>b</track><track>c</track><track>d</track></Playlist>";
+    assert_eq!(
+        from_str::<Playlist>(xml),
+        Err(Error::UnexpectedValue(
+            "too many elements for Playlist::track: expected at most 3, found 4".to_owned()
+        ))
+    );
+
+    let xml = "<Playlist><track>a</track><tag>1</tag><tag>2</tag></Playlist>";
+    assert_eq!(
+        from_str::<Playlist>(xml),
+        Err(Error::UnexpectedValue(
+            "too many elements for Playlist::tag: expected at most 1, found 2".to_owned()
+        ))
+    );
+}
@@ -0,0 +1,129 @@
+use std::borrow::Cow;
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{dom, from_str, Error, FromXml, Position};
+
+#[derive(Debug, FromXml, PartialEq)]
+#[xml(ns("URI", x = "BAR"))]
+struct Document<'a> {
+    #[xml(attribute)]
+    id: Option<u32>,
+    #[xml(borrow)]
+    title: Cow<'a, str>,
+    #[xml(ns("BAR"))]
+    flag: Vec<bool>,
+    value: Option<f64>,
+    inner: Option<Inner>,
+}
+
+#[derive(Debug, FromXml, PartialEq)]
+#[xml(ns("URI"))]
+struct Inner {
+    #[xml(attribute)]
+    name: String,
+    items: Vec<String>,
+}
+
+#[test]
+fn deep_nesting() {
+    let depth = 100_000;
+    let xml = format!("{}{}", "<a>".repeat(depth), "</a>".repeat(depth));
+    assert_eq!(
+        from_str::<dom::Element>(&xml),
+        Err(Error::NestingTooDeep(Position {
+            line: 1,
+            column: 3 * 128 + 1,
+        }))
+    );
+
+    let xml = format!("<Inner xmlns=\"URI\" name=\"n\">{}", "<a>".repeat(depth));
+    assert_eq!(
+        from_str::<Inner>(&xml),
+        Err(Error::NestingTooDeep(Position {
+            line: 1,
+            column: 28 + 3 * 127 + 1,
+        }))
+    );
+}
+
+/// Feed mutations of a valid document to the deserializer, none of which may panic
+#[test]
+fn mutations() {
+    const SEED: &str = r#"<?xml version="1.0"?><Document xmlns="URI" xmlns:x="BAR" id="7"><title>a &amp; b &#x7c; &#1234; ö</title><x:flag>true</x:flag><x:flag>0</x:flag><value>1.5e3</value><inner name="&lt;n&gt;"><items>one</items><items><![CDATA[two]]></items><!-- c --></inner></Document>"#;
+    const TOKENS: &[&str] = &[
+        "<",
+        ">",
+        "/",
+        "&",
+        ";",
+        "&#",
+        "&#x",
+        "=",
+        "\"",
+        ":",
+        "x:",
+        "xmlns",
+        "<!--",
+        "-->",
+        "<![CDATA[",
+        "]]>",
+        "<?",
+        "?>",
+        "<!DOCTYPE a>",
+        "\u{fffd}",
+        "ö",
+        "\0",
+        "",
+        "-1",
+    ];
+
+    assert!(from_str::<Document>(SEED).is_ok());
+    assert!(from_str::<dom::Element>(SEED).is_ok());
+
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut random = move |n: usize| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % n as u64) as usize
+    };
+
+    for _ in 0..20_000 {
+        let mut input = SEED.to_owned();
+        for _ in 0..=random(4) {
+            let mut at = random(input.len() + 1);
+            while !input.is_char_boundary(at) {
+                at -= 1;
+            }
+
+            match random(3) {
+                0 => {
+                    let mut end = (at + random(8)).min(input.len());
+                    while !input.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    input.replace_range(at..end, "");
+                }
+                1 => input.insert_str(at, TOKENS[random(TOKENS.len())]),
+                _ => {
+                    let len = random(32);
+                    let mut start = random(SEED.len().saturating_sub(len) + 1);
+                    while !SEED.is_char_boundary(start) {
+                        start -= 1;
+                    }
+                    let mut end = (start + len).min(SEED.len());
+                    while !SEED.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    input.insert_str(at, &SEED[start..end]);
+                }
+            }
+        }
+
+        let _ = from_str::<Document>(&input);
+        let _ = from_str::<dom::Element>(&input);
+        #[cfg(feature = "utf16")]
+        let _ = instant_xml::from_bytes::<Inner>(input.as_bytes());
+    }
+}
@@ -0,0 +1,37 @@
+use std::path::{Path, PathBuf};
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[derive(Debug, Eq, PartialEq, FromXml, ToXml)]
+struct Config {
+    #[xml(attribute)]
+    root: PathBuf,
+    include: Vec<PathBuf>,
+}
+
+#[test]
+fn path_buf() {
+    let v = Config {
+        root: PathBuf::from("/srv/\"data\""),
+        include: vec![PathBuf::from("a&b/<c>.xml"), PathBuf::from("plain.xml")],
+    };
+    let xml = r#"<Config root="/srv/&quot;data&quot;"><include>a&amp;b/&lt;c&gt;.xml</include><include>plain.xml</include></Config>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Config>(xml).unwrap(), v);
+}
+
+#[derive(Debug, ToXml)]
+struct Borrowed<'a> {
+    path: &'a Path,
+}
+
+#[test]
+fn path() {
+    let v = Borrowed {
+        path: Path::new("logs/app & co.log"),
+    };
+    let xml = "<Borrowed><path>logs/app &amp; co.log</path></Borrowed>";
+    assert_eq!(to_string(&v).unwrap(), xml);
+}
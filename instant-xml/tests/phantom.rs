@@ -0,0 +1,67 @@
+use std::marker::PhantomData;
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+/// A type that implements neither `ToXml` nor `FromXml`
+#[derive(Debug, Eq, PartialEq)]
+struct Meters;
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Length<U> {
+    #[xml(attribute)]
+    value: u32,
+    unit: PhantomData<U>,
+}
+
+#[test]
+fn phantom_field() {
+    let v = Length::<Meters> {
+        value: 3,
+        unit: PhantomData,
+    };
+    let xml = r#"<Length value="3"></Length>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Length<Meters>>(xml).unwrap(), v);
+
+    // Elements named like the marker field are ignored as unknown content
+    let xml = r#"<Length value="3"><unit>m</unit></Length>"#;
+    assert_eq!(from_str::<Length<Meters>>(xml).unwrap(), v);
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Tagged<T, U>(T, PhantomData<U>);
+
+#[test]
+fn phantom_unnamed() {
+    let v = Tagged::<String, Meters>("a".to_owned(), PhantomData);
+    let xml = "<Tagged>a</Tagged>";
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Tagged<String, Meters>>(xml).unwrap(), v);
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(transparent)]
+struct Inline<U> {
+    name: String,
+    marker: PhantomData<U>,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Outer {
+    inline: Inline<Meters>,
+}
+
+#[test]
+fn phantom_inline() {
+    let v = Outer {
+        inline: Inline {
+            name: "x".to_owned(),
+            marker: PhantomData,
+        },
+    };
+    let xml = "<Outer><name>x</name></Outer>";
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Outer>(xml).unwrap(), v);
+}
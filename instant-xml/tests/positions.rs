@@ -0,0 +1,110 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, from_str_with_options, Error, FromXml, Options, Position};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Envelope {
+    body: Body,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Body {
+    count: u32,
+    name: String,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct GetResponse {
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+#[xml(rename = "items")]
+struct Item {
+    price: u32,
+}
+
+fn located<'xml, T: FromXml<'xml> + std::fmt::Debug>(xml: &'xml str) -> Error {
+    let options = Options {
+        positions: true,
+        ..Options::default()
+    };
+
+    from_str_with_options::<T>(xml, options).unwrap_err()
+}
+
+#[test]
+fn positions() {
+    let xml = "<Envelope>\n  <Body>\n    <count>x</count>\n  </Body>\n</Envelope>";
+    let err = located::<Envelope>(xml);
+    assert_eq!(
+        err,
+        Error::Located {
+            error: Box::new(Error::UnexpectedValue(
+                "unable to parse u32 from `x` for Body::count".to_owned()
+            )),
+            position: Position {
+                line: 3,
+                column: 12
+            },
+            path: "Envelope > Body > count".to_owned(),
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "unexpected value: 'unable to parse u32 from `x` for Body::count' (at 3:12, in Envelope > Body > count)"
+    );
+
+    let xml = "<Envelope>\n  <Body>\n    <count>1</count>\n  </Body>\n</Envelope>";
+    assert_eq!(
+        located::<Envelope>(xml),
+        Error::Located {
+            error: Box::new(Error::MissingValue("Body::name")),
+            position: Position { line: 4, column: 3 },
+            path: "Envelope > Body".to_owned(),
+        }
+    );
+
+    // Positions are only added when asked for
+    assert_eq!(
+        from_str::<Envelope>(xml).unwrap_err(),
+        Error::MissingValue("Body::name")
+    );
+
+    // Errors that carry a position are not wrapped again
+    assert!(matches!(
+        located::<Envelope>("<Envelope><</Envelope>"),
+        Error::Parse(_)
+    ));
+}
+
+#[test]
+fn paths() {
+    let xml = "<GetResponse><items><price>1</price></items><items><price>2</price></items><items><price>3</price></items><items><price>x</price></items></GetResponse>";
+    assert_eq!(
+        located::<GetResponse>(xml),
+        Error::Located {
+            error: Box::new(Error::UnexpectedValue(
+                "unable to parse u32 from `x` for Item::price".to_owned()
+            )),
+            position: Position {
+                line: 1,
+                column: 121
+            },
+            path: "GetResponse > items[3] > price".to_owned(),
+        }
+    );
+
+    let xml = "<GetResponse><items><price>1</price></items><items></items></GetResponse>";
+    assert_eq!(
+        located::<GetResponse>(xml),
+        Error::Located {
+            error: Box::new(Error::MissingValue("Item::price")),
+            position: Position {
+                line: 1,
+                column: 52
+            },
+            path: "GetResponse > items[1]".to_owned(),
+        }
+    );
+}
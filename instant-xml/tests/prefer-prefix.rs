@@ -0,0 +1,120 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{Serializer, ToXml};
+
+const SOAP: &str = "http://schemas.xmlsoap.org/soap/envelope/";
+
+#[derive(ToXml)]
+#[xml(ns(SOAP))]
+struct Envelope {
+    body: Body,
+}
+
+#[derive(ToXml)]
+#[xml(ns(SOAP))]
+struct Body {
+    payload: Payload,
+}
+
+#[derive(ToXml)]
+#[xml(ns("urn:payload", soap = SOAP))]
+struct Payload {
+    #[xml(attribute, ns(soap))]
+    version: u8,
+    value: u8,
+}
+
+fn serialize<T: ToXml>(value: &T, prefixes: &[(&'static str, &'static str)]) -> String {
+    let mut output = String::new();
+    {
+        let mut serializer = Serializer::new(&mut output);
+        for (ns, prefix) in prefixes {
+            serializer.prefer_prefix(ns, prefix);
+        }
+        value.serialize(None, &mut serializer).unwrap();
+    }
+    output
+}
+
+#[test]
+fn prefer_prefix() {
+    let v = Envelope {
+        body: Body {
+            payload: Payload {
+                version: 1,
+                value: 2,
+            },
+        },
+    };
+
+    assert_eq!(
+        serialize(&v, &[]),
+        concat!(
+            r#"<Envelope xmlns="http://schemas.xmlsoap.org/soap/envelope/"><Body>"#,
+            r#"<Payload xmlns="urn:payload" xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" soap:version="1">"#,
+            "<value>2</value></Payload></Body></Envelope>"
+        )
+    );
+
+    assert_eq!(
+        serialize(&v, &[(SOAP, "s")]),
+        concat!(
+            r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body>"#,
+            r#"<Payload xmlns="urn:payload" s:version="1"><value>2</value></Payload>"#,
+            "</s:Body></s:Envelope>"
+        )
+    );
+}
+
+#[test]
+fn declared_prefix() {
+    let v = Payload {
+        version: 1,
+        value: 2,
+    };
+
+    assert_eq!(
+        serialize(&v, &[(SOAP, "s")]),
+        concat!(
+            r#"<Payload xmlns="urn:payload" xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" s:version="1">"#,
+            "<value>2</value></Payload>"
+        )
+    );
+}
+
+#[test]
+fn prefix_scope() {
+    let v = [
+        Body {
+            payload: Payload {
+                version: 1,
+                value: 2,
+            },
+        },
+        Body {
+            payload: Payload {
+                version: 3,
+                value: 4,
+            },
+        },
+    ];
+
+    let mut output = String::new();
+    {
+        let mut serializer = Serializer::new(&mut output);
+        serializer.prefer_prefix(SOAP, "s");
+        for body in &v {
+            body.serialize(None, &mut serializer).unwrap();
+        }
+    }
+
+    assert_eq!(
+        output,
+        concat!(
+            r#"<s:Body xmlns:s="http://schemas.xmlsoap.org/soap/envelope/">"#,
+            r#"<Payload xmlns="urn:payload" s:version="1"><value>2</value></Payload></s:Body>"#,
+            r#"<s:Body xmlns:s="http://schemas.xmlsoap.org/soap/envelope/">"#,
+            r#"<Payload xmlns="urn:payload" s:version="3"><value>4</value></Payload></s:Body>"#,
+        )
+    );
+}
@@ -0,0 +1,107 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{to_string_pretty, ToXml};
+
+#[derive(ToXml)]
+struct Document {
+    #[xml(attribute)]
+    id: u32,
+    title: String,
+    body: Body,
+    empty: Empty,
+}
+
+#[derive(ToXml)]
+struct Body {
+    p: Vec<Paragraph>,
+    figure: Figure,
+}
+
+#[derive(ToXml)]
+#[xml(rename = "p")]
+struct Paragraph {
+    b: Bold,
+    #[xml(direct)]
+    text: String,
+}
+
+#[derive(ToXml)]
+#[xml(rename = "b")]
+struct Bold {
+    #[xml(direct)]
+    text: String,
+}
+
+#[derive(ToXml)]
+#[xml(rename = "figure")]
+struct Figure {
+    b: Bold,
+    i: Italic,
+}
+
+#[derive(ToXml)]
+#[xml(rename = "i")]
+struct Italic {
+    caption: String,
+}
+
+#[derive(ToXml)]
+struct Empty;
+
+fn document() -> Document {
+    Document {
+        id: 1,
+        title: " spaced  title ".to_owned(),
+        body: Body {
+            p: vec![Paragraph {
+                b: Bold {
+                    text: "Bold".to_owned(),
+                },
+                text: " text".to_owned(),
+            }],
+            figure: Figure {
+                b: Bold {
+                    text: "x".to_owned(),
+                },
+                i: Italic {
+                    caption: "y".to_owned(),
+                },
+            },
+        },
+        empty: Empty,
+    }
+}
+
+#[test]
+fn pretty() {
+    let expected = r#"<Document id="1">
+  <title> spaced  title </title>
+  <Body>
+    <p><b>Bold</b> text</p>
+    <figure>
+      <b>x</b>
+      <i>
+        <caption>y</caption>
+      </i>
+    </figure>
+  </Body>
+  <Empty />
+</Document>"#;
+    assert_eq!(expected, to_string_pretty(&document(), "  ", &[]).unwrap());
+}
+
+#[test]
+fn pretty_declared_mixed() {
+    let expected = r#"<Document id="1">
+	<title> spaced  title </title>
+	<Body>
+		<p><b>Bold</b> text</p>
+		<figure><b>x</b><i><caption>y</caption></i></figure>
+	</Body>
+	<Empty />
+</Document>"#;
+    assert_eq!(
+        expected,
+        to_string_pretty(&document(), "\t", &["figure"]).unwrap()
+    );
+}
@@ -0,0 +1,40 @@
+use std::ops::{Range, RangeInclusive};
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, Error, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("urn:filter"))]
+struct Filter {
+    name: String,
+    #[xml(range = "min..max")]
+    price: RangeInclusive<u32>,
+    #[xml(range)]
+    window: Range<u64>,
+}
+
+#[test]
+fn range() {
+    let v = Filter {
+        name: "cheap".to_owned(),
+        price: 1..=5,
+        window: 10..20,
+    };
+
+    let xml = r#"<Filter xmlns="urn:filter"><name>cheap</name><min>1</min><max>5</max><start>10</start><end>20</end></Filter>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let xml = r#"<Filter xmlns="urn:filter"><end>20</end><max>5</max><name>cheap</name><start>10</start><min>1</min></Filter>"#;
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[test]
+fn missing_bound() {
+    let xml = r#"<Filter xmlns="urn:filter"><name>cheap</name><min>1</min><start>10</start><end>20</end></Filter>"#;
+    assert_eq!(
+        from_str::<Filter>(xml),
+        Err(Error::MissingValue("Filter::price::max"))
+    );
+}
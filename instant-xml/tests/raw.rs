@@ -0,0 +1,87 @@
+use std::borrow::Cow;
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, Raw, ToXml};
+
+#[derive(ToXml)]
+#[xml(ns("urn:envelope"))]
+struct Envelope<'a> {
+    #[xml(attribute)]
+    id: u32,
+    #[xml(raw)]
+    body: String,
+    footer: Raw<'a>,
+    note: &'a str,
+}
+
+#[derive(ToXml)]
+struct Fragment {
+    #[xml(raw, direct)]
+    content: String,
+}
+
+#[test]
+fn raw() {
+    let v = Envelope {
+        id: 1,
+        body: r#"<order xmlns="urn:shop"><qty>2</qty></order>"#.to_owned(),
+        footer: Raw::from("<sig>a&amp;b</sig>"),
+        note: "<not raw>",
+    };
+
+    assert_eq!(
+        to_string(&v).unwrap(),
+        r#"<Envelope xmlns="urn:envelope" id="1"><body><order xmlns="urn:shop"><qty>2</qty></order></body><footer><sig>a&amp;b</sig></footer><note>&lt;not raw&gt;</note></Envelope>"#
+    );
+
+    let v = Fragment {
+        content: "Hello <b>world</b>".to_owned(),
+    };
+    assert_eq!(
+        to_string(&v).unwrap(),
+        "<Fragment>Hello <b>world</b></Fragment>"
+    );
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("urn:soap"))]
+struct Body<'a> {
+    #[xml(raw)]
+    content: &'a str,
+    #[xml(raw, default)]
+    trailer: String,
+    #[xml(raw)]
+    header: Cow<'a, str>,
+}
+
+#[test]
+fn capture_raw() {
+    let xml = r#"<Body xmlns="urn:soap"><header></header><content><m:Order xmlns:m="urn:shop"><qty>2</qty><note>a &amp; b<![CDATA[<x>]]></note></m:Order> tail</content><trailer><empty/></trailer></Body>"#;
+    let v = from_str::<Body>(xml).unwrap();
+    assert_eq!(
+        v,
+        Body {
+            content: r#"<m:Order xmlns:m="urn:shop"><qty>2</qty><note>a &amp; b<![CDATA[<x>]]></note></m:Order> tail"#,
+            trailer: "<empty/>".to_owned(),
+            header: Cow::Borrowed(""),
+        }
+    );
+    assert!(matches!(v.header, Cow::Borrowed(_)));
+
+    let xml = r#"<Body xmlns="urn:soap"><content/><header>text only</header></Body>"#;
+    let v = from_str::<Body>(xml).unwrap();
+    assert_eq!(
+        v,
+        Body {
+            content: "",
+            trailer: String::new(),
+            header: Cow::Borrowed("text only"),
+        }
+    );
+
+    assert_eq!(
+        to_string(&v).unwrap(),
+        r#"<Body xmlns="urn:soap"><content></content><trailer></trailer><header>text only</header></Body>"#
+    );
+}
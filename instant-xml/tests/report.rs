@@ -0,0 +1,76 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str_with_report, FromXml, SkipReason, Skipped};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+#[xml(ns("URI"))]
+struct Line {
+    #[xml(attribute)]
+    sku: String,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+#[xml(ns("URI"))]
+struct Order {
+    #[xml(attribute)]
+    id: u32,
+    lines: Vec<Line>,
+    note: Option<String>,
+}
+
+fn skipped(path: &str, ns: &str, reason: SkipReason) -> Skipped {
+    Skipped {
+        path: path.to_owned(),
+        ns: ns.to_owned(),
+        reason,
+    }
+}
+
+#[test]
+fn report() {
+    let xml = r#"<Order xmlns="URI" xmlns:x="OTHER" id="1" x:id="2" extra="3">
+        <Line sku="a" color="red"></Line>
+        stray
+        <x:note>hi</x:note>
+        <Line sku="b"><nested /></Line>
+        <gift />
+    </Order>"#;
+
+    let (order, report) = from_str_with_report::<Order>(xml).unwrap();
+    assert_eq!(
+        order,
+        Order {
+            id: 1,
+            lines: vec![
+                Line {
+                    sku: "a".to_owned()
+                },
+                Line {
+                    sku: "b".to_owned()
+                },
+            ],
+            note: None,
+        }
+    );
+
+    assert_eq!(
+        report,
+        vec![
+            skipped("Order/@id", "OTHER", SkipReason::Namespace),
+            skipped("Order/@extra", "", SkipReason::Unknown),
+            skipped("Order/Line/@color", "", SkipReason::Unknown),
+            skipped("Order/text()", "", SkipReason::Text),
+            skipped("Order/note", "OTHER", SkipReason::Namespace),
+            skipped("Order/Line/nested", "URI", SkipReason::Unknown),
+            skipped("Order/gift", "URI", SkipReason::Unknown),
+        ]
+    );
+}
+
+#[test]
+fn no_report() {
+    let (order, report) =
+        from_str_with_report::<Order>(r#"<Order xmlns="URI" id="1"></Order>"#).unwrap();
+    assert_eq!(order.id, 1);
+    assert_eq!(report, vec![]);
+}
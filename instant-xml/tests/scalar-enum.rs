@@ -1,6 +1,6 @@
 use similar_asserts::assert_eq;
 
-use instant_xml::{from_str, to_string, FromXml, ToXml};
+use instant_xml::{from_str, to_string, Error, FromXml, ToXml};
 
 #[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
 #[xml(scalar)]
@@ -88,3 +88,63 @@ fn scalar_enum_ns_match() {
     </DIDL-Lite>"#;
     assert_eq!(v, from_str(xml).unwrap());
 }
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(scalar, rename_all = "lowercase")]
+enum Status {
+    Active,
+    #[xml(rename = "off")]
+    Inactive,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Account {
+    #[xml(attribute)]
+    state: Status,
+    status: Status,
+    previous: Option<Status>,
+}
+
+#[test]
+fn scalar_enum_rename() {
+    let v = Account {
+        state: Status::Inactive,
+        status: Status::Active,
+        previous: Some(Status::Inactive),
+    };
+    let xml = r#"<Account state="off"><status>active</status><previous>off</previous></Account>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    assert_eq!(
+        from_str::<Account>(r#"<Account state="on"><status>active</status></Account>"#)
+            .unwrap_err(),
+        Error::UnexpectedValue(
+            "enum variant not found for 'on' in field Account::state".to_owned()
+        )
+    );
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("URI"))]
+struct FieldNs {
+    foo: Foo,
+    bar: Bar,
+}
+
+/// Like other scalars, an enum without a namespace of its own takes the namespace of its field,
+/// rather than forcing the element out of the parent's default namespace with `xmlns=""`
+#[test]
+fn scalar_enum_field_ns() {
+    let v = FieldNs {
+        foo: Foo::B,
+        bar: Bar::A,
+    };
+    let xml = r#"<FieldNs xmlns="URI"><foo>B</foo><bar>A</bar></FieldNs>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    // An enum with a namespace of its own still requires it
+    let xml = r#"<FieldNs xmlns="URI" xmlns:o="OTHER"><foo>B</foo><o:bar>A</o:bar></FieldNs>"#;
+    assert!(from_str::<FieldNs>(xml).is_err());
+}
@@ -1,8 +1,9 @@
 use std::borrow::Cow;
+use std::num::{NonZeroI8, NonZeroU32};
 
 use similar_asserts::assert_eq;
 
-use instant_xml::{from_str, FromXml, ToXml};
+use instant_xml::{from_str, to_string, Error, FromXml, ToXml};
 
 #[derive(Debug, PartialEq, Eq, FromXml, ToXml)]
 #[xml(ns("URI"))]
@@ -95,3 +96,34 @@ fn scalar_element_attr() {
         }
     );
 }
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct NonZero {
+    #[xml(attribute)]
+    count: NonZeroU32,
+    offset: NonZeroI8,
+}
+
+#[test]
+fn non_zero() {
+    let v = NonZero {
+        count: NonZeroU32::new(3).unwrap(),
+        offset: NonZeroI8::new(-2).unwrap(),
+    };
+
+    let xml = r#"<NonZero count="3"><offset>-2</offset></NonZero>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let xml = r#"<NonZero count="0"><offset>-2</offset></NonZero>"#;
+    assert!(matches!(
+        from_str::<NonZero>(xml),
+        Err(Error::UnexpectedValue(_))
+    ));
+
+    let xml = r#"<NonZero count="1"><offset>0</offset></NonZero>"#;
+    assert!(matches!(
+        from_str::<NonZero>(xml),
+        Err(Error::UnexpectedValue(_))
+    ));
+}
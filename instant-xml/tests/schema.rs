@@ -0,0 +1,203 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::schema::{to_xsd, Schemas};
+use instant_xml::{Error, Schema, ToXml};
+
+#[derive(Schema, ToXml)]
+#[xml(ns("urn:orders", meta = "urn:meta"))]
+struct Order {
+    #[xml(attribute)]
+    id: u32,
+    #[xml(attribute, ns(meta))]
+    source: Option<String>,
+    customer: Customer,
+    lines: Vec<Line>,
+    note: Option<String>,
+    status: Status,
+    payment: Payment,
+    #[xml(ns("urn:meta"))]
+    created: String,
+}
+
+#[derive(Schema, ToXml)]
+#[xml(ns("urn:orders"))]
+struct Customer {
+    name: String,
+    #[xml(nil)]
+    email: Option<String>,
+}
+
+#[derive(Schema, ToXml)]
+#[xml(ns("urn:orders"))]
+struct Line {
+    #[xml(attribute)]
+    sku: String,
+    #[xml(direct)]
+    quantity: u16,
+}
+
+#[allow(dead_code)]
+#[derive(Schema, ToXml)]
+#[xml(scalar, ns("urn:orders"))]
+enum Status {
+    Open,
+    #[xml(rename = "shipped")]
+    Shipped,
+}
+
+#[allow(dead_code)]
+#[derive(Schema, ToXml)]
+#[xml(forward)]
+enum Payment {
+    Card(Card),
+    Invoice(Invoice),
+}
+
+#[derive(Schema, ToXml)]
+#[xml(ns("urn:orders"))]
+struct Card {
+    number: String,
+}
+
+#[derive(Schema, ToXml)]
+#[xml(ns("urn:orders"))]
+struct Invoice;
+
+#[test]
+fn schema() {
+    let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:ns0="urn:meta" xmlns:tns="urn:orders" targetNamespace="urn:orders" elementFormDefault="qualified">
+  <xs:import namespace="urn:meta"/>
+  <xs:element name="Order" type="tns:Order"/>
+  <xs:complexType name="Card">
+    <xs:sequence>
+      <xs:element name="number" type="xs:string"/>
+    </xs:sequence>
+  </xs:complexType>
+  <xs:complexType name="Customer">
+    <xs:sequence>
+      <xs:element name="name" type="xs:string"/>
+      <xs:element name="email" type="xs:string" minOccurs="0" nillable="true"/>
+    </xs:sequence>
+  </xs:complexType>
+  <xs:complexType name="Invoice"/>
+  <xs:complexType name="Line">
+    <xs:simpleContent>
+      <xs:extension base="xs:unsignedShort">
+        <xs:attribute name="sku" type="xs:string" use="required"/>
+      </xs:extension>
+    </xs:simpleContent>
+  </xs:complexType>
+  <xs:complexType name="Order">
+    <xs:sequence>
+      <xs:element name="Customer" type="tns:Customer"/>
+      <xs:element name="Line" type="tns:Line" minOccurs="0" maxOccurs="unbounded"/>
+      <xs:element name="note" type="xs:string" minOccurs="0"/>
+      <xs:element name="status" type="tns:Status"/>
+      <xs:choice>
+        <xs:element name="Card" type="tns:Card"/>
+        <xs:element name="Invoice" type="tns:Invoice"/>
+      </xs:choice>
+      <xs:element ref="ns0:created"/>
+    </xs:sequence>
+    <xs:attribute name="id" type="xs:unsignedInt" use="required"/>
+    <xs:attribute ref="ns0:source"/>
+  </xs:complexType>
+  <xs:simpleType name="Status">
+    <xs:restriction base="xs:string">
+      <xs:enumeration value="Open"/>
+      <xs:enumeration value="shipped"/>
+    </xs:restriction>
+  </xs:simpleType>
+</xs:schema>
+"#;
+
+    assert_eq!(to_xsd::<Order>().unwrap(), expected);
+}
+
+#[test]
+fn other_namespace() {
+    let mut schemas = Schemas::new();
+    schemas.add::<Order>().unwrap();
+    assert_eq!(
+        schemas.to_xsd("urn:meta").unwrap(),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:tns="urn:meta" xmlns:ns1="urn:orders" targetNamespace="urn:meta" elementFormDefault="qualified">
+  <xs:import namespace="urn:orders"/>
+  <xs:element name="created" type="xs:string"/>
+  <xs:attribute name="source" type="xs:string"/>
+</xs:schema>
+"#
+    );
+}
+
+#[derive(Schema, ToXml)]
+struct Tree {
+    children: Vec<Tree>,
+}
+
+#[test]
+fn recursive() {
+    assert_eq!(
+        to_xsd::<Tree>().unwrap(),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" elementFormDefault="qualified">
+  <xs:element name="Tree" type="Tree"/>
+  <xs:complexType name="Tree">
+    <xs:sequence>
+      <xs:element name="Tree" type="Tree" minOccurs="0" maxOccurs="unbounded"/>
+    </xs:sequence>
+  </xs:complexType>
+</xs:schema>
+"#
+    );
+}
+
+#[derive(Schema, ToXml)]
+#[xml(ns("urn:pages"))]
+struct Page<T> {
+    items: Vec<T>,
+}
+
+#[derive(Schema, ToXml)]
+#[xml(ns("urn:pages"))]
+struct User {
+    name: String,
+}
+
+#[derive(Schema, ToXml)]
+#[xml(ns("urn:pages"), rename = "User")]
+struct Account {
+    id: u32,
+}
+
+#[derive(Schema, ToXml)]
+#[xml(ns("urn:pages"))]
+struct Report {
+    users: Page<User>,
+    accounts: Page<Account>,
+}
+
+#[test]
+fn conflicting_types() {
+    let mut schemas = Schemas::new();
+    schemas.add::<Page<User>>().unwrap();
+    schemas.add::<Page<User>>().unwrap();
+    assert!(matches!(
+        schemas.add::<Page<Account>>(),
+        Err(Error::SchemaConflict(_))
+    ));
+    assert!(matches!(
+        schemas.to_xsd("urn:pages"),
+        Err(Error::SchemaConflict(_))
+    ));
+
+    assert!(matches!(to_xsd::<Report>(), Err(Error::SchemaConflict(_))));
+
+    let mut schemas = Schemas::new();
+    schemas.add::<User>().unwrap();
+    assert_eq!(
+        schemas.add::<Account>(),
+        Err(Error::SchemaConflict("element {urn:pages}User".to_owned()))
+    );
+}
@@ -0,0 +1,59 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{Serializer, ToXml};
+
+#[derive(ToXml)]
+struct Payment {
+    #[xml(attribute)]
+    kind: String,
+    card: String,
+}
+
+#[derive(ToXml)]
+struct Order {
+    #[xml(attribute)]
+    id: u32,
+    payment: Payment,
+    note: String,
+}
+
+#[test]
+fn value_hook() {
+    let v = Order {
+        id: 7,
+        payment: Payment {
+            kind: "visa".to_owned(),
+            card: "4111111111111111".to_owned(),
+        },
+        note: "fish & chips".to_owned(),
+    };
+
+    let mut paths = Vec::new();
+    let mut output = String::new();
+    let mut serializer = Serializer::new(&mut output);
+    serializer.set_value_hook(|path, value| {
+        paths.push(path.to_owned());
+        match path {
+            "Order/Payment/card" => Some(format!("****{}", &value[value.len() - 4..])),
+            "Order/@id" => Some("<redacted>".to_owned()),
+            _ => None,
+        }
+    });
+
+    v.serialize(None, &mut serializer).unwrap();
+    drop(serializer);
+
+    assert_eq!(
+        output,
+        r#"<Order id="&lt;redacted&gt;"><Payment kind="visa"><card>****1111</card></Payment><note>fish &amp; chips</note></Order>"#
+    );
+    assert_eq!(
+        paths,
+        [
+            "Order/@id",
+            "Order/Payment/@kind",
+            "Order/Payment/card",
+            "Order/note"
+        ]
+    );
+}
@@ -0,0 +1,137 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::ser::{Context, Prefix};
+use instant_xml::{to_string, Error, Id, Serializer, ToXml};
+
+const A1: &str = "urn:a1";
+const A2: &str = "urn:a2";
+
+#[derive(ToXml)]
+#[xml(ns("URI", a = A1))]
+struct Outer {
+    #[xml(attribute, ns(a))]
+    x: u8,
+    inner: Inner,
+    #[xml(ns(A1))]
+    after: u8,
+}
+
+#[derive(ToXml)]
+#[xml(ns("URI", a = A2))]
+struct Inner {
+    #[xml(attribute, ns(a))]
+    y: u8,
+    #[xml(ns(A2))]
+    z: u8,
+    #[xml(ns(A1))]
+    w: u8,
+}
+
+#[test]
+fn conflicting_prefix() {
+    let v = Outer {
+        x: 1,
+        inner: Inner { y: 2, z: 3, w: 4 },
+        after: 5,
+    };
+
+    let xml = concat!(
+        r#"<Outer xmlns="URI" xmlns:a="urn:a1" a:x="1">"#,
+        r#"<Inner xmlns:ns0="urn:a2" ns0:y="2"><ns0:z>3</ns0:z><a:w>4</a:w></Inner>"#,
+        "<a:after>5</a:after></Outer>"
+    );
+    assert_eq!(to_string(&v).unwrap(), xml);
+}
+
+struct Element;
+
+impl ToXml for Element {
+    fn serialize<W: std::fmt::Write + ?Sized>(
+        &self,
+        _: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        let prefix = serializer.write_start("Element", "URI")?;
+        serializer.write_attr("id", "urn:attr", "1")?;
+        serializer.write_attr("other", "urn:attr", "2")?;
+        serializer.end_start()?;
+        serializer.write_close(prefix.as_deref(), "Element")
+    }
+}
+
+#[test]
+fn undeclared_attribute_ns() {
+    assert_eq!(
+        to_string(&Element).unwrap(),
+        r#"<Element xmlns="URI" xmlns:ns0="urn:attr" ns0:id="1" ns0:other="2"></Element>"#
+    );
+}
+
+struct Nested(u8);
+
+impl ToXml for Nested {
+    fn serialize<W: std::fmt::Write + ?Sized>(
+        &self,
+        _: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        let prefix = serializer.write_start("Nested", "URI")?;
+        serializer.write_attr("depth", "urn:attr", &self.0)?;
+        serializer.end_start()?;
+        if self.0 < 2 {
+            Nested(self.0 + 1).serialize(None, serializer)?;
+        }
+        serializer.write_close(prefix.as_deref(), "Nested")
+    }
+}
+
+#[test]
+fn declarations_in_scope() {
+    let xml = concat!(
+        r#"<Nested xmlns="URI" xmlns:ns0="urn:attr" ns0:depth="0">"#,
+        r#"<Nested ns0:depth="1"><Nested ns0:depth="2"></Nested></Nested></Nested>"#,
+    );
+    assert_eq!(to_string(&Nested(0)).unwrap(), xml);
+
+    let v = [Nested(2), Nested(2)];
+    let xml = concat!(
+        r#"<Nested xmlns="URI" xmlns:ns0="urn:attr" ns0:depth="2"></Nested>"#,
+        r#"<Nested xmlns="URI" xmlns:ns0="urn:attr" ns0:depth="2"></Nested>"#,
+    );
+    assert_eq!(to_string(&v).unwrap(), xml);
+}
+
+/// Binds the prefix `a` to a different namespace at every level
+struct Layer(usize);
+
+impl ToXml for Layer {
+    fn serialize<W: std::fmt::Write + ?Sized>(
+        &self,
+        _: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        let prefix = serializer.write_start("Layer", "URI")?;
+        let new = Context {
+            default_ns: "URI",
+            prefixes: [Prefix {
+                prefix: "a",
+                ns: Box::leak(format!("urn:{}", self.0).into_boxed_str()),
+            }],
+        };
+        let old = serializer.push(new)?;
+        serializer.end_start()?;
+        if self.0 < 20 {
+            Layer(self.0 + 1).serialize(None, serializer)?;
+        }
+        serializer.write_close(prefix.as_deref(), "Layer")?;
+        serializer.pop(old);
+        Ok(())
+    }
+}
+
+#[test]
+fn many_conflicting_prefixes() {
+    let xml = to_string(&Layer(0)).unwrap();
+    assert!(xml.starts_with(r#"<Layer xmlns="URI" xmlns:a="urn:0"><Layer xmlns:ns0="urn:1">"#));
+    assert!(xml.contains(r#"<Layer xmlns:ns19="urn:20"></Layer>"#));
+}
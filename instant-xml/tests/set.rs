@@ -0,0 +1,63 @@
+use std::collections::{BTreeSet, HashSet};
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, Error, FromXml, ToXml, Unique};
+
+#[derive(Debug, Eq, FromXml, Hash, Ord, PartialEq, PartialOrd, ToXml)]
+struct Tag {
+    #[xml(attribute)]
+    name: String,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Post {
+    tags: BTreeSet<Tag>,
+    labels: HashSet<String>,
+}
+
+#[test]
+fn sets() {
+    let v = Post {
+        tags: BTreeSet::from([
+            Tag {
+                name: "b".to_owned(),
+            },
+            Tag {
+                name: "a".to_owned(),
+            },
+        ]),
+        labels: HashSet::from(["x".to_owned()]),
+    };
+
+    let xml = r#"<Post><Tag name="a"></Tag><Tag name="b"></Tag><labels>x</labels></Post>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let xml = r#"<Post><Tag name="a"></Tag><Tag name="a"></Tag><labels>x</labels><labels>x</labels></Post>"#;
+    let v = from_str::<Post>(xml).unwrap();
+    assert_eq!(v.tags.len(), 1);
+    assert_eq!(v.labels.len(), 1);
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Strict {
+    labels: Unique<BTreeSet<String>>,
+}
+
+#[test]
+fn unique() {
+    let v = Strict {
+        labels: BTreeSet::from(["x".to_owned(), "y".to_owned()]).into(),
+    };
+
+    let xml = r#"<Strict><labels>x</labels><labels>y</labels></Strict>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let xml = r#"<Strict><labels>x</labels><labels>x</labels></Strict>"#;
+    assert_eq!(
+        from_str::<Strict>(xml),
+        Err(Error::DuplicateValue("Strict::labels"))
+    );
+}
@@ -0,0 +1,92 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Item {
+    #[xml(attribute)]
+    id: u32,
+}
+
+#[derive(Debug, Default, Eq, FromXml, PartialEq, ToXml)]
+struct Field {
+    #[xml(list, skip_empty)]
+    tags: Vec<String>,
+    #[xml(list)]
+    flags: Vec<String>,
+}
+
+#[test]
+fn field() {
+    let v = Field::default();
+    let xml = "<Field><flags></flags></Field>";
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Field>(xml).unwrap(), v);
+
+    let v = Field {
+        tags: vec!["a".into(), "b".into()],
+        flags: vec!["c".into()],
+    };
+    let xml = "<Field><tags>a b</tags><flags>c</flags></Field>";
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Field>(xml).unwrap(), v);
+}
+
+#[derive(Debug, Default, Eq, FromXml, PartialEq, ToXml)]
+#[xml(skip_empty)]
+struct Container {
+    #[xml(attribute, list)]
+    ids: Vec<u32>,
+    name: String,
+    #[xml(list)]
+    tags: Vec<String>,
+    items: Vec<Item>,
+}
+
+#[test]
+fn container() {
+    let v = Container::default();
+    let xml = "<Container><name></name></Container>";
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Container>(xml).unwrap(), v);
+
+    let v = Container {
+        ids: vec![1, 2],
+        name: "a".into(),
+        tags: vec!["b".into()],
+        items: vec![Item { id: 3 }],
+    };
+    let xml =
+        r#"<Container ids="1 2"><name>a</name><tags>b</tags><Item id="3"></Item></Container>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Container>(xml).unwrap(), v);
+}
+
+#[derive(Debug, Default, Eq, FromXml, PartialEq, ToXml)]
+#[xml(skip_empty)]
+struct Wrapped {
+    #[xml(wrapper = "items")]
+    items: Vec<Item>,
+    #[xml(wrapper = "extra", keep_empty)]
+    extra: Vec<Item>,
+    #[xml(list, keep_empty)]
+    flags: Vec<String>,
+}
+
+#[test]
+fn wrapper() {
+    // Empty wrappers are omitted, unless the field asks for an explicit empty wrapper
+    let v = Wrapped::default();
+    let xml = "<Wrapped><extra></extra><flags></flags></Wrapped>";
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Wrapped>(xml).unwrap(), v);
+
+    let v = Wrapped {
+        items: vec![Item { id: 1 }],
+        extra: vec![Item { id: 2 }],
+        flags: vec!["a".into()],
+    };
+    let xml = r#"<Wrapped><items><Item id="1"></Item></items><extra><Item id="2"></Item></extra><flags>a</flags></Wrapped>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Wrapped>(xml).unwrap(), v);
+}
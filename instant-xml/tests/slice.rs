@@ -0,0 +1,53 @@
+use std::borrow::Cow;
+
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_slice, Error, FromXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Note {
+    body: String,
+}
+
+const XML: &str = r#"<?xml version="1.0" encoding="UTF-16"?><Note><body>hi</body></Note>"#;
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Borrowed<'a> {
+    #[xml(borrow)]
+    body: Cow<'a, str>,
+}
+
+#[test]
+fn slice() {
+    let mut bytes = vec![0xef, 0xbb, 0xbf];
+    bytes.extend_from_slice(
+        br#"<?xml version="1.0" encoding="utf-8"?><Borrowed><body>hi</body></Borrowed>"#,
+    );
+    assert_eq!(
+        from_slice::<Borrowed>(&bytes).unwrap(),
+        Borrowed {
+            body: Cow::Borrowed("hi")
+        }
+    );
+    assert_eq!(
+        from_slice::<Borrowed>(b"<Borrowed><body>hi</body></Borrowed>").unwrap(),
+        Borrowed {
+            body: Cow::Borrowed("hi")
+        }
+    );
+
+    assert_eq!(
+        from_slice::<Note>(&[0xff, 0xfe, b'<', 0]).unwrap_err(),
+        Error::UnsupportedEncoding("UTF-16".to_owned())
+    );
+    assert_eq!(
+        from_slice::<Note>(XML.as_bytes()).unwrap_err(),
+        Error::UnsupportedEncoding("UTF-16".to_owned())
+    );
+
+    let xml = XML.replace("UTF-16", "ISO-8859-1");
+    assert_eq!(
+        from_slice::<Note>(xml.as_bytes()).unwrap_err(),
+        Error::UnsupportedEncoding("ISO-8859-1".to_owned())
+    );
+}
@@ -0,0 +1,41 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{to_string, SoapAction, ToXml};
+
+#[derive(ToXml)]
+#[xml(
+    ns("urn:schemas-upnp-org:service:AVTransport:1"),
+    soap_action = "urn:schemas-upnp-org:service:AVTransport:1#Stop"
+)]
+struct Stop {
+    #[xml(rename = "InstanceID")]
+    instance_id: u32,
+}
+
+#[derive(ToXml)]
+#[xml(soap_action = "urn:example#Get")]
+struct Get<'a, T> {
+    key: &'a str,
+    value: T,
+}
+
+fn request<T: SoapAction + ToXml>(payload: &T) -> (&'static str, String) {
+    (T::SOAP_ACTION, to_string(payload).unwrap())
+}
+
+#[test]
+fn soap_action() {
+    assert_eq!(
+        request(&Stop { instance_id: 0 }),
+        (
+            "urn:schemas-upnp-org:service:AVTransport:1#Stop",
+            r#"<Stop xmlns="urn:schemas-upnp-org:service:AVTransport:1"><InstanceID>0</InstanceID></Stop>"#.to_owned()
+        )
+    );
+
+    assert_eq!(<Get<'_, u8> as SoapAction>::SOAP_ACTION, "urn:example#Get");
+    assert_eq!(
+        request(&Get { key: "a", value: 1 }).1,
+        "<Get><key>a</key><value>1</value></Get>"
+    );
+}
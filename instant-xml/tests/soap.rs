@@ -0,0 +1,129 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::soap::{self, Envelope, Fault, Version, SOAP11, SOAP12};
+use instant_xml::{from_str, to_string, Error, FromXml, Id, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("urn:stock"))]
+struct Price {
+    symbol: String,
+    value: u32,
+}
+
+#[test]
+fn body() {
+    let price = Price {
+        symbol: "ACME".to_owned(),
+        value: 12,
+    };
+
+    let envelope = Envelope::new(Version::Soap11, price);
+    let xml = to_string(&envelope).unwrap();
+    assert_eq!(
+        xml,
+        concat!(
+            r#"<Envelope xmlns="http://schemas.xmlsoap.org/soap/envelope/"><Body>"#,
+            r#"<Price xmlns="urn:stock"><symbol>ACME</symbol><value>12</value></Price>"#,
+            "</Body></Envelope>"
+        )
+    );
+
+    assert_eq!(from_str::<Envelope<Price>>(&xml).unwrap(), envelope);
+
+    let input = r#"<env:Envelope xmlns:env="http://www.w3.org/2003/05/soap-envelope">
+        <env:Header><token>secret</token></env:Header>
+        <env:Body><Price xmlns="urn:stock"><symbol>ACME</symbol><value>12</value></Price></env:Body>
+    </env:Envelope>"#;
+    assert_eq!(soap::from_str::<Price>(input).unwrap(), envelope.body);
+}
+
+#[test]
+fn fault11() {
+    let input = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+        <soap:Body>
+            <soap:Fault>
+                <faultcode>soap:Server</faultcode>
+                <faultstring>Price lookup failed</faultstring>
+                <faultactor>urn:stock:backend</faultactor>
+                <detail><error xmlns="urn:stock">unknown symbol</error></detail>
+            </soap:Fault>
+        </soap:Body>
+    </soap:Envelope>"#;
+
+    let fault = match soap::from_str::<Price>(input) {
+        Err(Error::Fault(fault)) => fault,
+        result => panic!("unexpected result: {result:?}"),
+    };
+
+    assert_eq!(fault.code().id(), Id::new(SOAP11, "Server"));
+    assert_eq!(fault.reason(), "Price lookup failed");
+    assert_eq!(
+        fault.to_string(),
+        "{http://schemas.xmlsoap.org/soap/envelope/}Server: Price lookup failed"
+    );
+
+    let detail = fault.detail().unwrap();
+    let error = detail.element(Id::new("urn:stock", "error")).unwrap();
+    assert_eq!(error.text(), "unknown symbol");
+
+    match *fault {
+        Fault::Soap11(fault) => assert_eq!(fault.actor.as_deref(), Some("urn:stock:backend")),
+        fault => panic!("unexpected fault: {fault:?}"),
+    }
+}
+
+#[test]
+fn fault12() {
+    let input = r#"<env:Envelope xmlns:env="http://www.w3.org/2003/05/soap-envelope"
+            xmlns:st="urn:stock">
+        <env:Body>
+            <env:Fault>
+                <env:Code>
+                    <env:Value>env:Sender</env:Value>
+                    <env:Subcode>
+                        <env:Value>st:UnknownSymbol</env:Value>
+                    </env:Subcode>
+                </env:Code>
+                <env:Reason>
+                    <env:Text xml:lang="en">Unknown symbol</env:Text>
+                    <env:Text xml:lang="nl">Onbekend symbool</env:Text>
+                </env:Reason>
+                <env:Detail><st:symbol>XYZ</st:symbol></env:Detail>
+            </env:Fault>
+        </env:Body>
+    </env:Envelope>"#;
+
+    let fault = match from_str::<Envelope<Price>>(input) {
+        Err(Error::Fault(fault)) => fault,
+        result => panic!("unexpected result: {result:?}"),
+    };
+
+    assert_eq!(fault.code().id(), Id::new(SOAP12, "Sender"));
+    assert_eq!(fault.reason(), "Unknown symbol");
+    let symbol = fault
+        .detail()
+        .unwrap()
+        .element(Id::new("urn:stock", "symbol"));
+    assert_eq!(symbol.unwrap().text(), "XYZ");
+
+    let fault = match *fault {
+        Fault::Soap12(fault) => fault,
+        fault => panic!("unexpected fault: {fault:?}"),
+    };
+
+    assert_eq!(fault.subcodes.len(), 1);
+    assert_eq!(
+        fault.subcodes[0].id(),
+        Id::new("urn:stock", "UnknownSymbol")
+    );
+    assert_eq!(fault.reasons[1].lang, "nl");
+    assert_eq!(fault.reasons[1].text, "Onbekend symbool");
+
+    // A fault can also be deserialized on its own
+    let input = r#"<env:Fault xmlns:env="http://www.w3.org/2003/05/soap-envelope">
+        <env:Code><env:Value>env:Receiver</env:Value></env:Code>
+        <env:Reason><env:Text xml:lang="en">Down</env:Text></env:Reason>
+    </env:Fault>"#;
+    let fault = from_str::<Fault>(input).unwrap();
+    assert_eq!(fault.to_string(), format!("{{{SOAP12}}}Receiver: Down"));
+}
@@ -0,0 +1,49 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::adapters::Spanned;
+use instant_xml::{from_str, to_string, FromXml, Position, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Config {
+    name: Spanned<String>,
+    server: Vec<Spanned<Server>>,
+    port: Option<Spanned<u16>>,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Server {
+    #[xml(attribute)]
+    host: String,
+}
+
+#[test]
+fn spanned() {
+    let xml = "<Config>\n  <name>main</name>\n  <Server host=\"a\"/>\n  <Server host=\"b\"></Server>\n</Config>";
+    let config = from_str::<Config>(xml).unwrap();
+
+    assert_eq!(config.name.value, "main");
+    assert_eq!(&xml[config.name.span.clone()], "<name>main</name>");
+    assert_eq!(config.name.position(xml), Position { line: 2, column: 3 });
+
+    assert_eq!(config.server.len(), 2);
+    assert_eq!(&xml[config.server[0].span.clone()], "<Server host=\"a\"/>");
+    assert_eq!(
+        &xml[config.server[1].span.clone()],
+        "<Server host=\"b\"></Server>"
+    );
+    assert_eq!(config.server[1].value.host, "b");
+    assert_eq!(
+        config.server[1].position(xml),
+        Position { line: 4, column: 3 }
+    );
+    assert_eq!(config.port, None);
+
+    // Serialization writes only the values
+    assert_eq!(
+        to_string(&config).unwrap(),
+        "<Config><name>main</name><Server host=\"a\"></Server><Server host=\"b\"></Server></Config>"
+    );
+
+    let root = from_str::<Spanned<Server>>("<Server host=\"c\" />").unwrap();
+    assert_eq!(root.span, 0..19);
+}
@@ -0,0 +1,65 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{to_string, to_writer, Error, Stream, ToXml};
+
+#[derive(ToXml)]
+#[xml(rename = "row")]
+struct Row {
+    #[xml(attribute)]
+    id: u32,
+    name: String,
+}
+
+#[derive(ToXml)]
+struct Export {
+    #[xml(attribute)]
+    table: &'static str,
+    rows: Stream<Box<dyn Iterator<Item = Row>>>,
+    tag: Stream<Vec<&'static str>>,
+}
+
+#[test]
+fn stream() {
+    let export = Export {
+        table: "users",
+        rows: Stream::new(Box::new((1..=3).map(|id| Row {
+            id,
+            name: format!("user {id}"),
+        }))),
+        tag: Stream::new(vec!["a", "b"]),
+    };
+
+    let mut output = String::new();
+    to_writer(&export, &mut output).unwrap();
+    assert_eq!(
+        output,
+        concat!(
+            r#"<Export table="users">"#,
+            r#"<row id="1"><name>user 1</name></row>"#,
+            r#"<row id="2"><name>user 2</name></row>"#,
+            r#"<row id="3"><name>user 3</name></row>"#,
+            "<tag>a</tag><tag>b</tag>",
+            "</Export>"
+        )
+    );
+
+    assert_eq!(
+        to_string(&export),
+        Err(Error::Other("stream was already serialized".into()))
+    );
+}
+
+#[test]
+fn empty() {
+    let export = Export {
+        table: "users",
+        rows: Stream::new(Box::new(std::iter::empty())),
+        tag: Stream::from(Vec::new()),
+    };
+
+    assert_eq!(
+        to_string(&export).unwrap(),
+        r#"<Export table="users"></Export>"#
+    );
+    assert_eq!(export.tag.into_inner(), None);
+}
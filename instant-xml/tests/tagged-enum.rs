@@ -0,0 +1,146 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, Error, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(rename = "shape", tag)]
+enum Shape {
+    Circle {
+        #[xml(attribute)]
+        id: u32,
+        radius: u16,
+    },
+    #[xml(rename = "Rect")]
+    Rectangle {
+        width: u16,
+        height: u16,
+    },
+    Empty,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("URI"), tag = "kind")]
+enum Animal {
+    Cat { lives: u8 },
+    Dog,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("URI"))]
+struct Zoo {
+    animals: Vec<Animal>,
+}
+
+#[test]
+fn xsi_type() {
+    let v = Shape::Circle { id: 1, radius: 5 };
+    let xml = r#"<shape xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="Circle" id="1"><radius>5</radius></shape>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let v = Shape::Rectangle {
+        width: 3,
+        height: 4,
+    };
+    let xml = r#"<shape xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="Rect"><width>3</width><height>4</height></shape>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let v = Shape::Empty;
+    let xml =
+        r#"<shape xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="Empty"></shape>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    // The type attribute can come after other attributes
+    let xml = r#"<shape id="2" i:type="Circle" xmlns:i="http://www.w3.org/2001/XMLSchema-instance"><radius>7</radius></shape>"#;
+    assert_eq!(Shape::Circle { id: 2, radius: 7 }, from_str(xml).unwrap());
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("URI"), tag)]
+enum Vehicle {
+    Car { wheels: u8 },
+    Bike,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("OTHER", v = "URI"))]
+struct Garage {
+    vehicle: Vehicle,
+}
+
+#[test]
+fn xsi_type_namespace() {
+    // The type name is resolved against the namespaces in scope
+    let xml = r#"<v:Vehicle xmlns:v="URI" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="v:Car"><v:wheels>4</v:wheels></v:Vehicle>"#;
+    assert_eq!(Vehicle::Car { wheels: 4 }, from_str(xml).unwrap());
+
+    let xml = r#"<Vehicle xmlns="URI" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="Bike"></Vehicle>"#;
+    assert_eq!(xml, to_string(&Vehicle::Bike).unwrap());
+    assert_eq!(Vehicle::Bike, from_str(xml).unwrap());
+
+    // A type with the right local name in another namespace is not a variant
+    let xml = r#"<v:Vehicle xmlns:v="URI" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="Bike"></v:Vehicle>"#;
+    assert_eq!(
+        from_str::<Vehicle>(xml),
+        Err(Error::UnexpectedValue(
+            "unknown type `Bike` for Vehicle".to_owned()
+        ))
+    );
+
+    let xml = r#"<Vehicle xmlns="URI" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="x:Bike"></Vehicle>"#;
+    assert_eq!(
+        from_str::<Vehicle>(xml),
+        Err(Error::UnknownPrefix("x".to_owned()))
+    );
+
+    // Written with a prefix, the type name takes the same prefix
+    let v = Garage {
+        vehicle: Vehicle::Car { wheels: 3 },
+    };
+    let xml = r#"<Garage xmlns="OTHER" xmlns:v="URI"><v:Vehicle xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="v:Car"><v:wheels>3</v:wheels></v:Vehicle></Garage>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[test]
+fn xsi_type_errors() {
+    assert_eq!(
+        from_str::<Shape>("<shape><radius>7</radius></shape>"),
+        Err(Error::MissingValue("Shape::type"))
+    );
+
+    let xml =
+        r#"<shape xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="Triangle" />"#;
+    assert_eq!(
+        from_str::<Shape>(xml),
+        Err(Error::UnexpectedValue(
+            "unknown type `Triangle` for Shape".to_owned()
+        ))
+    );
+}
+
+#[test]
+fn attribute_tag() {
+    let v = Zoo {
+        animals: vec![Animal::Cat { lives: 9 }, Animal::Dog],
+    };
+    let xml = r#"<Zoo xmlns="URI"><Animal kind="Cat"><lives>9</lives></Animal><Animal kind="Dog"></Animal></Zoo>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    // The tag attribute is unqualified, also when the element has a prefix
+    let v = Farm {
+        animal: Animal::Dog,
+    };
+    let xml = r#"<Farm xmlns="OTHER" xmlns:a="URI"><a:Animal kind="Dog"></a:Animal></Farm>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("OTHER", a = "URI"))]
+struct Farm {
+    animal: Animal,
+}
@@ -0,0 +1,24 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[derive(Debug, FromXml, PartialEq, ToXml)]
+#[xml(rename = "price")]
+struct Price {
+    #[xml(attribute)]
+    currency: String,
+    #[xml(text)]
+    amount: f64,
+}
+
+#[test]
+fn text() {
+    let v = Price {
+        currency: "EUR".to_owned(),
+        amount: 10.5,
+    };
+    let xml = r#"<price currency="EUR">10.5</price>"#;
+
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
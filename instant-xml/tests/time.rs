@@ -0,0 +1,66 @@
+#![cfg(feature = "time")]
+
+use similar_asserts::assert_eq;
+use time::macros::{date, datetime, time};
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[derive(Debug, Eq, PartialEq, FromXml, ToXml)]
+struct Test {
+    dt: OffsetDateTime,
+}
+
+#[test]
+fn offset_datetime() {
+    let test = Test {
+        dt: datetime!(2022-11-21 21:17:23 UTC),
+    };
+    let xml = "<Test><dt>2022-11-21T21:17:23Z</dt></Test>";
+    assert_eq!(to_string(&test).unwrap(), xml);
+    assert_eq!(from_str::<Test>(xml).unwrap(), test);
+
+    let test = Test {
+        dt: datetime!(2022-11-21 21:17:23.5 +05:30),
+    };
+    let xml = "<Test><dt>2022-11-21T21:17:23.5+05:30</dt></Test>";
+    assert_eq!(to_string(&test).unwrap(), xml);
+    let de = from_str::<Test>(xml).unwrap();
+    assert_eq!(de, test);
+    assert_eq!(de.dt.offset(), test.dt.offset());
+
+    let xml = "<Test><dt>2022-11-21T21:17:23</dt></Test>";
+    assert!(from_str::<Test>(xml).is_err());
+}
+
+#[derive(Debug, Eq, PartialEq, FromXml, ToXml)]
+struct Shift {
+    #[xml(attribute)]
+    day: Option<Date>,
+    start: PrimitiveDateTime,
+    end: Time,
+}
+
+#[test]
+fn primitive() {
+    let v = Shift {
+        day: Some(date!(2022 - 11 - 21)),
+        start: datetime!(2022-11-21 09:30:00.25),
+        end: time!(17:00:05),
+    };
+
+    let xml = r#"<Shift day="2022-11-21"><start>2022-11-21T09:30:00.25</start><end>17:00:05</end></Shift>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Shift>(xml).unwrap(), v);
+
+    let v = Shift { day: None, ..v };
+    let xml = "<Shift><start>2022-11-21T09:30:00.25</start><end>17:00:05</end></Shift>";
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Shift>(xml).unwrap(), v);
+
+    let xml = "<Shift><start>2022-11-21T09:30:00.25</start><end>17:00</end></Shift>";
+    assert!(from_str::<Shift>(xml).is_err());
+
+    let xml = "<Shift><start>2022-11-21 09:30:00</start><end>17:00:05</end></Shift>";
+    assert!(from_str::<Shift>(xml).is_err());
+}
@@ -0,0 +1,72 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, Error, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Header {
+    #[xml(attribute)]
+    id: u32,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Body {
+    text: String,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Message {
+    parts: (Header, Body),
+    coord: (String, u32, Option<i8>),
+}
+
+#[test]
+fn tuple() {
+    let v = Message {
+        parts: (
+            Header { id: 1 },
+            Body {
+                text: "hi".to_owned(),
+            },
+        ),
+        coord: ("north".to_owned(), 7, Some(-1)),
+    };
+
+    let xml = r#"<Message><Header id="1"></Header><Body><text>hi</text></Body><coord>north</coord><coord>7</coord><coord>-1</coord></Message>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let v = Message {
+        parts: (
+            Header { id: 1 },
+            Body {
+                text: "hi".to_owned(),
+            },
+        ),
+        coord: ("north".to_owned(), 7, None),
+    };
+
+    let xml = r#"<Message><Header id="1"></Header><Body><text>hi</text></Body><coord>north</coord><coord>7</coord></Message>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[test]
+fn out_of_order() {
+    let xml = r#"<Message><Body><text>hi</text></Body><Header id="1"></Header><coord>north</coord><coord>7</coord></Message>"#;
+    assert_eq!(
+        from_str::<Message>(xml),
+        Err(Error::DuplicateValue("Message::parts"))
+    );
+
+    let xml = r#"<Message><Header id="1"></Header><Body><text>hi</text></Body><coord>north</coord><coord>7</coord><coord>1</coord><coord>2</coord></Message>"#;
+    assert_eq!(
+        from_str::<Message>(xml),
+        Err(Error::DuplicateValue("Message::coord"))
+    );
+
+    let xml = r#"<Message><Header id="1"></Header><Body><text>hi</text></Body><coord>north</coord></Message>"#;
+    assert_eq!(
+        from_str::<Message>(xml),
+        Err(Error::MissingValue("Message::coord"))
+    );
+}
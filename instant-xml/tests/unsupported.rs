@@ -0,0 +1,84 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, from_str_with_limits, Error, FromXml, Limits, Position};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Note {
+    body: String,
+}
+
+#[test]
+fn processing_instructions() {
+    assert_eq!(
+        from_str::<Note>(
+            r#"<?xml version="1.0"?><?xml-stylesheet href="note.xsl"?><Note><?render fast?><body>hi</body><?end?></Note>"#
+        ),
+        Ok(Note {
+            body: "hi".to_owned()
+        })
+    );
+}
+
+#[test]
+fn doctype() {
+    let note = Note {
+        body: "hi".to_owned(),
+    };
+
+    assert_eq!(
+        from_str::<Note>(r#"<!DOCTYPE Note SYSTEM "note.dtd"><Note><body>hi</body></Note>"#),
+        Ok(Note {
+            body: "hi".to_owned()
+        })
+    );
+
+    assert_eq!(
+        from_str::<Note>(
+            "<?xml version=\"1.0\"?>\n<!DOCTYPE Note [\n<!ENTITY x \"y\">\n<!ELEMENT Note ANY>\n]>\n<Note><body>hi</body></Note>"
+        ),
+        Ok(note)
+    );
+
+    // Declared entities are not expanded
+    assert_eq!(
+        from_str::<Note>("<!DOCTYPE Note [\n<!ENTITY x \"y\">\n]><Note><body>&x;</body></Note>"),
+        Err(Error::InvalidEntity("x".to_owned()))
+    );
+}
+
+#[test]
+fn reject_dtd() {
+    let limits = Limits {
+        reject_dtd: true,
+        ..Limits::default()
+    };
+
+    assert_eq!(
+        from_str_with_limits::<Note>(
+            r#"<!DOCTYPE Note SYSTEM "note.dtd"><Note><body>hi</body></Note>"#,
+            limits
+        )
+        .map(|(v, _)| v),
+        Err(Error::ExternalDtdNotSupported(Position {
+            line: 1,
+            column: 1
+        }))
+    );
+
+    assert_eq!(
+        from_str_with_limits::<Note>(
+            "<!DOCTYPE Note [\n<!ENTITY x \"y\">\n]><Note><body>&x;</body></Note>",
+            limits
+        )
+        .map(|(v, _)| v),
+        Err(Error::DtdNotSupported(Position { line: 1, column: 1 }))
+    );
+}
+
+#[test]
+fn display() {
+    assert_eq!(
+        Error::ExternalDtdNotSupported(Position { line: 3, column: 7 }).to_string(),
+        "external DTDs are not supported (at 3:7)"
+    );
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "url")]
+
+use similar_asserts::assert_eq;
+use url::Url;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[derive(Debug, Eq, PartialEq, FromXml, ToXml)]
+struct Link {
+    #[xml(attribute)]
+    href: Url,
+    icon: Option<Url>,
+}
+
+#[test]
+fn url() {
+    let v = Link {
+        href: Url::parse("https://example.com/feed?a=1&b=2").unwrap(),
+        icon: None,
+    };
+    let xml = r#"<Link href="https://example.com/feed?a=1&amp;b=2"></Link>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Link>(xml).unwrap(), v);
+
+    let v = Link {
+        icon: Some(Url::parse("http://example.com/icon.png?size=16&fmt=png").unwrap()),
+        ..v
+    };
+    let xml = r#"<Link href="https://example.com/feed?a=1&amp;b=2"><icon>http://example.com/icon.png?size=16&amp;fmt=png</icon></Link>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Link>(xml).unwrap(), v);
+
+    let xml = r#"<Link href="/relative/path"></Link>"#;
+    assert!(from_str::<Link>(xml).is_err());
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "uuid")]
+
+use similar_asserts::assert_eq;
+use uuid::Uuid;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[derive(Debug, Eq, PartialEq, FromXml, ToXml)]
+struct Device {
+    #[xml(attribute)]
+    correlation: Option<Uuid>,
+    udn: Uuid,
+}
+
+#[test]
+fn uuid() {
+    let udn = Uuid::from_u128(0x9f3c_82b1_4a0e_4d2f_b6a7_0c1d_2e3f_4a5b);
+    let v = Device {
+        correlation: None,
+        udn,
+    };
+    let xml = "<Device><udn>9f3c82b1-4a0e-4d2f-b6a7-0c1d2e3f4a5b</udn></Device>";
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Device>(xml).unwrap(), v);
+
+    let v = Device {
+        correlation: Some(udn),
+        udn,
+    };
+    let xml = r#"<Device correlation="9f3c82b1-4a0e-4d2f-b6a7-0c1d2e3f4a5b"><udn>9f3c82b1-4a0e-4d2f-b6a7-0c1d2e3f4a5b</udn></Device>"#;
+    assert_eq!(to_string(&v).unwrap(), xml);
+    assert_eq!(from_str::<Device>(xml).unwrap(), v);
+
+    for form in [
+        "9F3C82B1-4A0E-4D2F-B6A7-0C1D2E3F4A5B",
+        "9f3c82b14a0e4d2fb6a70c1d2e3f4a5b",
+        "{9f3c82b1-4a0e-4d2f-b6a7-0c1d2e3f4a5b}",
+        "urn:uuid:9f3c82b1-4a0e-4d2f-b6a7-0c1d2e3f4a5b",
+    ] {
+        let xml = format!("<Device><udn>{form}</udn></Device>");
+        assert_eq!(from_str::<Device>(&xml).unwrap().udn, udn);
+    }
+
+    let xml = "<Device><udn>9f3c82b1-4a0e-4d2f</udn></Device>";
+    assert!(from_str::<Device>(xml).is_err());
+}
@@ -0,0 +1,233 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::schema::Schemas;
+use instant_xml::validate::Problem;
+use instant_xml::{from_str_validated, to_string, Error, FromXml, Schema, ToXml, Validator};
+
+const XSD: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:tns="urn:orders"
+    targetNamespace="urn:orders" elementFormDefault="qualified">
+  <xs:element name="Order">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="customer" type="tns:Name"/>
+        <xs:element name="line" type="tns:Line" maxOccurs="3"/>
+        <xs:choice minOccurs="0">
+          <xs:element name="card" type="tns:Card"/>
+          <xs:element name="invoice" type="xs:string" nillable="true"/>
+        </xs:choice>
+      </xs:sequence>
+      <xs:attribute name="id" type="xs:unsignedInt" use="required"/>
+      <xs:attribute name="status" type="tns:Status"/>
+    </xs:complexType>
+  </xs:element>
+  <xs:simpleType name="Name">
+    <xs:restriction base="xs:string">
+      <xs:minLength value="1"/>
+      <xs:maxLength value="10"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:simpleType name="Status">
+    <xs:restriction base="xs:token">
+      <xs:enumeration value="open"/>
+      <xs:enumeration value="shipped"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:complexType name="Line">
+    <xs:simpleContent>
+      <xs:extension base="xs:positiveInteger">
+        <xs:attribute name="sku" use="required">
+          <xs:simpleType>
+            <xs:restriction base="xs:string">
+              <xs:pattern value="[A-Z]{2}-\d{3}"/>
+            </xs:restriction>
+          </xs:simpleType>
+        </xs:attribute>
+      </xs:extension>
+    </xs:simpleContent>
+  </xs:complexType>
+  <xs:complexType name="Card">
+    <xs:all>
+      <xs:element name="number" type="xs:string"/>
+      <xs:element name="expiry" type="xs:gYearMonth" minOccurs="0"/>
+    </xs:all>
+  </xs:complexType>
+</xs:schema>
+"#;
+
+#[test]
+fn valid() {
+    let validator = Validator::from_xsd(XSD).unwrap();
+    let input = r#"<Order xmlns="urn:orders" id="7" status=" shipped ">
+        <customer>Alice</customer>
+        <line sku="AB-123">2</line>
+        <line sku="CD-456">1</line>
+        <card><expiry>2030-01</expiry><number>4111</number></card>
+    </Order>"#;
+    assert_eq!(validator.validate_str(input).unwrap(), vec![]);
+
+    let nil = r#"<Order xmlns="urn:orders" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" id="7">
+        <customer>Alice</customer>
+        <line sku="AB-123">2</line>
+        <invoice xsi:nil="true"/>
+    </Order>"#;
+    assert_eq!(validator.validate_str(nil).unwrap(), vec![]);
+}
+
+#[test]
+fn violations() {
+    let validator = Validator::from_xsd(XSD).unwrap();
+    let input = r#"<Order xmlns="urn:orders" status="lost" extra="1">
+        <customer>Alice Wonderland</customer>
+        <line sku="ab-123">0</line>
+        <line sku="CD-456">1</line>
+        <card><expiry>2030-01</expiry></card>
+    </Order>"#;
+
+    let violations = validator.validate_str(input).unwrap();
+    let problems = violations
+        .iter()
+        .map(|violation| (violation.path.as_str(), &violation.problem))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        problems,
+        vec![
+            (
+                "Order > @status",
+                &Problem::Facet {
+                    facet: "enumeration",
+                    value: "lost".to_owned(),
+                }
+            ),
+            ("Order", &Problem::UnexpectedAttribute("extra".to_owned())),
+            ("Order", &Problem::MissingAttribute("id".to_owned())),
+            (
+                "Order > customer",
+                &Problem::Facet {
+                    facet: "maxLength",
+                    value: "Alice Wonderland".to_owned(),
+                }
+            ),
+            (
+                "Order > line > @sku",
+                &Problem::Facet {
+                    facet: "pattern",
+                    value: "ab-123".to_owned(),
+                }
+            ),
+            (
+                "Order > line",
+                &Problem::InvalidValue {
+                    value: "0".to_owned(),
+                    ty: "positiveInteger".to_owned(),
+                }
+            ),
+            (
+                "Order > card",
+                &Problem::MissingElement {
+                    expected: vec!["{urn:orders}number".to_owned()],
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn content_model() {
+    let validator = Validator::from_xsd(XSD).unwrap();
+    let lines = r#"<line sku="AB-123">1</line>"#.repeat(4);
+    let input =
+        format!(r#"<Order xmlns="urn:orders" id="1"><customer>A</customer>{lines}</Order>"#);
+    let violations = validator.validate_str(&input).unwrap();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].path, "Order");
+    assert_eq!(
+        violations[0].problem,
+        Problem::UnexpectedElement {
+            found: "{urn:orders}line".to_owned(),
+            expected: vec![
+                "{urn:orders}card".to_owned(),
+                "{urn:orders}invoice".to_owned(),
+            ],
+        }
+    );
+
+    let input = r#"<Order xmlns="urn:orders" id="1"><line sku="AB-123">1</line></Order>"#;
+    let violations = validator.validate_str(input).unwrap();
+    assert_eq!(
+        violations[0].to_string(),
+        "unexpected element {urn:orders}line, expected one of {urn:orders}customer (in Order)"
+    );
+
+    let input = r#"<Order xmlns="urn:orders" id="1">text<customer>A</customer></Order>"#;
+    let violations = validator.validate_str(input).unwrap();
+    assert_eq!(violations[0].problem, Problem::UnexpectedText);
+    assert_eq!(
+        violations[1].problem,
+        Problem::MissingElement {
+            expected: vec!["{urn:orders}line".to_owned()],
+        }
+    );
+
+    let input = r#"<Other xmlns="urn:orders"/>"#;
+    assert_eq!(
+        validator.validate_str(input).unwrap()[0].problem,
+        Problem::UndeclaredElement("{urn:orders}Other".to_owned())
+    );
+}
+
+#[test]
+fn invalid_schema() {
+    let include = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+        <xs:include schemaLocation="other.xsd"/>
+    </xs:schema>"#;
+    assert!(Validator::from_xsd(include).is_err());
+
+    let undefined = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+        <xs:element name="a" type="b"/>
+    </xs:schema>"#;
+    let validator = Validator::from_xsd(undefined).unwrap();
+    assert!(validator.validate_str("<a/>").is_err());
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, Schema, ToXml)]
+#[xml(ns("urn:items"))]
+struct Item {
+    #[xml(attribute)]
+    id: u8,
+    name: String,
+    tags: Vec<String>,
+}
+
+#[test]
+fn derived() {
+    let mut schemas = Schemas::new();
+    schemas.add::<Item>().unwrap();
+    let validator = Validator::from_schemas(&schemas).unwrap();
+
+    let item = Item {
+        id: 1,
+        name: "first".to_owned(),
+        tags: vec!["a".to_owned(), "b".to_owned()],
+    };
+
+    let xml = to_string(&item).unwrap();
+    assert_eq!(validator.validate_str(&xml).unwrap(), vec![]);
+    assert_eq!(from_str_validated::<Item>(&xml, &validator).unwrap(), item);
+
+    let input = r#"<Item xmlns="urn:items" id="300"><tags>a</tags><name>x</name></Item>"#;
+    match from_str_validated::<Item>(input, &validator) {
+        Err(Error::Invalid(violations)) => assert_eq!(
+            violations
+                .iter()
+                .map(|violation| violation.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "invalid unsignedByte value `300` (in Item > @id)",
+                "unexpected element {urn:items}tags, expected one of {urn:items}name (in Item)",
+            ]
+        ),
+        result => panic!("unexpected result: {result:?}"),
+    }
+}
@@ -0,0 +1,72 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, from_str_with_options, FromXml, Options};
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Entry {
+    #[xml(attribute)]
+    key: String,
+    name: String,
+    #[xml(preserve_space)]
+    code: String,
+    notes: Vec<String>,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq)]
+struct Note {
+    #[xml(direct)]
+    text: String,
+}
+
+const TRIM: Options = Options {
+    lenient_bools: false,
+    trim_text: true,
+    positions: false,
+};
+
+#[test]
+fn trim_text() {
+    let xml = r#"<Entry key=" a ">
+        <name>
+            Widget
+        </name>
+        <code>  x = 1;
+</code>
+        <notes>  first </notes>
+        <notes xml:space="preserve">  second </notes>
+    </Entry>"#;
+
+    assert_eq!(
+        from_str_with_options::<Entry>(xml, TRIM).unwrap(),
+        Entry {
+            key: " a ".into(),
+            name: "Widget".into(),
+            code: "  x = 1;\n".into(),
+            notes: vec!["first".into(), "  second ".into()],
+        }
+    );
+
+    // Whitespace is kept by default
+    let entry = from_str::<Entry>(xml).unwrap();
+    assert_eq!(entry.name, "\n            Widget\n        ");
+    assert_eq!(entry.notes, vec!["  first ", "  second "]);
+}
+
+#[test]
+fn xml_space() {
+    let xml = "<Note xml:space=\"preserve\">\n  text\n</Note>";
+    assert_eq!(
+        from_str_with_options::<Note>(xml, TRIM).unwrap(),
+        Note {
+            text: "\n  text\n".into()
+        }
+    );
+
+    let xml = "<Note xml:space=\"default\">\n  text &amp; more\n</Note>";
+    assert_eq!(
+        from_str_with_options::<Note>(xml, TRIM).unwrap(),
+        Note {
+            text: "text & more".into()
+        }
+    );
+}
@@ -0,0 +1,100 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml};
+
+#[derive(Debug, FromXml, PartialEq, ToXml)]
+struct Reading {
+    #[xml(attribute, with = "comma_decimal")]
+    value: f64,
+    #[xml(with = "epoch")]
+    at: Timestamp,
+    #[xml(with = "comma_decimal", default)]
+    delta: f64,
+}
+
+#[derive(Debug, PartialEq)]
+struct Timestamp(u64);
+
+mod epoch {
+    use std::fmt;
+
+    use instant_xml::{Deserializer, Error, Id, Serializer, ToXml};
+
+    use super::Timestamp;
+
+    pub fn serialize<W: fmt::Write + ?Sized>(
+        value: &Timestamp,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        value.0.serialize(field, serializer)
+    }
+
+    pub fn deserialize(
+        into: &mut Option<Timestamp>,
+        _: &'static str,
+        deserializer: &mut Deserializer<'_, '_>,
+    ) -> Result<(), Error> {
+        let value = deserializer.take_str()?.unwrap_or_default();
+        match value.parse() {
+            Ok(secs) => *into = Some(Timestamp(secs)),
+            Err(_) => return Err(Error::UnexpectedValue(format!("invalid epoch `{value}`"))),
+        }
+
+        Ok(())
+    }
+}
+
+mod comma_decimal {
+    use std::fmt;
+
+    use instant_xml::{Deserializer, Error, Id, Serializer, ToXml};
+
+    pub fn serialize<W: fmt::Write + ?Sized>(
+        value: &f64,
+        field: Option<Id<'_>>,
+        serializer: &mut Serializer<W>,
+    ) -> Result<(), Error> {
+        value
+            .to_string()
+            .replace('.', ",")
+            .serialize(field, serializer)
+    }
+
+    pub fn deserialize(
+        into: &mut Option<f64>,
+        _: &'static str,
+        deserializer: &mut Deserializer<'_, '_>,
+    ) -> Result<(), Error> {
+        let value = deserializer.take_str()?.unwrap_or_default();
+        match value.replace(',', ".").parse() {
+            Ok(value) => *into = Some(value),
+            Err(_) => return Err(Error::UnexpectedValue(format!("invalid decimal `{value}`"))),
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn with_module() {
+    let v = Reading {
+        value: 10.5,
+        at: Timestamp(1_700_000_000),
+        delta: 0.25,
+    };
+
+    let xml = r#"<Reading value="10,5"><at>1700000000</at><delta>0,25</delta></Reading>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let xml = r#"<Reading value="1,0"><at>1</at></Reading>"#;
+    assert_eq!(
+        from_str::<Reading>(xml).unwrap(),
+        Reading {
+            value: 1.0,
+            at: Timestamp(1),
+            delta: 0.0
+        }
+    );
+}
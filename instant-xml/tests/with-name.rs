@@ -0,0 +1,36 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::adapters::WithName;
+use instant_xml::{from_str, to_string, FromXml, Id, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("urn:props"))]
+struct Properties {
+    count: WithName<u32>,
+    #[xml(any)]
+    other: Vec<WithName<String>>,
+}
+
+#[test]
+fn with_name() {
+    let xml = r#"<Properties xmlns="urn:props"><count>3</count><color>red</color><x:size xmlns:x="urn:extra">large</x:size></Properties>"#;
+    let v = from_str::<Properties>(xml).unwrap();
+    assert_eq!(
+        v,
+        Properties {
+            count: WithName::new(Id::new("urn:props", "count"), 3),
+            other: vec![
+                WithName::new(Id::new("urn:props", "color"), "red".to_owned()),
+                WithName::new(Id::new("urn:extra", "size"), "large".to_owned()),
+            ],
+        }
+    );
+
+    let names = v.other.iter().map(|v| v.id()).collect::<Vec<_>>();
+    assert!(names[1].is("urn:extra", "size"));
+
+    assert_eq!(
+        to_string(&v).unwrap(),
+        r#"<Properties xmlns="urn:props"><count>3</count><color>red</color><size xmlns="urn:extra">large</size></Properties>"#
+    );
+}
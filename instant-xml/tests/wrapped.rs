@@ -0,0 +1,104 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{from_str, to_string, FromXml, ToXml, Wrapped, Wrapper};
+
+struct Items;
+
+impl Wrapper for Items {
+    const NAME: &'static str = "items";
+}
+
+struct Note;
+
+impl Wrapper for Note {
+    const NAME: &'static str = "note";
+    const NS: &'static str = "URI";
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Item {
+    #[xml(attribute)]
+    id: u32,
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Order {
+    items: Wrapped<Items, Vec<Item>>,
+    note: Option<Wrapped<Note, String>>,
+}
+
+#[test]
+fn wrapped() {
+    let v = Order {
+        items: Wrapped::new(vec![Item { id: 1 }, Item { id: 2 }]),
+        note: Some("hello".to_owned().into()),
+    };
+
+    let xml = r#"<Order><items><Item id="1"></Item><Item id="2"></Item></items><note xmlns="URI">hello</note></Order>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let v = Order {
+        items: Wrapped::new(Vec::new()),
+        note: None,
+    };
+
+    let xml = r#"<Order><items></items></Order>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[test]
+fn wrapped_root() {
+    let v = Wrapped::<Items, Item>::new(Item { id: 3 });
+    let xml = r#"<items><Item id="3"></Item></items>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+#[xml(ns("URI"))]
+struct Catalog {
+    #[xml(wrapper = "items")]
+    items: Vec<Item>,
+    #[xml(wrapper = "tags", rename = "tag")]
+    tags: Vec<String>,
+    #[xml(wrapper = "notes", rename = "note", skip_empty)]
+    notes: Vec<String>,
+}
+
+#[test]
+fn wrapper_field() {
+    let v = Catalog {
+        items: vec![Item { id: 1 }, Item { id: 2 }],
+        tags: vec!["a".into(), "b".into()],
+        notes: vec!["c".into()],
+    };
+
+    let xml = r#"<Catalog xmlns="URI"><items><Item xmlns="" id="1"></Item><Item xmlns="" id="2"></Item></items><tags><tag>a</tag><tag>b</tag></tags><notes><note>c</note></notes></Catalog>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    let v = Catalog {
+        items: Vec::new(),
+        tags: Vec::new(),
+        notes: Vec::new(),
+    };
+
+    let xml = r#"<Catalog xmlns="URI"><items></items><tags></tags></Catalog>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+
+    // Other children of the wrapper are ignored
+    let xml = r#"<Catalog xmlns="URI"><tags>
+        <tag>a</tag><other /><tag>b</tag>
+    </tags></Catalog>"#;
+    assert_eq!(
+        from_str::<Catalog>(xml).unwrap(),
+        Catalog {
+            items: Vec::new(),
+            tags: vec!["a".into(), "b".into()],
+            notes: Vec::new(),
+        }
+    );
+}
@@ -0,0 +1,82 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::{Error, Serializer, ToXml};
+
+#[derive(ToXml)]
+struct Image {
+    #[xml(attribute)]
+    src: String,
+}
+
+#[derive(ToXml)]
+#[xml(ns("urn:doc"))]
+struct Caption {
+    text: String,
+}
+
+#[test]
+fn writer() {
+    let mut output = String::new();
+    let mut serializer = Serializer::new(&mut output);
+    serializer.write_declaration(None).unwrap();
+    serializer.start_element("doc", "urn:doc").unwrap();
+    serializer.attribute("version", "", "1 & 2").unwrap();
+    serializer
+        .attribute("lang", "http://www.w3.org/XML/1998/namespace", "en")
+        .unwrap();
+    serializer.attribute("id", "urn:meta", "d1").unwrap();
+
+    serializer.start_element("title", "urn:doc").unwrap();
+    serializer.text("Fish <& chips>").unwrap();
+    serializer.end_element().unwrap();
+
+    serializer.start_element("br", "urn:doc").unwrap();
+    serializer.end_element().unwrap();
+
+    serializer
+        .value(&Image {
+            src: "a.png".to_owned(),
+        })
+        .unwrap();
+    serializer
+        .value(&Caption {
+            text: "caption".to_owned(),
+        })
+        .unwrap();
+
+    serializer.start_element("raw", "").unwrap();
+    serializer.raw("<b>bold</b>").unwrap();
+    serializer.value(&42u32).unwrap();
+    serializer.end_element().unwrap();
+
+    serializer.end_element().unwrap();
+    drop(serializer);
+
+    assert_eq!(
+        output,
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<doc xmlns="urn:doc" version="1 &amp; 2" xml:lang="en" xmlns:ns0="urn:meta" ns0:id="d1">"#,
+            "<title>Fish &lt;&amp; chips&gt;</title>",
+            "<br />",
+            r#"<Image xmlns="" src="a.png"></Image>"#,
+            "<Caption><text>caption</text></Caption>",
+            r#"<raw xmlns=""><b>bold</b>42</raw>"#,
+            "</doc>"
+        )
+    );
+}
+
+#[test]
+fn invalid() {
+    let mut output = String::new();
+    let mut serializer = Serializer::new(&mut output);
+    assert!(matches!(
+        serializer.end_element(),
+        Err(Error::UnexpectedState(_))
+    ));
+
+    serializer.start_element("a", "").unwrap();
+    serializer.text("text").unwrap();
+    assert!(serializer.attribute("late", "", "x").is_err());
+}
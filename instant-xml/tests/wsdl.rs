@@ -0,0 +1,139 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::soap::{self, HttpRequest};
+use instant_xml::{wsdl, Error};
+
+#[allow(dead_code)]
+mod stock {
+    include!("wsdl/stock.rs");
+}
+
+use stock::*;
+
+const WSDL: &str = include_str!("wsdl/stock.wsdl");
+
+#[test]
+fn generate() {
+    assert_eq!(wsdl::generate(WSDL).unwrap(), include_str!("wsdl/stock.rs"));
+}
+
+#[test]
+fn call() {
+    let request = GetPrice {
+        symbol: "ACME".to_owned(),
+        currency: None,
+    };
+
+    let response = soap::call::<GetPriceOperation, Error>(&request, |http| {
+        assert_eq!(
+            http,
+            HttpRequest {
+                headers: vec![
+                    ("Content-Type", "text/xml; charset=utf-8".to_owned()),
+                    ("SOAPAction", "\"urn:stock#GetPrice\"".to_owned()),
+                ],
+                body: concat!(
+                    r#"<Envelope xmlns="http://schemas.xmlsoap.org/soap/envelope/"><Body>"#,
+                    r#"<getPrice xmlns="urn:stock"><symbol>ACME</symbol></getPrice>"#,
+                    "</Body></Envelope>"
+                )
+                .to_owned(),
+            }
+        );
+
+        Ok(r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body>
+            <getPriceResponse xmlns="urn:stock" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+                <quote exchange="NYSE"><lastPrice>12.5</lastPrice><closed>true</closed></quote>
+                <note xsi:nil="true"/>
+            </getPriceResponse>
+        </s:Body></s:Envelope>"#
+            .to_owned())
+    })
+    .unwrap();
+
+    assert_eq!(
+        response,
+        GetPriceResponse {
+            quote: vec![Quote {
+                exchange: "NYSE".to_owned(),
+                type_: None,
+                last_price: 12.5,
+                volume: None,
+                closed: Some(true),
+            }],
+            note: None,
+        }
+    );
+
+    let fault = soap::call::<GetPriceOperation, Error>(&request, |_| {
+        Ok(r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body>
+            <s:Fault><faultcode>s:Client</faultcode><faultstring>Unknown symbol</faultstring></s:Fault>
+        </s:Body></s:Envelope>"#
+            .to_owned())
+    });
+
+    match fault {
+        Err(Error::Fault(fault)) => assert_eq!(fault.reason(), "Unknown symbol"),
+        result => panic!("unexpected result: {result:?}"),
+    }
+}
+
+#[test]
+fn rpc() {
+    let http = HttpRequest::new::<PingOperation>(&Ping { count: 3 }).unwrap();
+    assert_eq!(
+        http.body,
+        concat!(
+            r#"<Envelope xmlns="http://schemas.xmlsoap.org/soap/envelope/"><Body>"#,
+            r#"<Ping xmlns="urn:stock:rpc"><count xmlns="">3</count></Ping>"#,
+            "</Body></Envelope>"
+        )
+    );
+
+    let response = soap::from_str::<PingResponse>(
+        r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body>
+            <r:PingResponse xmlns:r="urn:stock:rpc"><reply>pong</reply></r:PingResponse>
+        </s:Body></s:Envelope>"#,
+    );
+    assert_eq!(response.unwrap().reply, "pong");
+}
+
+#[test]
+fn soap12() {
+    let request = GetPrice {
+        symbol: "ACME".to_owned(),
+        currency: Some("EUR".to_owned()),
+    };
+
+    let http = HttpRequest::new::<StockQuoteSoap12GetPriceOperation>(&request).unwrap();
+    assert_eq!(
+        http.headers,
+        vec![(
+            "Content-Type",
+            r#"application/soap+xml; charset=utf-8; action="urn:stock#GetPrice""#.to_owned()
+        )]
+    );
+    assert!(http
+        .body
+        .starts_with(r#"<Envelope xmlns="http://www.w3.org/2003/05/soap-envelope"><Body>"#));
+    assert_eq!(STOCK_QUOTE_PORT12, "http://example.com/stock12");
+}
+
+#[test]
+fn prelude_names() {
+    let renamed = WSDL.replace(r#"name="quote""#, r#"name="string""#);
+    let generated = wsdl::generate(&renamed).unwrap();
+    assert!(generated.contains("pub struct String2 {"));
+    assert!(generated.contains("pub string: Vec<String2>,"));
+    assert!(generated.contains("pub symbol: String,"));
+    assert!(!generated.contains("pub struct String {"));
+}
+
+#[test]
+fn unsupported() {
+    let encoded = WSDL.replace(r#"use="literal" namespace"#, r#"use="encoded" namespace"#);
+    assert!(wsdl::generate(&encoded).is_err());
+
+    let schema = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema""#;
+    assert!(wsdl::generate(schema).is_err());
+}
@@ -0,0 +1,81 @@
+// Generated from a WSDL document by instant-xml
+
+use ::instant_xml::{FromXml, ToXml};
+
+#[derive(Clone, Debug, FromXml, PartialEq, ToXml)]
+#[xml(rename = "getPrice", ns("urn:stock"))]
+pub struct GetPrice {
+    pub symbol: String,
+    pub currency: Option<String>,
+}
+
+#[derive(Clone, Debug, FromXml, PartialEq, ToXml)]
+#[xml(rename = "quote", ns("urn:stock"))]
+pub struct Quote {
+    #[xml(attribute)]
+    pub exchange: String,
+    #[xml(attribute, rename = "type")]
+    pub type_: Option<String>,
+    #[xml(rename = "lastPrice")]
+    pub last_price: f64,
+    pub volume: Option<u64>,
+    pub closed: Option<bool>,
+}
+
+#[derive(Clone, Debug, FromXml, PartialEq, ToXml)]
+#[xml(rename = "getPriceResponse", ns("urn:stock"))]
+pub struct GetPriceResponse {
+    pub quote: Vec<Quote>,
+    #[xml(nil)]
+    pub note: Option<String>,
+}
+
+/// The `GetPrice` operation of the `StockQuoteSoap` binding
+pub struct GetPriceOperation;
+
+impl ::instant_xml::soap::Operation for GetPriceOperation {
+    type Request = GetPrice;
+    type Response = GetPriceResponse;
+    const ACTION: &'static str = "urn:stock#GetPrice";
+    const VERSION: ::instant_xml::soap::Version = ::instant_xml::soap::Version::Soap11;
+}
+
+#[derive(Clone, Debug, FromXml, PartialEq, ToXml)]
+#[xml(ns("urn:stock:rpc"))]
+pub struct Ping {
+    #[xml(no_ns)]
+    pub count: i32,
+}
+
+#[derive(Clone, Debug, FromXml, PartialEq, ToXml)]
+#[xml(ns("urn:stock:rpc"))]
+pub struct PingResponse {
+    #[xml(no_ns)]
+    pub reply: String,
+}
+
+/// The `Ping` operation of the `StockQuoteSoap` binding
+pub struct PingOperation;
+
+impl ::instant_xml::soap::Operation for PingOperation {
+    type Request = Ping;
+    type Response = PingResponse;
+    const ACTION: &'static str = "urn:stock#Ping";
+    const VERSION: ::instant_xml::soap::Version = ::instant_xml::soap::Version::Soap11;
+}
+
+/// The `GetPrice` operation of the `StockQuoteSoap12` binding
+pub struct StockQuoteSoap12GetPriceOperation;
+
+impl ::instant_xml::soap::Operation for StockQuoteSoap12GetPriceOperation {
+    type Request = GetPrice;
+    type Response = GetPriceResponse;
+    const ACTION: &'static str = "urn:stock#GetPrice";
+    const VERSION: ::instant_xml::soap::Version = ::instant_xml::soap::Version::Soap12;
+}
+
+/// The address of the `StockQuotePort` port of the `StockQuoteService` service
+pub const STOCK_QUOTE_PORT: &str = "http://example.com/stock";
+
+/// The address of the `StockQuotePort12` port of the `StockQuoteService` service
+pub const STOCK_QUOTE_PORT12: &str = "http://example.com/stock12";
@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+
+use similar_asserts::assert_eq;
+
+use instant_xml::xmlrpc::{Fault, MethodCall, MethodResponse, Value};
+use instant_xml::{from_str, to_string};
+
+#[test]
+fn method_call() {
+    let mut member = BTreeMap::new();
+    member.insert("count".to_owned(), Value::Int(3));
+    member.insert("name".to_owned(), Value::from("a & b"));
+
+    let call = MethodCall::new(
+        "examples.getStateName",
+        vec![
+            Value::Int(41),
+            Value::Boolean(true),
+            Value::Double(-1.5),
+            Value::DateTime("19980717T14:08:55".to_owned()),
+            Value::Base64(b"you can't read this!".to_vec()),
+            Value::Struct(member),
+            Value::Array(vec![Value::from("x"), Value::Nil]),
+        ],
+    );
+
+    let xml = concat!(
+        "<methodCall><methodName>examples.getStateName</methodName><params>",
+        "<param><value><int>41</int></value></param>",
+        "<param><value><boolean>1</boolean></value></param>",
+        "<param><value><double>-1.5</double></value></param>",
+        "<param><value><dateTime.iso8601>19980717T14:08:55</dateTime.iso8601></value></param>",
+        "<param><value><base64>eW91IGNhbid0IHJlYWQgdGhpcyE=</base64></value></param>",
+        "<param><value><struct>",
+        "<member><name>count</name><value><int>3</int></value></member>",
+        "<member><name>name</name><value><string>a &amp; b</string></value></member>",
+        "</struct></value></param>",
+        "<param><value><array><data>",
+        "<value><string>x</string></value><value><nil /></value>",
+        "</data></array></value></param>",
+        "</params></methodCall>",
+    );
+
+    assert_eq!(to_string(&call).unwrap(), xml);
+    assert_eq!(from_str::<MethodCall>(xml).unwrap(), call);
+}
+
+#[test]
+fn untyped() {
+    let xml = r#"<?xml version="1.0"?>
+<methodCall>
+  <methodName>echo</methodName>
+  <params>
+    <param><value>plain text</value></param>
+    <param>
+      <value>
+        <i4>-7</i4>
+      </value>
+    </param>
+    <param><value></value></param>
+  </params>
+</methodCall>"#;
+
+    let call = from_str::<MethodCall>(xml).unwrap();
+    assert_eq!(call.method_name, "echo");
+    assert_eq!(
+        call.params,
+        vec![Value::from("plain text"), Value::Int(-7), Value::from("")]
+    );
+
+    assert!(from_str::<MethodCall>(
+        "<methodCall><methodName>x</methodName><params><param><value><i4>x</i4></value></param></params></methodCall>"
+    )
+    .is_err());
+    assert!(from_str::<Value>("<value><float>1</float></value>").is_err());
+}
+
+#[test]
+fn method_response() {
+    let response = MethodResponse::Success(Value::from("South Dakota"));
+    let xml = concat!(
+        "<methodResponse><params><param>",
+        "<value><string>South Dakota</string></value>",
+        "</param></params></methodResponse>",
+    );
+
+    assert_eq!(to_string(&response).unwrap(), xml);
+    let value = from_str::<MethodResponse>(xml)
+        .unwrap()
+        .into_result()
+        .unwrap();
+    assert_eq!(value.as_str(), Some("South Dakota"));
+
+    let fault = MethodResponse::Fault(Fault {
+        code: 4,
+        string: "Too many parameters.".to_owned(),
+    });
+
+    let xml = concat!(
+        "<methodResponse><fault><value><struct>",
+        "<member><name>faultCode</name><value><int>4</int></value></member>",
+        "<member><name>faultString</name><value><string>Too many parameters.</string></value></member>",
+        "</struct></value></fault></methodResponse>",
+    );
+
+    assert_eq!(to_string(&fault).unwrap(), xml);
+    let fault = from_str::<MethodResponse>(xml)
+        .unwrap()
+        .into_result()
+        .unwrap_err();
+    assert_eq!(fault.to_string(), "Too many parameters. (fault code 4)");
+}
@@ -0,0 +1,65 @@
+use similar_asserts::assert_eq;
+
+use instant_xml::xsd::{AnyUri, Id, IdRef, Language, NcName, Token};
+use instant_xml::{from_str, to_string, Error, FromXml, ToXml};
+
+#[derive(Debug, Eq, FromXml, PartialEq, ToXml)]
+struct Link {
+    #[xml(attribute)]
+    id: Id,
+    #[xml(attribute)]
+    target: Option<IdRef>,
+    href: AnyUri,
+    lang: Language,
+    label: Token,
+    kind: NcName,
+}
+
+#[test]
+fn round_trip() {
+    let v = Link {
+        id: Id::new("a1").unwrap(),
+        target: Some(IdRef::new("b2").unwrap()),
+        href: AnyUri::new("https://example.com/a%20b?x=1&y=2").unwrap(),
+        lang: Language::new("pt-BR").unwrap(),
+        label: Token::new("hello world").unwrap(),
+        kind: NcName::new("my-kind.v2").unwrap(),
+    };
+
+    let xml = r#"<Link id="a1" target="b2"><href>https://example.com/a%20b?x=1&amp;y=2</href><lang>pt-BR</lang><label>hello world</label><kind>my-kind.v2</kind></Link>"#;
+    assert_eq!(xml, to_string(&v).unwrap());
+    assert_eq!(v, from_str(xml).unwrap());
+}
+
+#[test]
+fn collapse_whitespace() {
+    let xml = "<Link id=\" a1 \"><href> urn:x </href><lang>en</lang><label>\n  hello\t\tworld  </label><kind>k</kind></Link>";
+    let v = from_str::<Link>(xml).unwrap();
+    assert_eq!(v.id.as_str(), "a1");
+    assert_eq!(&*v.href, "urn:x");
+    assert_eq!(&*v.label, "hello world");
+
+    assert_eq!(Token::new(" a\r\n\tb  c ").unwrap().as_str(), "a b c");
+}
+
+#[test]
+fn invalid() {
+    assert!(NcName::new("a:b").is_err());
+    assert!(NcName::new("1abc").is_err());
+    assert!(NcName::new("").is_err());
+    assert!(Language::new("englishlanguage").is_err());
+    assert!(Language::new("en-").is_err());
+    assert!(Language::new("1a").is_err());
+    assert!(AnyUri::new("http://a/%zz").is_err());
+    assert!(AnyUri::new("http://a/\u{7}").is_err());
+    // Beyond escapes and control characters, the lexical space is not checked
+    assert!(AnyUri::new("not a uri").is_ok());
+
+    assert_eq!(
+        from_str::<Link>(
+            "<Link id=\"a b\"><href>x</href><lang>en</lang><label>l</label><kind>k</kind></Link>"
+        )
+        .unwrap_err(),
+        Error::UnexpectedValue("invalid ID `a b`".to_owned())
+    );
+}